@@ -176,6 +176,12 @@ pub fn run<U: 'static, T: GiuiEventLoop<U> + 'static>(width: u32, height: u32) -
                 if let Some(cursor) = gui.cursor_change() {
                     window.set_cursor_icon(cursor);
                 }
+                if let Some(allowed) = gui.ime_allowed_change() {
+                    window.set_ime_allowed(allowed);
+                }
+                if let Some(position) = gui.ime_position_change() {
+                    window.set_ime_position(position);
+                }
                 if is_animating {
                     window.request_redraw();
                 }
@@ -200,6 +206,12 @@ pub fn run<U: 'static, T: GiuiEventLoop<U> + 'static>(width: u32, height: u32) -
                 if let Some(cursor) = gui.cursor_change() {
                     window.set_cursor_icon(cursor);
                 }
+                if let Some(allowed) = gui.ime_allowed_change() {
+                    window.set_ime_allowed(allowed);
+                }
+                if let Some(position) = gui.ime_position_change() {
+                    window.set_ime_position(position);
+                }
                 match event {
                     WindowEvent::CloseRequested => {
                         *control = ControlFlow::Exit;
@@ -258,7 +270,7 @@ pub fn run<U: 'static, T: GiuiEventLoop<U> + 'static>(width: u32, height: u32) -
                             let height = x.rect[3] - x.rect[1];
                             SpriteInstance {
                                 scale: [width, height],
-                                angle: 0.0,
+                                angle: x.rotation,
                                 uv_rect: x.uv_rect,
                                 color: x.color.to_array(),
                                 pos: [x.rect[0] + width / 2.0, x.rect[1] + height / 2.0],
@@ -182,7 +182,7 @@ fn main() {
                             let height = x.rect[3] - x.rect[1];
                             SpriteInstance {
                                 scale: [width, height],
-                                angle: 0.0,
+                                angle: x.rotation,
                                 uv_rect: x.uv_rect,
                                 color: x.color.to_array(),
                                 pos: [x.rect[0] + width / 2.0, x.rect[1] + height / 2.0],
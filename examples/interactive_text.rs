@@ -39,8 +39,7 @@ impl GiuiEventLoop<()> for App {
         );
         let click_here = find(pangram, "click here");
         let two_lines = find(pangram, "two lines.");
-        let mut click_span =
-            spanned_text.add_span(click_here.clone(), Span::Color([0, 0, 255, 255].into()));
+        spanned_text.add_span(click_here.clone(), Span::Color([0, 0, 255, 255].into()));
         let mut two_span =
             spanned_text.add_span(two_lines.clone(), Span::Color([0, 0, 255, 255].into()));
         let text = Text::from_spanned_string(spanned_text, (0, 0));
@@ -49,53 +48,35 @@ impl GiuiEventLoop<()> for App {
         gui.create_control()
             .graphic(Texture::new(fonts.white_texture, [0.0, 0.0, 1.0, 1.0]))
             .child(gui, move |cb, _| {
-                cb.graphic(text).behaviour(InteractiveText::new(vec![
-                    (
-                        click_here.clone(),
-                        Box::new(move |mouse: MouseInfo, this: Id, ctx: &mut Context| {
-                            let text = match ctx.get_graphic_mut(this) {
-                                Graphic::Text(x) => x,
-                                _ => return,
-                            };
-                            match mouse.event {
-                                MouseEvent::Enter => {
-                                    click_span = text.add_span(
-                                        click_here.clone(),
-                                        Span::Underline(Some([0, 0, 255, 255].into())),
-                                    );
-                                }
-                                MouseEvent::Exit => {
-                                    text.remove_span(click_span);
-                                }
-                                _ if mouse.click() => {
-                                    println!("click!!");
-                                }
-                                _ => {}
+                let interactive = InteractiveText::new(vec![(
+                    two_lines.clone(),
+                    Box::new(move |mouse: MouseInfo, this: Id, ctx: &mut Context| {
+                        let text = match ctx.get_graphic_mut(this) {
+                            Graphic::Text(x) => x,
+                            _ => return,
+                        };
+                        match mouse.event {
+                            MouseEvent::Enter => {
+                                two_span = text.add_span(
+                                    two_lines.clone(),
+                                    Span::Underline(Some([0, 0, 255, 255].into())),
+                                );
                             }
-                        }),
-                    ),
-                    (
-                        two_lines.clone(),
-                        Box::new(move |mouse: MouseInfo, this: Id, ctx: &mut Context| {
-                            let text = match ctx.get_graphic_mut(this) {
-                                Graphic::Text(x) => x,
-                                _ => return,
-                            };
-                            match mouse.event {
-                                MouseEvent::Enter => {
-                                    two_span = text.add_span(
-                                        two_lines.clone(),
-                                        Span::Underline(Some([0, 0, 255, 255].into())),
-                                    );
-                                }
-                                MouseEvent::Exit => {
-                                    text.remove_span(two_span);
-                                }
-                                _ => {}
+                            MouseEvent::Exit => {
+                                text.remove_span(two_span);
                             }
-                        }),
-                    ),
-                ]))
+                            _ => {}
+                        }
+                    }),
+                )])
+                .with_link(
+                    click_here.clone(),
+                    "https://github.com/Rodrigodd/giui",
+                    |url, _, _| {
+                        println!("open {}", url);
+                    },
+                );
+                cb.graphic(text).behaviour(interactive)
             })
             .layout(MarginLayout::default())
             .build(gui);
@@ -7,7 +7,7 @@ use std::{
 
 use giui::{
     event::SetValue,
-    graphics::{Graphic, Panel, Text, TextStyle, Texture},
+    graphics::{Graphic, Panel, Shadow, Text, TextStyle, Texture},
     layouts::{FitGraphic, HBoxLayout, MarginLayout, VBoxLayout},
     style::{ButtonStyle, MenuStyle, OnFocusStyle, SelectionColor, TabStyle, TextFieldStyle},
     widgets::{
@@ -172,8 +172,12 @@ impl StyleSheet {
                     hover: Texture::new(texture, [0.6, 0.1, 0.3, 0.3]).into(),
                     pressed: Texture::new(texture, [0.1, 0.6, 0.3, 0.3]).into(),
                     focus: Texture::new(texture, [0.5, 0.5, 0.001, 0.001]).into(),
+                    disabled: None,
                 },
                 arrow: Texture::new(icon_texture, [0.0, 0.0, 1.0, 1.0]).into(),
+                check: Texture::new(texture, [0.2, 0.2, 0.2, 0.2])
+                    .with_color([40, 40, 40, 255].into())
+                    .into(),
                 separator: Texture::new(texture, [0.2, 0.2, 0.2, 0.2])
                     .with_color([180, 180, 180, 255].into())
                     .into(),
@@ -183,6 +187,7 @@ impl StyleSheet {
                     font_id: fonts.notosans,
                     ..Default::default()
                 },
+                shadow: Some(Shadow::default()),
             }
             .into(),
             text_field: TextFieldStyle {
@@ -191,6 +196,9 @@ impl StyleSheet {
                     focus: Panel::new(texture, [0.5, 0.5, 0.5, 0.5], [10.0; 4]).into(),
                 },
                 caret_color: Color::BLACK,
+                caret_width: None,
+                inactive_selection_color: None,
+                selection_radius: 0.0,
                 selection_color: SelectionColor {
                     bg: [170, 0, 255, 255].into(),
                     fg: Some(Color::WHITE),
@@ -207,6 +215,7 @@ impl StyleSheet {
                 hover: Graphic::from(Panel::new(texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4])),
                 pressed: Graphic::from(Panel::new(texture, [0.0, 0.5, 0.5, 0.5], [10.0; 4])),
                 focus: Graphic::from(Panel::new(texture, [0.5, 0.5, 0.5, 0.5], [10.0; 4])),
+                disabled: None,
             }),
             tab_button: Rc::new(TabStyle {
                 hover: Graphic::from(Panel::new(tab_texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4])),
@@ -225,6 +234,7 @@ impl StyleSheet {
                 hover: white.clone().with_color([100, 100, 100, 255].into()).into(),
                 pressed: white.with_color([120, 120, 120, 255].into()).into(),
                 focus: Graphic::None,
+                disabled: None,
             }),
         }
     }
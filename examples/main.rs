@@ -1,12 +1,13 @@
 #![allow(clippy::useless_vec)]
 
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 use giui::{
     font::FontId,
-    graphics::{Graphic, Icon, Panel, Text, TextStyle, Texture},
+    graphics::{Graphic, Icon, Panel, Shadow, Text, TextStyle, Texture},
     layouts::{FitGraphic, GridLayout, HBoxLayout, MarginLayout, RatioLayout, VBoxLayout},
     style::{ButtonStyle, MenuStyle, OnFocusStyle, SelectionColor, TabStyle, TextFieldStyle},
+    util::Rng,
     widgets::{
         self, Blocker, Button, ButtonGroup, CloseMenu, ContextMenu, DropMenu, Dropdown, Hoverable,
         Item, Menu, MenuBar, MenuItem, ScrollBar, ScrollView, Slider, TabButton, TextField, Toggle,
@@ -116,16 +117,21 @@ impl common::GiuiEventLoop<()> for Main {
             hover: Graphic::from(Panel::new(texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4])),
             pressed: Graphic::from(Panel::new(texture, [0.0, 0.5, 0.5, 0.5], [10.0; 4])),
             focus: Graphic::from(Panel::new(texture, [0.5, 0.5, 0.5, 0.5], [10.0; 4])),
+            disabled: None,
         });
         let menu_button_style = Rc::new(ButtonStyle {
             normal: Graphic::from(Texture::new(texture, [0.1, 0.1, 0.3, 0.3])),
             hover: Graphic::from(Texture::new(texture, [0.6, 0.1, 0.3, 0.3])),
             pressed: Graphic::from(Texture::new(texture, [0.1, 0.6, 0.3, 0.3])),
             focus: Graphic::from(Texture::new(texture, [0.5, 0.5, 0.001, 0.001])),
+            disabled: None,
         });
         let menu_style = Rc::new(MenuStyle {
             button: (*menu_button_style).clone(),
             arrow: Texture::new(icon_texture, [0.0, 0.0, 1.0, 1.0]).into(),
+            check: Texture::new(texture, [0.2, 0.2, 0.2, 0.2])
+                .with_color([40, 40, 40, 255].into())
+                .into(),
             separator: Texture::new(texture, [0.2, 0.2, 0.2, 0.2])
                 .with_color([180, 180, 180, 255].into())
                 .into(),
@@ -135,6 +141,7 @@ impl common::GiuiEventLoop<()> for Main {
                 font_id: fonts.notosans,
                 ..Default::default()
             },
+            shadow: Some(Shadow::default()),
         });
         let tab_style = Rc::new(TabStyle {
             hover: Graphic::from(Panel::new(tab_texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4])),
@@ -151,6 +158,7 @@ impl common::GiuiEventLoop<()> for Main {
             hover: painel.clone().with_color([240, 0, 0, 255].into()),
             pressed: painel.clone().with_color([230, 0, 0, 255].into()),
             focus: painel.clone().with_color([255, 0, 0, 255].into()),
+            disabled: None,
         });
 
         let style = Style {
@@ -318,6 +326,12 @@ fn build_gui(gui: &mut Gui, proxy: EventLoopProxy<()>, style: Style) {
                                 "Cut".to_string(),
                                 Box::new(move |_, _| println!("Click on 'Cut'")),
                             ),
+                            Separator,
+                            Checkable(
+                                "Show Toolbar".to_string(),
+                                Cell::new(true),
+                                Box::new(move |_, _, on| println!("Show Toolbar: {}", on)),
+                            ),
                         ],
                     )),
                     Rc::new(Menu::new(
@@ -899,6 +913,9 @@ fn build_gui(gui: &mut Gui, proxy: EventLoopProxy<()>, style: Style) {
                     false,
                     TextFieldStyle {
                         caret_color: Color::BLACK,
+                        caret_width: None,
+                        inactive_selection_color: None,
+                        selection_radius: 0.0,
                         selection_color: SelectionColor {
                             bg: [170, 0, 255, 255].into(),
                             fg: Some(Color::WHITE),
@@ -1019,20 +1036,16 @@ fn build_gui(gui: &mut Gui, proxy: EventLoopProxy<()>, style: Style) {
             ))
             .build(gui);
 
-        let mut seed = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        seed = seed ^ (seed << 64);
+        let mut rng = Rng::new(0x5eed);
         for i in 0..5 {
-            let color = (seed.rotate_left(i * 3)) as u32 | 0xff;
+            let color = rng.color();
             create_item(
                 &mut gui.get_context(),
                 list,
                 style.painel.clone(),
-                format!("This is the item number {} with the color which hexadecimal representation is #{:0x}", i + 1, color), 
+                format!("This is the item number {} with the color which hexadecimal representation is {}", i + 1, color.to_hex_string()), 
                 style.fonts.notosans,
-                Color::from_u32(color),
+                color,
                 style.button_style.clone()
             );
         }
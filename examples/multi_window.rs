@@ -108,6 +108,7 @@ fn main() {
         hover: Panel::new(texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4]).into(),
         pressed: Panel::new(texture, [0.0, 0.5, 0.5, 0.5], [10.0; 4]).into(),
         focus: Panel::new(texture, [0.5, 0.5, 0.5, 0.5], [10.0; 4]).into(),
+        disabled: None,
     });
 
     let window = Rc::new(window);
@@ -303,7 +304,7 @@ fn main() {
                             let height = x.rect[3] - x.rect[1];
                             SpriteInstance {
                                 scale: [width, height],
-                                angle: 0.0,
+                                angle: x.rotation,
                                 uv_rect: x.uv_rect,
                                 color: x.color.to_array(),
                                 pos: [x.rect[0] + width / 2.0, x.rect[1] + height / 2.0],
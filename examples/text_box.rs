@@ -45,6 +45,9 @@ fn main() {
                         fg: Some(Color::WHITE),
                     },
                     caret_color: Color::BLACK,
+                    caret_width: None,
+                    inactive_selection_color: None,
+                    selection_radius: 0.0,
                 },
                 fonts.notosans,
                 (),
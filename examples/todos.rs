@@ -46,12 +46,16 @@ fn main() {
                             fg: Some(Color::WHITE),
                         },
                         caret_color: Color::BLACK,
+                        caret_width: None,
+                        inactive_selection_color: None,
+                        selection_radius: 0.0,
                     },
                     button_style: ButtonStyle {
                         normal: Panel::new(texture, [0.0, 0.0, 0.5, 0.5], [10.0; 4]).into(),
                         hover: Panel::new(texture, [0.5, 0.0, 0.5, 0.5], [10.0; 4]).into(),
                         pressed: Panel::new(texture, [0.0, 0.5, 0.5, 0.5], [10.0; 4]).into(),
                         focus: Panel::new(texture, [0.5, 0.5, 0.5, 0.5], [10.0; 4]).into(),
+                        disabled: None,
                     }
                     .into(),
                 },
@@ -0,0 +1,284 @@
+//! Accessibility information of the controls, for screen readers.
+//!
+//! Each [`Behaviour`](crate::Behaviour) describes its control with
+//! [`accessibility_role`](crate::Behaviour::accessibility_role) and
+//! [`accessibility_value`](crate::Behaviour::accessibility_value). The name of a control is the
+//! string of its [`Text`](crate::graphics::Text) graphic or, for controls like buttons, the
+//! strings of the Text graphics of its descendants.
+//!
+//! [`Gui::accessibility_tree`] builds the tree of all active controls. With the `accesskit`
+//! feature, [`Gui::accesskit_update`] converts it to a [`accesskit::TreeUpdate`], to be pushed to
+//! the platform adapter.
+
+use crate::{graphics::Graphic, widgets::CheckState, Gui, Id};
+
+/// The kind of a control, for screen readers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// A control without a specific role, like a container.
+    Unknown,
+    /// A static text. Controls with a Text graphic and a Unknown role have this role.
+    Label,
+    Button,
+    CheckBox,
+    /// A on/off switch, like a [`Toggle`](crate::widgets::Toggle) created with `new_switch`.
+    Switch,
+    Slider,
+    SpinButton,
+    TextInput,
+    ProgressBar,
+    ScrollView,
+    ScrollBar,
+    List,
+    ListItem,
+    Tree,
+    TreeItem,
+    Menu,
+    MenuBar,
+    MenuItem,
+    ComboBox,
+    Tab,
+    Window,
+    Tooltip,
+    Splitter,
+}
+impl Role {
+    /// If the name of a control with this role comes from the text of its descendants.
+    fn name_from_contents(self) -> bool {
+        matches!(
+            self,
+            Role::Button
+                | Role::CheckBox
+                | Role::Switch
+                | Role::ListItem
+                | Role::TreeItem
+                | Role::MenuItem
+                | Role::Tab
+                | Role::Tooltip
+        )
+    }
+}
+
+/// The current value of a control, for screen readers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessValue {
+    Numeric { value: f64, min: f64, max: f64 },
+    Checked(CheckState),
+    Text(String),
+}
+
+/// A node of the accessibility tree. See [`Gui::accessibility_tree`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    pub id: Id,
+    pub role: Role,
+    pub name: Option<String>,
+    pub value: Option<AccessValue>,
+    /// The rect of the control, in logical pixels.
+    pub bounds: [f32; 4],
+    pub focused: bool,
+    /// The active and visible children of the control.
+    pub children: Vec<AccessNode>,
+}
+
+impl Gui {
+    /// Build the accessibility tree of all active and visible controls, starting at the root control.
+    pub fn accessibility_tree(&self) -> AccessNode {
+        self.access_node(Id::ROOT_ID)
+            .expect("the root control always exist")
+    }
+
+    /// Return true if the accessibility tree may have changed since the last call, and clear the
+    /// dirty flag. The tree changes when controls are added, removed, activated or deactivated,
+    /// when the focus changes, and when [`Context::dirty_accessibility`](crate::Context::dirty_accessibility)
+    /// is called.
+    pub fn take_accessibility_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.accessibility_dirty)
+    }
+
+    fn access_node(&self, id: Id) -> Option<AccessNode> {
+        let control = self.controls.get(id)?;
+        let behaviour = control.behaviour.as_ref();
+        let mut role = behaviour.map_or(Role::Unknown, |x| x.accessibility_role());
+        let name = match &control.graphic {
+            Graphic::Text(text) => {
+                if role == Role::Unknown {
+                    role = Role::Label;
+                }
+                Some(text.string().to_owned())
+            }
+            _ if role.name_from_contents() => {
+                let mut name = String::new();
+                self.contents_text(id, &mut name);
+                Some(name).filter(|x| !x.is_empty())
+            }
+            _ => None,
+        };
+        if id == Id::ROOT_ID {
+            role = Role::Window;
+        }
+        let children = control
+            .children
+            .iter()
+            .filter(|&&x| {
+                self.controls
+                    .get(x)
+                    .map_or(false, |x| x.active && x.visible)
+            })
+            .filter_map(|&x| self.access_node(x))
+            .collect();
+        Some(AccessNode {
+            id,
+            role,
+            name,
+            value: behaviour.and_then(|x| x.accessibility_value()),
+            bounds: *control.rect.get_rect(),
+            focused: self.current_focus == Some(id),
+            children,
+        })
+    }
+
+    /// Append the strings of the Text graphics of the active and visible descendants of `id` to `name`,
+    /// separated by spaces.
+    fn contents_text(&self, id: Id, name: &mut String) {
+        let control = match self.controls.get(id) {
+            Some(x) => x,
+            None => return,
+        };
+        for &child in &control.children {
+            let child_control = match self.controls.get(child) {
+                Some(x) if x.active && x.visible => x,
+                _ => continue,
+            };
+            if let Graphic::Text(text) = &child_control.graphic {
+                if !name.is_empty() {
+                    name.push(' ');
+                }
+                name.push_str(text.string());
+            }
+            self.contents_text(child, name);
+        }
+    }
+}
+
+#[cfg(feature = "accesskit")]
+mod accesskit_impl {
+    use accesskit::{Action, Checked, NodeBuilder, NodeId, Rect, Tree, TreeUpdate};
+
+    use super::{AccessNode, AccessValue, Role};
+    use crate::{widgets::CheckState, Gui, Id};
+
+    impl From<Role> for accesskit::Role {
+        fn from(role: Role) -> Self {
+            match role {
+                Role::Unknown => accesskit::Role::GenericContainer,
+                Role::Label => accesskit::Role::StaticText,
+                Role::Button => accesskit::Role::Button,
+                Role::CheckBox => accesskit::Role::CheckBox,
+                Role::Switch => accesskit::Role::Switch,
+                Role::Slider => accesskit::Role::Slider,
+                Role::SpinButton => accesskit::Role::SpinButton,
+                Role::TextInput => accesskit::Role::TextInput,
+                Role::ProgressBar => accesskit::Role::ProgressIndicator,
+                Role::ScrollView => accesskit::Role::ScrollView,
+                Role::ScrollBar => accesskit::Role::ScrollBar,
+                Role::List => accesskit::Role::List,
+                Role::ListItem => accesskit::Role::ListItem,
+                Role::Tree => accesskit::Role::Tree,
+                Role::TreeItem => accesskit::Role::TreeItem,
+                Role::Menu => accesskit::Role::Menu,
+                Role::MenuBar => accesskit::Role::MenuBar,
+                Role::MenuItem => accesskit::Role::MenuItem,
+                Role::ComboBox => accesskit::Role::ComboBox,
+                Role::Tab => accesskit::Role::Tab,
+                Role::Window => accesskit::Role::Window,
+                Role::Tooltip => accesskit::Role::Tooltip,
+                Role::Splitter => accesskit::Role::Splitter,
+            }
+        }
+    }
+
+    /// The accesskit NodeId of a control. Unique, because it includes the generation of the Id.
+    pub fn node_id(id: Id) -> NodeId {
+        NodeId(((id.generation() as u64) << 32) | id.index() as u64)
+    }
+
+    impl Gui {
+        /// Build a accesskit TreeUpdate with all active controls, if the tree may have changed
+        /// since the last call. See [`Gui::take_accessibility_dirty`].
+        pub fn accesskit_update(&mut self) -> Option<TreeUpdate> {
+            if self.take_accessibility_dirty() {
+                Some(self.accesskit_tree())
+            } else {
+                None
+            }
+        }
+
+        /// Build a accesskit TreeUpdate with all active controls. Should be used for the initial
+        /// tree requested by the platform adapter.
+        pub fn accesskit_tree(&mut self) -> TreeUpdate {
+            let root = self.accessibility_tree();
+            let scale = self.scale_factor();
+            let mut nodes = Vec::new();
+            let mut focus = node_id(Id::ROOT_ID);
+            let mut stack = vec![&root];
+            while let Some(node) = stack.pop() {
+                if node.focused {
+                    focus = node_id(node.id);
+                }
+                nodes.push((node_id(node.id), self.accesskit_node(node, scale)));
+                stack.extend(node.children.iter().rev());
+            }
+            TreeUpdate {
+                nodes,
+                tree: Some(Tree::new(node_id(Id::ROOT_ID))),
+                focus,
+            }
+        }
+
+        fn accesskit_node(&mut self, node: &AccessNode, scale: f64) -> accesskit::Node {
+            let mut builder = NodeBuilder::new(node.role.into());
+            let [x0, y0, x1, y1] = node.bounds;
+            builder.set_bounds(Rect {
+                x0: x0 as f64 * scale,
+                y0: y0 as f64 * scale,
+                x1: x1 as f64 * scale,
+                y1: y1 as f64 * scale,
+            });
+            if let Some(name) = &node.name {
+                builder.set_name(name.as_str());
+            }
+            match &node.value {
+                Some(AccessValue::Numeric { value, min, max }) => {
+                    builder.set_numeric_value(*value);
+                    builder.set_min_numeric_value(*min);
+                    builder.set_max_numeric_value(*max);
+                }
+                Some(AccessValue::Checked(state)) => builder.set_checked(match state {
+                    CheckState::Unchecked => Checked::False,
+                    CheckState::Checked => Checked::True,
+                    CheckState::Indeterminate => Checked::Mixed,
+                }),
+                Some(AccessValue::Text(text)) => builder.set_value(text.as_str()),
+                None => {}
+            }
+            if self
+                .controls
+                .get(node.id)
+                .and_then(|x| x.behaviour.as_ref())
+                .map_or(false, |x| {
+                    x.input_flags().contains(crate::InputFlags::FOCUS)
+                })
+            {
+                builder.add_action(Action::Focus);
+            }
+            builder.set_children(
+                node.children
+                    .iter()
+                    .map(|x| node_id(x.id))
+                    .collect::<Vec<_>>(),
+            );
+            builder.build(&mut self.accesskit_classes)
+        }
+    }
+}
@@ -1,3 +1,76 @@
+use std::{fmt, str::FromStr};
+
+#[cfg(test)]
+mod test {
+    use super::Color;
+
+    #[test]
+    fn hsv_round_trip() {
+        for &color in &[
+            0xff0000ff, 0x00ff00ff, 0x0000ffff, 0x336699ff, 0xffffffff, 0x000000ff,
+        ] {
+            let color = Color::from_u32(color);
+            let [h, s, v] = color.to_hsv();
+            assert_eq!(Color::from_hsv(h, s, v), color);
+        }
+        assert_eq!(
+            Color::from_hsv(120.0, 1.0, 1.0),
+            Color::from_u32(0x00ff00ff)
+        );
+        assert_eq!(
+            Color::from_hsv(-240.0, 1.0, 1.0),
+            Color::from_u32(0x00ff00ff)
+        );
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for &color in &[
+            0xff0000ff, 0x00ff00ff, 0x0000ffff, 0x336699ff, 0xffffffff, 0x000000ff,
+        ] {
+            let color = Color::from_u32(color);
+            let [h, s, l] = color.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l), color);
+        }
+        assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color::from_u32(0xff0000ff));
+        assert_eq!(Color::from_hsl(0.0, 0.0, 0.5), Color::from_u32(0x808080ff));
+    }
+
+    #[test]
+    fn lighten_darken() {
+        let color = Color::from_u32(0x808080ff);
+        assert_eq!(color.lighten(0.5), Color::WHITE);
+        assert_eq!(color.darken(0.6), Color::BLACK);
+        assert_eq!(color.with_alpha(10).darken(1.0).a, 10);
+    }
+
+    #[test]
+    fn contrast() {
+        assert!((Color::BLACK.contrast_ratio(Color::WHITE) - 21.0).abs() < 0.001);
+        assert!((Color::WHITE.contrast_ratio(Color::WHITE) - 1.0).abs() < 0.001);
+        let gray = Color::from_u32(0x777777ff);
+        assert!((gray.contrast_ratio(Color::WHITE) - 4.48).abs() < 0.01);
+        assert_eq!(Color::best_on(Color::from_u32(0x3399ffff)), Color::BLACK);
+        assert_eq!(Color::best_on(Color::from_u32(0x003366ff)), Color::WHITE);
+    }
+
+    #[test]
+    fn hex() {
+        let color = Color::from_u32(0x336699ff);
+        assert_eq!(Color::from_hex("#369"), Ok(color));
+        assert_eq!(Color::from_hex("#369f"), Ok(color));
+        assert_eq!(Color::from_hex("#336699"), Ok(color));
+        assert_eq!("#336699FF".parse(), Ok(color));
+        assert_eq!(Color::from_hex(&color.to_hex_string()), Ok(color));
+        assert_eq!(color.to_hex_string(), "#336699ff");
+
+        use super::ParseColorError::*;
+        assert_eq!(Color::from_hex("336699"), Err(MissingHash));
+        assert_eq!(Color::from_hex("#33669"), Err(InvalidLength(5)));
+        assert_eq!(Color::from_hex("#33g699"), Err(InvalidDigit('g')));
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Color {
     pub r: u8,
@@ -30,6 +103,206 @@ impl Color {
     pub const fn to_array(self) -> [u8; 4] {
         [self.r, self.g, self.b, self.a]
     }
+
+    /// Linearly interpolate each component between `self`, when `t` is 0.0, and `other`, when `t`
+    /// is 1.0.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let (a, b) = (self.to_array(), other.to_array());
+        Color::from_array(
+            [0, 1, 2, 3].map(|i| (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t).round() as u8),
+        )
+    }
+
+    /// Multiply each component of the colors, as if they were in the range 0.0 to 1.0.
+    pub fn multiply(self, other: Color) -> Color {
+        let (a, b) = (self.to_array(), other.to_array());
+        Color::from_array([0, 1, 2, 3].map(|i| (a[i] as u16 * b[i] as u16 / 255) as u8))
+    }
+
+    /// Parse a color in the form `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, where each letter is
+    /// a hexadecimal digit. If the alpha is omitted, the color is opaque.
+    pub fn from_hex(s: &str) -> Result<Color, ParseColorError> {
+        let digits = s.strip_prefix('#').ok_or(ParseColorError::MissingHash)?;
+        let mut values = Vec::with_capacity(8);
+        for c in digits.chars() {
+            let value = c.to_digit(16).ok_or(ParseColorError::InvalidDigit(c))?;
+            values.push(value as u8);
+        }
+        let mut color = [0, 0, 0, 255];
+        match values.len() {
+            3 | 4 => {
+                for (x, v) in color.iter_mut().zip(values) {
+                    *x = v << 4 | v;
+                }
+            }
+            6 | 8 => {
+                for (x, v) in color.iter_mut().zip(values.chunks(2)) {
+                    *x = v[0] << 4 | v[1];
+                }
+            }
+            len => return Err(ParseColorError::InvalidLength(len)),
+        }
+        Ok(Color::from_array(color))
+    }
+
+    /// Format the color in the form `#rrggbbaa`.
+    pub fn to_hex_string(self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    pub fn with_alpha(mut self, a: u8) -> Color {
+        self.a = a;
+        self
+    }
+
+    /// Create a opaque color from its hue, in degrees, and its saturation and value, from 0.0 to
+    /// 1.0.
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let (s, v) = (s.max(0.0).min(1.0), v.max(0.0).min(1.0));
+        let c = v * s;
+        Self::from_chroma(h, c, v - c)
+    }
+
+    /// Return the hue, in degrees from 0.0 to 360.0, and the saturation and value, from 0.0 to
+    /// 1.0, of this color. The alpha is ignored.
+    pub fn to_hsv(self) -> [f32; 3] {
+        let (h, max, min) = self.hue();
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        [h, s, max]
+    }
+
+    /// Create a opaque color from its hue, in degrees, and its saturation and lightness, from 0.0
+    /// to 1.0.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        let (s, l) = (s.max(0.0).min(1.0), l.max(0.0).min(1.0));
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_chroma(h, c, l - c / 2.0)
+    }
+
+    /// Return the hue, in degrees from 0.0 to 360.0, and the saturation and lightness, from 0.0
+    /// to 1.0, of this color. The alpha is ignored.
+    pub fn to_hsl(self) -> [f32; 3] {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d > 0.0 {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        } else {
+            0.0
+        };
+        [h, s.min(1.0), l]
+    }
+
+    /// Increase the lightness of the color by `amount`, from 0.0 to 1.0. Keeps the alpha.
+    pub fn lighten(self, amount: f32) -> Color {
+        let [h, s, l] = self.to_hsl();
+        Self::from_hsl(h, s, l + amount).with_alpha(self.a)
+    }
+
+    /// Decrease the lightness of the color by `amount`, from 0.0 to 1.0. Keeps the alpha.
+    pub fn darken(self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// The relative luminance of the color, from 0.0 for black to 1.0 for white, as defined by
+    /// WCAG 2. The alpha is ignored.
+    pub fn relative_luminance(self) -> f32 {
+        let [r, g, b] = [self.r, self.g, self.b].map(|x| {
+            let x = x as f32 / 255.0;
+            if x <= 0.03928 {
+                x / 12.92
+            } else {
+                ((x + 0.055) / 1.055).powf(2.4)
+            }
+        });
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// The WCAG 2 contrast ratio between the two colors, from 1.0 to 21.0. A ratio of at least 4.5
+    /// is recommended for normal text. The alpha is ignored.
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Return black or white, whichever has the greatest contrast on the given background.
+    pub fn best_on(bg: Color) -> Color {
+        if Color::BLACK.contrast_ratio(bg) >= Color::WHITE.contrast_ratio(bg) {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        }
+    }
+
+    /// Return the hue, in degrees, and the maximum and minimum of the rgb components, from 0.0 to
+    /// 1.0.
+    fn hue(self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|x| x as f32 / 255.0);
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let (fmax, fmin) = (max as f32 / 255.0, min as f32 / 255.0);
+        let d = fmax - fmin;
+        let h = if max == min {
+            0.0
+        } else if max == self.r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (h, fmax, fmin)
+    }
+
+    /// Create a opaque color from its hue, in degrees, its chroma and the value that is added to
+    /// each component.
+    fn from_chroma(h: f32, c: f32, m: f32) -> Color {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let [r, g, b] = match h as u32 {
+            0 => [c, x, 0.0],
+            1 => [x, c, 0.0],
+            2 => [0.0, c, x],
+            3 => [0.0, x, c],
+            4 => [x, 0.0, c],
+            _ => [c, 0.0, x],
+        };
+        let [r, g, b] = [r, g, b].map(|x| ((x + m) * 255.0).round().max(0.0).min(255.0) as u8);
+        Color { r, g, b, a: 255 }
+    }
+}
+/// The error returned when parsing a [`Color`] from a hexadecimal string fails.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseColorError {
+    /// The string does not start with `#`.
+    MissingHash,
+    /// The number of digits is not 3, 4, 6 or 8.
+    InvalidLength(usize),
+    /// The string contains a character that is not a hexadecimal digit.
+    InvalidDigit(char),
+}
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseColorError::MissingHash => write!(f, "color must start with '#'"),
+            ParseColorError::InvalidLength(len) => write!(
+                f,
+                "color must have 3, 4, 6 or 8 hexadecimal digits, but has {}",
+                len
+            ),
+            ParseColorError::InvalidDigit(c) => {
+                write!(f, "invalid hexadecimal digit {:?} in color", c)
+            }
+        }
+    }
+}
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
 }
 impl From<[u8; 4]> for Color {
     fn from(value: [u8; 4]) -> Self {
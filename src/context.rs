@@ -1,13 +1,25 @@
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
+    time::Duration,
 };
 
-use winit::{event::ModifiersState, window::CursorIcon};
+use winit::{
+    event::{ModifiersState, VirtualKeyCode},
+    window::CursorIcon,
+};
 
 use crate::{
-    control::BuilderContext, event, font::Fonts, graphics::Graphic, next_animation_id,
-    time::Instant, Animation, AnimationId, Control, ControlBuilder, Controls, Gui, Id, Rect,
+    control::BuilderContext,
+    event,
+    font::Fonts,
+    graphics::{Graphic, Shadow},
+    next_animation_id,
+    style::GuiTheme,
+    time::Instant,
+    Accelerator, Animation, AnimationId, ContinuousAnimation, Control, ControlBuilder, Controls,
+    Gui, Id, IntervalId, KeyboardKind, Rect, RenderDirtyFlags, RequestVirtualKeyboard,
+    ShimmerAnimation,
 };
 
 pub enum Event {
@@ -19,6 +31,10 @@ pub enum Event {
         length: f32,
         animation: Box<dyn Animation>,
     },
+    AddContinuousAnimation {
+        id: AnimationId,
+        animation: Box<dyn ContinuousAnimation>,
+    },
     RemoveAnimation {
         id: AnimationId,
     },
@@ -134,6 +150,12 @@ impl<'a> Context<'a> {
         self.fonts
     }
 
+    /// Stop the mouse event being handled in `on_mouse_event` from bubbling up to the ancestors
+    /// with [`InputFlags::CHILD_MOUSE`](crate::InputFlags::CHILD_MOUSE).
+    pub fn stop_propagation(&mut self) {
+        self.gui.stop_propagation = true;
+    }
+
     pub fn send_event<T: 'static>(&mut self, event: T) {
         self.events.push(Event::Event(Box::new(event)));
     }
@@ -155,6 +177,26 @@ impl<'a> Context<'a> {
         self.gui.cancel_scheduled_event(event_id);
     }
 
+    /// Send a event, created by `event_factory`, to the control with the given id, every `period`.
+    /// See [`Gui::schedule_interval`].
+    pub fn schedule_interval<E, F>(
+        &mut self,
+        id: Id,
+        event_factory: F,
+        period: Duration,
+    ) -> IntervalId
+    where
+        E: Any,
+        F: FnMut() -> E + 'static,
+    {
+        self.gui.schedule_interval(id, event_factory, period)
+    }
+
+    /// Stop the interval with the given id. See [`Gui::cancel_interval`].
+    pub fn cancel_interval(&mut self, interval_id: IntervalId) {
+        self.gui.cancel_interval(interval_id);
+    }
+
     /// Add a new animation.
     ///
     /// The returned `AnimationId` can be used to remove the added animation with
@@ -173,6 +215,22 @@ impl<'a> Context<'a> {
         id
     }
 
+    /// Add a new animation without a fixed length, that runs until it finishes.
+    ///
+    /// The returned `AnimationId` can be used to remove the added animation with
+    /// [`Context::remove_animation`]. This id is unique.
+    pub fn add_continuous_animation<A: 'static + ContinuousAnimation>(
+        &mut self,
+        animation: A,
+    ) -> AnimationId {
+        let id = next_animation_id();
+        self.events.push(Event::AddContinuousAnimation {
+            id,
+            animation: Box::new(animation),
+        });
+        id
+    }
+
     /// Remove the animation with the given `id`.
     ///
     /// The id is the one returned by [`Context::add_animation`] when the animation to be removed
@@ -186,6 +244,63 @@ impl<'a> Context<'a> {
         self.send_event(cursor);
     }
 
+    /// Notify that the accessibility information of some control has changed, like the value
+    /// returned by [`Behaviour::accessibility_value`](crate::Behaviour::accessibility_value).
+    pub fn dirty_accessibility(&mut self) {
+        self.gui.accessibility_dirty = true;
+    }
+
+    /// Allow or disallow IME input, like when a text field gains or loses focus. See
+    /// [`Gui::ime_allowed_change`].
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        self.gui.ime_allowed = Some(allowed);
+    }
+
+    /// Request the virtual keyboard of the platform to be shown, with the given kind of layout,
+    /// or hidden. See [`Gui::set_virtual_keyboard_callback`](crate::Gui::set_virtual_keyboard_callback).
+    pub fn request_virtual_keyboard(&mut self, show: bool, kind: KeyboardKind) {
+        if let Some(callback) = &mut self.gui.virtual_keyboard {
+            callback(RequestVirtualKeyboard { show, kind });
+        }
+    }
+
+    /// Set the position, in logical pixels, where the IME candidate window should be placed,
+    /// usually just below the caret. See [`Gui::ime_position_change`].
+    pub fn set_ime_position(&mut self, position: [f32; 2]) {
+        self.gui.ime_position = Some(position);
+    }
+
+    /// Start a drag-and-drop operation from the control `source`, carrying `payload`, following
+    /// the given mouse. Usually called in `on_mouse_event`, when [`MouseInfo::is_dragging`]
+    /// becomes true. Any drag in progress is ended, without dropping.
+    ///
+    /// While the mouse moves, the control under it, and then its ancestors, receive
+    /// [`Behaviour::on_drag_over`], until one accepts the payload. When the mouse is released,
+    /// the payload is delivered to that control in [`Behaviour::on_drop`], and the source
+    /// receives [`Behaviour::on_drag_end`].
+    ///
+    /// `image` is a control that follows the mouse, like a copy of the dragged item. Its margins
+    /// are taken as relative to the mouse position, and it is removed when the drag ends.
+    ///
+    /// [`MouseInfo::is_dragging`]: crate::MouseInfo::is_dragging
+    /// [`Behaviour::on_drag_over`]: crate::Behaviour::on_drag_over
+    /// [`Behaviour::on_drop`]: crate::Behaviour::on_drop
+    /// [`Behaviour::on_drag_end`]: crate::Behaviour::on_drag_end
+    pub fn start_drag<T: Any>(
+        &mut self,
+        mouse_id: crate::MouseId,
+        source: Id,
+        payload: T,
+        image: Option<Id>,
+    ) {
+        self.send_event(event::StartDrag {
+            mouse_id,
+            source,
+            payload: Box::new(payload),
+            image,
+        });
+    }
+
     /// If lock is true, locks the cursor over the current control that is receiving mouse events.
     /// This means that even if the mouse position go out of the area of the control, the control
     /// will continue receiving mouse events, and MouseExit will not be emitted. This is useful
@@ -212,6 +327,31 @@ impl<'a> Context<'a> {
         self.events.push(Event::Dirty(id));
     }
 
+    /// Synchronously lay out all controls, so [`Context::get_rect`] returns up to date rects,
+    /// including the ones of the controls built by this context. See
+    /// [`Gui::layout_now`](crate::Gui::layout_now).
+    ///
+    /// Only the start of the built controls is handled immediately, the other events are still
+    /// handled when this context is dropped. This runs
+    /// [`Gui::update_all_layouts`](crate::Gui::update_all_layouts) immediately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called by a control whose behaviour is also its layout, like the ones built with
+    /// [`ControlBuilder::behaviour_and_layout`](crate::ControlBuilder::behaviour_and_layout).
+    pub fn force_layout(&mut self) {
+        for event in std::mem::take(&mut self.events) {
+            match event {
+                Event::Dirty(id) => self.gui.dirty_layout(id),
+                Event::Event(event) if event.is::<event::StartControl>() => {
+                    self.gui.send_event(event)
+                }
+                event => self.events.push(event),
+            }
+        }
+        self.gui.flush_layout();
+    }
+
     pub fn get_rect(&self, id: Id) -> [f32; 4] {
         self.gui.controls.get(id).unwrap().rect.rect
     }
@@ -292,6 +432,24 @@ impl<'a> Context<'a> {
         self.dirty_layout(id);
     }
 
+    pub fn get_max_size(&self, id: Id) -> [f32; 2] {
+        self.gui.controls.get(id).unwrap().rect.get_max_size()
+    }
+
+    /// Set the max size of the control. Use [`f32::INFINITY`] to leave a component unbounded.
+    pub fn set_max_size(&mut self, id: Id, max_size: [f32; 2]) {
+        self.gui
+            .controls
+            .get_mut(id)
+            .unwrap()
+            .rect
+            .set_max_size(max_size);
+        // the rect of the control is decided by the layout of its parent
+        if let Some(parent) = self.get_parent(id) {
+            self.dirty_layout(parent);
+        }
+    }
+
     pub fn get_graphic_mut(&mut self, id: Id) -> &mut Graphic {
         self.render_dirty = true;
         let control = self.gui.controls.get_mut(id).unwrap();
@@ -299,13 +457,62 @@ impl<'a> Context<'a> {
         &mut control.graphic
     }
 
-    pub fn set_graphic(&mut self, id: Id, graphic: Graphic) {
+    pub fn set_graphic(&mut self, id: Id, mut graphic: Graphic) {
+        if let Graphic::Shimmer(shimmer) = &mut graphic {
+            let control = self.gui.controls.get(id).unwrap();
+            // a ShimmerAnimation may already be running, for the previous graphic.
+            let running = match &control.graphic {
+                Graphic::Shimmer(x) => x.animated,
+                _ => false,
+            };
+            shimmer.animated = running || control.really_active;
+            if !running && control.really_active {
+                self.events.push(Event::AddContinuousAnimation {
+                    id: next_animation_id(),
+                    animation: Box::new(ShimmerAnimation { id }),
+                });
+            }
+        }
         let control = self.gui.controls.get_mut(id).unwrap();
         control.graphic = graphic;
         control.rect.dirty_render_dirty_flags();
         self.render_dirty = true;
     }
 
+    pub fn get_opacity(&self, id: Id) -> f32 {
+        self.gui.controls.get(id).unwrap().opacity
+    }
+
+    /// The cursor shown while the mouse is over the control. See [`ControlBuilder::cursor`].
+    pub fn get_hover_cursor(&self, id: Id) -> Option<CursorIcon> {
+        self.gui.controls.get(id).unwrap().cursor
+    }
+
+    /// Set the cursor shown while the mouse is over the control, or over a descendant of it that
+    /// does not have its own cursor. It takes effect in the next mouse movement.
+    pub fn set_hover_cursor(&mut self, id: Id, cursor: Option<CursorIcon>) {
+        self.gui.controls.get_mut(id).unwrap().cursor = cursor;
+    }
+
+    /// Set the opacity of the control and of all its descendants, from 0.0, fully transparent, to
+    /// 1.0, fully opaque. Unlike deactivating it, a control with opacity 0.0 is still laid out and
+    /// still receive input.
+    pub fn set_opacity(&mut self, id: Id, opacity: f32) {
+        let control = self.gui.controls.get_mut(id).unwrap();
+        control.opacity = opacity.max(0.0).min(1.0);
+        // force the descendants to be rebuilt
+        control.rect.render_dirty_flags |= RenderDirtyFlags::RECT;
+        self.render_dirty = true;
+    }
+
+    /// Set the shadow drawn behind the graphic of the control, or remove it if None.
+    pub fn set_shadow(&mut self, id: Id, shadow: Option<Shadow>) {
+        let control = self.gui.controls.get_mut(id).unwrap();
+        control.shadow = shadow;
+        control.rect.dirty_render_dirty_flags();
+        self.render_dirty = true;
+    }
+
     pub fn get_rect_and_graphic(&mut self, id: Id) -> (&mut Rect, &mut Graphic) {
         let control = self.gui.controls.get_mut(id).unwrap();
         self.render_dirty = true;
@@ -320,14 +527,92 @@ impl<'a> Context<'a> {
         self.gui.controls.get(id).map_or(false, |x| x.active)
     }
 
+    /// Return if the control with the given Id is visible. See [`Context::set_visible`].
+    ///
+    /// If the control was removed, or the Id is invalid, returns false.
+    pub fn is_visible(&self, id: Id) -> bool {
+        self.gui.is_visible(id)
+    }
+
+    /// Show or hide the control, after this context is dropped. A hidden control keeps its space
+    /// in the layout, but it and its descendants are not rendered, and don't receive input or
+    /// focus. See [`Gui::set_visible`](crate::Gui::set_visible).
+    pub fn set_visible(&mut self, id: Id, visible: bool) {
+        self.send_event(event::SetVisible { id, visible });
+    }
+
     pub fn set_focus(&mut self, id: Id) {
         self.send_event(event::RequestFocus { id });
     }
 
+    /// Remove the focus from the control, if it is still focused when this context is dropped.
+    /// Used by a widget that stops being focusable, like when it is disabled.
+    pub fn lose_focus(&mut self, id: Id) {
+        self.send_event(event::LoseFocus { id });
+    }
+
     pub fn get_focus(&mut self) -> Option<Id> {
         self.gui.current_focus
     }
 
+    /// Set the current theme of the Gui, after this context is dropped. See
+    /// [`Gui::set_theme`](crate::Gui::set_theme).
+    pub fn set_theme(&mut self, name: &str) {
+        self.send_event(event::SetTheme(name.to_owned()));
+    }
+
+    /// The current theme of the Gui, if any.
+    pub fn theme(&self) -> Option<&GuiTheme> {
+        self.gui.theme()
+    }
+
+    /// Make the given control modal. See [`Gui::push_modal`](crate::Gui::push_modal).
+    pub fn push_modal(&mut self, id: Id) {
+        self.send_event(event::PushModal { id });
+    }
+
+    /// Pop the current modal. See [`Gui::pop_modal`](crate::Gui::pop_modal).
+    pub fn pop_modal(&mut self) {
+        self.send_event(event::PopModal);
+    }
+
+    /// The control that is currently modal, if any.
+    pub fn current_modal(&self) -> Option<Id> {
+        self.gui.current_modal()
+    }
+
+    /// Register a callback for a accelerator. See [`Gui::add_accelerator`](crate::Gui::add_accelerator).
+    pub fn add_accelerator<F: FnMut(&mut Context) + 'static>(
+        &mut self,
+        accelerator: Accelerator,
+        callback: F,
+    ) {
+        self.gui.add_accelerator(accelerator, callback);
+    }
+
+    /// Register a global keyboard shortcut. See
+    /// [`Gui::register_shortcut`](crate::Gui::register_shortcut).
+    pub fn register_shortcut<F: FnMut(&mut Context) -> bool + 'static>(
+        &mut self,
+        modifiers: ModifiersState,
+        key: VirtualKeyCode,
+        callback: F,
+    ) {
+        self.gui.register_shortcut(modifiers, key, callback);
+    }
+
+    /// Unregister the callback of a global keyboard shortcut. See
+    /// [`Gui::unregister_shortcut`](crate::Gui::unregister_shortcut).
+    pub fn unregister_shortcut(&mut self, modifiers: ModifiersState, key: VirtualKeyCode) {
+        self.gui.unregister_shortcut(modifiers, key);
+    }
+
+    /// Unregister the callback of a accelerator. See
+    /// [`Gui::remove_accelerator`](crate::Gui::remove_accelerator).
+    pub fn remove_accelerator(&mut self, accelerator: Accelerator) {
+        self.gui.remove_accelerator(accelerator);
+    }
+
     pub fn is_focus(&self, id: Id) -> bool {
         self.gui.controls.get(id).unwrap().focus
     }
@@ -379,6 +664,16 @@ impl<'a> Context<'a> {
         self.dirty_layout(id);
     }
 
+    /// Move the Control with the given Id to the given position in the children vector of its
+    /// parent, changing where it is placed by layouts that follow the order of the children. If
+    /// `index` is out of bounds, it is moved to the last position.
+    pub fn move_to_index(&mut self, id: Id, index: usize) {
+        self.gui.controls.move_to_index(id, index);
+        if let Some(parent) = self.get_parent(id) {
+            self.dirty_layout(parent);
+        }
+    }
+
     pub fn get_parent(&self, id: Id) -> Option<Id> {
         self.gui.controls.get(id).unwrap().parent
     }
@@ -391,6 +686,16 @@ impl<'a> Context<'a> {
     pub fn get_active_children(&self, id: Id) -> Vec<Id> {
         self.gui.controls.get_active_children(id).unwrap()
     }
+
+    /// See [`Gui::find_by_name`].
+    pub fn find_by_name(&self, name: &str) -> Option<Id> {
+        self.gui.controls.find_by_name(name)
+    }
+
+    /// See [`Gui::find_all_by_name`].
+    pub fn find_all_by_name(&self, name: &str) -> &[Id] {
+        self.gui.controls.find_all_by_name(name)
+    }
 }
 
 pub struct MinSizeContext<'a> {
@@ -482,8 +787,11 @@ pub struct LayoutContext<'a> {
     resources: &'a mut HashMap<TypeId, Box<dyn Any>>,
     fonts: &'a Fonts,
     scale_factor: f64,
+    /// If the rects set by the layout are rounded to the physical pixel grid.
+    pixel_snapping: bool,
     pub(crate) dirtys: Vec<Id>,
     pub(crate) events: Vec<Box<dyn Any>>,
+    pub(crate) relayout: bool,
 }
 impl BuilderContext for LayoutContext<'_> {
     fn get_from_type_id(&self, type_id: TypeId) -> &dyn Any {
@@ -543,6 +851,7 @@ impl<'a> LayoutContext<'a> {
         resources: &'a mut HashMap<TypeId, Box<dyn Any>>,
         fonts: &'a Fonts,
         scale_factor: f64,
+        pixel_snapping: bool,
     ) -> Self {
         Self {
             this,
@@ -550,11 +859,20 @@ impl<'a> LayoutContext<'a> {
             resources,
             fonts,
             scale_factor,
+            pixel_snapping,
             dirtys: Vec::new(),
             events: Vec::new(),
+            relayout: false,
         }
     }
 
+    /// Request the min size and the layout of this control to be computed again, after the
+    /// current update. This is needed when the min size of the control depends on the size given
+    /// to it, like the height of a wrapping layout depends on its width.
+    pub fn relayout(&mut self) {
+        self.relayout = true;
+    }
+
     /// Set the value of the type T that is owned by the Gui. Any value set before will be dropped
     /// and replaced.
     pub fn set<T: Any + 'static>(&mut self, value: T) {
@@ -623,15 +941,24 @@ impl<'a> LayoutContext<'a> {
     }
 
     pub fn set_rect(&mut self, id: Id, rect: [f32; 4]) {
+        let rect = self.snap_to_pixels(rect);
         self.controls.get_mut(id).unwrap().rect.set_rect(rect);
     }
 
     pub fn set_designed_rect(&mut self, id: Id, rect: [f32; 4]) {
-        self.controls
-            .get_mut(id)
-            .unwrap()
-            .rect
-            .set_designed_rect(rect);
+        let rect = self.controls.get(id).unwrap().rect.fit_designed_rect(rect);
+        let rect = self.snap_to_pixels(rect);
+        self.controls.get_mut(id).unwrap().rect.set_rect(rect);
+    }
+
+    /// Round the rect to the physical pixel grid, if pixel snapping is enabled. See
+    /// [`Gui::set_pixel_snapping`](crate::Gui::set_pixel_snapping).
+    fn snap_to_pixels(&self, rect: [f32; 4]) -> [f32; 4] {
+        if !self.pixel_snapping {
+            return rect;
+        }
+        let scale_factor = self.scale_factor as f32;
+        rect.map(|x| (x * scale_factor).round() / scale_factor)
     }
 
     pub fn get_layouting(&self, id: Id) -> &Rect {
@@ -812,6 +1139,18 @@ impl<'a> RenderContext<'a> {
         self.gui.controls.get(id).unwrap().rect.get_size()
     }
 
+    pub fn get_shadow(&self, id: Id) -> Option<Shadow> {
+        self.gui.controls.get(id).unwrap().shadow
+    }
+
+    pub fn get_opacity(&self, id: Id) -> f32 {
+        self.gui.controls.get(id).unwrap().opacity
+    }
+
+    pub fn is_visible(&self, id: Id) -> bool {
+        self.gui.controls.get(id).unwrap().visible
+    }
+
     pub fn get_graphic_mut(&mut self, id: Id) -> &mut Graphic {
         self.render_dirty = true;
         let control = self.gui.controls.get_mut(id).unwrap();
@@ -842,4 +1181,10 @@ impl<'a> RenderContext<'a> {
     pub fn get_active_children(&self, id: Id) -> Vec<Id> {
         self.gui.controls.get_active_children(id).unwrap()
     }
+
+    /// Return true if the control is the root of a window added by
+    /// [`Gui::add_window`](crate::Gui::add_window).
+    pub(crate) fn is_window_root(&self, id: Id) -> bool {
+        self.gui.is_window_root(id)
+    }
 }
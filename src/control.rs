@@ -1,11 +1,17 @@
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
+    collections::HashMap,
     num::NonZeroU32,
     rc::Rc,
 };
 
-use crate::{graphics::Graphic, Behaviour, Id, Layout, Rect, RectFill};
+use winit::window::CursorIcon;
+
+use crate::{
+    graphics::{Graphic, Shadow},
+    Behaviour, Id, Layout, Rect, RectFill,
+};
 
 pub trait BuilderContext {
     /// Get a reference to the value of type T that is owned by the Gui.
@@ -107,6 +113,20 @@ impl ControlBuilder {
         self.control.rect.min_size[1] = min_height;
         self
     }
+    /// Set the max size of the control. The layouts never make the control bigger than it, even
+    /// when it would be stretched to fill its parent, unless its min size is bigger.
+    pub fn max_size(mut self, max_size: [f32; 2]) -> Self {
+        self.control.rect.max_size = max_size;
+        self
+    }
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.control.rect.max_size[0] = max_width;
+        self
+    }
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.control.rect.max_size[1] = max_height;
+        self
+    }
     pub fn fill_x(mut self, fill: RectFill) -> Self {
         self.control.rect.set_fill_x(fill);
         self
@@ -123,6 +143,29 @@ impl ControlBuilder {
         self.control.rect.expand_y = expand;
         self
     }
+    /// Set the grow and shrink weights used by a [`FlexLayout`](crate::layouts::FlexLayout).
+    pub fn flex(mut self, grow: f32, shrink: f32) -> Self {
+        self.control.rect.flex.grow = grow;
+        self.control.rect.flex.shrink = shrink;
+        self
+    }
+    /// Set the preferred size used by a [`FlexLayout`](crate::layouts::FlexLayout).
+    pub fn flex_basis(mut self, basis: f32) -> Self {
+        self.control.rect.flex.basis = basis;
+        self
+    }
+    /// Set the number of columns and rows that the control spans inside of a
+    /// [`GridLayout`](crate::layouts::GridLayout).
+    pub fn span(mut self, columns: u32, rows: u32) -> Self {
+        self.control.rect.span = [columns, rows];
+        self
+    }
+    /// If true, the default, the graphics of the descendants of this control are clipped by its
+    /// rect. Set to false to allow children to draw outside of it, like a shadow or a badge.
+    pub fn clip_children(mut self, clip_children: bool) -> Self {
+        self.control.rect.clip_children = clip_children;
+        self
+    }
     pub fn behaviour<T: Behaviour + 'static>(mut self, behaviour: T) -> Self {
         // TODO: remove this someday
         debug_assert!(self.control.behaviour.is_none());
@@ -144,6 +187,28 @@ impl ControlBuilder {
         self.control.graphic = graphic.into();
         self
     }
+    /// Set the opacity of this control and of all its descendants, from 0.0 to 1.0.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.control.opacity = opacity.max(0.0).min(1.0);
+        self
+    }
+    /// If false, this control and its descendants are hidden. See
+    /// [`Context::set_visible`](crate::Context::set_visible).
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.control.visible = visible;
+        self
+    }
+    /// Show the given cursor while the mouse is over this control, or over a descendant of it
+    /// that does not have its own cursor.
+    pub fn cursor(mut self, cursor: CursorIcon) -> Self {
+        self.control.cursor = Some(cursor);
+        self
+    }
+    /// Draw a shadow behind the graphic of this control.
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.control.shadow = Some(shadow);
+        self
+    }
     pub fn parent(mut self, parent: Id) -> Self {
         self.control.parent = Some(parent);
         self
@@ -159,6 +224,29 @@ impl ControlBuilder {
         self
     }
 
+    /// If it is true, this control is a focus scope: while the focus is inside of it, Tab
+    /// navigation only cycles through its descendants, wrapping around at its ends.
+    pub fn focus_scope(mut self, focus_scope: bool) -> Self {
+        self.control.focus_scope = focus_scope;
+        self
+    }
+
+    /// Tag this control with a style key. When a theme is set, with
+    /// [`Gui::set_theme`](crate::Gui::set_theme), the style or color of the theme with this key
+    /// is applied to the control.
+    pub fn style_key(mut self, key: &str) -> Self {
+        self.control.style_key = Some(key.to_owned());
+        self
+    }
+
+    /// Give a name to this control. The name is used in the path returned by
+    /// [`Gui::debug_path`](crate::Gui::debug_path), and the control can be queried by it with
+    /// [`Gui::find_by_name`](crate::Gui::find_by_name). Names don't need to be unique.
+    pub fn name(mut self, name: &str) -> Self {
+        self.control.name = Some(name.to_owned());
+        self
+    }
+
     pub fn child<F>(self, ctx: &mut dyn BuilderContext, create_child: F) -> Self
     where
         F: FnOnce(ControlBuilder, &mut dyn BuilderContext) -> ControlBuilder,
@@ -336,6 +424,8 @@ pub struct Controls {
     free_head: Option<u32>,
     pub(crate) controls: Vec<ControlEntry>,
     generation: NonZeroU32,
+    /// The ids of the alive controls with each name, in the order they were built.
+    names: HashMap<String, Vec<Id>>,
 }
 impl Controls {
     /// Create a new Controls, with a single ROOT Control with the given width and height.
@@ -356,6 +446,7 @@ impl Controls {
             free_head: None,
             controls: vec![ControlEntry::Started { control: root }],
             generation: next_generation(),
+            names: HashMap::new(),
         }
     }
 
@@ -413,6 +504,9 @@ impl Controls {
             }
             ControlEntry::Reserved { generation, .. } => {
                 assert_eq!(control.generation, *generation);
+                if let Some(name) = &control.name {
+                    self.names.entry(name.clone()).or_default().push(id);
+                }
                 self.controls[id.index()] = ControlEntry::Builded { control };
             }
             ControlEntry::Started { .. } => panic!("Control already started"),
@@ -422,10 +516,31 @@ impl Controls {
     #[allow(clippy::or_fun_call)]
     pub fn remove(&mut self, id: Id) {
         self.generation = next_generation();
-        self.controls[id.index()] = ControlEntry::Free {
-            free_next: self.free_head,
-        };
+        let entry = std::mem::replace(
+            &mut self.controls[id.index()],
+            ControlEntry::Free {
+                free_next: self.free_head,
+            },
+        );
         self.free_head = Some(id.index);
+        if let Some(name) = entry.get(id).and_then(|x| x.name.as_ref()) {
+            if let Some(ids) = self.names.get_mut(name) {
+                ids.retain(|&x| x != id);
+                if ids.is_empty() {
+                    self.names.remove(name);
+                }
+            }
+        }
+    }
+
+    /// Get the first built control, that is still alive, with the given name.
+    pub fn find_by_name(&self, name: &str) -> Option<Id> {
+        self.names.get(name).and_then(|ids| ids.first().copied())
+    }
+
+    /// Get all alive controls with the given name, in the order they were built.
+    pub fn find_all_by_name(&self, name: &str) -> &[Id] {
+        self.names.get(name).map_or(&[], |ids| ids.as_slice())
     }
 
     /// Move the Control with the given Id, to the last position in the children vector of its
@@ -454,6 +569,24 @@ impl Controls {
         }
     }
 
+    /// Move the Control with the given Id to the given position in the children vector of its
+    /// parent. If `index` is out of bounds, it is moved to the last position.
+    pub fn move_to_index(&mut self, id: Id, index: usize) {
+        if let Some(parent) = self.get(id).and_then(|x| x.parent) {
+            let children = &mut self
+                .get_mut(parent)
+                .expect("Control's parent is unintialized")
+                .children;
+            let i = children.iter().position(|x| *x == id).unwrap();
+            let index = index.min(children.len() - 1);
+            if i < index {
+                children[i..=index].rotate_left(1);
+            } else {
+                children[index..=i].rotate_right(1);
+            }
+        }
+    }
+
     pub fn is_child(&mut self, parent: Id, child: Id) -> bool {
         Some(parent) == self.get(child).and_then(|x| x.parent)
     }
@@ -469,6 +602,18 @@ impl Controls {
         false
     }
 
+    /// Return true if the control and all of its ancestors are visible.
+    pub fn is_really_visible(&self, id: Id) -> bool {
+        let mut curr = Some(id);
+        while let Some(id) = curr {
+            match self.get(id) {
+                Some(control) if control.visible => curr = control.parent,
+                _ => return false,
+            }
+        }
+        true
+    }
+
     pub fn get_all_children(&self, id: Id) -> Option<&[Id]> {
         Some(&self.get(id)?.children)
     }
@@ -497,6 +642,34 @@ impl Controls {
         Some(stack)
     }
 
+    /// Return a readable path from the root to the given control. Each control in the path is
+    /// written as its name, or as its index in its parent, if it has no name.
+    pub fn debug_path(&self, id: Id) -> String {
+        let mut segments = Vec::new();
+        let mut curr = id;
+        while let Some(control) = self.get(curr) {
+            let segment = match (&control.name, control.parent) {
+                (Some(name), _) => name.clone(),
+                (None, None) => "root".to_owned(),
+                (None, Some(parent)) => self
+                    .get(parent)
+                    .and_then(|x| x.children.iter().position(|&x| x == curr))
+                    .map_or_else(|| curr.to_string(), |i| format!("[{}]", i)),
+            };
+            segments.push(segment);
+            match control.parent {
+                Some(parent) => curr = parent,
+                None => break,
+            }
+        }
+        if segments.is_empty() {
+            // the control is not alive
+            return id.to_string();
+        }
+        segments.reverse();
+        segments.join("/")
+    }
+
     /// Return the id of the lowest common ancestor of both controls. This is used to only update
     /// the focus flag of the controls that changed.
     pub fn lowest_common_ancestor(&self, a: Id, b: Id) -> Option<Id> {
@@ -555,6 +728,13 @@ pub struct Control {
     pub(crate) generation: NonZeroU32,
     pub(crate) rect: Rect,
     pub(crate) graphic: Graphic,
+    pub(crate) shadow: Option<Shadow>,
+    pub(crate) opacity: f32,
+    /// If false, the control and its descendants are laid out, but not rendered, and don't
+    /// receive input.
+    pub(crate) visible: bool,
+    /// The cursor shown while the mouse is over this control, or over a descendant without one.
+    pub(crate) cursor: Option<CursorIcon>,
     pub(crate) behaviour: Option<Box<dyn Behaviour>>,
     // Every control has a layout. This is a Option only to allow to temporarily take owership of it.
     pub(crate) layout: Option<Box<dyn Layout>>,
@@ -562,6 +742,12 @@ pub struct Control {
     pub(crate) children: Vec<Id>,
     pub(crate) active: bool,
     pub(crate) focus: bool,
+    /// If Tab navigation inside of this control is kept inside of it.
+    pub(crate) focus_scope: bool,
+    /// The key of the style of the current theme that is applied to this control.
+    pub(crate) style_key: Option<String>,
+    /// A name for identifying this control in logs.
+    pub(crate) name: Option<String>,
     pub(crate) really_active: bool,
 }
 impl Control {
@@ -570,11 +756,18 @@ impl Control {
             generation,
             rect: Default::default(),
             graphic: Default::default(),
+            shadow: None,
+            opacity: 1.0,
+            visible: true,
+            cursor: None,
             behaviour: Default::default(),
             layout: Some(Box::new(())),
             parent: Default::default(),
             children: Default::default(),
             focus: Default::default(),
+            focus_scope: false,
+            style_key: None,
+            name: None,
             active: Default::default(),
             really_active: Default::default(),
         }
@@ -0,0 +1,246 @@
+//! Easing functions, to be used with [`Animation`]s.
+//!
+//! Each function maps the linear progress `t` of a animation, from 0.0 to 1.0, to a eased
+//! progress, that starts at 0.0 and ends at 1.0, but may go outside this range in between (like
+//! the back and elastic functions). Use [`EasedAnimation`] to apply a easing to a animation.
+
+use crate::{Animation, Context};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn end_points() {
+        let easings: &[fn(f32) -> f32] = &[
+            linear,
+            ease_in,
+            ease_out,
+            ease_in_out,
+            back_in,
+            back_out,
+            back_in_out,
+            elastic_in,
+            elastic_out,
+        ];
+        for easing in easings {
+            assert!(easing(0.0).abs() < 1e-5);
+            assert!((easing(1.0) - 1.0).abs() < 1e-5);
+        }
+        let bezier = CubicBezier::EASE;
+        assert!(bezier.ease(0.0).abs() < 1e-5);
+        assert!((bezier.ease(1.0) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cubic_bezier() {
+        let linear = CubicBezier::new(0.25, 0.25, 0.75, 0.75);
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert!((linear.ease(t) - t).abs() < 1e-4);
+        }
+        // symmetric curve
+        let ease_in_out = CubicBezier::EASE_IN_OUT;
+        assert!((ease_in_out.ease(0.5) - 0.5).abs() < 1e-4);
+        assert!((ease_in_out.ease(0.2) + ease_in_out.ease(0.8) - 1.0).abs() < 1e-4);
+        assert!(ease_in_out.ease(0.2) < 0.2);
+    }
+}
+
+/// A easing curve. Implemented for any `Fn(f32) -> f32`, like the functions of this module, and
+/// for [`CubicBezier`].
+pub trait Easing {
+    /// Map the linear progress `t`, from 0.0 to 1.0, to the eased progress.
+    fn ease(&self, t: f32) -> f32;
+}
+impl<F: Fn(f32) -> f32> Easing for F {
+    fn ease(&self, t: f32) -> f32 {
+        (self)(t)
+    }
+}
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+/// Starts slow, and accelerates until the end.
+pub fn ease_in(t: f32) -> f32 {
+    t * t
+}
+
+/// Starts fast, and decelerates until the end.
+pub fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+/// Accelerates until the middle, and decelerates until the end.
+pub fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+    }
+}
+
+/// How much the back functions overshoot.
+const BACK: f32 = 1.70158;
+/// The overshoot of [`back_in_out`], that is bigger because each half has half of the duration.
+const BACK_IN_OUT: f32 = BACK * 1.525;
+
+/// Moves slightly backwards, before accelerating to the end.
+pub fn back_in(t: f32) -> f32 {
+    (BACK + 1.0) * t * t * t - BACK * t * t
+}
+
+/// Overshoots the end, and then comes back.
+pub fn back_out(t: f32) -> f32 {
+    1.0 - back_in(1.0 - t)
+}
+
+/// Moves slightly backwards at the start, and overshoots the end.
+pub fn back_in_out(t: f32) -> f32 {
+    let half = |t: f32| (BACK_IN_OUT + 1.0) * t * t * t - BACK_IN_OUT * t * t;
+    if t < 0.5 {
+        half(2.0 * t) / 2.0
+    } else {
+        1.0 - half(2.0 - 2.0 * t) / 2.0
+    }
+}
+
+/// Oscillates with growing amplitude, before snapping to the end.
+pub fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else {
+        let period = 2.0 * std::f32::consts::PI / 3.0;
+        -(2.0f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * period).sin()
+    }
+}
+
+/// Overshoots the end, and oscillates around it with decaying amplitude.
+pub fn elastic_out(t: f32) -> f32 {
+    1.0 - elastic_in(1.0 - t)
+}
+
+/// A easing curve defined by a cubic bézier, with the end points fixed at (0, 0) and (1, 1), the
+/// same as the `cubic-bezier` function of CSS.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CubicBezier {
+    /// The first control point.
+    pub p1: [f32; 2],
+    /// The second control point.
+    pub p2: [f32; 2],
+}
+impl CubicBezier {
+    pub const EASE: CubicBezier = CubicBezier::new(0.25, 0.1, 0.25, 1.0);
+    pub const EASE_IN: CubicBezier = CubicBezier::new(0.42, 0.0, 1.0, 1.0);
+    pub const EASE_OUT: CubicBezier = CubicBezier::new(0.0, 0.0, 0.58, 1.0);
+    pub const EASE_IN_OUT: CubicBezier = CubicBezier::new(0.42, 0.0, 0.58, 1.0);
+
+    /// Create a curve with the control points `(x1, y1)` and `(x2, y2)`. `x1` and `x2` must be in
+    /// the range 0.0 to 1.0, otherwise the curve is not a function of `t`.
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self {
+            p1: [x1, y1],
+            p2: [x2, y2],
+        }
+    }
+
+    /// The coordinate of the given axis of the curve, at the curve parameter `s`.
+    fn sample(&self, axis: usize, s: f32) -> f32 {
+        let c = 3.0 * self.p1[axis];
+        let b = 3.0 * (self.p2[axis] - self.p1[axis]) - c;
+        let a = 1.0 - c - b;
+        ((a * s + b) * s + c) * s
+    }
+
+    /// The derivative of the x coordinate of the curve, at the curve parameter `s`.
+    fn sample_dx(&self, s: f32) -> f32 {
+        let c = 3.0 * self.p1[0];
+        let b = 3.0 * (self.p2[0] - self.p1[0]) - c;
+        let a = 1.0 - c - b;
+        (3.0 * a * s + 2.0 * b) * s + c
+    }
+
+    /// Find the curve parameter where the x coordinate of the curve is `x`.
+    fn solve_x(&self, x: f32) -> f32 {
+        const EPSILON: f32 = 1e-6;
+
+        // try newton's method first, because it converges fast
+        let mut s = x;
+        for _ in 0..8 {
+            let error = self.sample(0, s) - x;
+            if error.abs() < EPSILON {
+                return s;
+            }
+            let dx = self.sample_dx(s);
+            if dx.abs() < EPSILON {
+                break;
+            }
+            s -= error / dx;
+        }
+
+        // fallback to bisection, that always converges, because x(s) is monotonic
+        let (mut low, mut high) = (0.0, 1.0);
+        s = x;
+        while low < high {
+            let sample = self.sample(0, s);
+            if (sample - x).abs() < EPSILON {
+                break;
+            }
+            if x > sample {
+                low = s;
+            } else {
+                high = s;
+            }
+            let next = (low + high) / 2.0;
+            if next == s {
+                break;
+            }
+            s = next;
+        }
+        s
+    }
+}
+impl Easing for CubicBezier {
+    fn ease(&self, t: f32) -> f32 {
+        if t <= 0.0 {
+            0.0
+        } else if t >= 1.0 {
+            1.0
+        } else {
+            self.sample(1, self.solve_x(t))
+        }
+    }
+}
+
+/// A [`Animation`] that applies a [`Easing`] to the `t` of the inner animation.
+///
+/// The inner animation receives the eased `t`, and `dt` is the variation of the eased `t`. As
+/// the `t` of a Animation is always exactly 1.0 in the last call, the inner animation also
+/// receives exactly 1.0 in its last call, independent of the easing.
+pub struct EasedAnimation<A: Animation, E: Easing> {
+    animation: A,
+    easing: E,
+    /// The eased `t` of the last update.
+    last_t: f32,
+}
+impl<A: Animation, E: Easing> EasedAnimation<A, E> {
+    pub fn new(easing: E, animation: A) -> Self {
+        Self {
+            animation,
+            easing,
+            last_t: 0.0,
+        }
+    }
+}
+impl<A: Animation, E: Easing> Animation for EasedAnimation<A, E> {
+    fn on_update(&mut self, t: f32, _: f32, length: f32, ctx: &mut Context) {
+        let t = if t >= 1.0 { 1.0 } else { self.easing.ease(t) };
+        let dt = t - self.last_t;
+        self.last_t = t;
+        self.animation.on_update(t, dt, length, ctx);
+    }
+}
@@ -21,12 +21,35 @@ impl FontId {
     }
 }
 
+/// The parts of a bold or italic style that are synthesized, because the font has no face for
+/// them. See [`Fonts::resolve_style`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Synthesis {
+    /// The glyphs are drawn twice, side by side, to make them thicker.
+    pub bold: bool,
+    /// The glyphs are sheared, to slant them to the right.
+    pub italic: bool,
+}
+impl Synthesis {
+    /// The horizontal shear applied to synthetic italic glyphs, as the tangent of the slant
+    /// angle (about 12 degrees).
+    pub const ITALIC_SHEAR: f32 = 0.2;
+
+    /// The distance between the two draws of a synthetic bold glyph, and the increase in its
+    /// advance, as a fraction of the font size.
+    pub const BOLD_OFFSET: f32 = 1.0 / 24.0;
+}
+
 pub struct Font {
     // TODO: keeping a FontVec and data is redundant.
     pub data: Vec<u8>,
     id: FontId,
     inner: FontVec,
     pub fallback: Option<FontId>,
+    /// The bold face of this font.
+    pub bold: Option<FontId>,
+    /// The italic face of this font.
+    pub italic: Option<FontId>,
 }
 impl Font {
     pub fn new(data: &[u8]) -> Self {
@@ -38,6 +61,8 @@ impl Font {
             },
             inner,
             fallback: None,
+            bold: None,
+            italic: None,
         }
     }
 
@@ -46,6 +71,18 @@ impl Font {
         self
     }
 
+    /// Set the font used for bold text. Otherwise, bold is synthesized.
+    pub fn with_bold(mut self, bold: FontId) -> Self {
+        self.bold = Some(bold);
+        self
+    }
+
+    /// Set the font used for italic text. Otherwise, italic is synthesized.
+    pub fn with_italic(mut self, italic: FontId) -> Self {
+        self.italic = Some(italic);
+        self
+    }
+
     pub fn id(&self) -> FontId {
         self.id
     }
@@ -123,6 +160,9 @@ impl AbFont for Font {
 
 pub struct Fonts {
     fonts: Vec<Font>,
+    /// Incremented each time the fonts change. Text layouts shaped with a older revision are
+    /// shaped again.
+    revision: u64,
 }
 
 impl Default for Fonts {
@@ -132,7 +172,10 @@ impl Default for Fonts {
 }
 impl Fonts {
     pub fn new() -> Self {
-        Self { fonts: Vec::new() }
+        Self {
+            fonts: Vec::new(),
+            revision: 0,
+        }
     }
 
     pub fn add(&mut self, mut font: Font) -> FontId {
@@ -141,13 +184,49 @@ impl Fonts {
         };
         font.id = id;
         self.fonts.push(font);
+        self.revision += 1;
         id
     }
 
+    /// The current revision of the fonts. It changes each time a font is added, or
+    /// [`Fonts::invalidate`] is called.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Invalidate the text layouts shaped with the current fonts, so they are shaped again the
+    /// next time they are used.
+    pub fn invalidate(&mut self) {
+        self.revision += 1;
+    }
+
     pub fn get(&self, id: FontId) -> Option<&Font> {
         self.fonts.get(id.index())
     }
 
+    /// Find the face of the given font to use for a bold and/or italic text, and which of the
+    /// styles must be synthesized, because the font has no face registered for them.
+    ///
+    /// The italic face is looked up in the bold face, so a bold italic face can be registered
+    /// as the italic face of the bold one.
+    pub fn resolve_style(&self, id: FontId, bold: bool, italic: bool) -> (FontId, Synthesis) {
+        let mut id = id;
+        let mut synthesis = Synthesis::default();
+        if bold {
+            match self.get(id).and_then(|x| x.bold) {
+                Some(bold) => id = bold,
+                None => synthesis.bold = true,
+            }
+        }
+        if italic {
+            match self.get(id).and_then(|x| x.italic) {
+                Some(italic) => id = italic,
+                None => synthesis.italic = true,
+            }
+        }
+        (id, synthesis)
+    }
+
     pub fn as_slice(&self) -> &[Font] {
         &self.fonts
     }
@@ -1,13 +1,15 @@
 pub use crate::text::{Text, TextStyle};
 use crate::{font::Fonts, Color};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Sprite {
     pub texture: u32,
     pub color: Color,
     /// A rect, in the form \[x1, y1, x2, y2\].
     pub rect: [f32; 4],
     pub uv_rect: [f32; 4],
+    /// The rotation of the sprite around the center of its rect, in radians, clockwise.
+    pub rotation: f32,
 }
 
 #[derive(Clone, Debug)]
@@ -18,6 +20,10 @@ pub enum Graphic {
     Icon(Icon),
     AnimatedIcon(AnimatedIcon),
     Text(Text),
+    RoundRect(RoundRect),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    Shimmer(Shimmer),
     None,
 }
 impl Default for Graphic {
@@ -45,6 +51,26 @@ impl From<AnimatedIcon> for Graphic {
         Self::AnimatedIcon(v)
     }
 }
+impl From<RoundRect> for Graphic {
+    fn from(round_rect: RoundRect) -> Self {
+        Self::RoundRect(round_rect)
+    }
+}
+impl From<LinearGradient> for Graphic {
+    fn from(gradient: LinearGradient) -> Self {
+        Self::LinearGradient(gradient)
+    }
+}
+impl From<RadialGradient> for Graphic {
+    fn from(gradient: RadialGradient) -> Self {
+        Self::RadialGradient(gradient)
+    }
+}
+impl From<Shimmer> for Graphic {
+    fn from(shimmer: Shimmer) -> Self {
+        Self::Shimmer(shimmer)
+    }
+}
 impl From<Text> for Graphic {
     fn from(text: Text) -> Self {
         Self::Text(text)
@@ -77,6 +103,19 @@ impl Graphic {
                 uv_rects.swap(3, 5);
                 uv_rects.swap(6, 8);
             }
+            Graphic::RoundRect(RoundRect { radius, .. }) => {
+                radius.swap(0, 1);
+                radius.swap(2, 3);
+            }
+            Graphic::LinearGradient(LinearGradient { angle, .. }) => {
+                *angle = std::f32::consts::PI - *angle;
+            }
+            Graphic::RadialGradient(RadialGradient { center, .. }) => {
+                center[0] = 1.0 - center[0];
+            }
+            Graphic::Shimmer(Shimmer { reverse, .. }) => {
+                *reverse = !*reverse;
+            }
             Graphic::Text(_) => {}
             Graphic::None => {}
         }
@@ -108,6 +147,17 @@ impl Graphic {
                 uv_rects.swap(1, 7);
                 uv_rects.swap(2, 8);
             }
+            Graphic::RoundRect(RoundRect { radius, .. }) => {
+                radius.swap(0, 3);
+                radius.swap(1, 2);
+            }
+            Graphic::LinearGradient(LinearGradient { angle, .. }) => {
+                *angle = -*angle;
+            }
+            Graphic::RadialGradient(RadialGradient { center, .. }) => {
+                center[1] = 1.0 - center[1];
+            }
+            Graphic::Shimmer(_) => {}
             Graphic::Text(_) => {}
             Graphic::None => {}
         }
@@ -128,12 +178,27 @@ impl Graphic {
         self
     }
 
+    /// Set the rotation, in radians, of a Icon or Texture graphic. Does nothing for other
+    /// graphics.
+    pub fn set_rotation(&mut self, new_rotation: f32) {
+        match self {
+            Graphic::Texture(Texture { rotation, .. }) | Graphic::Icon(Icon { rotation, .. }) => {
+                *rotation = new_rotation;
+            }
+            _ => {}
+        }
+    }
+
     pub fn get_color(&self) -> Color {
         match self {
             Graphic::Panel(Panel { color, .. })
             | Graphic::Texture(Texture { color, .. })
             | Graphic::Icon(Icon { color, .. })
-            | Graphic::AnimatedIcon(AnimatedIcon { color, .. }) => *color,
+            | Graphic::AnimatedIcon(AnimatedIcon { color, .. })
+            | Graphic::RoundRect(RoundRect { color, .. })
+            | Graphic::LinearGradient(LinearGradient { color, .. })
+            | Graphic::RadialGradient(RadialGradient { color, .. })
+            | Graphic::Shimmer(Shimmer { color, .. }) => *color,
             Graphic::Text(x) => x.color(),
             Graphic::None => [255, 255, 255, 255].into(),
         }
@@ -152,6 +217,18 @@ impl Graphic {
             })
             | Graphic::AnimatedIcon(AnimatedIcon {
                 color, color_dirty, ..
+            })
+            | Graphic::RoundRect(RoundRect {
+                color, color_dirty, ..
+            })
+            | Graphic::LinearGradient(LinearGradient {
+                color, color_dirty, ..
+            })
+            | Graphic::RadialGradient(RadialGradient {
+                color, color_dirty, ..
+            })
+            | Graphic::Shimmer(Shimmer {
+                color, color_dirty, ..
             }) => {
                 *color = new_color;
                 *color_dirty = true;
@@ -174,10 +251,24 @@ impl Graphic {
             })
             | Graphic::AnimatedIcon(AnimatedIcon {
                 color, color_dirty, ..
+            })
+            | Graphic::LinearGradient(LinearGradient {
+                color, color_dirty, ..
+            })
+            | Graphic::RadialGradient(RadialGradient {
+                color, color_dirty, ..
+            })
+            | Graphic::Shimmer(Shimmer {
+                color, color_dirty, ..
             }) => {
                 color.a = new_alpha;
                 *color_dirty = true;
             }
+            Graphic::RoundRect(x) => {
+                x.color.a = new_alpha;
+                x.border_color.a = new_alpha;
+                x.color_dirty = true;
+            }
             Graphic::Text(x) => x.color_mut().a = new_alpha,
             Graphic::None => {}
         }
@@ -188,13 +279,47 @@ impl Graphic {
         self
     }
 
+    /// Return true if both graphics are a Panel, Texture, Icon or RoundRect that only differ by
+    /// their color, so that one can be turned into the other by [`Graphic::set_color`].
+    pub fn same_shape(&self, other: &Graphic) -> bool {
+        match (self, other) {
+            (Graphic::Panel(a), Graphic::Panel(b)) => {
+                a.texture == b.texture && a.uv_rects == b.uv_rects && a.border == b.border
+            }
+            (Graphic::Texture(a), Graphic::Texture(b)) => {
+                a.texture == b.texture && a.uv_rect == b.uv_rect && a.rotation == b.rotation
+            }
+            (Graphic::Icon(a), Graphic::Icon(b)) => {
+                a.texture == b.texture
+                    && a.uv_rect == b.uv_rect
+                    && a.size == b.size
+                    && a.rotation == b.rotation
+            }
+            (Graphic::RoundRect(a), Graphic::RoundRect(b)) => {
+                a.radius == b.radius
+                    && a.border_color == b.border_color
+                    && a.border_width == b.border_width
+            }
+            _ => false,
+        }
+    }
+
     pub fn need_rebuild(&self) -> bool {
         match self {
             Graphic::Panel(_) => false,
             Graphic::Texture(_) => false,
             Graphic::Icon(_) => false,
             Graphic::AnimatedIcon(_) => true,
-            Graphic::Text(Text { text_dirty, .. }) => *text_dirty,
+            // the sprites have different colors, so they can't be recolored.
+            Graphic::RoundRect(RoundRect { color_dirty, .. })
+            | Graphic::LinearGradient(LinearGradient { color_dirty, .. })
+            | Graphic::RadialGradient(RadialGradient { color_dirty, .. })
+            | Graphic::Shimmer(Shimmer { color_dirty, .. }) => *color_dirty,
+            Graphic::Text(Text {
+                text_dirty,
+                rects_dirty,
+                ..
+            }) => *text_dirty || *rects_dirty,
             Graphic::None => false,
         }
     }
@@ -205,6 +330,10 @@ impl Graphic {
             | Graphic::Texture(Texture { color_dirty, .. })
             | Graphic::Icon(Icon { color_dirty, .. })
             | Graphic::AnimatedIcon(AnimatedIcon { color_dirty, .. })
+            | Graphic::RoundRect(RoundRect { color_dirty, .. })
+            | Graphic::LinearGradient(LinearGradient { color_dirty, .. })
+            | Graphic::RadialGradient(RadialGradient { color_dirty, .. })
+            | Graphic::Shimmer(Shimmer { color_dirty, .. })
             | Graphic::Text(Text { color_dirty, .. }) => *color_dirty,
             Graphic::None => false,
         }
@@ -216,6 +345,10 @@ impl Graphic {
             Graphic::Texture(Texture { color_dirty, .. }) => *color_dirty = false,
             Graphic::Icon(Icon { color_dirty, .. }) => *color_dirty = false,
             Graphic::AnimatedIcon(AnimatedIcon { color_dirty, .. }) => *color_dirty = false,
+            Graphic::RoundRect(RoundRect { color_dirty, .. }) => *color_dirty = false,
+            Graphic::LinearGradient(LinearGradient { color_dirty, .. }) => *color_dirty = false,
+            Graphic::RadialGradient(RadialGradient { color_dirty, .. }) => *color_dirty = false,
+            Graphic::Shimmer(Shimmer { color_dirty, .. }) => *color_dirty = false,
             Graphic::Text(Text {
                 color_dirty,
                 text_dirty,
@@ -241,6 +374,9 @@ impl Graphic {
             Graphic::Panel(panel) => panel.min_size(),
             Graphic::AnimatedIcon(icon) => icon.size,
             Graphic::Texture(..) => [0.0; 2],
+            Graphic::RoundRect(..) => [0.0; 2],
+            Graphic::LinearGradient(..) | Graphic::RadialGradient(..) => [0.0; 2],
+            Graphic::Shimmer(..) => [0.0; 2],
             Graphic::None => return None,
         })
     }
@@ -268,6 +404,8 @@ pub struct Icon {
     pub color: Color,
     /// If the color have change since the last render.
     pub color_dirty: bool,
+    /// The rotation of the icon around its center, in radians, clockwise.
+    pub rotation: f32,
 }
 impl Icon {
     pub fn new(texture: u32, uv_rect: [f32; 4], size: [f32; 2]) -> Self {
@@ -277,9 +415,15 @@ impl Icon {
             size,
             color: [255, 255, 255, 255].into(),
             color_dirty: true,
+            rotation: 0.0,
         }
     }
 
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
     pub fn with_color(mut self, color: Color) -> Self {
         self.set_color(color);
         self
@@ -302,6 +446,7 @@ impl Icon {
             color: self.color,
             rect: [x, y, x + w, y + h],
             uv_rect: self.uv_rect,
+            rotation: self.rotation,
         }
     }
 }
@@ -351,6 +496,7 @@ impl AnimatedIcon {
             color: self.color,
             rect: [x, y, x + w, y + h],
             uv_rect: self.frames[(self.curr_time * self.fps) as usize],
+            rotation: 0.0,
         };
 
         self.curr_time = (self.curr_time + dt) % (self.frames.len() as f32 / self.fps);
@@ -371,10 +517,14 @@ pub struct Texture {
     pub color: Color,
     /// If the color have change since the last render.
     pub color_dirty: bool,
+    /// The rotation of the texture around the center of the control, in radians, clockwise.
+    pub rotation: f32,
 }
 impl Clone for Texture {
     fn clone(&self) -> Self {
-        Self::new(self.texture, self.uv_rect).with_color(self.color)
+        Self::new(self.texture, self.uv_rect)
+            .with_color(self.color)
+            .with_rotation(self.rotation)
     }
 }
 impl Texture {
@@ -386,6 +536,7 @@ impl Texture {
             uv_rect,
             color: [255, 255, 255, 255].into(),
             color_dirty: true,
+            rotation: 0.0,
         }
     }
 
@@ -395,9 +546,15 @@ impl Texture {
             color: self.color,
             rect,
             uv_rect: self.uv_rect,
+            rotation: self.rotation,
         }
     }
 
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
     pub fn with_color(mut self, color: Color) -> Self {
         self.set_color(color);
         self
@@ -418,29 +575,129 @@ pub struct Panel {
     pub color_dirty: bool,
 }
 impl Panel {
-    #[allow(clippy::many_single_char_names)]
+    /// Create a panel that slices `uv_rect`, in the form \[x, y, width, height\], in 9 equal
+    /// sized parts. `border` is the size, in pixels, of the borders of the panel on the screen, in
+    /// the form \[left, top, right, bottom\].
     pub fn new(texture: u32, uv_rect: [f32; 4], border: [f32; 4]) -> Self {
-        // divide the given uv_rect in 9 equal sized ones
-        let w = uv_rect[2];
-        let h = uv_rect[3];
+        // the uv_rect may be flipped, but the insets are always positive.
+        let w = uv_rect[2].abs() / 3.0;
+        let h = uv_rect[3].abs() / 3.0;
+        let mut panel = Self {
+            texture,
+            uv_rects: [[0.0; 4]; 9],
+            border: [0.0; 4],
+            color: [255, 255, 255, 255].into(),
+            color_dirty: true,
+        };
+        panel.set_uv(uv_rect, [w, h, w, h]);
+        panel.set_border(border);
+        panel
+    }
 
-        let x = [uv_rect[0], uv_rect[0] + w / 3.0, uv_rect[0] + w * 2.0 / 3.0];
-        let y = [uv_rect[1], uv_rect[1] + h / 3.0, uv_rect[1] + h * 2.0 / 3.0];
+    /// The size, in pixels, of the borders of the panel on the screen, in the form \[left, top,
+    /// right, bottom\].
+    pub fn border(&self) -> [f32; 4] {
+        self.border
+    }
 
-        let mut uv_rects = [[0.0; 4]; 9];
-        for (i, uv_rect) in uv_rects.iter_mut().enumerate() {
-            let n = i % 3;
-            let m = i / 3;
-            *uv_rect = [x[n], y[m], w / 3.0, h / 3.0];
+    /// Set the size of the borders of the panel. Negative values are clamped to zero.
+    ///
+    /// The border changes the min size of the panel, so the layout of the control must be
+    /// dirtied after calling this.
+    pub fn set_border(&mut self, border: [f32; 4]) {
+        if border.iter().any(|&x| x < 0.0) {
+            log::warn!("panel border {:?} has negative values", border);
         }
+        self.border = [
+            border[0].max(0.0),
+            border[1].max(0.0),
+            border[2].max(0.0),
+            border[3].max(0.0),
+        ];
+        self.color_dirty = true;
+    }
 
-        Self {
-            texture,
-            uv_rects,
-            border,
-            color: [255, 255, 255, 255].into(),
-            color_dirty: true,
+    /// The rect of the texture that is sliced by the panel, in the form \[x, y, width, height\].
+    pub fn uv_rect(&self) -> [f32; 4] {
+        let [x, y, _, _] = self.uv_rects[0];
+        let [x2, _, w2, _] = self.uv_rects[2];
+        let [_, y2, _, h2] = self.uv_rects[6];
+        [x, y, x2 + w2 - x, y2 + h2 - y]
+    }
+
+    /// The size of the corner slices of the panel, in texture coordinates, in the form \[left,
+    /// top, right, bottom\].
+    pub fn uv_insets(&self) -> [f32; 4] {
+        // the slices have negative sizes when the panel is flipped.
+        [
+            self.uv_rects[0][2].abs(),
+            self.uv_rects[0][3].abs(),
+            self.uv_rects[8][2].abs(),
+            self.uv_rects[8][3].abs(),
+        ]
+    }
+
+    /// Change the rect of the texture that is sliced by the panel, keeping its current insets.
+    pub fn set_uv_rect(&mut self, uv_rect: [f32; 4]) {
+        let insets = self.uv_insets();
+        self.set_uv(uv_rect, insets);
+    }
+
+    /// Change the size of the corner slices of the panel, keeping its current uv_rect.
+    pub fn set_uv_insets(&mut self, insets: [f32; 4]) {
+        let uv_rect = self.uv_rect();
+        self.set_uv(uv_rect, insets);
+    }
+
+    /// Slice `uv_rect`, in the form \[x, y, width, height\], in 9 parts, where the corners have
+    /// the size given by `insets`, in the form \[left, top, right, bottom\].
+    ///
+    /// If the insets of a axis don't fit in the uv_rect, they are scaled down until they fit.
+    #[allow(clippy::many_single_char_names)]
+    pub fn set_uv(&mut self, uv_rect: [f32; 4], insets: [f32; 4]) {
+        let mut insets = insets;
+        if insets.iter().any(|&x| x < 0.0) {
+            log::warn!("panel insets {:?} have negative values", insets);
+            for x in insets.iter_mut() {
+                *x = x.max(0.0);
+            }
+        }
+        // the uv_rect may be flipped, with a negative size.
+        for (i, size) in [uv_rect[2].abs(), uv_rect[3].abs()].iter().enumerate() {
+            let sum = insets[i] + insets[i + 2];
+            if sum > *size {
+                log::warn!(
+                    "panel insets {:?} exceed the size of the uv_rect {:?}",
+                    insets,
+                    uv_rect
+                );
+                let scale = if sum > 0.0 { size / sum } else { 0.0 };
+                insets[i] *= scale;
+                insets[i + 2] *= scale;
+            }
         }
+
+        let sx = uv_rect[2].signum();
+        let sy = uv_rect[3].signum();
+        let w = [
+            insets[0] * sx,
+            uv_rect[2] - (insets[0] + insets[2]) * sx,
+            insets[2] * sx,
+        ];
+        let h = [
+            insets[1] * sy,
+            uv_rect[3] - (insets[1] + insets[3]) * sy,
+            insets[3] * sy,
+        ];
+        let x = [uv_rect[0], uv_rect[0] + w[0], uv_rect[0] + w[0] + w[1]];
+        let y = [uv_rect[1], uv_rect[1] + h[0], uv_rect[1] + h[0] + h[1]];
+
+        for (i, uv_rect) in self.uv_rects.iter_mut().enumerate() {
+            let n = i % 3;
+            let m = i / 3;
+            *uv_rect = [x[n], y[m], w[n], h[m]];
+        }
+        self.color_dirty = true;
     }
 
     /// The min size of a panel is the smallest size where it borders don't suffer scaling.
@@ -484,8 +741,498 @@ impl Panel {
                 color: self.color,
                 rect: [x, y, x + w, y + h],
                 uv_rect: self.uv_rects[i],
+                rotation: 0.0,
             })
         }
         sprites
     }
 }
+
+/// A rectangle with rounded corners and a optional border, that is rendered without a texture.
+#[derive(Clone, Debug)]
+pub struct RoundRect {
+    /// The color of the inside of the rectangle.
+    pub color: Color,
+    /// The radius of each corner, in the order top-left, top-right, bottom-right and bottom-left.
+    ///
+    /// Each radius is clamped to half of the smallest side of the rectangle.
+    pub radius: [f32; 4],
+    pub border_color: Color,
+    /// The width of the border. Zero means no border.
+    pub border_width: f32,
+    /// If the color have change since the last render.
+    pub color_dirty: bool,
+}
+impl RoundRect {
+    /// Create a RoundRect with the given color, and with the same radius in all corners.
+    pub fn new(color: Color, radius: f32) -> Self {
+        Self {
+            color,
+            radius: [radius; 4],
+            border_color: color,
+            border_width: 0.0,
+            color_dirty: true,
+        }
+    }
+
+    /// Set the radius of each corner, in the order top-left, top-right, bottom-right and
+    /// bottom-left.
+    pub fn with_radii(mut self, radius: [f32; 4]) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_border(mut self, border_color: Color, border_width: f32) -> Self {
+        self.border_color = border_color;
+        self.border_width = border_width;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.color_dirty = true;
+    }
+
+    /// The horizontal span of a rounded rectangle at the height `y`.
+    fn span(rect: [f32; 4], radius: [f32; 4], y: f32) -> Option<[f32; 2]> {
+        if y < rect[1] || y > rect[3] || rect[0] >= rect[2] {
+            return None;
+        }
+        // how much a corner with radius r cuts from the side, at the distance dy from its center
+        let inset = |r: f32, dy: f32| {
+            if dy > 0.0 {
+                r - (r * r - dy * dy).max(0.0).sqrt()
+            } else {
+                0.0
+            }
+        };
+        let top = |r: f32| rect[1] + r - y;
+        let bottom = |r: f32| y - (rect[3] - r);
+        let left = inset(radius[0], top(radius[0])).max(inset(radius[3], bottom(radius[3])));
+        let right = inset(radius[1], top(radius[1])).max(inset(radius[2], bottom(radius[2])));
+        Some([rect[0] + left, rect[2] - right])
+    }
+
+    /// Build the rounded rectangle as horizontal strips of one pixel height, that are merged
+    /// when consecutive strips are equal. `texture` must be a white texture, and `rect` and the
+    /// pixels are in physical size, so the radius and border are multiplied by `scale_factor`.
+    pub fn get_sprites(&self, rect: [f32; 4], scale_factor: f32, texture: u32) -> Vec<Sprite> {
+        let width = (rect[2] - rect[0]).max(0.0);
+        let height = (rect[3] - rect[1]).max(0.0);
+        let max_radius = width.min(height) / 2.0;
+        let radius = self
+            .radius
+            .map(|r| (r * scale_factor).max(0.0).min(max_radius));
+        let border = (self.border_width * scale_factor).max(0.0).min(max_radius);
+        let inner_rect = [
+            rect[0] + border,
+            rect[1] + border,
+            rect[2] - border,
+            rect[3] - border,
+        ];
+        let inner_radius = radius.map(|r| (r - border).max(0.0));
+
+        let mut sprites: Vec<Sprite> = Vec::new();
+        // the strips of the last row, and the index of its first sprite
+        let mut last_row: Vec<([f32; 2], Color)> = Vec::new();
+        let mut last_start = 0;
+        let mut row = Vec::with_capacity(3);
+        let mut y = rect[1];
+        while y < rect[3] {
+            let y2 = (y + 1.0).min(rect[3]);
+            let center = (y + y2) / 2.0;
+
+            row.clear();
+            if let Some(outer) = Self::span(rect, radius, center) {
+                let inner = if border > 0.0 {
+                    Self::span(inner_rect, inner_radius, center)
+                } else {
+                    Some(outer)
+                };
+                match inner {
+                    Some(inner) => {
+                        row.push(([outer[0], inner[0]], self.border_color));
+                        row.push(([inner[0], inner[1]], self.color));
+                        row.push(([inner[1], outer[1]], self.border_color));
+                    }
+                    None => row.push((outer, self.border_color)),
+                }
+                row.retain(|(x, color)| x[1] > x[0] && color.a != 0);
+            }
+
+            if row == last_row {
+                for sprite in &mut sprites[last_start..] {
+                    sprite.rect[3] = y2;
+                }
+            } else {
+                last_start = sprites.len();
+                sprites.extend(row.iter().map(|&(x, color)| Sprite {
+                    texture,
+                    color,
+                    rect: [x[0], y, x[1], y2],
+                    uv_rect: [0.0, 0.0, 1.0, 1.0],
+                    rotation: 0.0,
+                }));
+                std::mem::swap(&mut row, &mut last_row);
+            }
+            y = y2;
+        }
+        sprites
+    }
+}
+
+/// The color at the position `t` of a gradient with the given stops, sorted by position.
+fn gradient_color(stops: &[(f32, Color)], t: f32) -> Color {
+    match stops.iter().position(|x| x.0 > t) {
+        None => stops.last().map_or(Color::WHITE, |x| x.1),
+        Some(0) => stops[0].1,
+        Some(i) => {
+            let (t0, a) = stops[i - 1];
+            let (t1, b) = stops[i];
+            a.lerp(b, (t - t0) / (t1 - t0))
+        }
+    }
+}
+
+/// Build a gradient as a grid of cells of the given size, each one with the color at its center.
+/// Consecutive cells in a row with the same color are merged, and so are consecutive equal rows.
+fn gradient_sprites(
+    rect: [f32; 4],
+    cell_size: [f32; 2],
+    texture: u32,
+    color_at: impl Fn(f32, f32) -> Color,
+) -> Vec<Sprite> {
+    let mut sprites: Vec<Sprite> = Vec::new();
+    let mut last_row_start = 0;
+    let mut y = rect[1];
+    while y < rect[3] {
+        let y2 = (y + cell_size[1]).min(rect[3]);
+        let row_start = sprites.len();
+        let mut x = rect[0];
+        while x < rect[2] {
+            let x2 = (x + cell_size[0]).min(rect[2]);
+            let color = color_at((x + x2) / 2.0, (y + y2) / 2.0);
+            let merge =
+                sprites.len() > row_start && sprites.last().map_or(false, |x| x.color == color);
+            if merge {
+                sprites.last_mut().unwrap().rect[2] = x2;
+            } else {
+                sprites.push(Sprite {
+                    texture,
+                    color,
+                    rect: [x, y, x2, y2],
+                    uv_rect: [0.0, 0.0, 1.0, 1.0],
+                    rotation: 0.0,
+                });
+            }
+            x = x2;
+        }
+        let (last_row, row) = sprites.split_at_mut(row_start);
+        let last_row = &mut last_row[last_row_start..];
+        let equal = last_row.len() == row.len()
+            && last_row.iter().zip(row.iter()).all(|(a, b)| {
+                (a.color, [a.rect[0], a.rect[2]]) == (b.color, [b.rect[0], b.rect[2]])
+            });
+        if equal && !row.is_empty() {
+            for sprite in last_row {
+                sprite.rect[3] = y2;
+            }
+            sprites.truncate(row_start);
+        } else {
+            last_row_start = row_start;
+        }
+        y = y2;
+    }
+    sprites
+}
+
+/// A linear gradient, that fills the entire control.
+#[derive(Clone, Debug)]
+pub struct LinearGradient {
+    /// The positions, from 0.0 to 1.0, and colors of the gradient. Must be sorted by position.
+    pub stops: Vec<(f32, Color)>,
+    /// The direction of the gradient, in radians, clockwise. 0.0 goes from left to right, and
+    /// PI/2 goes from top to bottom.
+    pub angle: f32,
+    /// The color that the gradient is multiplied by.
+    pub color: Color,
+    /// If the color have change since the last render.
+    pub color_dirty: bool,
+}
+impl LinearGradient {
+    /// The size of each cell, in pixels, when the gradient is not aligned with a axis.
+    const CELL_SIZE: f32 = 4.0;
+
+    pub fn new(stops: Vec<(f32, Color)>, angle: f32) -> Self {
+        Self {
+            stops,
+            angle,
+            color: Color::WHITE,
+            color_dirty: true,
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.color_dirty = true;
+    }
+
+    /// Build the gradient as strips, or as a grid of small cells if the angle is not a multiple of
+    /// PI/2. `texture` must be a white texture.
+    pub fn get_sprites(&self, rect: [f32; 4], texture: u32) -> Vec<Sprite> {
+        let (sin, cos) = self.angle.sin_cos();
+        let center = [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+        // half of the length of the gradient, so that the corners are at 0.0 and 1.0
+        let half_length = ((rect[2] - rect[0]) * cos.abs() + (rect[3] - rect[1]) * sin.abs()) / 2.0;
+        let cell_size = if sin.abs() < 1e-4 {
+            [1.0, rect[3] - rect[1]]
+        } else if cos.abs() < 1e-4 {
+            [rect[2] - rect[0], 1.0]
+        } else {
+            [Self::CELL_SIZE; 2]
+        };
+        gradient_sprites(rect, cell_size, texture, |x, y| {
+            let dist = (x - center[0]) * cos + (y - center[1]) * sin;
+            let t = 0.5 + dist / (2.0 * half_length);
+            gradient_color(&self.stops, t).multiply(self.color)
+        })
+    }
+}
+
+/// A radial gradient, that fills the entire control.
+#[derive(Clone, Debug)]
+pub struct RadialGradient {
+    /// The positions, from 0.0 to 1.0, and colors of the gradient. Must be sorted by position.
+    pub stops: Vec<(f32, Color)>,
+    /// The center of the gradient, relative to the control: [0.0, 0.0] is the top-left corner,
+    /// and [1.0, 1.0] is the bottom-right corner.
+    pub center: [f32; 2],
+    /// The radius of the gradient, relative to the smallest side of the control.
+    pub radius: f32,
+    /// The color that the gradient is multiplied by.
+    pub color: Color,
+    /// If the color have change since the last render.
+    pub color_dirty: bool,
+}
+impl RadialGradient {
+    /// The size of each cell, in pixels.
+    const CELL_SIZE: f32 = 4.0;
+
+    pub fn new(stops: Vec<(f32, Color)>, center: [f32; 2], radius: f32) -> Self {
+        Self {
+            stops,
+            center,
+            radius,
+            color: Color::WHITE,
+            color_dirty: true,
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.color_dirty = true;
+    }
+
+    /// Build the gradient as a grid of small cells. `texture` must be a white texture.
+    pub fn get_sprites(&self, rect: [f32; 4], texture: u32) -> Vec<Sprite> {
+        let (width, height) = (rect[2] - rect[0], rect[3] - rect[1]);
+        let center = [
+            rect[0] + width * self.center[0],
+            rect[1] + height * self.center[1],
+        ];
+        let radius = self.radius * width.min(height);
+        gradient_sprites(rect, [Self::CELL_SIZE; 2], texture, |x, y| {
+            let t = (x - center[0]).hypot(y - center[1]) / radius;
+            gradient_color(&self.stops, t).multiply(self.color)
+        })
+    }
+}
+
+/// A placeholder for content that is still loading, also known as a skeleton: a rectangle of the
+/// base color, with a band of the highlight color sweeping over it, from left to right.
+///
+/// The sweep is driven by a [`ShimmerAnimation`](crate::ShimmerAnimation), that is added when a
+/// control with this graphic becomes active, and that finishes when the control is deactivated.
+#[derive(Debug)]
+pub struct Shimmer {
+    pub base: Color,
+    pub highlight: Color,
+    /// The number of sweeps per second.
+    pub speed: f32,
+    /// The width of the highlight band, relative to the width of the control.
+    pub band_width: f32,
+    /// The progress of the current sweep, from 0.0, when the band is just outside of the left
+    /// side, to 1.0, when the band is just outside of the right side.
+    pub phase: f32,
+    /// If true, the band sweeps from right to left.
+    pub reverse: bool,
+    /// The color that the graphic is multiplied by.
+    pub color: Color,
+    /// If the color, or the phase, have change since the last render.
+    pub color_dirty: bool,
+    /// If there is a ShimmerAnimation updating this graphic.
+    pub(crate) animated: bool,
+}
+impl Clone for Shimmer {
+    fn clone(&self) -> Self {
+        Self {
+            animated: false,
+            color_dirty: true,
+            ..*self
+        }
+    }
+}
+impl Shimmer {
+    pub fn new(base: Color, highlight: Color) -> Self {
+        Self {
+            base,
+            highlight,
+            speed: 0.8,
+            band_width: 0.5,
+            phase: 0.0,
+            reverse: false,
+            color: Color::WHITE,
+            color_dirty: true,
+            animated: false,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_band_width(mut self, band_width: f32) -> Self {
+        self.band_width = band_width;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.color_dirty = true;
+    }
+
+    pub fn set_colors(&mut self, base: Color, highlight: Color) {
+        self.base = base;
+        self.highlight = highlight;
+        self.color_dirty = true;
+    }
+
+    /// Advance the sweep by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        self.phase = (self.phase + dt * self.speed).rem_euclid(1.0);
+        self.color_dirty = true;
+    }
+
+    /// Build the graphic as vertical strips. `texture` must be a white texture.
+    pub fn get_sprites(&self, rect: [f32; 4], texture: u32) -> Vec<Sprite> {
+        let width = rect[2] - rect[0];
+        let half_band = self.band_width.max(0.0) * width / 2.0;
+        let phase = if self.reverse {
+            1.0 - self.phase
+        } else {
+            self.phase
+        };
+        let center = rect[0] - half_band + (width + 2.0 * half_band) * phase;
+        gradient_sprites(rect, [1.0, rect[3] - rect[1]], texture, |x, _| {
+            let t = if half_band > 0.0 {
+                (1.0 - (x - center).abs() / half_band).max(0.0)
+            } else {
+                0.0
+            };
+            // smoothstep, for a softer band
+            let t = t * t * (3.0 - 2.0 * t);
+            self.base.lerp(self.highlight, t).multiply(self.color)
+        })
+    }
+}
+
+/// A blurred shadow drawn behind the graphic of a control, with the shape of its rect.
+///
+/// It is set with [`ControlBuilder::shadow`](crate::ControlBuilder::shadow) or
+/// [`Context::set_shadow`](crate::Context::set_shadow). It is not clipped by the rect of the
+/// control, but is still clipped by the ancestors of the control.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Shadow {
+    /// The offset of the shadow relative to the rect of the control.
+    pub offset: [f32; 2],
+    /// The distance over which the shadow fades out. Half of it is inside of the rect.
+    pub blur: f32,
+    pub color: Color,
+}
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            offset: [0.0, 4.0],
+            blur: 8.0,
+            color: [0, 0, 0, 96].into(),
+        }
+    }
+}
+impl Shadow {
+    /// The size of each cell of the blurred border, in pixels.
+    const CELL_SIZE: f32 = 2.0;
+
+    pub fn new(offset: [f32; 2], blur: f32, color: Color) -> Self {
+        Self {
+            offset,
+            blur,
+            color,
+        }
+    }
+
+    /// Build the shadow as a grid of cells. `rect` is the rect of the control and the pixels are
+    /// in physical size, so the offset and blur are multiplied by `scale_factor`. `texture` must be
+    /// a white texture.
+    pub fn get_sprites(&self, rect: [f32; 4], scale_factor: f32, texture: u32) -> Vec<Sprite> {
+        let [dx, dy] = self.offset.map(|x| x * scale_factor);
+        let blur = (self.blur * scale_factor).max(0.0);
+        let half = blur / 2.0;
+        let rect = [
+            rect[0] + dx - half,
+            rect[1] + dy - half,
+            rect[2] + dx + half,
+            rect[3] + dy + half,
+        ];
+        if blur <= 0.0 {
+            return gradient_sprites(
+                rect,
+                [rect[2] - rect[0], rect[3] - rect[1]],
+                texture,
+                |_, _| self.color,
+            );
+        }
+        // how much the shadow covers at the distance `d` inside of a edge
+        let coverage = |d: f32| {
+            let t = (d / blur).max(0.0).min(1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        gradient_sprites(rect, [Self::CELL_SIZE; 2], texture, |x, y| {
+            let cov_x = coverage(x - rect[0]) * coverage(rect[2] - x);
+            let cov_y = coverage(y - rect[1]) * coverage(rect[3] - y);
+            let mut color = self.color;
+            color.a = (color.a as f32 * cov_x * cov_y).round() as u8;
+            color
+        })
+    }
+}
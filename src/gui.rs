@@ -3,6 +3,7 @@ use std::{
     collections::{HashMap, VecDeque},
     num::NonZeroU32,
     ops::{Deref, DerefMut},
+    path::Path,
     sync::atomic::{AtomicU32, AtomicU64, Ordering},
     time::Duration,
 };
@@ -10,29 +11,35 @@ use std::{
 use keyed_priority_queue::KeyedPriorityQueue;
 use winit::{
     dpi::LogicalPosition,
-    event::{ElementState, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent},
-    window::CursorIcon,
+    event::{ElementState, Ime, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent},
+    window::{CursorIcon, WindowId},
 };
 
 use crate::{
+    accessibility::{AccessValue, Role},
     context::{Context, LayoutContext, MinSizeContext, RenderContext},
     control::BuilderContext,
     font::Fonts,
     graphics::Graphic,
+    style::GuiTheme,
     time::Instant,
     util::WithPriority,
-    Control, ControlBuilder, ControlEntry, Controls, LayoutDirtyFlags, Rect,
+    Control, ControlBuilder, ControlEntry, Controls, LayoutDirtyFlags, Rect, RenderDirtyFlags,
 };
 
 pub type MouseId = u64;
 /// The default mouse Id for the default mouse.
 const MOUSE_ID: MouseId = 0;
+/// The mouse Id used for the clicks synthesized by [`Gui::gamepad_navigate`].
+const GAMEPAD_MOUSE_ID: MouseId = MouseId::MAX;
 const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
 
 #[cfg(test)]
 mod test;
 
 pub mod event {
+    use std::{any::Any, rc::Rc};
+
     use super::{Id, MouseId};
     pub struct SetLockOver {
         pub lock: bool,
@@ -41,19 +48,45 @@ pub mod event {
     pub struct RequestFocus {
         pub id: Id,
     }
+    pub struct LoseFocus {
+        pub id: Id,
+    }
+    pub struct PushModal {
+        pub id: Id,
+    }
+    pub struct PopModal;
     pub struct ActiveControl {
         pub id: Id,
     }
     pub struct DeactiveControl {
         pub id: Id,
     }
+    pub struct SetVisible {
+        pub id: Id,
+        pub visible: bool,
+    }
     pub struct RemoveControl {
         pub id: Id,
     }
     pub struct StartControl {
         pub id: Id,
     }
+    /// See [`Context::start_drag`](crate::Context::start_drag).
+    pub struct StartDrag {
+        pub mouse_id: MouseId,
+        pub source: Id,
+        pub payload: Box<dyn Any>,
+        pub image: Option<Id>,
+    }
     pub struct SetValue<T>(pub T);
+    /// Enable or disable a interactive widget, like a Button or a TextField. A disabled widget
+    /// ignores the input, and is not focusable, so it is also skipped by the Tab navigation.
+    pub struct SetEnabled(pub bool);
+    /// Replace the style of a widget, like a Button or a TextField. Sent by a
+    /// [`StyleSheet`](crate::style_loader::StyleSheet) when it is reloaded.
+    pub struct SetStyle<T>(pub Rc<T>);
+    /// See [`Context::set_theme`](crate::Context::set_theme).
+    pub struct SetTheme(pub String);
 
     pub struct ToggleChanged {
         pub id: Id,
@@ -96,6 +129,26 @@ enum LazyEvent {
     OnDeactive(Id),
 }
 
+/// A direction for spatial focus navigation. See [`Gui::focus_in_direction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A gamepad input used for navigating the Gui. See [`Gui::gamepad_navigate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadInput {
+    /// The d-pad, or a analog stick, moves the focus in the given direction.
+    Direction(FocusDirection),
+    /// The "A" button, clicks the focused control.
+    Accept,
+    /// The "B" button, goes back or cancels, like the Escape key.
+    Back,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MouseButton {
     Left,
@@ -153,6 +206,64 @@ pub struct MouseButtons {
     pub middle: ButtonState,
 }
 
+/// The phase of a scroll gesture. Only precise devices, like touchpads, report the start and the
+/// end of a gesture. The scroll of a mouse wheel is always `Moved`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScrollPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+/// The scroll event received by [`Behaviour::on_scroll_event`].
+#[derive(Clone, Copy, Debug)]
+pub struct ScrollInfo {
+    /// The scrolled distance, in logical pixels. Each line of a mouse wheel scrolls 100 pixels.
+    pub delta: [f32; 2],
+    /// If the scroll comes from a device that reports deltas in pixels, like a touchpad, instead
+    /// of discrete lines, like a mouse wheel. A precise scroll is already smooth, and the system
+    /// may keep sending deltas after the gesture ends, to emulate momentum, so widgets should not
+    /// add their own smoothing or momentum to it.
+    pub is_precise: bool,
+    pub phase: ScrollPhase,
+}
+
+fn mid_point([a, b]: [[f32; 2]; 2]) -> [f32; 2] {
+    [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0]
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GesturePhase {
+    Started,
+    Moved,
+    Ended,
+}
+
+/// A gesture made with two touches, received by [`Behaviour::on_gesture`]. All the changes are
+/// relative to the previous event of the gesture.
+#[derive(Clone, Copy, Debug)]
+pub struct GestureInfo {
+    pub phase: GesturePhase,
+    /// The point in the middle of the two touches, in pixels.
+    pub center: [f32; 2],
+    /// The ratio between the current distance of the touches and the previous one. Greater than
+    /// 1.0 when pinching out.
+    pub scale: f32,
+    /// The angle, in radians, that the line between the touches rotated, clockwise.
+    pub rotation: f32,
+    /// How much the center moved, in pixels.
+    pub pan: [f32; 2],
+}
+
+/// A gesture in progress. See [`Behaviour::on_gesture`].
+struct Gesture {
+    /// The control receiving the gesture.
+    target: Id,
+    /// The positions of the two touches in the last event.
+    positions: [[f32; 2]; 2],
+}
+
 #[derive(Clone, Debug)]
 pub struct MouseInfo {
     /// The unique id of this mouse.
@@ -193,6 +304,16 @@ impl MouseInfo {
         self.click_count > 0 && matches!(self.event, MouseEvent::Up(MouseButton::Left))
     }
 
+    /// Returns `true` if the event is a MouseEvent::Up(MouseButton::Right).
+    pub fn right_click(&self) -> bool {
+        matches!(self.event, MouseEvent::Up(MouseButton::Right))
+    }
+
+    /// Returns `true` if the event is a MouseEvent::Up(MouseButton::Middle).
+    pub fn middle_click(&self) -> bool {
+        matches!(self.event, MouseEvent::Up(MouseButton::Middle))
+    }
+
     /// Returns `self.is_dragging_x || self.is_dragging_y`.
     pub fn is_dragging(&self) -> bool {
         self.is_dragging_x || self.is_dragging_y
@@ -237,6 +358,72 @@ pub enum KeyboardEvent {
     Release(VirtualKeyCode),
 }
 
+/// The kind of text expected by a text field, used to choose the layout of a virtual keyboard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyboardKind {
+    Text,
+    Number,
+    Email,
+    Url,
+    Phone,
+}
+
+/// A request to show or hide the virtual keyboard of the platform, like the soft keyboard of a
+/// mobile device. Sent to the callback registered with
+/// [`Gui::set_virtual_keyboard_callback`], when a text field gains or loses focus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RequestVirtualKeyboard {
+    pub show: bool,
+    pub kind: KeyboardKind,
+}
+
+/// A event of a Input Method Editor (IME), used for composing text, like CJK characters, with
+/// multiple key presses. Received by the focused control, in [`Behaviour::on_ime_event`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// The text being composed, that should be displayed at the caret, but is not yet part of
+    /// the text. The range is the byte range of the composing cursor, if any. A empty string
+    /// means that the composition was cleared.
+    Preedit(String, Option<(usize, usize)>),
+    /// The composed text, that should be inserted at the caret, replacing the preedit text.
+    Commit(String),
+}
+
+/// A keyboard shortcut, like Ctrl+S. See [`Gui::add_accelerator`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Accelerator {
+    pub modifiers: ModifiersState,
+    pub key: VirtualKeyCode,
+}
+impl Accelerator {
+    pub fn new(modifiers: ModifiersState, key: VirtualKeyCode) -> Self {
+        Self { modifiers, key }
+    }
+}
+impl std::fmt::Display for Accelerator {
+    /// Format the accelerator like "Ctrl+Shift+S".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl() {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt() {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift() {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.logo() {
+            write!(f, "Logo+")?;
+        }
+        let key = format!("{:?}", self.key);
+        // the digit keys are named Key0, Key1, etc.
+        match key.strip_prefix("Key") {
+            Some(digit) if !digit.is_empty() => write!(f, "{}", digit),
+            _ => write!(f, "{}", key),
+        }
+    }
+}
+
 /// Store data related to mouse input.
 #[derive(Default)]
 pub(crate) struct MouseInput {
@@ -249,9 +436,12 @@ pub(crate) struct MouseInput {
     pub click_count: u8,
     /// used to check for double clicks.
     pub last_down: Option<Instant>,
-    /// The position of the mouse in the last down event with the MouseButton::Left, in the
-    /// current_mouse control. This becames None after a up mouse event.
+    /// The position of the mouse in the first down event since all buttons were released. This
+    /// becames None after the up event of the same button.
     down_position: Option<[f32; 2]>,
+    /// The button of the down event of `down_position`. Only controls with
+    /// [`InputFlags::DRAG`] and this [`Behaviour::drag_button`] can be dragged.
+    down_button: Option<MouseButton>,
     /// If the mouse is draggin in x direction.
     ///
     /// The mouse will be dragging if it goes down and start moving. It starts after moving a
@@ -292,6 +482,35 @@ impl MouseInput {
 
 type ScheduledEventTo = WithPriority<(Instant, u64), (Id, Box<dyn Any>)>;
 
+pub type IntervalId = u64;
+
+/// The scheduled event of a interval. Is not sent to the control, but instead dispatch the event
+/// created by the interval.
+struct IntervalTick(IntervalId);
+
+struct Interval {
+    /// The control that receives the events.
+    id: Id,
+    event_factory: Box<dyn FnMut() -> Box<dyn Any>>,
+    period: Duration,
+    /// The event_id of the next scheduled IntervalTick.
+    event_id: u64,
+}
+
+/// A drag-and-drop operation in progress. See [`Context::start_drag`].
+pub(crate) struct Drag {
+    /// The mouse that is dragging.
+    pub mouse_id: MouseId,
+    /// The control that started the drag.
+    pub source: Id,
+    pub payload: Box<dyn Any>,
+    /// The control that follows the mouse, if any, and its margins relative to the mouse
+    /// position.
+    pub image: Option<(Id, [f32; 4])>,
+    /// The control that accepted the payload in the last `on_drag_over`, if any.
+    pub target: Option<Id>,
+}
+
 pub(crate) struct MouseInputs {
     /// The number of inputs currently being used
     used_len: usize,
@@ -405,6 +624,9 @@ pub trait Animation {
     /// total duration of the animation in seconds. This method is first called with `t = 0.0`
     /// immediately upon addition and always ends with `t = 1.0` (unless cancelled), which can be
     /// used for initialization and finish. `dt` is the variation of `t` from the last call to now.
+    ///
+    /// To use a non-linear `t`, wrap the animation in a
+    /// [`EasedAnimation`](crate::easing::EasedAnimation).
     fn on_update(&mut self, t: f32, dt: f32, length: f32, ctx: &mut Context);
 }
 impl<F: FnMut(f32, f32, f32, &mut Context)> Animation for F {
@@ -413,6 +635,265 @@ impl<F: FnMut(f32, f32, f32, &mut Context)> Animation for F {
     }
 }
 
+/// A [`Animation`] that linearly changes the opacity of a control, and of its descendants, from
+/// `from` to `to`. See [`Context::set_opacity`].
+pub struct FadeAnimation {
+    pub id: Id,
+    pub from: f32,
+    pub to: f32,
+}
+impl Animation for FadeAnimation {
+    fn on_update(&mut self, t: f32, _: f32, _: f32, ctx: &mut Context) {
+        ctx.set_opacity(self.id, self.from + (self.to - self.from) * t);
+    }
+}
+
+/// A animation without a fixed length, like a physics simulation, that is updated every frame
+/// until it finishes.
+pub trait ContinuousAnimation {
+    /// Update the animation, and return false if it has finished.
+    ///
+    /// Is called every frame. `dt` is the time in seconds since the last call. This method is
+    /// first called with `dt = 0.0` immediately upon addition.
+    fn on_update(&mut self, dt: f32, ctx: &mut Context) -> bool;
+}
+
+/// A [`ContinuousAnimation`] that sweeps the band of the
+/// [`Shimmer`](crate::graphics::Shimmer) graphic of a control.
+///
+/// It is added automatically when a control with a Shimmer graphic becomes active, or when a
+/// Shimmer is set by [`Context::set_graphic`], and finishes when the control is deactivated,
+/// removed, or its graphic is no longer a Shimmer.
+pub struct ShimmerAnimation {
+    pub id: Id,
+}
+impl ContinuousAnimation for ShimmerAnimation {
+    fn on_update(&mut self, dt: f32, ctx: &mut Context) -> bool {
+        let control = match ctx.controls_mut().get_mut(self.id) {
+            Some(x) => x,
+            None => return false,
+        };
+        let really_active = control.really_active;
+        let shimmer = match &mut control.graphic {
+            Graphic::Shimmer(x) => x,
+            _ => return false,
+        };
+        if !really_active {
+            // it will be added again when the control is activated
+            shimmer.animated = false;
+            return false;
+        }
+        shimmer.advance(dt);
+        control.rect.dirty_render_dirty_flags();
+        true
+    }
+}
+
+/// A animation that is part of a [`AnimationSequence`] or [`AnimationGroup`].
+struct Track {
+    length: f32,
+    started: bool,
+    last_t: f32,
+    animation: Box<dyn Animation>,
+}
+impl Track {
+    fn new(length: f32, animation: Box<dyn Animation>) -> Self {
+        Self {
+            length: length.max(0.0),
+            started: false,
+            last_t: 0.0,
+            animation,
+        }
+    }
+
+    /// Update the animation to the given elapsed time, relative to its start, in seconds.
+    /// `scale` is the ratio between the real time and the time of the track.
+    fn update(&mut self, elapsed: f32, scale: f32, ctx: &mut Context) {
+        if elapsed < 0.0 || self.last_t >= 1.0 {
+            return;
+        }
+        let length = self.length * scale;
+        if !self.started {
+            self.started = true;
+            self.animation.on_update(0.0, 0.0, length, ctx);
+        }
+        let t = if self.length == 0.0 {
+            1.0
+        } else {
+            (elapsed / self.length).min(1.0)
+        };
+        if t > self.last_t {
+            self.animation.on_update(t, t - self.last_t, length, ctx);
+            self.last_t = t;
+        }
+    }
+}
+
+/// A [`Animation`] that plays a list of animations one after the other. Each animation starts
+/// when the previous one reaches `t == 1.0`.
+///
+/// The sequence is a single animation, so removing it cancels the whole chain. It should be added
+/// with [`AnimationSequence::length`] as its length. If added with a different length, the
+/// duration of each animation is scaled proportionally.
+#[derive(Default)]
+pub struct AnimationSequence {
+    tracks: Vec<Track>,
+}
+impl AnimationSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a animation, with the given length in seconds, to the end of the sequence.
+    pub fn then<A: 'static + Animation>(mut self, length: f32, animation: A) -> Self {
+        self.tracks.push(Track::new(length, Box::new(animation)));
+        self
+    }
+
+    /// The sum of the lengths of all animations.
+    pub fn length(&self) -> f32 {
+        self.tracks.iter().map(|x| x.length).sum()
+    }
+}
+impl Animation for AnimationSequence {
+    fn on_update(&mut self, t: f32, _: f32, length: f32, ctx: &mut Context) {
+        let total = self.length();
+        let scale = if total > 0.0 { length / total } else { 0.0 };
+        let elapsed = t * total;
+        let mut start = 0.0;
+        for track in &mut self.tracks {
+            // the last call must give t == 1.0 to all tracks, despite rounding errors.
+            let elapsed = if t >= 1.0 {
+                track.length
+            } else {
+                elapsed - start
+            };
+            track.update(elapsed, scale, ctx);
+            start += track.length;
+        }
+    }
+}
+
+/// A [`Animation`] that plays a group of animations in parallel. All animations start at the same
+/// time, and the group finishes when the longest one finishes.
+///
+/// The group is a single animation, so removing it cancels all animations. It should be added
+/// with [`AnimationGroup::length`] as its length. If added with a different length, the duration
+/// of each animation is scaled proportionally.
+#[derive(Default)]
+pub struct AnimationGroup {
+    tracks: Vec<Track>,
+}
+impl AnimationGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a animation, with the given length in seconds, to the group.
+    pub fn with<A: 'static + Animation>(mut self, length: f32, animation: A) -> Self {
+        self.tracks.push(Track::new(length, Box::new(animation)));
+        self
+    }
+
+    /// The length of the longest animation.
+    pub fn length(&self) -> f32 {
+        self.tracks.iter().map(|x| x.length).fold(0.0, f32::max)
+    }
+}
+impl Animation for AnimationGroup {
+    fn on_update(&mut self, t: f32, _: f32, length: f32, ctx: &mut Context) {
+        let total = self.length();
+        let scale = if total > 0.0 { length / total } else { 0.0 };
+        for track in &mut self.tracks {
+            let elapsed = if t >= 1.0 { track.length } else { t * total };
+            track.update(elapsed, scale, ctx);
+        }
+    }
+}
+
+/// A [`ContinuousAnimation`] that moves a value towards a target, as a damped spring, and calls
+/// `on_change` with the current value every frame.
+///
+/// The spring has unit mass, so its motion is only defined by its stiffness and damping. It
+/// finishes when both the distance to the target and the velocity fall below a threshold, and
+/// the last call to `on_change` is always with exactly the target value.
+pub struct Spring<F: FnMut(f32, &mut Context)> {
+    value: f32,
+    velocity: f32,
+    target: f32,
+    stiffness: f32,
+    damping: f32,
+    threshold: f32,
+    on_change: F,
+}
+impl<F: FnMut(f32, &mut Context)> Spring<F> {
+    /// The max time step of the integration, for numerical stability.
+    const MAX_STEP: f32 = 1.0 / 240.0;
+
+    /// Create a spring from the value `from` to the value `to`, with a stiffness of 170 and a
+    /// damping of 26, which gives a fast motion with almost no oscillation.
+    pub fn new(from: f32, to: f32, on_change: F) -> Self {
+        Self {
+            value: from,
+            velocity: 0.0,
+            target: to,
+            stiffness: 170.0,
+            damping: 26.0,
+            threshold: 0.01,
+            on_change,
+        }
+    }
+
+    /// Set the stiffness and damping of the spring. A damping smaller than
+    /// `2.0 * stiffness.sqrt()` makes the value oscillate around the target.
+    pub fn with_params(mut self, stiffness: f32, damping: f32) -> Self {
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+
+    /// Set the velocity, in units per second, at the start of the animation. Useful to continue
+    /// from a previous motion, like a fling.
+    pub fn with_velocity(mut self, velocity: f32) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Set the max distance to the target, and max velocity, at which the spring is considered at
+    /// rest. The default is 0.01.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Integrate the motion of the spring over `dt` seconds. Return false if it came to rest.
+    fn step(&mut self, dt: f32) -> bool {
+        let steps = (dt / Self::MAX_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        for _ in 0..steps as u32 {
+            let acceleration =
+                -self.stiffness * (self.value - self.target) - self.damping * self.velocity;
+            self.velocity += acceleration * h;
+            self.value += self.velocity * h;
+        }
+        let at_rest = (self.value - self.target).abs() < self.threshold
+            && self.velocity.abs() < self.threshold;
+        if at_rest || !self.value.is_finite() {
+            self.value = self.target;
+            self.velocity = 0.0;
+            return false;
+        }
+        true
+    }
+}
+impl<F: FnMut(f32, &mut Context)> ContinuousAnimation for Spring<F> {
+    fn on_update(&mut self, dt: f32, ctx: &mut Context) -> bool {
+        let running = self.step(dt);
+        (self.on_change)(self.value, ctx);
+        running
+    }
+}
+
 pub type AnimationId = u32;
 
 pub(crate) fn next_animation_id() -> u32 {
@@ -421,12 +902,66 @@ pub(crate) fn next_animation_id() -> u32 {
     id
 }
 
+enum AnimationCallback {
+    /// A animation with a fixed `length`, where `t` goes from 0.0 to 1.0.
+    Timed {
+        last_t: f32,
+        length: f32,
+        callback: Box<dyn Animation>,
+    },
+    /// A animation that runs until its callback returns false. `last_time` is the elapsed time, in
+    /// seconds, of the last update.
+    Continuous {
+        last_time: f32,
+        callback: Box<dyn ContinuousAnimation>,
+    },
+}
+
 struct ScheduledAnimation {
     id: AnimationId,
-    last_t: f32,
-    length: f32,
     start: Option<Instant>,
-    callback: Box<dyn Animation>,
+    callback: AnimationCallback,
+}
+
+/// Set the batch depth of a Gui, restoring the previous one when dropped, even when unwinding
+/// from a panic.
+struct BatchDepth<'a> {
+    gui: &'a mut Gui,
+    previous: u32,
+}
+impl<'a> BatchDepth<'a> {
+    fn set(gui: &'a mut Gui, depth: u32) -> Self {
+        let previous = std::mem::replace(&mut gui.batch_depth, depth);
+        Self { gui, previous }
+    }
+}
+impl Drop for BatchDepth<'_> {
+    fn drop(&mut self) {
+        self.gui.batch_depth = self.previous;
+    }
+}
+
+struct Modal {
+    id: Id,
+    /// The control that had focus when the modal was pushed.
+    prev_focus: Option<Id>,
+}
+
+/// The input state that is kept for each window. The one of the window currently receiving
+/// events is kept in the Gui itself.
+#[derive(Default)]
+struct WindowInput {
+    current_focus: Option<Id>,
+    modals: Vec<Modal>,
+    inputs: MouseInputs,
+}
+
+/// A additional window, created with [`Gui::add_window`].
+struct Window {
+    /// The control that contains all controls of this window.
+    root: Id,
+    /// The input state of this window, while it is not the current one.
+    input: WindowInput,
 }
 
 pub struct Gui {
@@ -439,15 +974,68 @@ pub struct Gui {
     // controls that need to update the layout
     dirty_layouts: Vec<Id>,
     lazy_events: VecDeque<LazyEvent>,
+    /// The distance the mouse must move, after a down event, to start dragging.
+    drag_threshold: f32,
+    /// If the layout rounds the rects to the physical pixel grid. See
+    /// [`Gui::set_pixel_snapping`].
+    pixel_snapping: bool,
+    /// If arrow keys, not handled by the focused control, move the focus. See
+    /// [`Gui::set_arrow_navigation`].
+    arrow_navigation: bool,
+    /// Set by [`Context::stop_propagation`], to stop the bubbling of the current mouse event.
+    pub(crate) stop_propagation: bool,
+    /// The number of nested calls to [`Gui::batch`]. While nonzero, layout updates and lazy
+    /// events are deferred.
+    batch_depth: u32,
 
     pub(crate) inputs: MouseInputs,
     /// The control currently receiving on_keyboard_event's.
     pub(crate) current_focus: Option<Id>,
+    /// The stack of modal controls. Only the last one, and its descendants, receive input.
+    modals: Vec<Modal>,
+    /// The additional windows. See [`Gui::add_window`].
+    windows: HashMap<WindowId, Window>,
+    /// The window currently receiving events, or None for the main window.
+    current_window: Option<WindowId>,
+    /// The input state of the main window, while it is not the current one.
+    main_window_input: WindowInput,
 
     scheduled_events: KeyedPriorityQueue<u64, ScheduledEventTo>,
+    intervals: HashMap<IntervalId, Interval>,
     animations: Vec<ScheduledAnimation>,
 
     change_cursor: Option<CursorIcon>,
+    /// The cursor of the control currently hovered, if any.
+    hover_cursor: Option<CursorIcon>,
+    /// The drag-and-drop operation in progress, if any.
+    pub(crate) drag: Option<Drag>,
+    /// The control that accepted the files being dragged from the OS, if any.
+    file_hover: Option<Id>,
+    /// If the files being hovered were already dropped. The next hover is a new drag.
+    file_dropped: bool,
+    /// The touches currently down, with their positions, in the order they started.
+    touches: Vec<(MouseId, [f32; 2])>,
+    /// The gesture being made by the first two touches, if any.
+    gesture: Option<Gesture>,
+    /// If IME input should be allowed, when it changes. See [`Gui::ime_allowed_change`].
+    pub(crate) ime_allowed: Option<bool>,
+    /// The position of the IME candidate window, when it changes.
+    pub(crate) ime_position: Option<[f32; 2]>,
+    /// Called to show or hide the virtual keyboard. See [`Gui::set_virtual_keyboard_callback`].
+    pub(crate) virtual_keyboard: Option<Box<dyn FnMut(RequestVirtualKeyboard)>>,
+    /// If the accessibility tree may have changed since the last call to
+    /// [`Gui::take_accessibility_dirty`].
+    pub(crate) accessibility_dirty: bool,
+    #[cfg(feature = "accesskit")]
+    pub(crate) accesskit_classes: accesskit::NodeClassSet,
+    /// The registered themes, by name. See [`Gui::add_theme`].
+    themes: HashMap<String, GuiTheme>,
+    /// The name of the current theme.
+    current_theme: Option<String>,
+    accelerators: HashMap<Accelerator, Box<dyn FnMut(&mut Context)>>,
+    /// The callbacks of the global shortcuts. They return if the key press was handled. They are
+    /// kept apart from the accelerators, so that removing one does not remove the other.
+    shortcuts: HashMap<Accelerator, Box<dyn FnMut(&mut Context) -> bool>>,
     scale_factor: f64,
 }
 impl Gui {
@@ -460,11 +1048,37 @@ impl Gui {
             redraw: true,
             dirty_layouts: Vec::new(),
             lazy_events: VecDeque::new(),
+            drag_threshold: 20.0,
+            pixel_snapping: false,
+            arrow_navigation: false,
+            stop_propagation: false,
+            batch_depth: 0,
             inputs: MouseInputs::default(),
             current_focus: None,
+            modals: Vec::new(),
+            windows: HashMap::new(),
+            current_window: None,
+            main_window_input: WindowInput::default(),
             scheduled_events: KeyedPriorityQueue::default(),
+            intervals: HashMap::new(),
             animations: Vec::new(),
             change_cursor: None,
+            hover_cursor: None,
+            drag: None,
+            file_hover: None,
+            file_dropped: false,
+            touches: Vec::new(),
+            gesture: None,
+            ime_allowed: None,
+            ime_position: None,
+            virtual_keyboard: None,
+            accessibility_dirty: true,
+            #[cfg(feature = "accesskit")]
+            accesskit_classes: accesskit::NodeClassSet::new(),
+            themes: HashMap::new(),
+            current_theme: None,
+            accelerators: HashMap::new(),
+            shortcuts: HashMap::new(),
             scale_factor,
         }
     }
@@ -514,15 +1128,72 @@ impl Gui {
 
         self.animations.push(ScheduledAnimation {
             id,
-            last_t: 0.0,
-            length,
             start: None,
-            callback: animation,
+            callback: AnimationCallback::Timed {
+                last_t: 0.0,
+                length,
+                callback: animation,
+            },
         });
 
         id
     }
 
+    /// Add a new animation without a fixed length, that runs until it finishes.
+    ///
+    /// The returned `AnimationId` can be used to remove the added animation with
+    /// [`Gui::remove_animation`]. This id is unique.
+    pub fn add_continuous_animation<A: 'static + ContinuousAnimation>(
+        &mut self,
+        animation: A,
+    ) -> AnimationId {
+        let id = next_animation_id();
+        self.add_continuous_animation_with_id(id, Box::new(animation));
+        id
+    }
+
+    /// Add a continuous animation with the given Id and immedially call it with `dt = 0.0`.
+    /// Add a [`ShimmerAnimation`] for the control, if its graphic is a Shimmer that is not
+    /// animated yet.
+    fn start_shimmer(&mut self, id: Id) {
+        if let Some(Graphic::Shimmer(shimmer)) = self.controls.get_mut(id).map(|x| &mut x.graphic) {
+            if shimmer.animated {
+                return;
+            }
+            shimmer.animated = true;
+            self.animations.push(ScheduledAnimation {
+                id: next_animation_id(),
+                start: None,
+                callback: AnimationCallback::Continuous {
+                    last_time: 0.0,
+                    callback: Box::new(ShimmerAnimation { id }),
+                },
+            });
+        }
+    }
+
+    fn add_continuous_animation_with_id(
+        &mut self,
+        id: AnimationId,
+        mut animation: Box<dyn ContinuousAnimation>,
+    ) {
+        log::trace!("continuous animation add {}", id);
+
+        // Immedialy update the animation.
+        if !animation.on_update(0.0, &mut self.get_context()) {
+            return;
+        }
+
+        self.animations.push(ScheduledAnimation {
+            id,
+            start: None,
+            callback: AnimationCallback::Continuous {
+                last_time: 0.0,
+                callback: animation,
+            },
+        });
+    }
+
     /// Remove the animation with the given `id`.
     ///
     /// The id is the one returned by [`Gui::add_animation`] when the animation to be removed was
@@ -627,6 +1298,7 @@ impl Gui {
     }
 
     fn start_control(&mut self, id: Id) -> Id {
+        self.accessibility_dirty = true;
         match std::mem::take(&mut self.controls.controls[id.index()]) {
             ControlEntry::Take => {
                 panic!("Added a taken control?");
@@ -702,6 +1374,7 @@ impl Gui {
                 return;
             }
             control.active = true;
+            self.accessibility_dirty = true;
         } else {
             return;
         }
@@ -748,6 +1421,7 @@ impl Gui {
                 return;
             }
             control.active = false;
+            self.accessibility_dirty = true;
         } else {
             return;
         }
@@ -805,6 +1479,50 @@ impl Gui {
         // self.mouse_moved(self.input.mouse_x, self.input.mouse_y);
     }
 
+    /// Return true if the control is visible. The control may still be hidden, if one of its
+    /// ancestors is not visible.
+    pub fn is_visible(&self, id: Id) -> bool {
+        self.controls.get(id).map_or(false, |x| x.visible)
+    }
+
+    /// Show or hide a control and its descendants. Unlike a deactivated control, a hidden control
+    /// is still laid out, keeping its space in the layout of its parent, but it is not rendered,
+    /// and it and its descendants don't receive input or focus.
+    pub fn set_visible(&mut self, id: Id, visible: bool) {
+        match self.controls.get_mut(id) {
+            Some(control) if control.visible != visible => {
+                control.visible = visible;
+                // force the descendants to be rebuilt
+                control.rect.render_dirty_flags |= RenderDirtyFlags::RECT;
+                self.accessibility_dirty = true;
+                self.redraw = true;
+            }
+            _ => return,
+        }
+        if visible {
+            return;
+        }
+
+        let mut parents = vec![id];
+        while let Some(id) = parents.pop() {
+            parents.extend(self.get_active_children(id).iter().rev());
+            for i in 0..self.inputs.len() {
+                if Some(id) == self.inputs[i].current_scroll {
+                    self.inputs[i].current_scroll = None;
+                }
+                if Some(id) == self.inputs[i].current_mouse {
+                    self.update_layout();
+                    let mouse = self.inputs[i].get_mouse_info(MouseEvent::Exit);
+                    self.call_event_no_lazy(id, |x, id, ctx| x.on_mouse_event(mouse, id, ctx));
+                    self.inputs[i].current_mouse = None;
+                }
+            }
+            if Some(id) == self.current_focus {
+                self.set_focus(None);
+            }
+        }
+    }
+
     /// Remove a control and all of its children
     pub fn remove_control(&mut self, id: Id) {
         self.lazy_events.push_back(LazyEvent::OnRemove(id, true));
@@ -818,6 +1536,36 @@ impl Gui {
         self.lazy_update();
     }
 
+    /// The distance, in logical pixels, that the mouse must move in one direction, after a down
+    /// event over a control with [`InputFlags::DRAG`], to start dragging it.
+    pub fn drag_threshold(&self) -> f32 {
+        self.drag_threshold
+    }
+
+    /// Set the distance that the mouse must move to start dragging. The default is 20 pixels.
+    /// Touch interfaces may want a smaller value.
+    pub fn set_drag_threshold(&mut self, drag_threshold: f32) {
+        self.drag_threshold = drag_threshold;
+    }
+
+    /// Call `f`, deferring the layout updates and lazy events (like `on_start` and `on_active`)
+    /// until it returns, and then process all of them in a single update. Calls can be nested.
+    ///
+    /// This is useful when creating or removing many controls at once, like when populating a
+    /// list, to avoid recomputing the layout after each one. Inside `f`, the layout of the controls
+    /// may be outdated, unless [`Gui::layout_now`] is called.
+    pub fn batch<R>(&mut self, f: impl FnOnce(&mut Gui) -> R) -> R {
+        let depth = self.batch_depth + 1;
+        let result = {
+            let mut guard = BatchDepth::set(self, depth);
+            f(&mut *guard.gui)
+        };
+        if self.batch_depth == 0 {
+            self.lazy_update();
+        }
+        result
+    }
+
     /// Remove all animations.
     pub fn clear_animations(&mut self) {
         self.animations.clear();
@@ -832,6 +1580,29 @@ impl Gui {
         self.change_cursor.take()
     }
 
+    /// Return if IME input should be allowed, if it has changed since the last call. Should be
+    /// passed to `Window::set_ime_allowed`, otherwise the window may not receive IME events.
+    pub fn ime_allowed_change(&mut self) -> Option<bool> {
+        self.ime_allowed.take()
+    }
+
+    /// Set the callback that shows or hides the virtual keyboard of the platform. It is called
+    /// when a text field gains or loses focus, for example.
+    pub fn set_virtual_keyboard_callback<F: FnMut(RequestVirtualKeyboard) + 'static>(
+        &mut self,
+        callback: F,
+    ) {
+        self.virtual_keyboard = Some(Box::new(callback));
+    }
+
+    /// Return the position where the IME candidate window should be placed, if it has changed
+    /// since the last call. Should be passed to `Window::set_ime_position`.
+    pub fn ime_position_change(&mut self) -> Option<LogicalPosition<f32>> {
+        self.ime_position
+            .take()
+            .map(|[x, y]| LogicalPosition::new(x, y))
+    }
+
     /// Handle if there is some scheduled event to be adressed, and
     /// return the instant for the next scheduled event
     pub fn handle_scheduled_event(&mut self) -> Option<Instant> {
@@ -841,7 +1612,11 @@ impl Gui {
                 Some(time) => {
                     if now >= time {
                         let (id, event) = self.scheduled_events.pop().unwrap().1.item;
-                        self.send_event_to(id, event);
+                        if let Some(&IntervalTick(interval_id)) = event.downcast_ref() {
+                            self.tick_interval(interval_id, time);
+                        } else {
+                            self.send_event_to(id, event);
+                        }
                         continue;
                     }
                     return self.scheduled_events.peek().map(|x| x.1.priority().0);
@@ -856,25 +1631,40 @@ impl Gui {
         let mut animations = std::mem::take(&mut self.animations);
 
         animations.retain_mut(|anim| {
-            let mut t = match anim.start {
-                Some(start) => start.elapsed().as_secs_f32() / anim.length,
+            let elapsed = match anim.start {
+                Some(start) => start.elapsed().as_secs_f32(),
                 None => {
                     anim.start = Some(Instant::now());
                     0.0
                 }
             };
 
-            if t >= 1.0 {
-                t = 1.0;
-            }
+            match &mut anim.callback {
+                AnimationCallback::Timed {
+                    last_t,
+                    length,
+                    callback,
+                } => {
+                    let t = (elapsed / *length).min(1.0);
+
+                    log::trace!("animation play {}, t = {}", anim.id, t);
+                    callback.on_update(t, t - *last_t, *length, &mut self.get_context());
 
-            log::trace!("animation play {}, t = {}", anim.id, t);
-            anim.callback
-                .on_update(t, t - anim.last_t, anim.length, &mut self.get_context());
+                    *last_t = t;
 
-            anim.last_t = t;
+                    t < 1.0
+                }
+                AnimationCallback::Continuous {
+                    last_time,
+                    callback,
+                } => {
+                    let dt = elapsed - *last_time;
+                    *last_time = elapsed;
 
-            t < 1.0
+                    log::trace!("continuous animation play {}, dt = {}", anim.id, dt);
+                    callback.on_update(dt, &mut self.get_context())
+                }
+            }
         });
 
         // return animations to self
@@ -915,6 +1705,9 @@ impl Gui {
                 } => {
                     self.add_animation_with_id(id, length, animation);
                 }
+                crate::Event::AddContinuousAnimation { id, animation } => {
+                    self.add_continuous_animation_with_id(id, animation);
+                }
                 crate::Event::RemoveAnimation { id } => {
                     self.remove_animation(id);
                 }
@@ -930,30 +1723,221 @@ impl Gui {
         Some(&self.controls.get(id)?.rect)
     }
 
+    /// Return a readable path from the root to the given control, for logging and debugging,
+    /// like `root/surface/[3]/list/[2]/text`. Each control in the path is written as its name,
+    /// given by [`ControlBuilder::name`](crate::ControlBuilder::name), or as its index in its
+    /// parent, if it has no name. If the control is not alive, return the Id itself.
+    pub fn debug_path(&self, id: Id) -> String {
+        self.controls.debug_path(id)
+    }
+
+    /// Get the Id of the first control built with the given name, given by
+    /// [`ControlBuilder::name`](crate::ControlBuilder::name), that is still alive.
+    pub fn find_by_name(&self, name: &str) -> Option<Id> {
+        self.controls.find_by_name(name)
+    }
+
+    /// Get the Id of all alive controls with the given name, in the order they were built.
+    pub fn find_all_by_name(&self, name: &str) -> &[Id] {
+        self.controls.find_all_by_name(name)
+    }
+
     /// Set the scale factor of the gui.
     ///
     /// This is used to scale the gui when rendering, allowing dpi awareness.
     pub fn set_scale_factor(&mut self, scale_factor: f64) {
         self.scale_factor = scale_factor;
+        if self.pixel_snapping {
+            // the pixel grid changed
+            self.dirty_layout(Id::ROOT_ID);
+        }
     }
 
-    /// Get the current scale factor of the gui.
-    pub fn scale_factor(&self) -> f64 {
-        self.scale_factor
+    /// If enabled, the rects computed by the layouts are rounded to the physical pixel grid, given
+    /// the scale factor. This avoids blurry borders and gaps between adjacent controls at
+    /// fractional scale factors, but controls may be positioned up to half a pixel off. Disabled
+    /// by default.
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        if self.pixel_snapping != enabled {
+            self.pixel_snapping = enabled;
+            self.dirty_layout(Id::ROOT_ID);
+        }
     }
 
-    /// Set the rect of the root control. Must be called when the window resize for example.
-    ///
-    /// The given rect must be in the format `[x1, y1, x2, y2]`.
-    pub fn set_root_rect(&mut self, rect: [f32; 4]) {
-        self.controls
-            .get_mut(Id::ROOT_ID)
-            .unwrap()
-            .rect
+    /// Register a theme with the given name, replacing any theme with the same name. If it is
+    /// the current theme, it is applied again.
+    pub fn add_theme(&mut self, name: &str, theme: GuiTheme) {
+        self.themes.insert(name.to_owned(), theme);
+        if self.current_theme.as_deref() == Some(name) {
+            self.set_theme(name);
+        }
+    }
+
+    /// Set the current theme, and apply it to all controls tagged with a style key, with
+    /// [`ControlBuilder::style_key`](crate::ControlBuilder::style_key). Controls started later
+    /// also have the theme applied to them. Return false if there is no theme with the given
+    /// name.
+    pub fn set_theme(&mut self, name: &str) -> bool {
+        if !self.themes.contains_key(name) {
+            log::warn!("there is no theme named '{}'", name);
+            return false;
+        }
+        self.current_theme = Some(name.to_owned());
+        self.lazy_update();
+        let tagged: Vec<Id> = self
+            .controls
+            .controls
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| match entry {
+                ControlEntry::Started { control } if control.style_key.is_some() => Some(Id {
+                    index: index as u32,
+                    generation: control.generation,
+                }),
+                _ => None,
+            })
+            .collect();
+        for id in tagged {
+            self.apply_theme(id);
+        }
+        true
+    }
+
+    /// The name of the current theme, if any.
+    pub fn theme_name(&self) -> Option<&str> {
+        self.current_theme.as_deref()
+    }
+
+    /// The current theme, if any.
+    pub fn theme(&self) -> Option<&GuiTheme> {
+        self.themes.get(self.current_theme.as_ref()?)
+    }
+
+    /// Apply the style or color of the current theme to the control, if it has a style key.
+    fn apply_theme(&mut self, id: Id) {
+        let key = match self.controls.get(id).and_then(|x| x.style_key.as_ref()) {
+            Some(x) => x,
+            None => return,
+        };
+        let theme = match self.theme() {
+            Some(x) => x,
+            None => return,
+        };
+        let (color, event) = (theme.color(key), theme.style_event(key));
+        if let Some(color) = color {
+            self.controls.get_mut(id).unwrap().graphic.set_color(color);
+            self.redraw = true;
+        } else if let Some(event) = event {
+            self.call_event_no_lazy(id, |this, id, ctx| this.on_event(event, id, ctx));
+        }
+    }
+
+    /// Get the current scale factor of the gui.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Set the rect of the root control. Must be called when the window resize for example.
+    ///
+    /// The given rect must be in the format `[x1, y1, x2, y2]`.
+    pub fn set_root_rect(&mut self, rect: [f32; 4]) {
+        self.controls
+            .get_mut(Id::ROOT_ID)
+            .unwrap()
+            .rect
             .set_rect(rect);
         self.dirty_layout(Id::ROOT_ID);
     }
 
+    /// Add a additional top-level window, with the given size. Return the root control of the
+    /// window, which all controls of the window must descend from.
+    ///
+    /// Each window has its own focus, modals and mouse state. The events of the window must be
+    /// passed to [`Gui::handle_window_event`], and it must be rendered by its own
+    /// [`GuiRender`](crate::GuiRender), with [`GuiRender::set_root`](crate::GuiRender::set_root).
+    /// The controls of all windows share the same [`Fonts`] and resources.
+    pub fn add_window(&mut self, window_id: WindowId, width: f32, height: f32) -> Id {
+        let root = self
+            .create_control()
+            .anchors([0.0; 4])
+            .margins([0.0, 0.0, width, height])
+            .focus_scope(true)
+            .build(self);
+        log::trace!("add window {:?} with root {}", window_id, root);
+        self.windows.insert(
+            window_id,
+            Window {
+                root,
+                input: WindowInput::default(),
+            },
+        );
+        root
+    }
+
+    /// Remove a window added by [`Gui::add_window`], and all of its controls.
+    pub fn remove_window(&mut self, window_id: WindowId) {
+        if self.current_window == Some(window_id) {
+            self.switch_window(None);
+        }
+        if let Some(window) = self.windows.remove(&window_id) {
+            self.remove_control(window.root);
+        }
+    }
+
+    /// Return the root control of the given window, if it was added by [`Gui::add_window`].
+    pub fn window_root(&self, window_id: WindowId) -> Option<Id> {
+        self.windows.get(&window_id).map(|x| x.root)
+    }
+
+    /// Set the size of a window added by [`Gui::add_window`]. Must be called when the window
+    /// resize, like [`Gui::set_root_rect`] for the main window.
+    pub fn set_window_size(&mut self, window_id: WindowId, width: f32, height: f32) {
+        let root = match self.window_root(window_id) {
+            Some(x) => x,
+            None => return,
+        };
+        self.controls.get_mut(root).unwrap().rect.margins = [0.0, 0.0, width, height];
+        self.dirty_layout(root);
+    }
+
+    /// Return true if the control is the root of a window added by [`Gui::add_window`].
+    pub(crate) fn is_window_root(&self, id: Id) -> bool {
+        self.windows.values().any(|x| x.root == id)
+    }
+
+    /// The root control of the window currently receiving events.
+    fn current_root(&self) -> Id {
+        self.current_window
+            .and_then(|x| self.window_root(x))
+            .unwrap_or(Id::ROOT_ID)
+    }
+
+    /// Make the given window, or the main window if None, the one receiving events, swapping the
+    /// focus, modals and mouse state.
+    fn switch_window(&mut self, window: Option<WindowId>) {
+        if window == self.current_window {
+            return;
+        }
+        log::trace!("switch to window {:?}", window);
+        let input = WindowInput {
+            current_focus: self.current_focus.take(),
+            modals: std::mem::take(&mut self.modals),
+            inputs: std::mem::take(&mut self.inputs),
+        };
+        match self.current_window.and_then(|x| self.windows.get_mut(&x)) {
+            Some(current) => current.input = input,
+            None => self.main_window_input = input,
+        }
+        let input = match window.and_then(|x| self.windows.get_mut(&x)) {
+            Some(window) => std::mem::take(&mut window.input),
+            None => std::mem::take(&mut self.main_window_input),
+        };
+        self.current_focus = input.current_focus;
+        self.modals = input.modals;
+        self.inputs = input.inputs;
+        self.current_window = window.filter(|x| self.windows.contains_key(x));
+    }
+
     pub fn send_event(&mut self, event: Box<dyn Any>) {
         log::trace!("send_event");
         if let Some(event::ActiveControl { id }) = event.downcast_ref() {
@@ -962,15 +1946,29 @@ impl Gui {
             self.deactive_control(*id);
         } else if let Some(event::RemoveControl { id }) = event.downcast_ref() {
             self.remove_control(*id);
+        } else if let Some(&event::SetVisible { id, visible }) = event.downcast_ref() {
+            self.set_visible(id, visible);
         } else if let Some(&event::SetLockOver { lock, mouse_id }) = event.downcast_ref() {
             let input = self.inputs.get_mouse(mouse_id);
             input.map(|x| x.hover_is_locked = lock);
         } else if let Some(event::RequestFocus { id }) = event.downcast_ref() {
             self.set_focus(Some(*id));
+        } else if let Some(event::LoseFocus { id }) = event.downcast_ref() {
+            if self.current_focus == Some(*id) {
+                self.set_focus(None);
+            }
+        } else if let Some(event::PushModal { id }) = event.downcast_ref() {
+            self.push_modal(*id);
+        } else if event.is::<event::PopModal>() {
+            self.pop_modal();
         } else if let Some(event::StartControl { id }) = event.downcast_ref() {
             self.start_control(*id);
+        } else if let Some(event::SetTheme(name)) = event.downcast_ref() {
+            self.set_theme(name);
         } else if let Some(cursor) = event.downcast_ref::<CursorIcon>() {
             self.change_cursor = Some(*cursor);
+        } else if let Ok(start_drag) = event.downcast::<event::StartDrag>() {
+            self.start_drag(*start_drag);
         }
     }
 
@@ -999,6 +1997,80 @@ impl Gui {
         self.scheduled_events.remove(&event_id);
     }
 
+    /// Send a event, created by `event_factory`, to the control with the given id, every `period`,
+    /// starting one period from now.
+    ///
+    /// The returned `IntervalId` can be used to stop the interval with [`Gui::cancel_interval`].
+    /// The interval also stops when the control is removed. If the events fall behind, for
+    /// example because the application was suspended, the missed events are skipped.
+    pub fn schedule_interval<E, F>(
+        &mut self,
+        id: Id,
+        mut event_factory: F,
+        period: Duration,
+    ) -> IntervalId
+    where
+        E: Any,
+        F: FnMut() -> E + 'static,
+    {
+        static INTERVAL_ID: AtomicU64 = AtomicU64::new(0);
+        let interval_id = INTERVAL_ID.fetch_add(1, Ordering::Relaxed);
+        // a zero period would never give control back to the event loop
+        let period = period.max(Duration::from_millis(1));
+        let event_id = self.send_event_to_scheduled(
+            id,
+            Box::new(IntervalTick(interval_id)),
+            Instant::now() + period,
+        );
+        self.intervals.insert(
+            interval_id,
+            Interval {
+                id,
+                event_factory: Box::new(move || Box::new(event_factory()) as Box<dyn Any>),
+                period,
+                event_id,
+            },
+        );
+        interval_id
+    }
+
+    /// Stop the interval with the given id. If the interval was already cancelled, or its control
+    /// was removed, this does nothing.
+    pub fn cancel_interval(&mut self, interval_id: IntervalId) {
+        if let Some(interval) = self.intervals.remove(&interval_id) {
+            self.scheduled_events.remove(&interval.event_id);
+        }
+    }
+
+    /// Dispatch the event of the given interval, and schedule its next tick. `time` is the
+    /// instant the tick was scheduled to.
+    fn tick_interval(&mut self, interval_id: IntervalId, time: Instant) {
+        let interval = match self.intervals.get_mut(&interval_id) {
+            Some(x) => x,
+            None => return,
+        };
+        let id = interval.id;
+        if self.controls.get(id).is_none() {
+            self.intervals.remove(&interval_id);
+            return;
+        }
+        let event = (interval.event_factory)();
+        let period = interval.period;
+
+        // schedule the next tick before dispatching, so the event handler can cancel it.
+        let now = Instant::now();
+        let mut next = time + period;
+        if next <= now {
+            next = now + period;
+        }
+        let event_id = self.send_event_to_scheduled(id, Box::new(IntervalTick(interval_id)), next);
+        if let Some(interval) = self.intervals.get_mut(&interval_id) {
+            interval.event_id = event_id;
+        }
+
+        self.send_event_to(id, event);
+    }
+
     fn call_event<F: FnOnce(&mut dyn Behaviour, Id, &mut Context)>(
         self: &mut Self,
         id: Id,
@@ -1064,8 +2136,17 @@ impl Gui {
         }
     }
 
+    /// Handle a event of the given window. Events of windows not added by [`Gui::add_window`] are
+    /// handled as events of the main window.
+    pub fn handle_window_event(&mut self, window_id: WindowId, event: &WindowEvent) {
+        let window = Some(window_id).filter(|x| self.windows.contains_key(x));
+        self.switch_window(window);
+        self.handle_event(event);
+    }
+
     pub fn handle_event(&mut self, event: &WindowEvent) {
         self.lazy_update();
+        self.prune_modals();
         match event {
             &WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 self.set_scale_factor(scale_factor);
@@ -1096,33 +2177,21 @@ impl Gui {
                 }
 
                 let location = LogicalPosition::<f32>::from_physical(location, self.scale_factor);
-                match phase {
-                    winit::event::TouchPhase::Started => {
-                        self.mouse_moved(id, location.x, location.y);
-                        self.mouse_down(id, MouseButton::Left);
-                    }
-                    winit::event::TouchPhase::Ended => {
-                        self.mouse_up(id, MouseButton::Left);
-                        self.mouse_exit(id);
-                    }
-                    winit::event::TouchPhase::Moved => {
-                        self.mouse_moved(id, location.x, location.y);
-                    }
-                    winit::event::TouchPhase::Cancelled => {
-                        self.mouse_exit(id);
-                    }
-                }
+                self.touch(id, phase, [location.x, location.y]);
             }
-            &WindowEvent::MouseWheel { delta, .. } => {
-                self.mouse_scroll(MOUSE_ID, delta);
+            &WindowEvent::MouseWheel { delta, phase, .. } => {
+                self.mouse_scroll(MOUSE_ID, delta, phase);
             }
+            WindowEvent::HoveredFile(path) => self.file_hovered(path),
+            WindowEvent::HoveredFileCancelled => self.file_hover_cancelled(),
+            WindowEvent::DroppedFile(path) => self.file_dropped(path),
             WindowEvent::CursorEntered { .. } => {}
             WindowEvent::CursorLeft { .. } => {
                 self.mouse_exit(MOUSE_ID);
             }
             WindowEvent::ReceivedCharacter(ch) => {
                 log::debug!("received character {:?}", ch);
-                if let Some(curr) = self.current_focus {
+                if let Some(curr) = self.current_focus.or_else(|| self.current_modal()) {
                     if ch.is_control() {
                         return;
                     }
@@ -1131,6 +2200,20 @@ impl Gui {
                     });
                 }
             }
+            WindowEvent::Ime(ime) => {
+                log::debug!("received ime {:?}", ime);
+                let event = match ime {
+                    Ime::Enabled => return,
+                    Ime::Preedit(text, cursor) => ImeEvent::Preedit(text.clone(), *cursor),
+                    Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+                    Ime::Disabled => ImeEvent::Preedit(String::new(), None),
+                };
+                if let Some(curr) = self.current_focus.or_else(|| self.current_modal()) {
+                    self.call_event_chain(curr, move |this, id, ctx| {
+                        this.on_ime_event(event.clone(), id, ctx)
+                    });
+                }
+            }
             WindowEvent::ModifiersChanged(modifiers) => self.modifiers = *modifiers,
             WindowEvent::KeyboardInput {
                 input:
@@ -1142,7 +2225,13 @@ impl Gui {
                 ..
             } => {
                 log::debug!("received key {:?}", keycode);
-                if let Some(curr) = self.current_focus {
+                let accelerator = Accelerator::new(self.modifiers, *keycode);
+                if *state == ElementState::Pressed
+                    && (self.call_shortcut(accelerator) || self.call_accelerator(accelerator))
+                {
+                    return;
+                }
+                if let Some(curr) = self.current_focus.or_else(|| self.current_modal()) {
                     let event = if *state == ElementState::Pressed {
                         KeyboardEvent::Pressed(*keycode)
                     } else {
@@ -1152,62 +2241,34 @@ impl Gui {
                         this.on_keyboard_event(event, id, ctx)
                     });
                     // if the key press was not handled, use it for navigation. Tab go to next
-                    // control, Shift+Tab go to previous.
+                    // control, Shift+Tab go to previous. Escape closes the current modal.
                     if !handled && *state == ElementState::Pressed {
                         let shift = self.modifiers.shift();
-                        let next = match *keycode {
-                            VirtualKeyCode::Tab if !shift => {
-                                let mut tree = self.controls.tree_starting_at(curr).unwrap();
-                                tree.pop(); // pop 'this'
-                                loop {
-                                    let id = match tree.pop() {
-                                        Some(id) => id,
-                                        None => break None,
-                                    };
-                                    tree.extend(
-                                        self.controls.get_active_children(id).unwrap().iter().rev(),
-                                    );
-                                    let is_focus = self
-                                        .controls
-                                        .get(id)
-                                        .unwrap()
-                                        .behaviour
-                                        .as_ref()
-                                        .map_or(false, |x| {
-                                            x.input_flags().contains(InputFlags::FOCUS)
-                                        });
-                                    if is_focus {
-                                        break Some(id);
-                                    }
+                        match *keycode {
+                            VirtualKeyCode::Tab => {
+                                let next = self.next_focus(curr, shift);
+                                if next.is_some() {
+                                    self.set_focus(next);
                                 }
                             }
-                            VirtualKeyCode::Tab => {
-                                let mut tree = self.controls.rev_tree_starting_at(curr).unwrap();
-                                tree.pop(); // pop 'this'
-                                loop {
-                                    let id = match tree.pop() {
-                                        Some(id) => id,
-                                        None => break None,
-                                    };
-                                    tree.extend(self.controls.get_active_children(id).unwrap());
-                                    let is_focus = self
-                                        .controls
-                                        .get(id)
-                                        .unwrap()
-                                        .behaviour
-                                        .as_ref()
-                                        .map_or(false, |x| {
-                                            x.input_flags().contains(InputFlags::FOCUS)
-                                        });
-                                    if is_focus {
-                                        break Some(id);
-                                    }
+                            VirtualKeyCode::Escape => {
+                                if let Some(modal) = self.pop_modal() {
+                                    self.deactive_control(modal);
                                 }
                             }
-                            _ => None,
-                        };
-                        if next.is_some() {
-                            self.set_focus(next);
+                            VirtualKeyCode::Up if self.arrow_navigation => {
+                                self.focus_in_direction(FocusDirection::Up);
+                            }
+                            VirtualKeyCode::Down if self.arrow_navigation => {
+                                self.focus_in_direction(FocusDirection::Down);
+                            }
+                            VirtualKeyCode::Left if self.arrow_navigation => {
+                                self.focus_in_direction(FocusDirection::Left);
+                            }
+                            VirtualKeyCode::Right if self.arrow_navigation => {
+                                self.focus_in_direction(FocusDirection::Right);
+                            }
+                            _ => {}
                         }
                     }
                 }
@@ -1216,6 +2277,362 @@ impl Gui {
         }
     }
 
+    /// Register a callback that is called when the given accelerator is pressed, independently of
+    /// which control has focus. The accelerators are handled before any control receives the key
+    /// press. Any accelerator registered before with the same keys is replaced.
+    pub fn add_accelerator<F: FnMut(&mut Context) + 'static>(
+        &mut self,
+        accelerator: Accelerator,
+        callback: F,
+    ) {
+        self.accelerators.insert(accelerator, Box::new(callback));
+    }
+
+    /// Unregister the callback of the given accelerator, if any.
+    pub fn remove_accelerator(&mut self, accelerator: Accelerator) {
+        self.accelerators.remove(&accelerator);
+    }
+
+    /// Call the callback of the given accelerator. Return false if there is none.
+    fn call_accelerator(&mut self, accelerator: Accelerator) -> bool {
+        let mut callback = match self.accelerators.remove(&accelerator) {
+            Some(x) => x,
+            None => return false,
+        };
+        log::debug!("calling accelerator {}", accelerator);
+        let mut ctx = self.get_context();
+        callback(&mut ctx);
+        drop(ctx);
+        // the callback may have registered a new callback for the same accelerator.
+        self.accelerators.entry(accelerator).or_insert(callback);
+        true
+    }
+
+    /// Register a global keyboard shortcut, whose callback returns if the key press was handled.
+    /// If it returns false, the key press is dispatched to the accelerators and to the focused
+    /// control, and used for Tab navigation, as usual.
+    ///
+    /// The shortcuts are handled before the accelerators, but are registered apart from them, so
+    /// a shortcut and a accelerator with the same keys don't replace each other. Any shortcut
+    /// registered before with the same keys is replaced.
+    pub fn register_shortcut<F: FnMut(&mut Context) -> bool + 'static>(
+        &mut self,
+        modifiers: ModifiersState,
+        key: VirtualKeyCode,
+        callback: F,
+    ) {
+        self.shortcuts
+            .insert(Accelerator::new(modifiers, key), Box::new(callback));
+    }
+
+    /// Unregister the callback of the given shortcut, if any.
+    pub fn unregister_shortcut(&mut self, modifiers: ModifiersState, key: VirtualKeyCode) {
+        self.shortcuts.remove(&Accelerator::new(modifiers, key));
+    }
+
+    /// Call the callback of the given shortcut. Return false if there is none, or if it did not
+    /// handle the key press.
+    fn call_shortcut(&mut self, accelerator: Accelerator) -> bool {
+        let mut callback = match self.shortcuts.remove(&accelerator) {
+            Some(x) => x,
+            None => return false,
+        };
+        log::debug!("calling shortcut {}", accelerator);
+        let mut ctx = self.get_context();
+        let handled = callback(&mut ctx);
+        drop(ctx);
+        // the callback may have registered a new callback for the same shortcut.
+        self.shortcuts.entry(accelerator).or_insert(callback);
+        handled
+    }
+
+    /// Find the next control that can receive focus, after the given one, in tree order. If
+    /// `reverse` is true, find the previous one. If the given control is inside a focus scope, or
+    /// there is a modal, the search wraps around inside of it.
+    fn next_focus(&mut self, curr: Id, reverse: bool) -> Option<Id> {
+        let scope = self.focus_scope(curr).or_else(|| self.current_modal());
+        let mut tree = if scope == Some(curr) {
+            vec![curr]
+        } else {
+            let mut tree = if reverse {
+                self.controls.rev_tree_starting_at(curr).unwrap()
+            } else {
+                self.controls.tree_starting_at(curr).unwrap()
+            };
+            tree.pop(); // pop 'this'
+            tree
+        };
+        let mut wrapped = false;
+        loop {
+            let in_scope = |this: &mut Self, id: Id| match scope {
+                Some(scope) => scope == id || this.controls.is_descendant(scope, id),
+                None => true,
+            };
+            let id = match tree.pop() {
+                Some(id) if in_scope(self, id) => id,
+                // reached the end of the scope, go back to its start
+                _ => match scope {
+                    Some(scope) if !wrapped => {
+                        wrapped = true;
+                        tree.clear();
+                        scope
+                    }
+                    _ => break None,
+                },
+            };
+            // the focus does not move to other windows
+            if Some(id) != scope && self.is_window_root(id) {
+                continue;
+            }
+            let children = self.controls.get_active_children(id).unwrap();
+            if reverse {
+                tree.extend(children);
+            } else {
+                tree.extend(children.iter().rev());
+            }
+            let is_focus = self
+                .controls
+                .get(id)
+                .unwrap()
+                .behaviour
+                .as_ref()
+                .map_or(false, |x| x.input_flags().contains(InputFlags::FOCUS));
+            if is_focus && self.controls.is_really_visible(id) {
+                break Some(id);
+            }
+        }
+    }
+
+    /// If enabled, the arrow keys that are not handled by the focused control move the focus to
+    /// the nearest control in that direction, with [`Gui::focus_in_direction`]. Disabled by
+    /// default.
+    pub fn set_arrow_navigation(&mut self, enabled: bool) {
+        self.arrow_navigation = enabled;
+    }
+
+    /// Move the focus to the nearest control, that can receive focus, in the given direction
+    /// from the currently focused one, based on their rects. If there is no focused control, the
+    /// first one in tree order receives focus. Only the controls in the same focus scope, or
+    /// modal, are considered.
+    ///
+    /// Return the new focused control, if the focus changed.
+    pub fn focus_in_direction(&mut self, direction: FocusDirection) -> Option<Id> {
+        self.update_layout();
+        let curr = match self.current_focus {
+            Some(curr) => curr,
+            None => {
+                let root = self.current_modal().unwrap_or_else(|| self.current_root());
+                let next = self.next_focus(root, false);
+                if next.is_some() {
+                    self.set_focus(next);
+                }
+                return next;
+            }
+        };
+
+        let center = |rect: [f32; 4]| [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+        let from = center(self.controls.get(curr).unwrap().rect.rect);
+        let scope = self
+            .focus_scope(curr)
+            .or_else(|| self.current_modal())
+            .unwrap_or_else(|| self.current_root());
+
+        let mut best: Option<(f32, Id)> = None;
+        let mut tree = vec![scope];
+        while let Some(id) = tree.pop() {
+            let children = self.get_active_children(id);
+            tree.extend(children.into_iter().filter(|x| !self.is_window_root(*x)));
+            let control = self.controls.get(id).unwrap();
+            let is_focus = control
+                .behaviour
+                .as_ref()
+                .map_or(false, |x| x.input_flags().contains(InputFlags::FOCUS));
+            if id == curr || !is_focus || !self.controls.is_really_visible(id) {
+                continue;
+            }
+            let to = center(control.rect.rect);
+            let dx = to[0] - from[0];
+            let dy = to[1] - from[1];
+            // the distance along the direction, and the distance perpendicular to it.
+            let (along, across) = match direction {
+                FocusDirection::Up => (-dy, dx),
+                FocusDirection::Down => (dy, dx),
+                FocusDirection::Left => (-dx, dy),
+                FocusDirection::Right => (dx, dy),
+            };
+            if along <= 0.0 {
+                continue;
+            }
+            // prefer controls that are aligned with the current one.
+            let score = along + 2.0 * across.abs();
+            if best.map_or(true, |(best, _)| score < best) {
+                best = Some((score, id));
+            }
+        }
+
+        let next = best.map(|(_, id)| id);
+        if next.is_some() {
+            self.set_focus(next);
+        }
+        next
+    }
+
+    /// Handle a input from a gamepad, or any other controller. The directions move the focus with
+    /// [`Gui::focus_in_direction`], `Accept` synthesizes a left click on the focused control, and
+    /// `Back` is handled like a press of the Escape key: it is first sent to the focused control,
+    /// and if not handled, closes the current modal.
+    ///
+    /// This does not depend on any gamepad crate, so the user must map the inputs of its gamepad
+    /// to this. Return `true` if the input was handled.
+    pub fn gamepad_navigate(&mut self, input: GamepadInput) -> bool {
+        log::debug!("received gamepad input {:?}", input);
+        self.update_layout();
+        match input {
+            GamepadInput::Direction(direction) => self.focus_in_direction(direction).is_some(),
+            GamepadInput::Accept => {
+                let curr = match self.current_focus {
+                    Some(x) => x,
+                    None => return false,
+                };
+                let control = self.controls.get(curr).unwrap();
+                let is_mouse = control
+                    .behaviour
+                    .as_ref()
+                    .map_or(false, |x| x.input_flags().contains(InputFlags::MOUSE));
+                if !is_mouse {
+                    return false;
+                }
+                let rect = control.rect.rect;
+                let pos = [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+                let mouse = |event, left, click_count| MouseInfo {
+                    id: GAMEPAD_MOUSE_ID,
+                    event,
+                    pos,
+                    buttons: MouseButtons {
+                        left,
+                        ..MouseButtons::default()
+                    },
+                    delta: None,
+                    click_count,
+                    is_dragging_x: false,
+                    is_dragging_y: false,
+                };
+                let (pressed, released) = (ButtonState::Pressed, ButtonState::Released);
+                let events = [
+                    mouse(MouseEvent::Enter, released, 0),
+                    mouse(MouseEvent::Down(MouseButton::Left), pressed, 1),
+                    mouse(MouseEvent::Up(MouseButton::Left), released, 1),
+                    mouse(MouseEvent::Exit, released, 0),
+                ];
+                for mouse in events {
+                    self.send_mouse_event_to(curr, mouse);
+                }
+                true
+            }
+            GamepadInput::Back => {
+                if let Some(curr) = self.current_focus.or_else(|| self.current_modal()) {
+                    let key = VirtualKeyCode::Escape;
+                    let handled = self.call_event_chain(curr, |this, id, ctx| {
+                        this.on_keyboard_event(KeyboardEvent::Pressed(key), id, ctx)
+                    });
+                    self.call_event_chain(curr, |this, id, ctx| {
+                        this.on_keyboard_event(KeyboardEvent::Release(key), id, ctx)
+                    });
+                    if handled {
+                        return true;
+                    }
+                }
+                match self.pop_modal() {
+                    Some(modal) => {
+                        self.deactive_control(modal);
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    /// Return the closest focus scope that contains the given control, including itself. See
+    /// [`ControlBuilder::focus_scope`](crate::ControlBuilder::focus_scope). The current modal is
+    /// also a focus scope.
+    fn focus_scope(&self, id: Id) -> Option<Id> {
+        let modal = self.current_modal();
+        let mut curr = Some(id);
+        while let Some(id) = curr {
+            let control = self.controls.get(id)?;
+            if control.focus_scope || modal == Some(id) {
+                return Some(id);
+            }
+            curr = control.parent;
+        }
+        None
+    }
+
+    /// Make the given control modal. While it is modal, only it and its descendants receive mouse
+    /// and keyboard input, and the focus cannot leave it. Pressing Escape, if not handled by any
+    /// control, pops the modal and deactivates it.
+    ///
+    /// The modal is popped when [`Gui::pop_modal`] is called, or when the control is deactivated
+    /// or removed. When popped, the focus is restored to the control that had it before.
+    pub fn push_modal(&mut self, id: Id) {
+        self.lazy_update();
+        if self.controls.get(id).is_none() {
+            log::error!("pushing modal {}, but it does not exist", id);
+            return;
+        }
+        log::trace!("push modal {}", id);
+        self.modals.push(Modal {
+            id,
+            prev_focus: self.current_focus,
+        });
+        // release any mouse that is locked outside of the modal
+        for i in 0..self.inputs.len() {
+            if let Some(curr) = self.inputs[i].current_mouse {
+                if !self.is_in_modal(curr) {
+                    self.inputs[i].hover_is_locked = false;
+                }
+            }
+        }
+        if let Some(focus) = self.current_focus {
+            if !self.is_in_modal(focus) {
+                self.set_focus(None);
+            }
+        }
+    }
+
+    /// Pop the current modal, returning its id, and restore the focus to the control that had it
+    /// before the modal was pushed.
+    pub fn pop_modal(&mut self) -> Option<Id> {
+        let modal = self.modals.pop()?;
+        log::trace!("pop modal {}", modal.id);
+        self.set_focus(modal.prev_focus);
+        Some(modal.id)
+    }
+
+    /// The control that is currently modal, if any.
+    pub fn current_modal(&self) -> Option<Id> {
+        self.modals.last().map(|x| x.id)
+    }
+
+    /// Return true if the given control can receive input, in respect to the current modal.
+    fn is_in_modal(&mut self, id: Id) -> bool {
+        match self.current_modal() {
+            Some(modal) => modal == id || self.controls.is_descendant(modal, id),
+            None => true,
+        }
+    }
+
+    /// Pop all modals that were deactivated or removed.
+    fn prune_modals(&mut self) {
+        while let Some(modal) = self.current_modal() {
+            if self.controls.get(modal).map_or(false, |x| x.really_active) {
+                break;
+            }
+            self.pop_modal();
+        }
+    }
+
     pub fn set_focus(&mut self, id: Option<Id>) {
         self.lazy_update();
         log::trace!(
@@ -1224,11 +2641,19 @@ impl Gui {
                 .unwrap_or_else(|| "None".to_string())
         );
 
+        if let Some(x) = id {
+            if !self.is_in_modal(x) {
+                log::trace!("{} is outside of the current modal", x);
+                return;
+            }
+        }
+
         let id = if id.map_or(false, |id| {
             self.controls.get(id).map_or(true, |x| !x.really_active)
+                || !self.controls.is_really_visible(id)
         }) {
             log::trace!(
-                "{} is not active yet, focusing None",
+                "{} is not active yet, or is hidden, focusing None",
                 id.map(|x| x.to_string())
                     .unwrap_or_else(|| "None".to_string())
             );
@@ -1246,6 +2671,7 @@ impl Gui {
             return;
         }
 
+        self.accessibility_dirty = true;
         match (self.current_focus, id) {
             (Some(prev), Some(next)) => {
                 self.current_focus = Some(next);
@@ -1300,6 +2726,7 @@ impl Gui {
 
     pub fn mouse_moved(&mut self, id: MouseId, mouse_x: f32, mouse_y: f32) {
         log::trace!("mouse {} moved", id);
+        self.prune_modals();
         let preseve_click_count = self.inputs.mouse_moved(id, mouse_x, mouse_y);
 
         let input = match self.inputs.get_mouse(id) {
@@ -1318,6 +2745,8 @@ impl Gui {
         let mut curr_scroll = None;
         let mut curr_drag = None;
         let mut curr_mouse = None;
+        let mut hover_cursor = self.hover_cursor;
+        let down_button = input.down_button;
         if input.current_mouse.is_some() && input.hover_is_locked
             || input.is_dragging_y
             || input.is_dragging_x
@@ -1326,47 +2755,32 @@ impl Gui {
             curr_mouse = input.current_mouse;
             curr_drag = curr_mouse;
         } else {
-            let mut curr = Id::ROOT_ID;
-            self.update_layout();
-            'l: loop {
-                if let Some(flags) = self
-                    .controls
-                    .get(curr)
-                    .unwrap()
-                    .behaviour
-                    .as_ref()
-                    .map(|x| x.input_flags())
-                {
+            hover_cursor = None;
+            for curr in self.controls_at(mouse_x, mouse_y).into_iter().rev() {
+                let control = self.controls.get(curr).unwrap();
+                if let Some(cursor) = control.cursor {
+                    hover_cursor = Some(cursor);
+                }
+                if let Some(flags) = control.behaviour.as_ref().map(|x| x.input_flags()) {
                     if flags.contains(InputFlags::SCROLL) {
                         curr_scroll = Some(curr);
                     }
-                    if flags.contains(InputFlags::DRAG) {
+                    let drag_button = control.behaviour.as_ref().map(|x| x.drag_button());
+                    if flags.contains(InputFlags::DRAG) && drag_button == down_button {
                         curr_drag = Some(curr);
                     }
                     if flags.contains(InputFlags::MOUSE) {
                         curr_mouse = Some(curr);
                     }
-                    if flags.contains(InputFlags::BLOCK_MOUSE) {
-                        break 'l;
-                    }
-                }
-                // the interator is reversed because the last child blocks the previous ones
-                for child in self.get_active_children(curr).iter().rev() {
-                    if self
-                        .controls
-                        .get(*child)
-                        .unwrap()
-                        .rect
-                        .contains(mouse_x, mouse_y)
-                    {
-                        curr = *child;
-                        continue 'l;
-                    }
                 }
-                break;
             }
         }
 
+        if hover_cursor != self.hover_cursor {
+            self.hover_cursor = hover_cursor;
+            self.change_cursor = Some(hover_cursor.unwrap_or(CursorIcon::Default));
+        }
+
         let input = self.inputs.get_mouse(id).unwrap();
         if input.current_scroll != curr_scroll {
             log::trace!(
@@ -1379,7 +2793,7 @@ impl Gui {
 
         // Handle dragging
 
-        const MIN_DRAG_DIST: f32 = 20.0;
+        let drag_threshold = self.drag_threshold;
 
         if curr_drag.is_none() {
             input.is_dragging_x = false;
@@ -1389,7 +2803,7 @@ impl Gui {
             if !input.is_dragging_x {
                 if let Some([x, _]) = input.down_position {
                     let dy = mouse_x - x;
-                    if dy.abs() >= MIN_DRAG_DIST {
+                    if dy.abs() >= drag_threshold {
                         log::trace!("dragging true");
                         input.is_dragging_x = true;
                         curr_mouse = curr_drag;
@@ -1400,7 +2814,7 @@ impl Gui {
             if !input.is_dragging_y {
                 if let Some([_, y]) = input.down_position {
                     let dx = mouse_y - y;
-                    if dx.abs() >= MIN_DRAG_DIST {
+                    if dx.abs() >= drag_threshold {
                         log::trace!("dragging true");
                         input.is_dragging_y = true;
                         curr_mouse = curr_drag;
@@ -1441,6 +2855,10 @@ impl Gui {
                 self.send_mouse_event_to(current_mouse, mouse_moved);
             }
         }
+
+        if self.drag.as_ref().map_or(false, |x| x.mouse_id == id) {
+            self.update_drag([mouse_x, mouse_y]);
+        }
     }
 
     pub fn mouse_down(&mut self, id: MouseId, button: MouseButton) {
@@ -1453,8 +2871,9 @@ impl Gui {
             }
         };
 
-        if button == MouseButton::Left {
+        if input.down_position.is_none() {
             input.down_position = input.position;
+            input.down_button = Some(button);
         }
 
         match button {
@@ -1471,7 +2890,10 @@ impl Gui {
                 .map_or("None".to_string(), |x| x.to_string())
         );
         let current_mouse = input.current_mouse;
-        self.set_focus(current_mouse);
+        // clicking outside of a modal does not remove the focus from it
+        if current_mouse.is_some() || self.current_modal().is_none() {
+            self.set_focus(current_mouse);
+        }
 
         let input = self.inputs.get_mouse(id).unwrap();
 
@@ -1517,15 +2939,138 @@ impl Gui {
         }
 
         let input = self.inputs.get_mouse(id).unwrap();
-        if button == MouseButton::Left {
+        if input.down_button == Some(button) {
             input.down_position = None;
+            input.down_button = None;
             input.is_dragging_x = false;
             input.is_dragging_y = false;
             log::trace!("dragging = false");
+            let position = input.position;
+            if self.drag.as_ref().map_or(false, |x| x.mouse_id == id) {
+                self.end_drag(position);
+            }
+        }
+    }
+
+    fn touch(&mut self, id: MouseId, phase: winit::event::TouchPhase, position: [f32; 2]) {
+        let [x, y] = position;
+        match phase {
+            winit::event::TouchPhase::Started => {
+                self.mouse_moved(id, x, y);
+                self.mouse_down(id, MouseButton::Left);
+            }
+            winit::event::TouchPhase::Ended => {
+                self.mouse_up(id, MouseButton::Left);
+                self.mouse_exit(id);
+            }
+            winit::event::TouchPhase::Moved => {
+                self.mouse_moved(id, x, y);
+            }
+            winit::event::TouchPhase::Cancelled => {
+                self.mouse_exit(id);
+            }
         }
+        self.update_gesture(id, phase, position);
     }
 
-    fn mouse_scroll(&mut self, id: MouseId, delta: winit::event::MouseScrollDelta) {
+    /// Recognize the pinch, rotation and pan made by the first two touches, and send them to the
+    /// control under them, that has the flag [`InputFlags::GESTURE`].
+    fn update_gesture(&mut self, id: MouseId, phase: winit::event::TouchPhase, position: [f32; 2]) {
+        use winit::event::TouchPhase;
+
+        let index = self.touches.iter().position(|x| x.0 == id);
+        match (phase, index) {
+            (TouchPhase::Started, None) => {
+                self.touches.push((id, position));
+                if self.touches.len() != 2 {
+                    return;
+                }
+                let positions = [self.touches[0].1, self.touches[1].1];
+                let center = mid_point(positions);
+                let mut target = None;
+                for curr in self.controls_at(center[0], center[1]).into_iter().rev() {
+                    let control = self.controls.get(curr).unwrap();
+                    let flags = control.behaviour.as_ref().map(|x| x.input_flags());
+                    if flags.map_or(false, |x| x.contains(InputFlags::GESTURE)) {
+                        target = Some(curr);
+                    }
+                }
+                if let Some(target) = target {
+                    self.gesture = Some(Gesture { target, positions });
+                    self.send_gesture(GestureInfo {
+                        phase: GesturePhase::Started,
+                        center,
+                        scale: 1.0,
+                        rotation: 0.0,
+                        pan: [0.0, 0.0],
+                    });
+                }
+            }
+            (TouchPhase::Moved, Some(index)) => {
+                self.touches[index].1 = position;
+                let gesture = match &mut self.gesture {
+                    Some(x) if index < 2 => x,
+                    _ => return,
+                };
+                let old = gesture.positions;
+                let new = [self.touches[0].1, self.touches[1].1];
+                gesture.positions = new;
+
+                let old_v = [old[1][0] - old[0][0], old[1][1] - old[0][1]];
+                let new_v = [new[1][0] - new[0][0], new[1][1] - new[0][1]];
+                let old_len = old_v[0].hypot(old_v[1]);
+                let scale = if old_len > 0.0 {
+                    new_v[0].hypot(new_v[1]) / old_len
+                } else {
+                    1.0
+                };
+                let mut rotation = new_v[1].atan2(new_v[0]) - old_v[1].atan2(old_v[0]);
+                if rotation > std::f32::consts::PI {
+                    rotation -= 2.0 * std::f32::consts::PI;
+                } else if rotation < -std::f32::consts::PI {
+                    rotation += 2.0 * std::f32::consts::PI;
+                }
+                let (old_center, center) = (mid_point(old), mid_point(new));
+                self.send_gesture(GestureInfo {
+                    phase: GesturePhase::Moved,
+                    center,
+                    scale,
+                    rotation,
+                    pan: [center[0] - old_center[0], center[1] - old_center[1]],
+                });
+            }
+            (TouchPhase::Ended | TouchPhase::Cancelled, Some(index)) => {
+                self.touches.remove(index);
+                if index < 2 {
+                    if let Some(gesture) = &self.gesture {
+                        let center = mid_point(gesture.positions);
+                        self.send_gesture(GestureInfo {
+                            phase: GesturePhase::Ended,
+                            center,
+                            scale: 1.0,
+                            rotation: 0.0,
+                            pan: [0.0, 0.0],
+                        });
+                        self.gesture = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn send_gesture(&mut self, gesture: GestureInfo) {
+        if let Some(target) = self.gesture.as_ref().map(|x| x.target) {
+            self.call_event(target, |this, id, ctx| this.on_gesture(gesture, id, ctx));
+        }
+    }
+
+    fn mouse_scroll(
+        &mut self,
+        id: MouseId,
+        delta: winit::event::MouseScrollDelta,
+        phase: winit::event::TouchPhase,
+    ) {
         let input = match self.inputs.get_mouse(id) {
             Some(x) => x,
             None => {
@@ -1535,7 +3080,7 @@ impl Gui {
         };
 
         if let Some(curr) = input.current_scroll {
-            //TODO: I should handle Line and Pixel Delta differences more wisely?
+            let is_precise = matches!(delta, winit::event::MouseScrollDelta::PixelDelta(_));
             let delta = match delta {
                 winit::event::MouseScrollDelta::LineDelta(x, y) => {
                     let line_scale = 100.0 / self.scale_factor as f32;
@@ -1546,7 +3091,23 @@ impl Gui {
                     [p.x, p.y]
                 }
             };
-            self.call_event(curr, |this, id, ctx| this.on_scroll_event(delta, id, ctx));
+            // Shift + wheel scrolls horizontally, for devices that only have a vertical wheel.
+            let delta = if self.modifiers.shift() && delta[0] == 0.0 {
+                [delta[1], 0.0]
+            } else {
+                delta
+            };
+            let scroll = ScrollInfo {
+                delta,
+                is_precise,
+                phase: match phase {
+                    winit::event::TouchPhase::Started => ScrollPhase::Started,
+                    winit::event::TouchPhase::Moved => ScrollPhase::Moved,
+                    winit::event::TouchPhase::Ended => ScrollPhase::Ended,
+                    winit::event::TouchPhase::Cancelled => ScrollPhase::Cancelled,
+                },
+            };
+            self.call_event(curr, |this, id, ctx| this.on_scroll_event(scroll, id, ctx));
         }
     }
 
@@ -1571,12 +3132,265 @@ impl Gui {
             self.send_mouse_event_to(curr, mouse);
         }
 
+        if self.drag.as_ref().map_or(false, |x| x.mouse_id == id) {
+            self.end_drag(None);
+        }
+
         self.inputs.mouse_exit(id);
     }
 
+    /// Return true if there is a drag-and-drop operation in progress. See
+    /// [`Context::start_drag`].
+    pub fn is_drag_and_dropping(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    fn start_drag(&mut self, start_drag: event::StartDrag) {
+        self.end_drag(None);
+        let event::StartDrag {
+            mouse_id,
+            source,
+            payload,
+            image,
+        } = start_drag;
+        log::trace!("start drag from {}", source);
+        let image = image.and_then(|id| Some((id, self.controls.get(id)?.rect.margins)));
+        self.drag = Some(Drag {
+            mouse_id,
+            source,
+            payload,
+            image,
+            target: None,
+        });
+        let position = self.inputs.get_mouse(mouse_id).and_then(|x| x.position);
+        if let Some(position) = position {
+            self.update_drag(position);
+        }
+    }
+
+    /// Find the control under the mouse that accepts the hovered file, calling `on_file_hover`
+    /// from the top most control to its ancestors.
+    fn file_hovered(&mut self, path: &Path) {
+        log::debug!("hovered file {:?}", path);
+        if std::mem::take(&mut self.file_dropped) {
+            self.file_hover = None;
+        }
+        let position = self.inputs.get_mouse(MOUSE_ID).and_then(|x| x.position);
+        let hits = position.map_or(Vec::new(), |[x, y]| self.controls_at(x, y));
+        let mut target = None;
+        for id in hits {
+            let mut accepted = false;
+            self.call_event(id, |this, id, ctx| {
+                accepted = this.on_file_hover(path, id, ctx)
+            });
+            if accepted {
+                target = Some(id);
+                break;
+            }
+        }
+        let previous = std::mem::replace(&mut self.file_hover, target);
+        if let Some(previous) = previous.filter(|&x| Some(x) != target) {
+            self.call_event(previous, |this, id, ctx| this.on_file_hover_exit(id, ctx));
+        }
+    }
+
+    fn file_hover_cancelled(&mut self) {
+        log::debug!("hovered file cancelled");
+        self.file_dropped = false;
+        if let Some(previous) = self.file_hover.take() {
+            self.call_event(previous, |this, id, ctx| this.on_file_hover_exit(id, ctx));
+        }
+    }
+
+    /// Deliver the dropped file to the control that accepted it when hovered.
+    fn file_dropped(&mut self, path: &Path) {
+        log::debug!("dropped file {:?}", path);
+        // some platforms may not emit HoveredFile before DroppedFile
+        if self.file_hover.is_none() && !self.file_dropped {
+            self.file_hovered(path);
+        }
+        self.file_dropped = true;
+        if let Some(target) = self.file_hover {
+            self.call_event(target, |this, id, ctx| this.on_file_drop(path, id, ctx));
+        }
+    }
+
+    /// Return the top most control at the given position. See [`Gui::controls_at`].
+    pub fn control_at(&mut self, x: f32, y: f32) -> Option<Id> {
+        self.controls_at(x, y).first().copied()
+    }
+
+    /// Return all controls at the given position, from the top most one to the root control, in
+    /// the same way that the mouse hovers the controls, without dispatching any events.
+    ///
+    /// Only active and visible controls are hit. The children of a control with [`InputFlags::BLOCK_MOUSE`]
+    /// are not hit. If there is a modal, only it and its descendants can be hit.
+    pub fn controls_at(&mut self, x: f32, y: f32) -> Vec<Id> {
+        self.controls_at_ignoring(x, y, None)
+    }
+
+    /// The same as [`Gui::controls_at`], but ignoring the control `ignore` and its descendants.
+    fn controls_at_ignoring(&mut self, x: f32, y: f32, ignore: Option<Id>) -> Vec<Id> {
+        self.update_layout();
+        // if there is a modal, only it and its descendants can be hit.
+        let modal = self.current_modal();
+        let mut curr = modal.unwrap_or_else(|| self.current_root());
+        let mut hits = Vec::new();
+        let hovering = modal.map_or(true, |modal| {
+            self.controls.get(modal).unwrap().rect.contains(x, y)
+        });
+        if !hovering {
+            return hits;
+        }
+        'l: loop {
+            hits.push(curr);
+            let control = self.controls.get(curr).unwrap();
+            if control
+                .behaviour
+                .as_ref()
+                .map_or(false, |x| x.input_flags().contains(InputFlags::BLOCK_MOUSE))
+            {
+                break;
+            }
+            // the interator is reversed because the last child blocks the previous ones
+            for child in self.get_active_children(curr).iter().rev() {
+                // other windows are not hit
+                if Some(*child) == ignore || self.is_window_root(*child) {
+                    continue;
+                }
+                // hidden controls are not hit
+                if !self.controls.get(*child).unwrap().visible {
+                    continue;
+                }
+                if self.controls.get(*child).unwrap().rect.contains(x, y) {
+                    curr = *child;
+                    continue 'l;
+                }
+            }
+            break;
+        }
+        hits.reverse();
+        hits
+    }
+
+    /// Move the drag image to the mouse position, and find the control that accepts the payload
+    /// under it, calling `on_drag_over` from the top most control to its ancestors.
+    fn update_drag(&mut self, position: [f32; 2]) {
+        let drag = match self.drag.as_mut() {
+            Some(x) => x,
+            None => return,
+        };
+        let image = drag.image;
+        if let Some((image, margins)) = image {
+            if let Some(parent) = self.get_parent(image) {
+                let parent = *self.controls.get(parent).unwrap().rect.get_rect();
+                let x = position[0] - parent[0];
+                let y = position[1] - parent[1];
+                let rect = &mut self.controls.get_mut(image).unwrap().rect;
+                rect.anchors = [0.0; 4];
+                rect.margins = [
+                    margins[0] + x,
+                    margins[1] + y,
+                    margins[2] + x,
+                    margins[3] + y,
+                ];
+                self.dirty_layout(image);
+            }
+        }
+
+        let mut target = None;
+        let hits = self.controls_at_ignoring(position[0], position[1], image.map(|x| x.0));
+        for id in hits {
+            let drag = match self.drag.take() {
+                Some(x) => x,
+                None => return,
+            };
+            let mut accepted = false;
+            let payload = &*drag.payload;
+            self.call_event(id, |this, id, ctx| {
+                accepted = this.on_drag_over(payload, position, id, ctx)
+            });
+            // the drag could have been restarted while handling the event
+            self.drag.get_or_insert(drag);
+            if accepted {
+                target = Some(id);
+                break;
+            }
+        }
+
+        let drag = match self.drag.as_mut() {
+            Some(x) => x,
+            None => return,
+        };
+        let previous = std::mem::replace(&mut drag.target, target);
+        if let Some(previous) = previous.filter(|&x| Some(x) != target) {
+            self.call_event(previous, |this, id, ctx| this.on_drag_exit(id, ctx));
+        }
+    }
+
+    /// Finish the current drag. If `position` is Some and there is a target, the payload is
+    /// dropped on it.
+    fn end_drag(&mut self, position: Option<[f32; 2]>) {
+        let drag = match self.drag.take() {
+            Some(x) => x,
+            None => return,
+        };
+        log::trace!("end drag from {}", drag.source);
+        if let Some((image, _)) = drag.image {
+            self.remove_control(image);
+        }
+        let mut dropped = false;
+        if let Some(target) = drag.target {
+            self.call_event(target, |this, id, ctx| this.on_drag_exit(id, ctx));
+            if let Some(position) = position {
+                let payload = drag.payload;
+                self.call_event(target, |this, id, ctx| {
+                    this.on_drop(payload, position, id, ctx)
+                });
+                dropped = true;
+            }
+        }
+        self.call_event(drag.source, |this, id, ctx| {
+            this.on_drag_end(dropped, id, ctx)
+        });
+    }
+
     // TODO: think more carefully in what functions must be public
     pub fn send_mouse_event_to(&mut self, id: Id, mouse: MouseInfo) {
-        self.call_event(id, move |this, id, ctx| this.on_mouse_event(mouse, id, ctx));
+        let bubbles = matches!(
+            mouse.event,
+            MouseEvent::Down(_) | MouseEvent::Up(_) | MouseEvent::Moved
+        );
+        self.stop_propagation = false;
+        let event = mouse.clone();
+        self.call_event(id, move |this, id, ctx| this.on_mouse_event(event, id, ctx));
+        if bubbles && !std::mem::take(&mut self.stop_propagation) {
+            self.bubble_mouse_event(id, mouse);
+        }
+    }
+
+    /// Send the mouse event received by `target` to each of its ancestors with
+    /// [`InputFlags::CHILD_MOUSE`], until one of them handles it.
+    fn bubble_mouse_event(&mut self, target: Id, mouse: MouseInfo) {
+        let mut curr = self.get_parent(target);
+        while let Some(id) = curr {
+            let wants_mouse = self
+                .controls
+                .get(id)
+                .and_then(|x| x.behaviour.as_ref())
+                .map_or(false, |x| x.input_flags().contains(InputFlags::CHILD_MOUSE));
+            if wants_mouse {
+                let mut handled = false;
+                let mouse = mouse.clone();
+                self.call_event(id, |this, id, ctx| {
+                    handled = this.on_child_mouse_event(mouse, target, id, ctx)
+                });
+                if handled {
+                    return;
+                }
+            }
+            curr = self.get_parent(id);
+        }
     }
 
     pub fn dirty_layout(&mut self, id: Id) {
@@ -1586,6 +3400,9 @@ impl Gui {
     }
 
     fn lazy_update(&mut self) {
+        if self.batch_depth > 0 {
+            return;
+        }
         loop {
             while let Some(event) = self.lazy_events.pop_front() {
                 match event {
@@ -1597,6 +3414,7 @@ impl Gui {
                         // TODO: on_start must receive a context that do not exposure the broke layout
                         log::trace!("starting {}", id);
                         self.call_event_no_lazy(id, |this, id, ctx| this.on_start(id, ctx));
+                        self.apply_theme(id);
                     }
                     LazyEvent::OnRemove(id, dirty_parent_layout) => {
                         if self.controls.get(id).is_none() {
@@ -1604,6 +3422,7 @@ impl Gui {
                             continue;
                         }
                         log::trace!("removing {}", id);
+                        self.accessibility_dirty = true;
 
                         if self.controls.get(id).unwrap().active {
                             // only deactive if it is not the ROOT_ID
@@ -1674,6 +3493,16 @@ impl Gui {
                             parents.extend(self.controls.get(id).unwrap().children.iter().rev());
                             self.controls.remove(id);
                         }
+                        // stop the intervals of the removed controls
+                        let (controls, scheduled_events) =
+                            (&self.controls, &mut self.scheduled_events);
+                        self.intervals.retain(|_, interval| {
+                            let alive = controls.get(interval.id).is_some();
+                            if !alive {
+                                scheduled_events.remove(&interval.event_id);
+                            }
+                            alive
+                        });
                         // uncommenting the line below allow infinity recursion to happen
                         // self.mouse_moved(self.input.mouse_x, self.input.mouse_y);
                     }
@@ -1699,6 +3528,7 @@ impl Gui {
                         }
 
                         log::trace!("activing {}", id);
+                        self.start_shimmer(id);
                         self.call_event_no_lazy(id, |this, id, ctx| this.on_active(id, ctx));
 
                         let mut tree = self.controls.get_active_children(id).unwrap();
@@ -1711,6 +3541,7 @@ impl Gui {
                                 );
                                 log::trace!("really active {}", id);
                                 self.controls.get_mut(id).unwrap().really_active = true;
+                                self.start_shimmer(id);
                                 self.call_event_no_lazy(id, |this, id, ctx| {
                                     this.on_active(id, ctx)
                                 });
@@ -1748,8 +3579,37 @@ impl Gui {
     }
 
     pub fn update_layout(&mut self) {
+        if self.batch_depth > 0 {
+            return;
+        }
         if !self.dirty_layouts.is_empty() {
             log::trace!("updating layout for {}", self.dirty_layouts.len());
+            self.flush_layout();
+        }
+    }
+
+    /// Synchronously lay out all controls, so the rects returned by [`Gui::get_rect`] are up to
+    /// date, without waiting for the next frame. Useful, for example, to position a popup relative
+    /// to a control that was just created.
+    ///
+    /// This first handles the pending events, like starting the controls that were just built,
+    /// and then runs [`Gui::update_all_layouts`] immediately, even if no layout is dirty, or if
+    /// inside of a [`Gui::batch`]. See also [`Context::force_layout`].
+    pub fn layout_now(&mut self) {
+        // the pending events are handled even inside of a batch, otherwise the controls built in
+        // it would not be started.
+        {
+            let mut guard = BatchDepth::set(self, 0);
+            guard.gui.lazy_update();
+        }
+        self.flush_layout();
+    }
+
+    pub(crate) fn flush_layout(&mut self) {
+        self.dirty_layouts.clear();
+        self.update_all_layouts();
+        // some layouts depend on the size given to them, and may request a second pass.
+        if !self.dirty_layouts.is_empty() {
             self.dirty_layouts.clear();
             self.update_all_layouts();
         }
@@ -1804,9 +3664,10 @@ impl Gui {
 
         // inorder traversal
         let mut parents = vec![id];
+        let mut relayouts = Vec::new();
         while let Some(id) = parents.pop() {
             {
-                let (events, dirtys) = {
+                let (events, dirtys, relayout) = {
                     let mut layout = self.controls.get_mut(id).unwrap().layout.take().unwrap();
                     let mut ctx = LayoutContext::new(
                         id,
@@ -1814,12 +3675,21 @@ impl Gui {
                         &mut self.resources,
                         &self.fonts,
                         self.scale_factor,
+                        self.pixel_snapping,
                     );
                     layout.update_layouts(id, &mut ctx);
-                    let LayoutContext { events, dirtys, .. } = ctx;
+                    let LayoutContext {
+                        events,
+                        dirtys,
+                        relayout,
+                        ..
+                    } = ctx;
                     self.controls.get_mut(id).unwrap().layout = Some(layout);
-                    (events, dirtys)
+                    (events, dirtys, relayout)
                 };
+                if relayout {
+                    relayouts.push(id);
+                }
                 for event in events {
                     //TODO: think carefully about this deactives
                     if let Some(event::DeactiveControl { id }) = event.downcast_ref() {
@@ -1901,6 +3771,9 @@ impl Gui {
                 }
             }
         }
+        for id in relayouts {
+            self.dirty_layout(id);
+        }
     }
 
     pub fn update_all_layouts(&mut self) {
@@ -1934,9 +3807,10 @@ impl Gui {
 
         // inorder traversal
         parents.push(Id::ROOT_ID);
+        let mut relayouts = Vec::new();
         while let Some(parent) = parents.pop() {
             {
-                let (events, _dirtys) = {
+                let (events, _dirtys, relayout) = {
                     let mut layout = self
                         .controls
                         .get_mut(parent)
@@ -1950,12 +3824,21 @@ impl Gui {
                         &mut self.resources,
                         &self.fonts,
                         self.scale_factor,
+                        self.pixel_snapping,
                     );
                     layout.update_layouts(parent, &mut ctx);
-                    let LayoutContext { events, dirtys, .. } = ctx;
+                    let LayoutContext {
+                        events,
+                        dirtys,
+                        relayout,
+                        ..
+                    } = ctx;
                     self.controls.get_mut(parent).unwrap().layout = Some(layout);
-                    (events, dirtys)
+                    (events, dirtys, relayout)
                 };
+                if relayout {
+                    relayouts.push(parent);
+                }
                 for event in events {
                     if let Some(event::DeactiveControl { id }) = event.downcast_ref() {
                         self.deactive_control(*id)
@@ -1974,6 +3857,9 @@ impl Gui {
         // self.start_control(id) calls dirty the id, but because all layouts are updated, this
         // dirty_layouts can be clear
         self.dirty_layouts.clear();
+        for id in relayouts {
+            self.dirty_layout(id);
+        }
     }
 }
 
@@ -1985,6 +3871,11 @@ bitflags! {
         const DRAG = 0x08;
         /// This indicates that this control will block child controls from getting a mouse event.
         const BLOCK_MOUSE = 0x10;
+        /// This indicates that this control receives the mouse events of its descendants, in
+        /// `on_child_mouse_event`.
+        const CHILD_MOUSE = 0x20;
+        /// This indicates that this control receives the multi-touch gestures, in `on_gesture`.
+        const GESTURE = 0x40;
     }
 }
 
@@ -2001,15 +3892,98 @@ pub trait Behaviour {
 
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {}
 
-    fn on_scroll_event(&mut self, delta: [f32; 2], this: Id, ctx: &mut Context) {}
+    fn on_scroll_event(&mut self, scroll: ScrollInfo, this: Id, ctx: &mut Context) {}
+
+    /// Called for the gestures made with two touches, which center was over this control when the
+    /// second touch started. Only called if the control has the flag [`InputFlags::GESTURE`].
+    fn on_gesture(&mut self, gesture: GestureInfo, this: Id, ctx: &mut Context) {}
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {}
 
+    /// The mouse button that starts dragging this control, if it has [`InputFlags::DRAG`]. The
+    /// default is [`MouseButton::Left`].
+    fn drag_button(&self) -> MouseButton {
+        MouseButton::Left
+    }
+
+    /// Called with the `Down`, `Up` and `Moved` mouse events received by the descendant `target`,
+    /// if this control has [`InputFlags::CHILD_MOUSE`]. The event bubbles up through the
+    /// ancestors of `target`, until a handler returns true, which indicates that the event was
+    /// handled. `target` itself can stop the bubbling by calling [`Context::stop_propagation`]
+    /// in `on_mouse_event`.
+    fn on_child_mouse_event(
+        &mut self,
+        mouse: MouseInfo,
+        target: Id,
+        this: Id,
+        ctx: &mut Context,
+    ) -> bool {
+        false
+    }
+
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {}
 
     fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
         false
     }
+
+    /// Called while a drag-and-drop operation moves over this control, or over a descendant that
+    /// did not accept it. Return true if the payload can be dropped here. This is a good place
+    /// to highlight the control, until `on_drag_exit` is called.
+    fn on_drag_over(
+        &mut self,
+        payload: &dyn Any,
+        position: [f32; 2],
+        this: Id,
+        ctx: &mut Context,
+    ) -> bool {
+        false
+    }
+
+    /// Called when the drag-and-drop operation that was accepted by `on_drag_over` moves out of
+    /// this control, or ends.
+    fn on_drag_exit(&mut self, this: Id, ctx: &mut Context) {}
+
+    /// Called when the payload, that was accepted by `on_drag_over`, is dropped over this
+    /// control.
+    fn on_drop(&mut self, payload: Box<dyn Any>, position: [f32; 2], this: Id, ctx: &mut Context) {}
+
+    /// Called in the control that started a drag-and-drop operation, when it ends. `dropped` is
+    /// false if the payload was not accepted by any control.
+    fn on_drag_end(&mut self, dropped: bool, this: Id, ctx: &mut Context) {}
+
+    /// Called when a file, dragged from outside of the application, hovers this control, or a
+    /// descendant that did not accept it. Return true if the file can be dropped here. This is a
+    /// good place to highlight the control, until `on_file_hover_exit` or `on_file_drop` is
+    /// called. When multiple files are dragged, this is called once for each one.
+    fn on_file_hover(&mut self, path: &Path, this: Id, ctx: &mut Context) -> bool {
+        false
+    }
+
+    /// Called when the files that were accepted by `on_file_hover` leave this control, without
+    /// being dropped.
+    fn on_file_hover_exit(&mut self, this: Id, ctx: &mut Context) {}
+
+    /// Called when a file, that was accepted by `on_file_hover`, is dropped over this control.
+    fn on_file_drop(&mut self, path: &Path, this: Id, ctx: &mut Context) {}
+
+    /// Receive the IME events, while focused. Return true if the event was handled, otherwise it
+    /// is propagated to the parent.
+    fn on_ime_event(&mut self, event: ImeEvent, this: Id, ctx: &mut Context) -> bool {
+        false
+    }
+
+    /// The role of the control, for screen readers. See the [`accessibility`](crate::accessibility)
+    /// module.
+    fn accessibility_role(&self) -> Role {
+        Role::Unknown
+    }
+
+    /// The current value of the control, like the value of a slider, for screen readers. When it
+    /// changes, call [`Context::dirty_accessibility`].
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        None
+    }
 }
 impl Behaviour for () {}
 
@@ -2034,6 +4008,12 @@ pub trait Layout {
             ctx.set_designed_rect(child, new_rect);
         }
     }
+
+    /// The name of the type of the layout. Used to describe the layout in a
+    /// [`Scene`](crate::scene::Scene).
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 impl Layout for () {}
 
@@ -2045,6 +4025,10 @@ impl<T: Layout> Layout for std::rc::Rc<std::cell::RefCell<T>> {
     fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
         self.as_ref().borrow_mut().update_layouts(this, ctx)
     }
+
+    fn type_name(&self) -> &'static str {
+        self.as_ref().borrow().type_name()
+    }
 }
 impl<T: Behaviour> Behaviour for std::rc::Rc<std::cell::RefCell<T>> {
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
@@ -2067,14 +4051,36 @@ impl<T: Behaviour> Behaviour for std::rc::Rc<std::cell::RefCell<T>> {
         self.as_ref().borrow_mut().input_flags()
     }
 
-    fn on_scroll_event(&mut self, delta: [f32; 2], this: Id, ctx: &mut Context) {
-        self.as_ref().borrow_mut().on_scroll_event(delta, this, ctx)
+    fn on_scroll_event(&mut self, scroll: ScrollInfo, this: Id, ctx: &mut Context) {
+        self.as_ref()
+            .borrow_mut()
+            .on_scroll_event(scroll, this, ctx)
+    }
+
+    fn on_gesture(&mut self, gesture: GestureInfo, this: Id, ctx: &mut Context) {
+        self.as_ref().borrow_mut().on_gesture(gesture, this, ctx)
     }
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
         self.as_ref().borrow_mut().on_mouse_event(mouse, this, ctx)
     }
 
+    fn drag_button(&self) -> MouseButton {
+        self.as_ref().borrow().drag_button()
+    }
+
+    fn on_child_mouse_event(
+        &mut self,
+        mouse: MouseInfo,
+        target: Id,
+        this: Id,
+        ctx: &mut Context,
+    ) -> bool {
+        self.as_ref()
+            .borrow_mut()
+            .on_child_mouse_event(mouse, target, this, ctx)
+    }
+
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
         self.as_ref().borrow_mut().on_focus_change(focus, this, ctx)
     }
@@ -2085,7 +4091,57 @@ impl<T: Behaviour> Behaviour for std::rc::Rc<std::cell::RefCell<T>> {
             .on_keyboard_event(event, this, ctx)
     }
 
+    fn on_ime_event(&mut self, event: ImeEvent, this: Id, ctx: &mut Context) -> bool {
+        self.as_ref().borrow_mut().on_ime_event(event, this, ctx)
+    }
+
+    fn on_drag_over(
+        &mut self,
+        payload: &dyn Any,
+        position: [f32; 2],
+        this: Id,
+        ctx: &mut Context,
+    ) -> bool {
+        self.as_ref()
+            .borrow_mut()
+            .on_drag_over(payload, position, this, ctx)
+    }
+
+    fn on_drag_exit(&mut self, this: Id, ctx: &mut Context) {
+        self.as_ref().borrow_mut().on_drag_exit(this, ctx)
+    }
+
+    fn on_drop(&mut self, payload: Box<dyn Any>, position: [f32; 2], this: Id, ctx: &mut Context) {
+        self.as_ref()
+            .borrow_mut()
+            .on_drop(payload, position, this, ctx)
+    }
+
+    fn on_drag_end(&mut self, dropped: bool, this: Id, ctx: &mut Context) {
+        self.as_ref().borrow_mut().on_drag_end(dropped, this, ctx)
+    }
+
+    fn on_file_hover(&mut self, path: &Path, this: Id, ctx: &mut Context) -> bool {
+        self.as_ref().borrow_mut().on_file_hover(path, this, ctx)
+    }
+
+    fn on_file_hover_exit(&mut self, this: Id, ctx: &mut Context) {
+        self.as_ref().borrow_mut().on_file_hover_exit(this, ctx)
+    }
+
+    fn on_file_drop(&mut self, path: &Path, this: Id, ctx: &mut Context) {
+        self.as_ref().borrow_mut().on_file_drop(path, this, ctx)
+    }
+
     fn on_remove(&mut self, this: Id, ctx: &mut Context) {
         self.as_ref().borrow_mut().on_remove(this, ctx)
     }
+
+    fn accessibility_role(&self) -> Role {
+        self.as_ref().borrow().accessibility_role()
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        self.as_ref().borrow().accessibility_value()
+    }
 }
@@ -3,10 +3,16 @@ use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
 use instant::Duration;
+use winit::{
+    event::{ModifiersState, MouseScrollDelta, TouchPhase, VirtualKeyCode},
+    window::WindowId,
+};
 
 use crate::widgets::{List, ListBuilder, ListViewLayout, ScrollView, ViewLayout};
 use crate::{
-    font::Fonts, Behaviour, Context, Gui, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    font::Fonts, Accelerator, Animation, AnimationGroup, AnimationSequence, Behaviour, Context,
+    FocusDirection, GamepadInput, Gui, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    ScrollInfo, Spring,
 };
 
 struct TestClickCount {
@@ -57,6 +63,26 @@ fn init_logger() {
         .try_init();
 }
 
+/// Send the press and the release of `key` to the gui, like a window would.
+fn press_key(gui: &mut Gui, key: VirtualKeyCode) {
+    use winit::event::{DeviceId, ElementState, KeyboardInput, WindowEvent};
+    for state in [ElementState::Pressed, ElementState::Released] {
+        #[allow(deprecated)]
+        let input = KeyboardInput {
+            scancode: 0,
+            state,
+            virtual_keycode: Some(key),
+            modifiers: ModifiersState::empty(),
+        };
+        gui.handle_event(&WindowEvent::KeyboardInput {
+            // the id is never used by the gui
+            device_id: unsafe { DeviceId::dummy() },
+            input,
+            is_synthetic: false,
+        });
+    }
+}
+
 #[test]
 fn mouse_click() {
     init_logger();
@@ -722,6 +748,86 @@ fn lock_cursor() {
     );
 }
 
+#[test]
+fn shift_scroll() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct TestScroll {
+        list: Arc<Mutex<Vec<[f32; 2]>>>,
+    }
+    impl Behaviour for TestScroll {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::SCROLL
+        }
+
+        fn on_scroll_event(&mut self, scroll: ScrollInfo, _this: Id, _ctx: &mut Context) {
+            self.list.lock().unwrap().push(scroll.delta);
+        }
+    }
+
+    let list = Arc::new(Mutex::new(Vec::new()));
+    gui.create_control()
+        .behaviour(TestScroll { list: list.clone() })
+        .build(&mut gui);
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, 1.0), TouchPhase::Moved);
+    gui.modifiers = ModifiersState::SHIFT;
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, 1.0), TouchPhase::Moved);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(1.0, 0.0), TouchPhase::Moved);
+
+    assert_eq!(
+        list.lock().unwrap().take().as_slice(),
+        &[[0.0, 100.0], [100.0, 0.0], [100.0, 0.0]]
+    );
+}
+
+#[test]
+fn scroll_info() {
+    use crate::ScrollPhase;
+    use winit::dpi::PhysicalPosition;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 2.0, Fonts::new());
+
+    struct TestScroll {
+        list: Rc<RefCell<Vec<ScrollInfo>>>,
+    }
+    impl Behaviour for TestScroll {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::SCROLL
+        }
+
+        fn on_scroll_event(&mut self, scroll: ScrollInfo, _this: Id, _ctx: &mut Context) {
+            self.list.borrow_mut().push(scroll);
+        }
+    }
+
+    let list = Rc::new(RefCell::new(Vec::new()));
+    gui.create_control()
+        .behaviour(TestScroll { list: list.clone() })
+        .build(&mut gui);
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, 1.0), TouchPhase::Moved);
+    let pixels = MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 8.0));
+    gui.mouse_scroll(0, pixels, TouchPhase::Started);
+    gui.mouse_scroll(0, pixels, TouchPhase::Ended);
+
+    let list = list.borrow();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[0].delta, [0.0, 50.0]);
+    assert!(!list[0].is_precise);
+    assert_eq!(list[0].phase, ScrollPhase::Moved);
+    assert_eq!(list[1].delta, [0.0, 4.0]);
+    assert!(list[1].is_precise);
+    assert_eq!(list[1].phase, ScrollPhase::Started);
+    assert_eq!(list[2].phase, ScrollPhase::Ended);
+}
+
 #[test]
 fn events() {
     init_logger();
@@ -841,3 +947,4640 @@ fn event_order() {
 
     assert_eq!(list.borrow_mut().as_slice(), &[0, 1, 2, 3]);
 }
+
+#[test]
+fn modal() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Focusable;
+    impl Behaviour for Focusable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::MOUSE | InputFlags::FOCUS
+        }
+    }
+
+    let background = gui.create_control().behaviour(Focusable).build(&mut gui);
+    let modal = gui
+        .create_control()
+        .margins([25.0, 25.0, -25.0, -25.0])
+        .behaviour(Focusable)
+        .build(&mut gui);
+
+    gui.mouse_moved(0, 10.0, 10.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.current_focus, Some(background));
+
+    gui.push_modal(modal);
+    assert_eq!(gui.current_modal(), Some(modal));
+    assert_eq!(gui.current_focus, None);
+
+    // the background cannot be hovered or focused while the modal is open
+    gui.mouse_moved(0, 12.0, 12.0);
+    assert_eq!(gui.inputs.get_mouse(0).unwrap().current_mouse, None);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.current_focus, None);
+    gui.set_focus(Some(background));
+    assert_eq!(gui.current_focus, None);
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.current_focus, Some(modal));
+
+    assert_eq!(gui.pop_modal(), Some(modal));
+    assert_eq!(gui.current_focus, Some(background));
+
+    // deactivating the modal also pops it
+    gui.push_modal(modal);
+    gui.deactive_control(modal);
+    gui.mouse_moved(0, 10.0, 10.0);
+    assert_eq!(gui.current_modal(), None);
+    assert_eq!(gui.current_focus, Some(background));
+}
+
+#[test]
+fn focus_scope() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Focusable;
+    impl Behaviour for Focusable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS
+        }
+    }
+
+    let before = gui.create_control().behaviour(Focusable).build(&mut gui);
+    let dialog = gui.create_control().focus_scope(true).build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(dialog)
+        .behaviour(Focusable)
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .parent(dialog)
+        .behaviour(Focusable)
+        .build(&mut gui);
+    let after = gui.create_control().behaviour(Focusable).build(&mut gui);
+    gui.get_context();
+
+    // inside the scope, the focus wraps around
+    assert_eq!(gui.next_focus(a, false), Some(b));
+    assert_eq!(gui.next_focus(b, false), Some(a));
+    assert_eq!(gui.next_focus(a, true), Some(b));
+
+    // outside of it, the scope is traversed as usual
+    assert_eq!(gui.next_focus(before, false), Some(a));
+    assert_eq!(gui.next_focus(after, true), Some(b));
+}
+
+#[test]
+fn focus_in_direction() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Focusable;
+    impl Behaviour for Focusable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS
+        }
+    }
+
+    // a 2x2 grid of buttons, with the bottom-right one a little to the right
+    let mut button = |margins: [f32; 4]| {
+        gui.create_control()
+            .anchors([0.0; 4])
+            .margins(margins)
+            .behaviour(Focusable)
+            .build(&mut gui)
+    };
+    let top_left = button([0.0, 0.0, 10.0, 10.0]);
+    let top_right = button([40.0, 0.0, 50.0, 10.0]);
+    let bottom_left = button([0.0, 40.0, 10.0, 50.0]);
+    let bottom_right = button([60.0, 40.0, 70.0, 50.0]);
+    gui.get_context();
+
+    // without focus, the first control is focused
+    assert_eq!(gui.focus_in_direction(FocusDirection::Up), Some(top_left));
+
+    assert_eq!(
+        gui.focus_in_direction(FocusDirection::Right),
+        Some(top_right)
+    );
+    assert_eq!(gui.focus_in_direction(FocusDirection::Up), None);
+    assert_eq!(gui.current_focus, Some(top_right));
+    assert_eq!(
+        gui.focus_in_direction(FocusDirection::Down),
+        Some(bottom_right)
+    );
+    assert_eq!(
+        gui.focus_in_direction(FocusDirection::Left),
+        Some(bottom_left)
+    );
+    assert_eq!(gui.focus_in_direction(FocusDirection::Up), Some(top_left));
+}
+
+#[test]
+fn gamepad_navigate() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Clickable(Rc<RefCell<u32>>);
+    impl Behaviour for Clickable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS | InputFlags::MOUSE
+        }
+        fn on_mouse_event(&mut self, mouse: MouseInfo, _this: Id, _ctx: &mut Context) {
+            if mouse.click() {
+                *self.0.borrow_mut() += 1;
+            }
+        }
+    }
+
+    let clicks = Rc::new(RefCell::new(0));
+    let left = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 10.0, 10.0])
+        .behaviour(Clickable(clicks.clone()))
+        .build(&mut gui);
+    let right = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([20.0, 0.0, 30.0, 10.0])
+        .behaviour(Clickable(clicks.clone()))
+        .build(&mut gui);
+    gui.get_context();
+
+    // without focus, there is nothing to click
+    assert!(!gui.gamepad_navigate(GamepadInput::Accept));
+    assert_eq!(*clicks.borrow(), 0);
+
+    gui.set_focus(Some(left));
+    assert!(gui.gamepad_navigate(GamepadInput::Direction(FocusDirection::Right)));
+    assert_eq!(gui.current_focus, Some(right));
+    assert!(gui.gamepad_navigate(GamepadInput::Accept));
+    assert_eq!(*clicks.borrow(), 1);
+
+    // no modal to close
+    assert!(!gui.gamepad_navigate(GamepadInput::Back));
+}
+
+#[test]
+fn multiple_windows() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Focusable;
+    impl Behaviour for Focusable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS | InputFlags::MOUSE
+        }
+    }
+
+    let window = WindowId::from(1);
+    let main = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 10.0, 10.0])
+        .behaviour(Focusable)
+        .build(&mut gui);
+    let root = gui.add_window(window, 50.0, 50.0);
+    assert_eq!(gui.window_root(window), Some(root));
+    let other = gui
+        .create_control()
+        .parent(root)
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 10.0, 10.0])
+        .behaviour(Focusable)
+        .build(&mut gui);
+    gui.get_context();
+
+    // each window only hits and focus its own controls
+    assert_eq!(gui.controls_at(5.0, 5.0), vec![main, Id::ROOT_ID]);
+    gui.set_focus(Some(main));
+    assert_eq!(gui.next_focus(main, false), None);
+
+    gui.switch_window(Some(window));
+    assert_eq!(gui.controls_at(5.0, 5.0), vec![other, root]);
+    assert_eq!(gui.current_focus, None);
+    assert_eq!(gui.next_focus(root, false), Some(other));
+    gui.set_focus(Some(other));
+
+    // the focus is kept per window
+    gui.switch_window(None);
+    assert_eq!(gui.current_focus, Some(main));
+    gui.switch_window(Some(window));
+    assert_eq!(gui.current_focus, Some(other));
+
+    gui.remove_window(window);
+    assert_eq!(gui.current_focus, Some(main));
+    assert_eq!(gui.window_root(window), None);
+}
+
+#[test]
+fn accelerator() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let count = Rc::new(RefCell::new(0));
+    let ctrl_s = Accelerator::new(ModifiersState::CTRL, VirtualKeyCode::S);
+    {
+        let count = count.clone();
+        gui.add_accelerator(ctrl_s, move |_| *count.borrow_mut() += 1);
+    }
+
+    assert!(gui.call_accelerator(ctrl_s));
+    assert!(!gui.call_accelerator(Accelerator::new(ModifiersState::empty(), VirtualKeyCode::S)));
+    assert_eq!(*count.borrow(), 1);
+
+    gui.remove_accelerator(ctrl_s);
+    assert!(!gui.call_accelerator(ctrl_s));
+    assert_eq!(*count.borrow(), 1);
+
+    let accelerator = Accelerator::new(
+        ModifiersState::CTRL | ModifiersState::SHIFT,
+        VirtualKeyCode::Key1,
+    );
+    assert_eq!(accelerator.to_string(), "Ctrl+Shift+1");
+}
+
+#[test]
+fn shortcut() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let enabled = Rc::new(RefCell::new(false));
+    {
+        let enabled = enabled.clone();
+        gui.register_shortcut(ModifiersState::CTRL, VirtualKeyCode::Q, move |_| {
+            *enabled.borrow()
+        });
+    }
+
+    let ctrl_q = Accelerator::new(ModifiersState::CTRL, VirtualKeyCode::Q);
+    // not handled, so the key press would reach the focused control
+    assert!(!gui.call_shortcut(ctrl_q));
+    *enabled.borrow_mut() = true;
+    assert!(gui.call_shortcut(ctrl_q));
+
+    // a accelerator with the same keys, like the ones of a MenuBar, does not replace it
+    gui.add_accelerator(ctrl_q, |_| ());
+    gui.remove_accelerator(ctrl_q);
+    assert!(gui.call_shortcut(ctrl_q));
+
+    gui.unregister_shortcut(ModifiersState::CTRL, VirtualKeyCode::Q);
+    assert!(!gui.call_shortcut(ctrl_q));
+}
+
+#[test]
+fn spring() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let values = Rc::new(RefCell::new(Vec::new()));
+    {
+        let values = values.clone();
+        gui.add_continuous_animation(Spring::new(0.0, 10.0, move |x, _| {
+            values.borrow_mut().push(x)
+        }));
+    }
+    assert_eq!(*values.borrow(), [0.0]);
+
+    // a underdamped spring overshoots the target, but comes to rest at it
+    let mut spring = Spring::new(0.0, 10.0, |_, _: &mut Context| {}).with_params(170.0, 5.0);
+    let mut max = 0.0f32;
+    let mut frames = 0;
+    while spring.step(1.0 / 60.0) {
+        max = max.max(spring.value);
+        frames += 1;
+        assert!(frames < 60 * 10);
+    }
+    assert!(max > 10.0);
+    assert_eq!(spring.value, 10.0);
+}
+
+#[test]
+fn animation_sequence() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let track = |i: u8| {
+        let calls = calls.clone();
+        move |t: f32, _: f32, length: f32, _: &mut Context| calls.borrow_mut().push((i, t, length))
+    };
+
+    let mut sequence = AnimationSequence::new()
+        .then(1.0, track(0))
+        .then(3.0, track(1));
+    assert_eq!(sequence.length(), 4.0);
+
+    // added with the double of its length
+    sequence.on_update(0.0, 0.0, 8.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(0, 0.0, 2.0)]);
+    sequence.on_update(0.125, 0.125, 8.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(0, 0.5, 2.0)]);
+    sequence.on_update(0.5, 0.375, 8.0, &mut gui.get_context());
+    assert_eq!(
+        calls.borrow_mut().take(),
+        [(0, 1.0, 2.0), (1, 0.0, 6.0), (1, 1.0 / 3.0, 6.0)]
+    );
+    sequence.on_update(1.0, 0.5, 8.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(1, 1.0, 6.0)]);
+
+    let mut group = AnimationGroup::new()
+        .with(1.0, track(0))
+        .with(2.0, track(1));
+    assert_eq!(group.length(), 2.0);
+
+    group.on_update(0.0, 0.0, 2.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(0, 0.0, 1.0), (1, 0.0, 2.0)]);
+    group.on_update(0.75, 0.75, 2.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(0, 1.0, 1.0), (1, 0.75, 2.0)]);
+    group.on_update(1.0, 0.25, 2.0, &mut gui.get_context());
+    assert_eq!(calls.borrow_mut().take(), [(1, 1.0, 2.0)]);
+}
+
+#[test]
+fn interval() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Tick;
+    struct Counter(Rc<RefCell<u32>>);
+    impl Behaviour for Counter {
+        fn on_event(&mut self, event: Box<dyn std::any::Any>, _this: Id, _ctx: &mut Context) {
+            if event.is::<Tick>() {
+                *self.0.borrow_mut() += 1;
+            }
+        }
+    }
+
+    let count = Rc::new(RefCell::new(0));
+    let a = gui
+        .create_control()
+        .behaviour(Counter(count.clone()))
+        .build(&mut gui);
+
+    let period = Duration::from_millis(10);
+    let interval = gui.schedule_interval(a, || Tick, period);
+    gui.handle_scheduled_event();
+    assert_eq!(*count.borrow(), 0);
+
+    // missed ticks are skipped
+    std::thread::sleep(period * 3);
+    gui.handle_scheduled_event();
+    assert_eq!(*count.borrow(), 1);
+    std::thread::sleep(period * 2);
+    gui.handle_scheduled_event();
+    assert_eq!(*count.borrow(), 2);
+
+    gui.cancel_interval(interval);
+    std::thread::sleep(period * 2);
+    assert_eq!(gui.handle_scheduled_event(), None);
+    assert_eq!(*count.borrow(), 2);
+
+    // removing the control stops the interval
+    gui.schedule_interval(a, || Tick, period);
+    gui.remove_control(a);
+    gui.get_context();
+    assert!(gui.intervals.is_empty());
+    assert_eq!(gui.handle_scheduled_event(), None);
+}
+
+#[test]
+fn scene_round_trip() {
+    use crate::{
+        graphics::{Shadow, Texture},
+        layouts::VBoxLayout,
+        Color,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let parent = gui
+        .create_control()
+        .margins([10.0, 10.0, -10.0, -10.0])
+        .graphic(Texture::new(1, [0.0, 0.0, 0.5, 0.5]).with_color(Color::from_u32(0x336699ff)))
+        .shadow(Shadow::default())
+        .layout(VBoxLayout::new(2.0, [0.0; 4], -1))
+        .build(&mut gui);
+    gui.create_control()
+        .parent(parent)
+        .min_size([20.0, 30.0])
+        .expand_x(true)
+        .opacity(0.5)
+        .active(false)
+        .build(&mut gui);
+
+    let scene = gui.to_scene();
+    assert_eq!(scene.controls.len(), 1);
+    let node = &scene.controls[0];
+    assert_eq!(node.margins, [10.0, 10.0, -10.0, -10.0]);
+    assert!(node.layout.as_ref().unwrap().ends_with("VBoxLayout"));
+    assert_eq!(node.children.len(), 1);
+    assert_eq!(node.children[0].min_size, [20.0, 30.0]);
+    assert_eq!(node.children[0].layout, None);
+    assert!(!node.children[0].active);
+
+    let json = serde_json::to_string(&scene).unwrap();
+    let loaded: crate::scene::Scene = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, scene);
+
+    let restored = Gui::from_scene(100.0, 100.0, 1.0, Fonts::new(), &loaded);
+    let mut restored_scene = restored.to_scene();
+    // layouts are not restored
+    restored_scene.controls[0].layout = scene.controls[0].layout.clone();
+    assert_eq!(restored_scene, scene);
+}
+
+#[test]
+fn accessibility_tree() {
+    use crate::{accessibility::Role, text::Text};
+
+    struct TestButton;
+    impl Behaviour for TestButton {
+        fn accessibility_role(&self) -> Role {
+            Role::Button
+        }
+    }
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    assert!(gui.take_accessibility_dirty());
+    assert!(!gui.take_accessibility_dirty());
+
+    let button = gui.create_control().behaviour(TestButton).build(&mut gui);
+    gui.create_control()
+        .parent(button)
+        .graphic(Text::new("Ok".into(), (0, 0), Default::default()))
+        .build(&mut gui);
+    let hidden = gui.create_control().active(false).build(&mut gui);
+    assert!(gui.take_accessibility_dirty());
+
+    let tree = gui.accessibility_tree();
+    assert_eq!(tree.role, Role::Window);
+    assert_eq!(tree.children.len(), 1);
+    let node = &tree.children[0];
+    assert_eq!(node.id, button);
+    assert_eq!(node.role, Role::Button);
+    assert_eq!(node.name.as_deref(), Some("Ok"));
+    assert_eq!(node.children[0].role, Role::Label);
+    assert_eq!(node.children[0].name.as_deref(), Some("Ok"));
+
+    gui.active_control(hidden);
+    assert!(gui.take_accessibility_dirty());
+    assert_eq!(gui.accessibility_tree().children.len(), 2);
+}
+
+#[test]
+fn drag_and_drop() {
+    use std::any::Any;
+
+    struct Source {
+        ended: Rc<RefCell<Option<bool>>>,
+    }
+    impl Behaviour for Source {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::MOUSE | InputFlags::DRAG
+        }
+
+        fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+            if mouse.event == MouseEvent::Moved && mouse.is_dragging() {
+                ctx.start_drag(mouse.id, this, 42u32, None);
+            }
+        }
+
+        fn on_drag_end(&mut self, dropped: bool, _this: Id, _ctx: &mut Context) {
+            *self.ended.borrow_mut() = Some(dropped);
+        }
+    }
+
+    struct Target {
+        over: Rc<RefCell<bool>>,
+        dropped: Rc<RefCell<Option<u32>>>,
+    }
+    impl Behaviour for Target {
+        fn on_drag_over(
+            &mut self,
+            payload: &dyn Any,
+            _position: [f32; 2],
+            _this: Id,
+            _ctx: &mut Context,
+        ) -> bool {
+            let accept = payload.is::<u32>();
+            *self.over.borrow_mut() = accept;
+            accept
+        }
+
+        fn on_drag_exit(&mut self, _this: Id, _ctx: &mut Context) {
+            *self.over.borrow_mut() = false;
+        }
+
+        fn on_drop(
+            &mut self,
+            payload: Box<dyn Any>,
+            _position: [f32; 2],
+            _this: Id,
+            _ctx: &mut Context,
+        ) {
+            *self.dropped.borrow_mut() = payload.downcast_ref::<u32>().copied();
+        }
+    }
+
+    init_logger();
+
+    let ended = Rc::new(RefCell::new(None));
+    let over = Rc::new(RefCell::new(false));
+    let dropped = Rc::new(RefCell::new(None));
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    gui.create_control()
+        .anchors([0.0, 0.0, 0.5, 1.0])
+        .behaviour(Source {
+            ended: ended.clone(),
+        })
+        .build(&mut gui);
+    let target = gui
+        .create_control()
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .behaviour(Target {
+            over: over.clone(),
+            dropped: dropped.clone(),
+        })
+        .build(&mut gui);
+    // a child without behaviour, that lets the drag through to the target
+    gui.create_control().parent(target).build(&mut gui);
+
+    gui.mouse_moved(0, 25.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 46.0, 50.0);
+    assert!(gui.is_drag_and_dropping());
+    assert!(!*over.borrow());
+
+    gui.mouse_moved(0, 75.0, 50.0);
+    assert!(*over.borrow());
+
+    gui.mouse_moved(0, 30.0, 50.0);
+    assert!(!*over.borrow());
+
+    gui.mouse_moved(0, 75.0, 50.0);
+    gui.mouse_up(0, MouseButton::Left);
+    assert!(!gui.is_drag_and_dropping());
+    assert!(!*over.borrow());
+    assert_eq!(*dropped.borrow(), Some(42));
+    assert_eq!(*ended.borrow(), Some(true));
+}
+
+#[test]
+fn file_drop() {
+    use std::path::{Path, PathBuf};
+    use winit::event::WindowEvent;
+
+    struct DropZone {
+        hover: Rc<RefCell<bool>>,
+        files: Rc<RefCell<Vec<PathBuf>>>,
+    }
+    impl Behaviour for DropZone {
+        fn on_file_hover(&mut self, _path: &Path, _this: Id, _ctx: &mut Context) -> bool {
+            *self.hover.borrow_mut() = true;
+            true
+        }
+
+        fn on_file_hover_exit(&mut self, _this: Id, _ctx: &mut Context) {
+            *self.hover.borrow_mut() = false;
+        }
+
+        fn on_file_drop(&mut self, path: &Path, _this: Id, _ctx: &mut Context) {
+            *self.hover.borrow_mut() = false;
+            self.files.borrow_mut().push(path.to_owned());
+        }
+    }
+
+    init_logger();
+
+    let hover = Rc::new(RefCell::new(false));
+    let files = Rc::new(RefCell::new(Vec::new()));
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    gui.create_control()
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .behaviour(DropZone {
+            hover: hover.clone(),
+            files: files.clone(),
+        })
+        .build(&mut gui);
+
+    // outside of the drop zone
+    gui.mouse_moved(0, 25.0, 50.0);
+    gui.handle_event(&WindowEvent::HoveredFile("a.txt".into()));
+    assert!(!*hover.borrow());
+    gui.handle_event(&WindowEvent::HoveredFileCancelled);
+
+    gui.mouse_moved(0, 75.0, 50.0);
+    gui.handle_event(&WindowEvent::HoveredFile("a.txt".into()));
+    assert!(*hover.borrow());
+    gui.handle_event(&WindowEvent::HoveredFileCancelled);
+    assert!(!*hover.borrow());
+
+    gui.handle_event(&WindowEvent::HoveredFile("a.txt".into()));
+    gui.handle_event(&WindowEvent::HoveredFile("b.txt".into()));
+    gui.handle_event(&WindowEvent::DroppedFile("a.txt".into()));
+    gui.handle_event(&WindowEvent::DroppedFile("b.txt".into()));
+    assert_eq!(
+        *files.borrow(),
+        vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")]
+    );
+}
+
+#[test]
+fn hit_testing() {
+    struct Blocker;
+    impl Behaviour for Blocker {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::BLOCK_MOUSE
+        }
+    }
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let left = gui
+        .create_control()
+        .anchors([0.0, 0.0, 0.5, 1.0])
+        .build(&mut gui);
+    let inner = gui
+        .create_control()
+        .parent(left)
+        .margins([10.0, 10.0, -10.0, -10.0])
+        .build(&mut gui);
+    let hidden = gui
+        .create_control()
+        .parent(left)
+        .active(false)
+        .build(&mut gui);
+    let right = gui
+        .create_control()
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .behaviour(Blocker)
+        .build(&mut gui);
+    gui.create_control().parent(right).build(&mut gui);
+
+    assert_eq!(gui.controls_at(25.0, 50.0), vec![inner, left, Id::ROOT_ID]);
+    assert_eq!(gui.controls_at(5.0, 50.0), vec![left, Id::ROOT_ID]);
+    assert_eq!(gui.control_at(75.0, 50.0), Some(right));
+
+    gui.active_control(hidden);
+    assert_eq!(gui.control_at(25.0, 50.0), Some(hidden));
+}
+
+#[test]
+fn pixel_snapping() {
+    init_logger();
+
+    for &scale_factor in &[1.25, 1.5] {
+        let mut gui = Gui::new(100.0, 100.0, scale_factor, Fonts::new());
+        gui.set_pixel_snapping(true);
+
+        // three panels splitting the width in thirds
+        let panels = (0..3)
+            .map(|i| {
+                let i = i as f32;
+                gui.create_control()
+                    .anchors([i / 3.0, 0.0, (i + 1.0) / 3.0, 1.0])
+                    .margins([0.0, 0.3, 0.0, 0.0])
+                    .build(&mut gui)
+            })
+            .collect::<Vec<_>>();
+        gui.update_layout();
+
+        let rects = panels
+            .iter()
+            .map(|x| *gui.get_rect(*x).unwrap().get_rect())
+            .collect::<Vec<_>>();
+        for rect in &rects {
+            for x in rect {
+                let physical = x * scale_factor as f32;
+                assert!(
+                    (physical - physical.round()).abs() < 1e-3,
+                    "{:?} is not on the pixel grid, at scale {}",
+                    rect,
+                    scale_factor
+                );
+            }
+        }
+        // no gaps or overlaps between adjacent panels
+        assert_eq!(rects[0][2], rects[1][0]);
+        assert_eq!(rects[1][2], rects[2][0]);
+
+        gui.set_pixel_snapping(false);
+        gui.update_layout();
+        let rect = *gui.get_rect(panels[0]).unwrap().get_rect();
+        assert_eq!(rect[1], 0.3);
+    }
+}
+
+#[test]
+fn render_to_image() {
+    use crate::{graphics::Texture, render::software::SoftwareRenderer, Color, GuiRender};
+
+    init_logger();
+
+    let mut gui = Gui::new(4.0, 4.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 4.0, 4.0]);
+    let red = gui
+        .create_control()
+        .anchors([0.0, 0.0, 0.5, 0.5])
+        .graphic(Texture::new(1, [0.0, 0.0, 1.0, 1.0]).with_color(Color::from_u32(0xff0000ff)))
+        .build(&mut gui);
+
+    let mut renderer = SoftwareRenderer::new();
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    let image = gui_render.render_to_image(&mut gui.get_render_context(), &mut renderer, 4, 4);
+    assert_eq!(image.len(), 4 * 4 * 4);
+    assert_eq!(&image[..4], &[255, 0, 0, 255]);
+    assert_eq!(&image[(3 * 4 + 3) * 4..], &[0, 0, 0, 0]);
+
+    let (size, image) =
+        gui_render.render_control_to_image(&mut gui.get_render_context(), &mut renderer, red);
+    assert_eq!(size, [2, 2]);
+    assert_eq!(image, [255, 0, 0, 255].repeat(4));
+}
+
+#[test]
+fn glyph_atlas_pages() {
+    use crate::{
+        font::Font, graphics::Text, render::software::SoftwareRenderer, text::TextStyle, GuiRender,
+    };
+
+    init_logger();
+
+    let mut fonts = Fonts::new();
+    let font_id = fonts.add(Font::new(include_bytes!(
+        "../../examples/NotoSans-Regular.ttf"
+    )));
+    let mut gui = Gui::new(200.0, 50.0, 1.0, fonts);
+    gui.set_root_rect([0.0, 0.0, 200.0, 50.0]);
+    let style = TextStyle {
+        font_id,
+        ..Default::default()
+    };
+    gui.create_control()
+        .graphic(Text::new("abcdefghij".to_string(), (-1, -1), style))
+        .build(&mut gui);
+
+    let mut renderer = SoftwareRenderer::new();
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+    gui_render.set_atlas_limits(32, 16);
+
+    gui_render.render_to_image(&mut gui.get_render_context(), &mut renderer, 200, 50);
+    let metrics = gui_render.atlas_metrics();
+    assert!(metrics.pages > 1);
+    assert_eq!(metrics.used_glyphs, 10);
+    assert_eq!(metrics.cached_glyphs, 10);
+    assert_eq!(metrics.total_area, 32 * 32 * metrics.pages as u64);
+    assert!(metrics.occupancy() > 0.0 && metrics.occupancy() <= 1.0);
+}
+
+#[test]
+fn render_damage() {
+    use crate::{graphics::Texture, render::software::SoftwareRenderer, Color, GuiRender};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 100.0, 100.0]);
+    let texture = Texture::new(1, [0.0, 0.0, 1.0, 1.0]);
+    let a = gui
+        .create_control()
+        .anchors([0.0, 0.0, 0.0, 0.0])
+        .margins([10.0, 10.0, 20.0, 20.0])
+        .graphic(texture.clone())
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .anchors([0.0, 0.0, 0.0, 0.0])
+        .margins([50.0, 50.0, 60.0, 60.0])
+        .graphic(texture)
+        .build(&mut gui);
+
+    let mut renderer = SoftwareRenderer::new();
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    gui_render.render(&mut gui.get_render_context(), &mut renderer);
+    assert_eq!(gui_render.damage().len(), 2);
+
+    gui_render.render(&mut gui.get_render_context(), &mut renderer);
+    assert_eq!(gui_render.damage(), &[] as &[[f32; 4]]);
+
+    gui.get_context()
+        .get_graphic_mut(a)
+        .set_color(Color::from_u32(0xff0000ff));
+    gui_render.render(&mut gui.get_render_context(), &mut renderer);
+    assert_eq!(gui_render.damage(), &[[10.0, 10.0, 20.0, 20.0]]);
+
+    gui.get_context().set_margins(b, [50.0, 50.0, 70.0, 60.0]);
+    gui_render.render(&mut gui.get_render_context(), &mut renderer);
+    assert_eq!(gui_render.damage(), &[[50.0, 50.0, 70.0, 60.0]]);
+
+    gui.remove_control(a);
+    gui_render.render(&mut gui.get_render_context(), &mut renderer);
+    assert_eq!(gui_render.damage(), &[[10.0, 10.0, 20.0, 20.0]]);
+}
+
+#[test]
+fn batch() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Started(Rc<RefCell<u32>>);
+    impl Behaviour for Started {
+        fn on_start(&mut self, _this: Id, _ctx: &mut Context) {
+            *self.0.borrow_mut() += 1;
+        }
+    }
+
+    let started = Rc::new(RefCell::new(0));
+    let ids = gui.batch(|gui| {
+        let ids = (0..10)
+            .map(|_| {
+                gui.create_control()
+                    .margins([10.0, 10.0, -10.0, -10.0])
+                    .behaviour(Started(started.clone()))
+                    .build(gui)
+            })
+            .collect::<Vec<_>>();
+        // nothing was processed yet
+        assert_eq!(*started.borrow(), 0);
+        assert_eq!(gui.get_context().get_rect(ids[0]), [0.0; 4]);
+        ids
+    });
+
+    assert_eq!(*started.borrow(), 10);
+    for id in ids {
+        assert_eq!(gui.get_context().get_rect(id), [10.0, 10.0, 90.0, 90.0]);
+    }
+
+    // a panic inside the batch does not leave the updates deferred
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        gui.batch(|gui| {
+            gui.create_control().build(gui);
+            panic!("panic inside batch");
+        })
+    }));
+    assert!(result.is_err());
+    let id = gui
+        .create_control()
+        .margins([10.0, 10.0, -10.0, -10.0])
+        .behaviour(Started(started.clone()))
+        .build(&mut gui);
+    assert_eq!(gui.get_context().get_rect(id), [10.0, 10.0, 90.0, 90.0]);
+    assert_eq!(*started.borrow(), 11);
+}
+
+#[test]
+fn mouse_event_bubbling() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Card {
+        list: Rc<RefCell<Vec<(Id, MouseEvent)>>>,
+        handle: bool,
+    }
+    impl Behaviour for Card {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::CHILD_MOUSE
+        }
+        fn on_child_mouse_event(
+            &mut self,
+            mouse: MouseInfo,
+            target: Id,
+            _this: Id,
+            _ctx: &mut Context,
+        ) -> bool {
+            self.list.borrow_mut().push((target, mouse.event));
+            self.handle
+        }
+    }
+
+    struct Child {
+        stop: bool,
+    }
+    impl Behaviour for Child {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::MOUSE
+        }
+        fn on_mouse_event(&mut self, _mouse: MouseInfo, _this: Id, ctx: &mut Context) {
+            if self.stop {
+                ctx.stop_propagation();
+            }
+        }
+    }
+
+    let outer_list = Rc::new(RefCell::new(Vec::new()));
+    let inner_list = Rc::new(RefCell::new(Vec::new()));
+    let outer = gui
+        .create_control()
+        .behaviour(Card {
+            list: outer_list.clone(),
+            handle: false,
+        })
+        .build(&mut gui);
+    let inner = gui
+        .create_control()
+        .parent(outer)
+        .behaviour(Card {
+            list: inner_list.clone(),
+            handle: false,
+        })
+        .build(&mut gui);
+    let left = gui
+        .create_control()
+        .parent(inner)
+        .anchors([0.0, 0.0, 0.5, 1.0])
+        .behaviour(Child { stop: false })
+        .build(&mut gui);
+    gui.create_control()
+        .parent(inner)
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .behaviour(Child { stop: true })
+        .build(&mut gui);
+
+    gui.mouse_moved(0, 25.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+
+    let expected = vec![
+        (left, MouseEvent::Moved),
+        (left, MouseEvent::Down(MouseButton::Left)),
+        (left, MouseEvent::Up(MouseButton::Left)),
+    ];
+    assert_eq!(*inner_list.borrow(), expected);
+    assert_eq!(*outer_list.borrow(), expected);
+
+    // the event stops at the first handler
+    gui.get_context().remove(inner);
+    let inner = gui
+        .create_control()
+        .parent(outer)
+        .behaviour(Card {
+            list: inner_list.clone(),
+            handle: true,
+        })
+        .build(&mut gui);
+    let left = gui
+        .create_control()
+        .parent(inner)
+        .anchors([0.0, 0.0, 0.5, 1.0])
+        .behaviour(Child { stop: false })
+        .build(&mut gui);
+    gui.create_control()
+        .parent(inner)
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .behaviour(Child { stop: true })
+        .build(&mut gui);
+    inner_list.borrow_mut().clear();
+    outer_list.borrow_mut().clear();
+
+    gui.mouse_moved(0, 26.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(inner_list.borrow().len(), 2);
+    assert_eq!(
+        inner_list.borrow()[1],
+        (left, MouseEvent::Down(MouseButton::Left))
+    );
+    assert!(outer_list.borrow().is_empty());
+
+    // the child can stop the bubbling
+    inner_list.borrow_mut().clear();
+    gui.mouse_moved(0, 75.0, 50.0);
+    gui.mouse_down(0, MouseButton::Right);
+    assert!(inner_list.borrow().is_empty());
+    assert!(outer_list.borrow().is_empty());
+}
+
+#[test]
+fn drag_button_and_threshold() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Pan {
+        dragging: Rc<RefCell<bool>>,
+    }
+    impl Behaviour for Pan {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::MOUSE | InputFlags::DRAG
+        }
+        fn drag_button(&self) -> MouseButton {
+            MouseButton::Right
+        }
+        fn on_mouse_event(&mut self, mouse: MouseInfo, _this: Id, _ctx: &mut Context) {
+            *self.dragging.borrow_mut() = mouse.is_dragging();
+        }
+    }
+
+    let dragging = Rc::new(RefCell::new(false));
+    gui.create_control()
+        .behaviour(Pan {
+            dragging: dragging.clone(),
+        })
+        .build(&mut gui);
+
+    // the left button does not start a drag
+    gui.mouse_moved(0, 10.0, 10.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 50.0, 10.0);
+    assert!(!*dragging.borrow());
+    gui.mouse_up(0, MouseButton::Left);
+
+    gui.mouse_down(0, MouseButton::Right);
+    gui.mouse_moved(0, 60.0, 10.0);
+    assert!(!*dragging.borrow());
+    gui.mouse_moved(0, 75.0, 10.0);
+    assert!(*dragging.borrow());
+    gui.mouse_up(0, MouseButton::Right);
+    gui.mouse_moved(0, 76.0, 10.0);
+    assert!(!*dragging.borrow());
+
+    // a smaller threshold
+    gui.set_drag_threshold(5.0);
+    assert_eq!(gui.drag_threshold(), 5.0);
+    gui.mouse_down(0, MouseButton::Right);
+    gui.mouse_moved(0, 82.0, 10.0);
+    assert!(*dragging.borrow());
+    gui.mouse_up(0, MouseButton::Right);
+}
+
+#[test]
+fn themes() {
+    use crate::{event::SetStyle, graphics::Texture, style::GuiTheme, Color};
+
+    init_logger();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Styled {
+        style: Rc<RefCell<Rc<String>>>,
+    }
+    impl Behaviour for Styled {
+        fn on_event(&mut self, event: Box<dyn std::any::Any>, _this: Id, _ctx: &mut Context) {
+            if let Some(SetStyle(style)) = event.downcast_ref::<SetStyle<String>>() {
+                *self.style.borrow_mut() = style.clone();
+            }
+        }
+    }
+
+    let light = Color::from_u32(0xeeeeeeff);
+    let dark = Color::from_u32(0x222222ff);
+    gui.add_theme(
+        "light",
+        GuiTheme::new()
+            .with_style("label", Rc::new("light label".to_string()))
+            .with_color("background", light),
+    );
+    gui.add_theme(
+        "dark",
+        GuiTheme::new()
+            .with_style("label", Rc::new("dark label".to_string()))
+            .with_color("background", dark),
+    );
+
+    let style = Rc::new(RefCell::new(Rc::new(String::new())));
+    gui.create_control()
+        .style_key("label")
+        .behaviour(Styled {
+            style: style.clone(),
+        })
+        .build(&mut gui);
+    let background = gui
+        .create_control()
+        .style_key("background")
+        .graphic(Texture::new(0, [0.0, 0.0, 1.0, 1.0]))
+        .build(&mut gui);
+    gui.get_context();
+    assert_eq!(style.borrow().as_str(), "");
+
+    assert!(gui.set_theme("dark"));
+    assert_eq!(gui.theme_name(), Some("dark"));
+    assert_eq!(style.borrow().as_str(), "dark label");
+    assert_eq!(gui.get_graphic(background).unwrap().get_color(), dark);
+
+    assert!(!gui.set_theme("sepia"));
+    assert_eq!(gui.theme_name(), Some("dark"));
+
+    gui.get_context().set_theme("light");
+    assert_eq!(style.borrow().as_str(), "light label");
+    assert_eq!(gui.get_graphic(background).unwrap().get_color(), light);
+
+    // controls started later receive the current theme
+    let style2 = Rc::new(RefCell::new(Rc::new(String::new())));
+    gui.create_control()
+        .style_key("label")
+        .behaviour(Styled {
+            style: style2.clone(),
+        })
+        .build(&mut gui);
+    gui.get_context();
+    assert_eq!(style2.borrow().as_str(), "light label");
+}
+
+#[test]
+fn gestures() {
+    use crate::{GestureInfo, GesturePhase};
+
+    init_logger();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Zoom {
+        gestures: Rc<RefCell<Vec<GestureInfo>>>,
+    }
+    impl Behaviour for Zoom {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::GESTURE
+        }
+        fn on_gesture(&mut self, gesture: GestureInfo, _this: Id, _ctx: &mut Context) {
+            self.gestures.borrow_mut().push(gesture);
+        }
+    }
+
+    let gestures = Rc::new(RefCell::new(Vec::new()));
+    gui.create_control()
+        .behaviour(Zoom {
+            gestures: gestures.clone(),
+        })
+        .build(&mut gui);
+    gui.get_context();
+
+    // a single touch is not a gesture
+    gui.touch(1, TouchPhase::Started, [40.0, 50.0]);
+    gui.touch(1, TouchPhase::Moved, [45.0, 50.0]);
+    assert!(gestures.borrow().is_empty());
+
+    gui.touch(2, TouchPhase::Started, [65.0, 50.0]);
+    gui.touch(2, TouchPhase::Moved, [85.0, 50.0]);
+    gui.touch(2, TouchPhase::Moved, [45.0, 90.0]);
+    gui.touch(1, TouchPhase::Ended, [45.0, 50.0]);
+    gui.touch(2, TouchPhase::Ended, [45.0, 90.0]);
+
+    let gestures = gestures.borrow();
+    assert_eq!(gestures.len(), 4);
+    assert_eq!(gestures[0].phase, GesturePhase::Started);
+    assert_eq!(gestures[0].center, [55.0, 50.0]);
+
+    // pinch out and pan
+    assert_eq!(gestures[1].phase, GesturePhase::Moved);
+    assert_eq!(gestures[1].scale, 2.0);
+    assert_eq!(gestures[1].rotation, 0.0);
+    assert_eq!(gestures[1].pan, [10.0, 0.0]);
+
+    // rotate a quarter turn, clockwise
+    assert!((gestures[2].rotation - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    assert_eq!(gestures[2].center, [45.0, 70.0]);
+
+    assert_eq!(gestures[3].phase, GesturePhase::Ended);
+}
+
+#[test]
+fn virtual_keyboard() {
+    use crate::{KeyboardKind, RequestVirtualKeyboard};
+
+    init_logger();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    struct Field;
+    impl Behaviour for Field {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS
+        }
+        fn on_focus_change(&mut self, focus: bool, _this: Id, ctx: &mut Context) {
+            ctx.request_virtual_keyboard(focus, KeyboardKind::Number);
+        }
+    }
+
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let requests_clone = requests.clone();
+    gui.set_virtual_keyboard_callback(move |request| requests_clone.borrow_mut().push(request));
+
+    let field = gui.create_control().behaviour(Field).build(&mut gui);
+    gui.set_focus(Some(field));
+    gui.set_focus(None);
+
+    assert_eq!(
+        requests.borrow().as_slice(),
+        &[
+            RequestVirtualKeyboard {
+                show: true,
+                kind: KeyboardKind::Number
+            },
+            RequestVirtualKeyboard {
+                show: false,
+                kind: KeyboardKind::Number
+            },
+        ]
+    );
+}
+
+#[test]
+fn scroll_bar_track_click() {
+    use crate::{graphics::Graphic, style::ButtonStyle, widgets::ScrollBar};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let [scroll_view, view, content, v_bar, v_handle] = [(); 5].map(|_| gui.reserve_id());
+
+    gui.create_control_reserved(scroll_view)
+        .behaviour_and_layout(ScrollView::new(
+            view,
+            content,
+            None,
+            Some((v_bar, v_handle)),
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ViewLayout::new(false, true))
+        .parent(scroll_view)
+        .build(&mut gui);
+    gui.create_control_reserved(content)
+        .parent(view)
+        .min_size([50.0, 1000.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(scroll_view)
+        .min_size([10.0, 10.0])
+        .behaviour(ScrollBar::new(v_handle, scroll_view, true, style))
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+
+    let page = gui.get_context().get_size(view)[1];
+    assert_eq!(gui.get_context().get_rect(content)[1], 0.0);
+
+    // a click below the handle scrolls one page down
+    let x = gui.get_context().get_rect(v_bar)[0] + 5.0;
+    gui.mouse_moved(0, x, 90.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.get_context().get_rect(content)[1], -page);
+
+    // and above it, one page up
+    gui.mouse_moved(0, x, 1.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.get_context().get_rect(content)[1], 0.0);
+}
+
+#[test]
+fn overlay_scroll_bars() {
+    use crate::{
+        graphics::Graphic,
+        style::{ButtonStyle, ScrollBarStyle},
+        widgets::ScrollBar,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let [scroll_view, view, content, v_bar, v_handle] = [(); 5].map(|_| gui.reserve_id());
+
+    gui.create_control_reserved(scroll_view)
+        .behaviour_and_layout(
+            ScrollView::new(view, content, None, Some((v_bar, v_handle))).with_scroll_bar_style(
+                ScrollBarStyle {
+                    overlay: true,
+                    fade_ms: 0,
+                },
+            ),
+        )
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ViewLayout::new(false, true))
+        .parent(scroll_view)
+        .build(&mut gui);
+    gui.create_control_reserved(content)
+        .parent(view)
+        .min_size([50.0, 1000.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(scroll_view)
+        .min_size([10.0, 10.0])
+        .behaviour(ScrollBar::new(v_handle, scroll_view, true, style))
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+
+    // the scroll bar is over the view, and hidden while idle
+    assert_eq!(gui.get_context().get_rect(view), [0.0, 0.0, 100.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(v_bar), [90.0, 0.0, 100.0, 100.0]);
+    assert_eq!(gui.get_context().get_opacity(v_bar), 0.0);
+
+    // and is shown while scrolling
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, -1.0), TouchPhase::Moved);
+    assert_eq!(gui.get_context().get_opacity(v_bar), 1.0);
+}
+
+#[test]
+fn scroll_view_getters() {
+    use crate::widgets::ScrollDelta;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let [scroll_view, view, content, v_bar, v_handle] = [(); 5].map(|_| gui.reserve_id());
+    let scroll = Rc::new(RefCell::new(ScrollView::new(
+        view,
+        content,
+        None,
+        Some((v_bar, v_handle)),
+    )));
+
+    gui.create_control_reserved(scroll_view)
+        .behaviour_and_layout(scroll.clone())
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ViewLayout::new(false, true))
+        .parent(scroll_view)
+        .build(&mut gui);
+    gui.create_control_reserved(content)
+        .parent(view)
+        .min_size([50.0, 1000.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(scroll_view)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .build(&mut gui);
+
+    gui.get_context();
+    assert_eq!(scroll.borrow().content_size(), [50.0, 1000.0]);
+    assert_eq!(scroll.borrow().viewport_size(), [90.0, 100.0]);
+    assert_eq!(scroll.borrow().scroll_offset(), [0.0, 0.0]);
+
+    gui.send_event_to(
+        scroll_view,
+        Box::new(ScrollDelta {
+            delta: [0.0, -30.0],
+        }),
+    );
+    gui.get_context();
+    assert_eq!(scroll.borrow().scroll_offset(), [0.0, 30.0]);
+}
+
+#[test]
+fn deselectable_button_group() {
+    use crate::{
+        graphics::Graphic,
+        style::TabStyle,
+        widgets::{ButtonGroup, TabButton},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(TabStyle {
+        unselected: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        selected: Graphic::None,
+    });
+
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let group = ButtonGroup::new_deselectable({
+        let changes = changes.clone();
+        move |selected, _| changes.borrow_mut().push(selected)
+    });
+
+    let page = gui.create_control().build(&mut gui);
+    let button = gui
+        .create_control()
+        .min_size([100.0, 100.0])
+        .behaviour(TabButton::new(group.clone(), page, false, style))
+        .build(&mut gui);
+    gui.get_context();
+
+    // clicking selects the button
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(group.selected(), Some(button));
+
+    // and clicking it again deselects it
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(group.selected(), None);
+    assert!(!gui.get_context().is_active(page));
+
+    assert_eq!(&*changes.borrow(), &[Some(button), None]);
+}
+
+#[test]
+fn closable_reorderable_tabs() {
+    use crate::{
+        graphics::Graphic,
+        layouts::HBoxLayout,
+        style::TabStyle,
+        widgets::{ButtonGroup, TabButton, TabClosed},
+    };
+    use std::any::Any;
+
+    struct TabStrip {
+        closed: Rc<RefCell<Vec<Id>>>,
+    }
+    impl Behaviour for TabStrip {
+        fn on_event(&mut self, event: Box<dyn Any>, _this: Id, _ctx: &mut Context) {
+            if let Some(&TabClosed { id }) = event.downcast_ref() {
+                self.closed.borrow_mut().push(id);
+            }
+        }
+    }
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(TabStyle {
+        unselected: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        selected: Graphic::None,
+    });
+    let group = ButtonGroup::new(|_, _| {});
+    let closed = Rc::new(RefCell::new(Vec::new()));
+
+    let strip = gui
+        .create_control()
+        .behaviour(TabStrip {
+            closed: closed.clone(),
+        })
+        .layout(HBoxLayout::new(0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let [first, second, close] = [(); 3].map(|_| gui.reserve_id());
+    for (tab, selected) in [(first, true), (second, false)] {
+        let page = gui.create_control().build(&mut gui);
+        gui.create_control_reserved(tab)
+            .parent(strip)
+            .min_size([50.0, 100.0])
+            .expand_x(true)
+            .behaviour(
+                TabButton::new(group.clone(), page, selected, style.clone())
+                    .with_close_button(close)
+                    .with_reordering(true),
+            )
+            .build(&mut gui);
+    }
+    gui.create_control_reserved(close)
+        .parent(first)
+        .anchors([1.0, 0.0, 1.0, 0.0])
+        .margins([-10.0, 0.0, 0.0, 10.0])
+        .build(&mut gui);
+
+    assert_eq!(gui.get_context().get_all_children(strip), &[first, second]);
+
+    // dragging the first tab past the middle of the second swaps them
+    gui.mouse_moved(0, 10.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 40.0, 50.0);
+    gui.mouse_moved(0, 80.0, 50.0);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.get_context().get_all_children(strip), &[second, first]);
+    assert_eq!(gui.get_context().get_rect(first), [50.0, 0.0, 100.0, 100.0]);
+
+    // clicking the close button sends TabClosed to the strip, and selects the neighbour tab
+    assert_eq!(group.selected(), Some(first));
+    gui.mouse_moved(0, 95.0, 5.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(&*closed.borrow(), &[first]);
+    assert_eq!(group.selected(), Some(second));
+}
+
+#[test]
+fn toggle_switch() {
+    use crate::{
+        graphics::Graphic,
+        style::{ButtonStyle, OnFocusStyle},
+        widgets::Toggle,
+        Color,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let button_style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let background_style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let value = Rc::new(RefCell::new(None));
+
+    let [toggle, track, knob] = [(); 3].map(|_| gui.reserve_id());
+    gui.create_control_reserved(toggle)
+        .behaviour(Toggle::new_switch(
+            track,
+            knob,
+            true,
+            [Color::BLACK, Color::WHITE],
+            button_style,
+            background_style,
+            {
+                let value = value.clone();
+                move |_, _, x| *value.borrow_mut() = Some(x)
+            },
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(track)
+        .parent(toggle)
+        .build(&mut gui);
+    gui.create_control_reserved(knob)
+        .parent(track)
+        .min_size([20.0, 0.0])
+        .build(&mut gui);
+
+    // the knob starts on the right, as the switch is on
+    assert_eq!(gui.get_context().get_rect(knob), [80.0, 0.0, 100.0, 100.0]);
+    assert_eq!(*value.borrow(), Some(true));
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(*value.borrow(), Some(false));
+}
+
+#[test]
+fn hoverable_clamped() {
+    use crate::{graphics::Text, widgets::Hoverable};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let hover = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([5.0, 5.0, 5.0, 5.0])
+        .min_size([30.0, 20.0])
+        .build(&mut gui);
+    let label = gui
+        .create_control()
+        .parent(hover)
+        .graphic(Text::new(String::new(), (0, 0), Default::default()))
+        .build(&mut gui);
+    gui.create_control()
+        .behaviour(Hoverable::new(hover, label, "hover".to_owned()))
+        .build(&mut gui);
+
+    gui.mouse_moved(0, 10.0, 10.0);
+    assert!(gui.get_context().is_active(hover));
+    assert_eq!(gui.get_context().get_rect(hover), [15.0, 15.0, 45.0, 35.0]);
+
+    // near the corner, the hover is moved to stay inside the root control
+    gui.mouse_moved(0, 95.0, 95.0);
+    assert_eq!(
+        gui.get_context().get_rect(hover),
+        [70.0, 80.0, 100.0, 100.0]
+    );
+
+    gui.mouse_moved(0, 200.0, 200.0);
+    assert!(!gui.get_context().is_active(hover));
+}
+
+#[test]
+fn debug_path() {
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let surface = gui.create_control().name("surface").build(&mut gui);
+    gui.create_control().parent(surface).build(&mut gui);
+    let list = gui.create_control().parent(surface).build(&mut gui);
+    let text = gui
+        .create_control()
+        .parent(list)
+        .name("text")
+        .build(&mut gui);
+
+    assert_eq!(gui.debug_path(Id::ROOT_ID), "root");
+    assert_eq!(gui.debug_path(surface), "root/surface");
+    assert_eq!(gui.debug_path(list), "root/surface/[1]");
+    assert_eq!(gui.debug_path(text), "root/surface/[1]/text");
+
+    gui.remove_control(text);
+    gui.get_context();
+    assert_eq!(gui.debug_path(text), text.to_string());
+}
+
+#[test]
+fn find_by_name() {
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let list = gui.create_control().name("list").build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(list)
+        .name("item")
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .parent(list)
+        .name("item")
+        .build(&mut gui);
+
+    assert_eq!(gui.find_by_name("list"), Some(list));
+    assert_eq!(gui.find_by_name("item"), Some(a));
+    assert_eq!(gui.find_all_by_name("item"), &[a, b]);
+    assert_eq!(gui.find_by_name("none"), None);
+    assert!(gui.find_all_by_name("none").is_empty());
+
+    gui.remove_control(a);
+    gui.get_context();
+    assert_eq!(gui.find_by_name("item"), Some(b));
+    assert_eq!(gui.find_all_by_name("item"), &[b]);
+
+    gui.remove_control(list);
+    gui.get_context();
+    assert_eq!(gui.find_by_name("list"), None);
+    assert!(gui.find_all_by_name("item").is_empty());
+}
+
+#[test]
+fn max_size() {
+    use crate::layouts::{FlexLayout, VBoxLayout};
+
+    let mut gui = Gui::new(200.0, 100.0, 1.0, Fonts::new());
+
+    // the default layout centers a filling control capped by its max size
+    let column = gui.create_control().max_width(100.0).build(&mut gui);
+    assert_eq!(
+        gui.get_context().get_rect(column),
+        [50.0, 0.0, 150.0, 100.0]
+    );
+
+    // the min size takes precedence over the max size
+    gui.get_context()
+        .set_max_size(column, [10.0, f32::INFINITY]);
+    gui.get_context().set_min_size(column, [20.0, 0.0]);
+    assert_eq!(
+        gui.get_context().get_rect(column),
+        [90.0, 0.0, 110.0, 100.0]
+    );
+    gui.remove_control(column);
+
+    // a capped child of a flex layout gives its space to its siblings
+    let row = gui
+        .create_control()
+        .layout(FlexLayout::new(false, 0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(row)
+        .flex(1.0, 1.0)
+        .max_size([40.0, 50.0])
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .parent(row)
+        .flex(1.0, 1.0)
+        .build(&mut gui);
+    assert_eq!(gui.get_context().get_rect(a), [0.0, 25.0, 40.0, 75.0]);
+    assert_eq!(gui.get_context().get_rect(b), [40.0, 0.0, 200.0, 100.0]);
+    gui.remove_control(row);
+
+    // a expanding child of a box layout does not grow above its max size
+    let column = gui
+        .create_control()
+        .layout(VBoxLayout::new(0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(column)
+        .expand_y(true)
+        .max_height(30.0)
+        .build(&mut gui);
+    let b = gui.create_control().parent(column).build(&mut gui);
+    assert_eq!(gui.get_context().get_rect(a), [0.0, 0.0, 200.0, 30.0]);
+    assert_eq!(gui.get_context().get_rect(b), [0.0, 30.0, 200.0, 30.0]);
+}
+
+#[test]
+fn visibility() {
+    use crate::layouts::VBoxLayout;
+
+    init_logger();
+
+    struct Focusable;
+    impl Behaviour for Focusable {
+        fn input_flags(&self) -> InputFlags {
+            InputFlags::FOCUS | InputFlags::MOUSE
+        }
+    }
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let column = gui
+        .create_control()
+        .layout(VBoxLayout::new(0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let error = gui
+        .create_control()
+        .parent(column)
+        .min_height(20.0)
+        .visible(false)
+        .behaviour(Focusable)
+        .build(&mut gui);
+    let field = gui
+        .create_control()
+        .parent(column)
+        .min_height(20.0)
+        .behaviour(Focusable)
+        .build(&mut gui);
+
+    // a hidden control keeps its space in the layout, but is not hit nor focused
+    assert_eq!(gui.get_context().get_rect(field), [0.0, 20.0, 100.0, 40.0]);
+    assert_eq!(gui.controls_at(50.0, 10.0), vec![column, Id::ROOT_ID]);
+    assert_eq!(gui.next_focus(Id::ROOT_ID, false), Some(field));
+    gui.set_focus(Some(error));
+    assert_eq!(gui.current_focus, None);
+
+    gui.get_context().set_visible(error, true);
+    assert!(gui.is_visible(error));
+    assert_eq!(
+        gui.controls_at(50.0, 10.0),
+        vec![error, column, Id::ROOT_ID]
+    );
+    assert_eq!(gui.next_focus(Id::ROOT_ID, false), Some(error));
+    gui.set_focus(Some(error));
+    assert_eq!(gui.current_focus, Some(error));
+
+    // hiding a ancestor of the focused control removes its focus
+    gui.get_context().set_visible(column, false);
+    assert_eq!(gui.current_focus, None);
+    assert!(gui.is_visible(error));
+    assert_eq!(gui.controls_at(50.0, 10.0), vec![Id::ROOT_ID]);
+    assert_eq!(gui.get_context().get_rect(field), [0.0, 20.0, 100.0, 40.0]);
+}
+
+#[test]
+fn layout_now() {
+    use std::cell::Cell;
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let a = gui
+        .create_control()
+        .margins([10.0, 10.0, -10.0, -10.0])
+        .build(&mut gui);
+    assert_eq!(gui.get_rect(a).unwrap().get_rect(), &[0.0; 4]);
+    gui.layout_now();
+    assert_eq!(
+        gui.get_rect(a).unwrap().get_rect(),
+        &[10.0, 10.0, 90.0, 90.0]
+    );
+
+    // a control built by a context can be laid out before the context is dropped
+    struct Spawner {
+        rect: Rc<Cell<[f32; 4]>>,
+    }
+    impl Behaviour for Spawner {
+        fn on_start(&mut self, this: Id, ctx: &mut Context) {
+            let child = ctx
+                .create_control()
+                .parent(this)
+                .margins([10.0, 20.0, -10.0, -20.0])
+                .build(ctx);
+            ctx.force_layout();
+            self.rect.set(ctx.get_rect(child));
+        }
+    }
+    let rect = Rc::new(Cell::new([0.0; 4]));
+    gui.create_control()
+        .behaviour(Spawner { rect: rect.clone() })
+        .build(&mut gui);
+    gui.get_context();
+    assert_eq!(rect.get(), [10.0, 20.0, 90.0, 80.0]);
+
+    // inside of a batch, the built controls are started and laid out
+    let rect = Rc::new(Cell::new([0.0; 4]));
+    let b = gui.batch(|gui| {
+        let b = gui
+            .create_control()
+            .margins([5.0, 5.0, -5.0, -5.0])
+            .behaviour(Spawner { rect: rect.clone() })
+            .build(gui);
+        gui.layout_now();
+        assert_eq!(rect.get(), [15.0, 25.0, 85.0, 75.0]);
+        assert_eq!(gui.get_rect(b).unwrap().get_rect(), &[5.0, 5.0, 95.0, 95.0]);
+        b
+    });
+    assert_eq!(gui.get_context().get_rect(b), [5.0, 5.0, 95.0, 95.0]);
+}
+
+#[test]
+fn shimmer() {
+    use crate::graphics::{Graphic, Shimmer};
+    use crate::Color;
+
+    let shimmer = Shimmer::new(Color::BLACK, Color::WHITE);
+    let sprites = shimmer.get_sprites([0.0, 0.0, 10.0, 10.0], 0);
+    assert!(sprites.iter().all(|x| x.color == Color::BLACK));
+
+    // at the middle of the sweep, the band is over the center of the rect
+    let mut shimmer = shimmer.with_speed(0.5);
+    shimmer.advance(1.0);
+    assert_eq!(shimmer.phase, 0.5);
+    let sprites = shimmer.get_sprites([0.0, 0.0, 10.0, 10.0], 0);
+    assert_eq!(sprites[0].color, Color::BLACK);
+    assert!(sprites
+        .iter()
+        .any(|x| x.color != Color::BLACK && x.rect[0] >= 4.0));
+    shimmer.advance(1.5);
+    assert_eq!(shimmer.phase, 0.25);
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let placeholder = gui
+        .create_control()
+        .graphic(Shimmer::new(Color::BLACK, Color::WHITE))
+        .build(&mut gui);
+    gui.get_context();
+    assert_eq!(gui.animation_count(), 1);
+
+    // replacing the graphic don't add a second animation
+    gui.get_context()
+        .set_graphic(placeholder, Shimmer::new(Color::BLACK, Color::WHITE).into());
+    assert_eq!(gui.animation_count(), 1);
+
+    // the animation finishes when the control is deactivated
+    gui.get_context().deactive(placeholder);
+    drop(gui.get_render_context());
+    assert_eq!(gui.animation_count(), 0);
+    match gui.get_graphic(placeholder) {
+        Some(Graphic::Shimmer(x)) => assert!(!x.animated),
+        _ => panic!("graphic is not a Shimmer"),
+    }
+
+    // and starts again when it is activated
+    gui.get_context().active(placeholder);
+    assert_eq!(gui.animation_count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn force_layout_in_own_layout() {
+    use crate::Layout;
+
+    // the layout of this control can't be computed while its behaviour is running
+    struct Forcer;
+    impl Behaviour for Forcer {
+        fn on_event(&mut self, _: Box<dyn std::any::Any>, _: Id, ctx: &mut Context) {
+            ctx.force_layout();
+        }
+    }
+    impl Layout for Forcer {}
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let forcer = gui
+        .create_control()
+        .behaviour_and_layout(Forcer)
+        .build(&mut gui);
+    gui.send_event_to(forcer, Box::new(()));
+}
+
+#[test]
+fn overlay_list_scroll_bars() {
+    use crate::style::ScrollBarStyle;
+
+    init_logger();
+
+    struct MyListBuilder;
+    impl ListBuilder for MyListBuilder {
+        fn item_count(&mut self, _: &mut dyn crate::BuilderContext) -> usize {
+            10
+        }
+
+        fn create_item<'a>(
+            &mut self,
+            _index: usize,
+            _list_id: Id,
+            cb: crate::ControlBuilder,
+            _ctx: &mut dyn crate::BuilderContext,
+        ) -> crate::ControlBuilder {
+            cb.min_size([15.0, 15.0])
+        }
+    }
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let [list, view, h_bar, h_handle, v_bar, v_handle] = [(); 6].map(|_| gui.reserve_id());
+
+    gui.create_control_reserved(list)
+        .behaviour_and_layout(
+            List::new(
+                10.0,
+                [10.0; 4],
+                view,
+                v_bar,
+                v_handle,
+                h_bar,
+                h_handle,
+                MyListBuilder,
+            )
+            .with_scroll_bar_style(ScrollBarStyle {
+                overlay: true,
+                fade_ms: 0,
+            }),
+        )
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ListViewLayout::new(true, true))
+        .parent(list)
+        .build(&mut gui);
+    gui.create_control_reserved(h_bar)
+        .parent(list)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+    gui.create_control_reserved(h_handle)
+        .parent(h_bar)
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(list)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .build(&mut gui);
+
+    // the scroll bar is over the items, and hidden while idle
+    let item = gui.get_context().get_active_children(view)[0];
+    assert_eq!(gui.get_context().get_rect(item), [10.0, 10.0, 90.0, 25.0]);
+    assert_eq!(gui.get_context().get_rect(v_bar), [90.0, 0.0, 100.0, 100.0]);
+    assert_eq!(gui.get_context().get_opacity(v_bar), 0.0);
+
+    // and is shown while scrolling
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, -1.0), TouchPhase::Moved);
+    assert_eq!(gui.get_context().get_opacity(v_bar), 1.0);
+}
+
+#[test]
+fn slider_step() {
+    use crate::{
+        event::SetValue,
+        graphics::Graphic,
+        style::OnFocusStyle,
+        widgets::{SetMaxValue, SetMinValue, Slider},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let value = Rc::new(RefCell::new(None));
+
+    let [slider, area, handle] = [(); 3].map(|_| gui.reserve_id());
+    let callback = {
+        let value = value.clone();
+        move |_: Id, _: &mut Context, x: i32| *value.borrow_mut() = Some(x)
+    };
+    gui.create_control_reserved(slider)
+        .behaviour(Slider::new(handle, area, 1, 100, 50, style, callback).with_step(10))
+        .build(&mut gui);
+    gui.create_control_reserved(area)
+        .parent(slider)
+        .build(&mut gui);
+    gui.create_control_reserved(handle)
+        .parent(slider)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+    gui.get_context();
+
+    let set_value = |gui: &mut Gui, x: i32| {
+        gui.send_event_to(slider, Box::new(SetValue(x)));
+        value.borrow_mut().take()
+    };
+
+    // the values are snapped relative to min
+    assert_eq!(set_value(&mut gui, 24), Some(21));
+    assert_eq!(set_value(&mut gui, 27), Some(31));
+    // the last step would go past max
+    assert_eq!(set_value(&mut gui, 100), Some(91));
+    assert_eq!(set_value(&mut gui, -5), Some(1));
+
+    // changing the range snaps the value again
+    set_value(&mut gui, 91);
+    gui.send_event_to(slider, Box::new(SetMaxValue(50)));
+    assert_eq!(value.borrow_mut().take(), Some(41));
+    gui.send_event_to(slider, Box::new(SetMinValue(0)));
+    assert_eq!(value.borrow_mut().take(), Some(40));
+
+    // a step bigger than the range never leaves it
+    gui.send_event_to(slider, Box::new(SetMinValue(3)));
+    gui.send_event_to(slider, Box::new(SetMaxValue(5)));
+    assert_eq!(value.borrow_mut().take(), Some(3));
+    assert_eq!(set_value(&mut gui, 5), Some(3));
+}
+
+#[test]
+fn slider_keyboard() {
+    use crate::{graphics::Graphic, style::OnFocusStyle, widgets::Slider};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let [slider, area, handle] = [(); 3].map(|_| gui.reserve_id());
+    let callback = {
+        let values = values.clone();
+        move |_: Id, _: &mut Context, x: i32| values.borrow_mut().push(x)
+    };
+    gui.create_control_reserved(slider)
+        .behaviour(Slider::new(handle, area, 0, 100, 50, style, callback).with_step(5))
+        .build(&mut gui);
+    gui.create_control_reserved(area)
+        .parent(slider)
+        .build(&mut gui);
+    gui.create_control_reserved(handle)
+        .parent(slider)
+        .margins([-5.0, 0.0, 5.0, 0.0])
+        .build(&mut gui);
+    gui.get_context();
+    gui.set_focus(Some(slider));
+
+    for key in [
+        VirtualKeyCode::Right,
+        VirtualKeyCode::Left,
+        VirtualKeyCode::Down,
+        VirtualKeyCode::PageUp,
+        VirtualKeyCode::Home,
+        VirtualKeyCode::PageDown,
+        VirtualKeyCode::End,
+        VirtualKeyCode::Up,
+    ] {
+        press_key(&mut gui, key);
+    }
+    // a page is 10% of the range, and the value don't leave the range
+    assert_eq!(&*values.borrow(), &[55, 50, 45, 55, 0, 100]);
+    // the handle follows the value
+    assert_eq!(
+        gui.get_context().get_rect(handle),
+        [95.0, 0.0, 105.0, 100.0]
+    );
+}
+
+#[test]
+fn range_slider() {
+    use crate::{
+        event::SetValue,
+        graphics::Graphic,
+        style::OnFocusStyle,
+        widgets::{RangeSlider, RangeSliderHandle, SetMaxValue, SetMinValue},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let value = Rc::new(RefCell::new(None));
+
+    let [slider, area, fill, start, end] = [(); 5].map(|_| gui.reserve_id());
+    let callback = {
+        let value = value.clone();
+        move |_: Id, _: &mut Context, x: (i32, i32)| *value.borrow_mut() = Some(x)
+    };
+    gui.create_control_reserved(slider)
+        .behaviour(RangeSlider::new(
+            [start, end],
+            fill,
+            area,
+            0,
+            100,
+            (20, 30),
+            callback,
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(area)
+        .parent(slider)
+        .build(&mut gui);
+    gui.create_control_reserved(fill)
+        .parent(slider)
+        .build(&mut gui);
+    for handle in [start, end] {
+        gui.create_control_reserved(handle)
+            .parent(slider)
+            .margins([-5.0, 0.0, 5.0, 0.0])
+            .behaviour(RangeSliderHandle::new(style.clone()))
+            .build(&mut gui);
+    }
+    assert_eq!(gui.get_context().get_rect(fill), [20.0, 0.0, 30.0, 100.0]);
+
+    // the start handle stops at the end handle
+    gui.set_focus(Some(start));
+    press_key(&mut gui, VirtualKeyCode::End);
+    assert_eq!(value.borrow_mut().take(), Some((30, 30)));
+    press_key(&mut gui, VirtualKeyCode::Right);
+    assert_eq!(value.borrow_mut().take(), None);
+
+    // and the end handle stops at the start handle
+    gui.mouse_moved(0, 30.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(gui.current_focus, Some(end));
+    gui.mouse_moved(0, 10.0, 50.0);
+    assert_eq!(value.borrow_mut().take(), None);
+    gui.mouse_moved(0, 60.0, 50.0);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(value.borrow_mut().take(), Some((30, 60)));
+
+    // changing the range clamps both handles
+    gui.send_event_to(slider, Box::new(SetMaxValue(10)));
+    assert_eq!(value.borrow_mut().take(), Some((10, 10)));
+    gui.send_event_to(slider, Box::new(SetValue((50, -50))));
+    assert_eq!(value.borrow_mut().take(), Some((0, 10)));
+
+    // a empty range don't produce NaN rects
+    gui.send_event_to(slider, Box::new(SetMinValue(10)));
+    assert_eq!(value.borrow_mut().take(), Some((10, 10)));
+    assert_eq!(gui.get_context().get_rect(start), [-5.0, 0.0, 5.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 0.0, 100.0]);
+}
+
+#[test]
+fn vertical_slider() {
+    use crate::{graphics::Graphic, style::OnFocusStyle, widgets::Slider};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let value = Rc::new(RefCell::new(None));
+
+    let [slider, area, handle] = [(); 3].map(|_| gui.reserve_id());
+    let callback = {
+        let value = value.clone();
+        move |_: Id, _: &mut Context, x: i32| *value.borrow_mut() = Some(x)
+    };
+    gui.create_control_reserved(slider)
+        .behaviour(Slider::new_vertical(
+            handle, area, 0, 100, 0, style, callback,
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(area)
+        .parent(slider)
+        .margins([0.0, 10.0, 0.0, -10.0])
+        .build(&mut gui);
+    gui.create_control_reserved(handle)
+        .parent(slider)
+        .margins([0.0, -5.0, 0.0, 5.0])
+        .build(&mut gui);
+
+    // the min value is at the bottom of the slide area
+    assert_eq!(gui.get_context().get_rect(handle), [0.0, 85.0, 100.0, 95.0]);
+
+    // the value grows upwards
+    gui.mouse_moved(0, 50.0, 70.0);
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(value.borrow_mut().take(), Some(25));
+    gui.mouse_moved(0, 50.0, 0.0);
+    assert_eq!(value.borrow_mut().take(), Some(100));
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.get_context().get_rect(handle), [0.0, 5.0, 100.0, 15.0]);
+}
+
+#[test]
+fn progress_bar() {
+    use crate::{
+        event::SetValue,
+        widgets::{ProgressBar, SetIndeterminate},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let [bar, fill] = [(); 2].map(|_| gui.reserve_id());
+    gui.create_control_reserved(bar)
+        .behaviour_and_layout(ProgressBar::new(fill, 0.25))
+        .build(&mut gui);
+    gui.create_control_reserved(fill)
+        .parent(bar)
+        .build(&mut gui);
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 25.0, 100.0]);
+
+    // the value is clamped between 0 and 1
+    gui.send_event_to(bar, Box::new(SetValue(2.0f32)));
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 100.0, 100.0]);
+    gui.send_event_to(bar, Box::new(SetValue(0.5f32)));
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 50.0, 100.0]);
+
+    // the indeterminate mode is driven by a animation, that starts at the left
+    gui.send_event_to(bar, Box::new(SetIndeterminate(true)));
+    assert_eq!(gui.animation_count(), 1);
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 0.0, 100.0]);
+
+    gui.send_event_to(bar, Box::new(SetIndeterminate(false)));
+    assert_eq!(gui.animation_count(), 0);
+    assert_eq!(gui.get_context().get_rect(fill), [0.0, 0.0, 50.0, 100.0]);
+}
+
+#[test]
+fn tooltip_delay() {
+    use crate::widgets::Tooltip;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let tooltip = gui.create_control().min_size([30.0, 20.0]).build(&mut gui);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 50.0, 50.0])
+        .behaviour(Tooltip::new(tooltip))
+        .build(&mut gui);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([50.0, 0.0, 100.0, 50.0])
+        .behaviour(Tooltip::new(tooltip).with_delay(Duration::ZERO))
+        .build(&mut gui);
+    assert!(!gui.get_context().is_active(tooltip));
+
+    // the tooltip is only shown after the delay
+    gui.mouse_moved(0, 10.0, 10.0);
+    assert!(gui.handle_scheduled_event().is_some());
+    assert!(!gui.get_context().is_active(tooltip));
+
+    // leaving the control cancels it
+    gui.mouse_moved(0, 60.0, 10.0);
+    assert_eq!(gui.handle_scheduled_event(), None);
+    assert!(gui.get_context().is_active(tooltip));
+    // it is kept inside of the root control
+    assert_eq!(
+        gui.get_context().get_rect(tooltip),
+        [70.0, 26.0, 100.0, 46.0]
+    );
+
+    gui.mouse_moved(0, 60.0, 80.0);
+    assert!(!gui.get_context().is_active(tooltip));
+}
+
+#[test]
+fn stepper() {
+    use crate::{
+        event::SetValue,
+        graphics::Graphic,
+        style::ButtonStyle,
+        widgets::{StepBy, Stepper, StepperButton},
+    };
+    use std::any::Any;
+
+    struct Field(Rc<RefCell<String>>);
+    impl Behaviour for Field {
+        fn on_event(&mut self, event: Box<dyn Any>, _this: Id, _ctx: &mut Context) {
+            if let Some(SetValue(text)) = event.downcast_ref::<SetValue<String>>() {
+                *self.0.borrow_mut() = text.clone();
+            }
+        }
+    }
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let text = Rc::new(RefCell::new(String::new()));
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let [stepper, field] = [(); 2].map(|_| gui.reserve_id());
+    let on_change = {
+        let values = values.clone();
+        move |_: Id, _: &mut Context, x: i32| values.borrow_mut().push(x)
+    };
+    gui.create_control_reserved(stepper)
+        .behaviour(Stepper::new(field, 0, 10, 5, on_change).with_step(2))
+        .build(&mut gui);
+    gui.create_control_reserved(field)
+        .parent(stepper)
+        .behaviour(Field(text.clone()))
+        .build(&mut gui);
+    gui.create_control()
+        .parent(stepper)
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 10.0, 10.0])
+        .behaviour(StepperButton::new(stepper, true, style))
+        .build(&mut gui);
+    gui.get_context();
+    assert_eq!(&*text.borrow(), "5");
+
+    // pressing the up button steps once, and holding it schedules the auto-repeat
+    gui.mouse_moved(0, 5.0, 5.0);
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(&*values.borrow(), &[7]);
+    assert_eq!(&*text.borrow(), "7");
+    assert!(gui.handle_scheduled_event().is_some());
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.handle_scheduled_event(), None);
+
+    // the value is clamped between min and max
+    gui.send_event_to(stepper, Box::new(StepBy(100)));
+    gui.send_event_to(stepper, Box::new(SetValue(-3)));
+    assert_eq!(&*values.borrow(), &[7, 10, 0]);
+    assert_eq!(&*text.borrow(), "0");
+}
+
+#[test]
+fn tri_state_toggle() {
+    use crate::{
+        event::SetValue,
+        graphics::{Graphic, Texture},
+        style::{ButtonStyle, OnFocusStyle},
+        widgets::{CheckState, TriStateToggle},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let button_style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let background_style = Rc::new(OnFocusStyle {
+        normal: Graphic::None,
+        focus: Graphic::None,
+    });
+    let states = Rc::new(RefCell::new(Vec::new()));
+    let markers = [
+        Texture::new(1, [0.0, 0.0, 1.0, 1.0]).into(),
+        Texture::new(2, [0.0, 0.0, 1.0, 1.0]).into(),
+    ];
+
+    let [toggle, button, marker] = [(); 3].map(|_| gui.reserve_id());
+    let on_change = {
+        let states = states.clone();
+        move |_: Id, _: &mut Context, x: CheckState| states.borrow_mut().push(x)
+    };
+    gui.create_control_reserved(toggle)
+        .behaviour(TriStateToggle::new(
+            button,
+            marker,
+            CheckState::Unchecked,
+            markers,
+            button_style,
+            background_style,
+            on_change,
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(button)
+        .parent(toggle)
+        .build(&mut gui);
+    gui.create_control_reserved(marker)
+        .parent(button)
+        .build(&mut gui);
+    gui.get_context();
+
+    // the texture and alpha of the marker
+    let marker_state = |gui: &mut Gui| match gui.get_graphic(marker) {
+        Some(Graphic::Texture(x)) => Some((x.texture, x.color.a)),
+        _ => None,
+    };
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(marker_state(&mut gui), Some((1, 255)));
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(marker_state(&mut gui), Some((2, 255)));
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(marker_state(&mut gui), Some((2, 0)));
+
+    gui.send_event_to(toggle, Box::new(SetValue(true)));
+    assert_eq!(marker_state(&mut gui), Some((1, 255)));
+
+    use CheckState::*;
+    assert_eq!(
+        &*states.borrow(),
+        &[Unchecked, Checked, Indeterminate, Unchecked, Checked]
+    );
+}
+
+#[test]
+fn split_pane() {
+    use crate::{
+        event::SetValue,
+        widgets::{SplitHandle, SplitPane},
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(110.0, 100.0, 1.0, Fonts::new());
+    let ratios = Rc::new(RefCell::new(Vec::new()));
+
+    let [pane, first, handle, second] = [(); 4].map(|_| gui.reserve_id());
+    let on_change = {
+        let ratios = ratios.clone();
+        move |_: Id, _: &mut Context, x: f32| ratios.borrow_mut().push(x)
+    };
+    gui.create_control_reserved(pane)
+        .behaviour_and_layout(
+            SplitPane::new(first, handle, second, false, 0.5).with_callback(on_change),
+        )
+        .build(&mut gui);
+    gui.create_control_reserved(first)
+        .parent(pane)
+        .min_size([20.0, 0.0])
+        .build(&mut gui);
+    gui.create_control_reserved(handle)
+        .parent(pane)
+        .min_size([10.0, 0.0])
+        .behaviour(SplitHandle::new(false))
+        .build(&mut gui);
+    gui.create_control_reserved(second)
+        .parent(pane)
+        .min_size([30.0, 0.0])
+        .build(&mut gui);
+
+    let x_range = |gui: &mut Gui, id: Id| {
+        let rect = gui.get_context().get_rect(id);
+        [rect[0].round(), rect[2].round()]
+    };
+    assert_eq!(x_range(&mut gui, first), [0.0, 50.0]);
+    assert_eq!(x_range(&mut gui, handle), [50.0, 60.0]);
+    assert_eq!(x_range(&mut gui, second), [60.0, 110.0]);
+
+    // dragging the handle is limited by the min size of the children
+    gui.mouse_moved(0, 55.0, 50.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 15.0, 50.0);
+    assert_eq!(x_range(&mut gui, first), [0.0, 20.0]);
+    gui.mouse_moved(0, 105.0, 50.0);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(x_range(&mut gui, first), [0.0, 70.0]);
+    assert_eq!(x_range(&mut gui, second), [80.0, 110.0]);
+
+    let rounded: Vec<f32> = ratios
+        .borrow()
+        .iter()
+        .map(|x| (x * 100.0).round())
+        .collect();
+    assert_eq!(rounded, [20.0, 70.0]);
+
+    gui.send_event_to(pane, Box::new(SetValue(0.25f32)));
+    assert_eq!(x_range(&mut gui, first), [0.0, 25.0]);
+}
+
+#[test]
+fn tree_view_keyboard() {
+    use crate::{
+        graphics::Graphic,
+        widgets::{TreeView, TreeViewBuilder},
+        BuilderContext, ControlBuilder,
+    };
+
+    init_logger();
+
+    struct Nodes;
+    impl TreeViewBuilder for Nodes {
+        fn child_count(&mut self, path: &[usize], _: &mut dyn BuilderContext) -> usize {
+            match path {
+                [] => 3,
+                [0] => 2,
+                [1] => 1,
+                _ => 0,
+            }
+        }
+
+        fn create_node(
+            &mut self,
+            _path: &[usize],
+            _list_id: Id,
+            cb: ControlBuilder,
+            _ctx: &mut dyn BuilderContext,
+        ) -> ControlBuilder {
+            cb.min_size([0.0, 15.0])
+        }
+    }
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let [list, view, h_bar, h_handle, v_bar, v_handle] = [(); 6].map(|_| gui.reserve_id());
+    gui.create_control_reserved(list)
+        .behaviour_and_layout(List::new(
+            0.0,
+            [0.0; 4],
+            view,
+            v_bar,
+            v_handle,
+            h_bar,
+            h_handle,
+            TreeView::new(10.0, [Graphic::None, Graphic::None], Nodes),
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ListViewLayout::new(true, true))
+        .parent(list)
+        .build(&mut gui);
+    for (bar, handle) in [(h_bar, h_handle), (v_bar, v_handle)] {
+        gui.create_control_reserved(bar)
+            .parent(list)
+            .build(&mut gui);
+        gui.create_control_reserved(handle)
+            .parent(bar)
+            .build(&mut gui);
+    }
+
+    let rows = |gui: &mut Gui| gui.get_context().get_active_children(view);
+
+    let before = rows(&mut gui);
+    assert_eq!(before.len(), 3);
+    gui.set_focus(Some(before[1]));
+
+    // expanding the focused node only inserts its child after it
+    press_key(&mut gui, VirtualKeyCode::Right);
+    let expanded = rows(&mut gui);
+    assert_eq!(expanded.len(), 4);
+    assert_eq!(&expanded[..2], &before[..2]);
+    assert_eq!(gui.get_context().get_focus(), Some(before[1]));
+
+    press_key(&mut gui, VirtualKeyCode::Left);
+    let collapsed = rows(&mut gui);
+    assert_eq!(collapsed.len(), 3);
+    assert_eq!(&collapsed[..2], &before[..2]);
+    assert_eq!(gui.get_context().get_focus(), Some(before[1]));
+}
+
+#[test]
+fn accordion() {
+    use crate::widgets::{Accordion, AccordionSection, AccordionViewLayout, ToggleSection};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let accordion = gui.reserve_id();
+    let sections = [(); 2].map(|_| AccordionSection {
+        header: gui.reserve_id(),
+        view: gui.reserve_id(),
+        content: gui.reserve_id(),
+    });
+    for section in &sections {
+        gui.create_control_reserved(section.header)
+            .parent(accordion)
+            .min_size([0.0, 10.0])
+            .build(&mut gui);
+        gui.create_control_reserved(section.view)
+            .parent(accordion)
+            .layout(AccordionViewLayout)
+            .build(&mut gui);
+        gui.create_control_reserved(section.content)
+            .parent(section.view)
+            .min_size([0.0, 30.0])
+            .build(&mut gui);
+    }
+    let view = [sections[0].view, sections[1].view];
+    let content = [sections[0].content, sections[1].content];
+    gui.create_control_reserved(accordion)
+        .behaviour_and_layout(Accordion::new(sections.into(), false).with_open(0))
+        .build(&mut gui);
+
+    let y_range = |gui: &mut Gui, id: Id| {
+        let rect = gui.get_context().get_rect(id);
+        [rect[1], rect[3]]
+    };
+    assert_eq!(y_range(&mut gui, view[0]), [10.0, 40.0]);
+    assert_eq!(y_range(&mut gui, content[0]), [10.0, 40.0]);
+    assert!(!gui.get_context().is_active(view[1]));
+
+    // the opening view starts empty, but the content keeps its full height
+    gui.send_event_to(accordion, Box::new(ToggleSection(1)));
+    assert!(gui.get_context().is_active(view[1]));
+    assert_eq!(y_range(&mut gui, view[1]), [50.0, 50.0]);
+    assert_eq!(y_range(&mut gui, content[1]), [50.0, 80.0]);
+
+    drop(gui.get_render_context());
+    std::thread::sleep(Duration::from_millis(300));
+    drop(gui.get_render_context());
+
+    // opening the second section closed the first one
+    assert!(!gui.get_context().is_active(view[0]));
+    assert_eq!(y_range(&mut gui, view[1]), [20.0, 50.0]);
+    assert_eq!(y_range(&mut gui, content[1]), [20.0, 50.0]);
+}
+
+#[test]
+fn disabled_focus() {
+    use crate::{
+        event::SetEnabled, graphics::Graphic, layouts::VBoxLayout, style::ButtonStyle,
+        widgets::Button,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let parent = gui
+        .create_control()
+        .layout(VBoxLayout::new(0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let [a, b, c] = [(); 3].map(|_| {
+        gui.create_control()
+            .parent(parent)
+            .min_size([0.0, 20.0])
+            .behaviour(Button::new(
+                style.clone(),
+                true,
+                |_: Id, _: &mut Context| (),
+            ))
+            .build(&mut gui)
+    });
+
+    gui.set_focus(Some(b));
+    assert_eq!(gui.get_context().get_focus(), Some(b));
+
+    // disabling the focused button drops the focus
+    gui.send_event_to(b, Box::new(SetEnabled(false)));
+    assert_eq!(gui.get_context().get_focus(), None);
+
+    // and it is skipped by Tab
+    gui.set_focus(Some(a));
+    press_key(&mut gui, VirtualKeyCode::Tab);
+    assert_eq!(gui.get_context().get_focus(), Some(c));
+
+    gui.send_event_to(b, Box::new(SetEnabled(true)));
+    gui.set_focus(Some(a));
+    press_key(&mut gui, VirtualKeyCode::Tab);
+    assert_eq!(gui.get_context().get_focus(), Some(b));
+
+    // disabling a unfocused control don't change the focus
+    gui.send_event_to(a, Box::new(SetEnabled(false)));
+    assert_eq!(gui.get_context().get_focus(), Some(b));
+}
+
+#[test]
+fn margin_layout_overlay() {
+    use crate::{layouts::MarginLayout, RectFill};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let stack = gui
+        .create_control()
+        .layout(MarginLayout::new([5.0; 4]))
+        .build(&mut gui);
+    let content = gui
+        .create_control()
+        .parent(stack)
+        .min_size([20.0, 10.0])
+        .build(&mut gui);
+    let badge = gui
+        .create_control()
+        .parent(stack)
+        .min_size([10.0, 10.0])
+        .fill_x(RectFill::ShrinkEnd)
+        .fill_y(RectFill::ShrinkStart)
+        .build(&mut gui);
+    let spinner = gui
+        .create_control()
+        .parent(stack)
+        .min_size([20.0, 20.0])
+        .fill_x(RectFill::ShrinkCenter)
+        .fill_y(RectFill::ShrinkCenter)
+        .build(&mut gui);
+
+    // sized by the biggest child, plus the margins
+    assert_eq!(gui.get_context().get_min_size(stack), [30.0, 30.0]);
+
+    // each child is aligned by its own fill
+    assert_eq!(gui.get_context().get_rect(content), [5.0, 5.0, 95.0, 95.0]);
+    assert_eq!(gui.get_context().get_rect(badge), [85.0, 5.0, 95.0, 15.0]);
+    assert_eq!(
+        gui.get_context().get_rect(spinner),
+        [40.0, 40.0, 60.0, 60.0]
+    );
+}
+
+#[test]
+fn flex_layout() {
+    use crate::layouts::FlexLayout;
+
+    init_logger();
+
+    let mut gui = Gui::new(200.0, 100.0, 1.0, Fonts::new());
+
+    // the free space is distributed by the grow weights
+    let row = gui
+        .create_control()
+        .layout(FlexLayout::new(false, 0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let [a, b] = [1.0, 3.0].map(|grow| {
+        gui.create_control()
+            .parent(row)
+            .flex(grow, 1.0)
+            .flex_basis(20.0)
+            .build(&mut gui)
+    });
+    let c = gui
+        .create_control()
+        .parent(row)
+        .min_size([10.0, 0.0])
+        .build(&mut gui);
+    assert_eq!(gui.get_context().get_min_size(row), [10.0, 0.0]);
+    assert_eq!(gui.get_context().get_rect(a), [0.0, 0.0, 57.5, 100.0]);
+    assert_eq!(gui.get_context().get_rect(b), [57.5, 0.0, 190.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(c), [190.0, 0.0, 200.0, 100.0]);
+    gui.remove_control(row);
+
+    // the missing space is removed by the shrink weights, but not below the min size
+    let row = gui
+        .create_control()
+        .layout(FlexLayout::new(false, 0.0, [0.0; 4], -1))
+        .build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(row)
+        .flex_basis(150.0)
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .parent(row)
+        .flex_basis(150.0)
+        .min_size([120.0, 0.0])
+        .build(&mut gui);
+    assert_eq!(gui.get_context().get_rect(a), [0.0, 0.0, 80.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(b), [80.0, 0.0, 200.0, 100.0]);
+}
+
+#[test]
+fn flow_layout() {
+    use crate::layouts::FlowLayout;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let flow = gui
+        .create_control()
+        .layout(FlowLayout::new([5.0, 5.0], [0.0; 4]))
+        .build(&mut gui);
+    let children = [[30.0, 10.0], [30.0, 20.0], [30.0, 10.0], [50.0, 10.0]].map(|min_size| {
+        gui.create_control()
+            .parent(flow)
+            .min_size(min_size)
+            .build(&mut gui)
+    });
+    let rects = |gui: &mut Gui| {
+        let ctx = gui.get_context();
+        children.map(|x| ctx.get_rect(x))
+    };
+
+    // each row has the height of its highest child
+    assert_eq!(
+        rects(&mut gui),
+        [
+            [0.0, 0.0, 30.0, 20.0],
+            [35.0, 0.0, 65.0, 20.0],
+            [70.0, 0.0, 100.0, 20.0],
+            [0.0, 25.0, 50.0, 35.0],
+        ]
+    );
+    assert_eq!(gui.get_context().get_min_size(flow), [50.0, 35.0]);
+
+    // a narrower layout wraps more, and is higher
+    gui.set_root_rect([0.0, 0.0, 60.0, 100.0]);
+    assert_eq!(
+        rects(&mut gui),
+        [
+            [0.0, 0.0, 30.0, 10.0],
+            [0.0, 15.0, 30.0, 35.0],
+            [0.0, 40.0, 30.0, 50.0],
+            [0.0, 55.0, 50.0, 65.0],
+        ]
+    );
+    assert_eq!(gui.get_context().get_min_size(flow), [50.0, 65.0]);
+}
+
+#[test]
+fn grid_layout_span() {
+    use crate::layouts::GridLayout;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let grid = gui
+        .create_control()
+        .layout(GridLayout::new([0.0; 2], [0.0; 4], 3).with_column_weights(vec![1.0, 3.0, 0.0]))
+        .build(&mut gui);
+    let a = gui
+        .create_control()
+        .parent(grid)
+        .span(2, 1)
+        .min_size([40.0, 10.0])
+        .build(&mut gui);
+    let b = gui
+        .create_control()
+        .parent(grid)
+        .min_size([20.0, 10.0])
+        .build(&mut gui);
+    let c = gui
+        .create_control()
+        .parent(grid)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+
+    // the spanned child grows the columns it spans equally
+    assert_eq!(gui.get_context().get_min_size(grid), [60.0, 20.0]);
+
+    // the free width is distributed by the weights of the columns
+    assert_eq!(gui.get_context().get_rect(a), [0.0, 0.0, 80.0, 10.0]);
+    assert_eq!(gui.get_context().get_rect(b), [80.0, 0.0, 100.0, 10.0]);
+    assert_eq!(gui.get_context().get_rect(c), [0.0, 10.0, 35.0, 20.0]);
+}
+
+#[test]
+fn ratio_layout_range() {
+    use crate::layouts::RatioLayout;
+
+    init_logger();
+
+    let mut gui = Gui::new(200.0, 100.0, 1.0, Fonts::new());
+    let ratio = gui
+        .create_control()
+        .layout(RatioLayout::with_range(1.0, 1.5, (0, 0)))
+        .build(&mut gui);
+    let child = gui.create_control().parent(ratio).build(&mut gui);
+
+    // too wide, letterboxed at the max ratio
+    assert_eq!(gui.get_context().get_rect(child), [25.0, 0.0, 175.0, 100.0]);
+
+    // inside of the range, fills all the space
+    gui.set_root_rect([0.0, 0.0, 120.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(child), [0.0, 0.0, 120.0, 100.0]);
+
+    // too high, letterboxed at the min ratio
+    gui.set_root_rect([0.0, 0.0, 60.0, 100.0]);
+    assert_eq!(gui.get_context().get_rect(child), [0.0, 20.0, 60.0, 80.0]);
+}
+
+/// Build a DropMenu with the given reserved id, and its Blocker, for the tests of the dropdowns.
+/// Each item is a MenuItem with a height of 10 pixels, that has the texture 1 when highlighted.
+fn build_drop_menu(gui: &mut Gui, menu: Id, filter: Option<Id>) {
+    use crate::{
+        graphics::{Graphic, Texture},
+        layouts::VBoxLayout,
+        style::ButtonStyle,
+        widgets::{Blocker, CloseMenu, DropMenu, MenuItem},
+    };
+
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Texture::new(1, [0.0, 0.0, 1.0, 1.0]).into(),
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let blocker = gui
+        .create_control()
+        .active(false)
+        .behaviour(Blocker::new(move |_: Id, ctx: &mut Context| {
+            ctx.send_event_to(menu, CloseMenu)
+        }))
+        .build(gui);
+    let create_item = move |_: &String, menu: Id, ctx: &mut Context| {
+        ctx.create_control()
+            .parent(menu)
+            .min_size([0.0, 10.0])
+            .behaviour(MenuItem::new(menu, style.clone()))
+            .build(ctx)
+    };
+    let mut drop_menu = DropMenu::new(blocker, create_item);
+    if let Some(text_field) = filter {
+        drop_menu = drop_menu.with_filter(text_field);
+    }
+    gui.create_control_reserved(menu)
+        .active(false)
+        .layout(VBoxLayout::new(0.0, [0.0; 4], -1))
+        .behaviour(drop_menu)
+        .build(gui);
+}
+
+/// The index of the item of the DropMenu, built by `build_drop_menu`, that is highlighted.
+fn highlighted_item(gui: &mut Gui, items: &[Id]) -> Option<usize> {
+    use crate::graphics::Graphic;
+    items
+        .iter()
+        .position(|&x| matches!(gui.get_graphic(x), Some(Graphic::Texture(_))))
+}
+
+#[test]
+fn drop_menu_keyboard() {
+    use crate::{graphics::Graphic, style::ButtonStyle, widgets::Dropdown};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let selected = Rc::new(RefCell::new(Vec::new()));
+
+    let menu = gui.reserve_id();
+    let on_select = {
+        let selected = selected.clone();
+        move |(i, item): (usize, String), _: Id, _: &mut Context| {
+            selected.borrow_mut().push((i, item))
+        }
+    };
+    let items = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 100.0, 20.0])
+        .behaviour(Dropdown::new(items, None, menu, on_select, style))
+        .build(&mut gui);
+    build_drop_menu(&mut gui, menu, None);
+
+    let open = |gui: &mut Gui| {
+        gui.mouse_moved(0, 50.0, 10.0);
+        gui.mouse_down(0, MouseButton::Left);
+        gui.mouse_up(0, MouseButton::Left);
+        assert!(gui.get_context().is_active(menu));
+        gui.get_context().get_active_children(menu)
+    };
+
+    let items = open(&mut gui);
+    assert_eq!(items.len(), 3);
+    assert_eq!(gui.get_context().get_focus(), Some(menu));
+    assert_eq!(highlighted_item(&mut gui, &items), None);
+
+    // the highlight moves with Up and Down, and stops at the ends
+    press_key(&mut gui, VirtualKeyCode::Down);
+    assert_eq!(highlighted_item(&mut gui, &items), Some(0));
+    for _ in 0..3 {
+        press_key(&mut gui, VirtualKeyCode::Down);
+    }
+    assert_eq!(highlighted_item(&mut gui, &items), Some(2));
+    press_key(&mut gui, VirtualKeyCode::Up);
+    assert_eq!(highlighted_item(&mut gui, &items), Some(1));
+
+    // Enter selects the highlighted item, and closes the menu
+    press_key(&mut gui, VirtualKeyCode::Return);
+    assert!(!gui.get_context().is_active(menu));
+    assert_eq!(&*selected.borrow(), &[(1, "b".to_string())]);
+
+    // reopening highlights and focus the selected item, and Escape closes without selecting
+    let items = open(&mut gui);
+    assert_eq!(highlighted_item(&mut gui, &items), Some(1));
+    assert_eq!(gui.get_context().get_focus(), Some(items[1]));
+    press_key(&mut gui, VirtualKeyCode::Escape);
+    assert!(!gui.get_context().is_active(menu));
+    assert_eq!(selected.borrow().len(), 1);
+}
+
+/// Create a Fonts with the NotoSans font, for the tests that need to shape text.
+fn noto_sans() -> (Fonts, crate::font::FontId) {
+    use crate::font::Font;
+    let mut fonts = Fonts::new();
+    let font_id = fonts.add(Font::new(include_bytes!(
+        "../../examples/NotoSans-Regular.ttf"
+    )));
+    (fonts, font_id)
+}
+
+/// Build a single line TextField, with the given reserved id, and return the id of its label.
+fn build_text_field<C: crate::widgets::TextFieldCallback + 'static>(
+    gui: &mut Gui,
+    text_field: Id,
+    parent: Id,
+    font_id: crate::font::FontId,
+    callback: C,
+) -> Id {
+    use crate::{
+        graphics::{Graphic, Text},
+        style::{OnFocusStyle, SelectionColor, TextFieldStyle},
+        text::TextStyle,
+        widgets::TextField,
+        Color,
+    };
+
+    let style = Rc::new(TextFieldStyle {
+        background: OnFocusStyle {
+            normal: Graphic::None,
+            focus: Graphic::None,
+        },
+        caret_color: Color::BLACK,
+        caret_width: None,
+        selection_color: SelectionColor {
+            fg: None,
+            bg: Color::BLACK,
+        },
+        inactive_selection_color: None,
+        selection_radius: 0.0,
+    });
+    let [caret, label] = [(); 2].map(|_| gui.reserve_id());
+    gui.create_control_reserved(text_field)
+        .parent(parent)
+        .min_size([0.0, 20.0])
+        .behaviour(TextField::new(caret, label, false, style, callback))
+        .build(gui);
+    gui.create_control_reserved(caret)
+        .parent(text_field)
+        .anchors([0.0; 4])
+        .build(gui);
+    let style = TextStyle {
+        font_id,
+        ..Default::default()
+    };
+    gui.create_control_reserved(label)
+        .parent(text_field)
+        .graphic(Text::new(String::new(), (-1, 0), style))
+        .build(gui);
+    label
+}
+
+/// Send each character of `text` to the gui, like a window would.
+fn type_text(gui: &mut Gui, text: &str) {
+    for ch in text.chars() {
+        gui.handle_event(&winit::event::WindowEvent::ReceivedCharacter(ch));
+    }
+}
+
+#[test]
+fn drop_menu_filter() {
+    use crate::{
+        graphics::Graphic,
+        style::ButtonStyle,
+        widgets::{DropMenuFilter, Dropdown},
+    };
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, fonts);
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let selected = Rc::new(RefCell::new(Vec::new()));
+
+    let [menu, text_field] = [(); 2].map(|_| gui.reserve_id());
+    let on_select = {
+        let selected = selected.clone();
+        move |(i, item): (usize, String), _: Id, _: &mut Context| {
+            selected.borrow_mut().push((i, item))
+        }
+    };
+    let items = ["Apple", "Banana", "Cherry", "apricot"].map(String::from);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 100.0, 20.0])
+        .behaviour(Dropdown::new(items.into(), None, menu, on_select, style).with_filtering(true))
+        .build(&mut gui);
+    build_drop_menu(&mut gui, menu, Some(text_field));
+    build_text_field(
+        &mut gui,
+        text_field,
+        menu,
+        font_id,
+        DropMenuFilter::new(menu),
+    );
+
+    gui.mouse_moved(0, 50.0, 10.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(gui.get_context().get_focus(), Some(text_field));
+    let items = |gui: &mut Gui| {
+        let children = gui.get_context().get_active_children(menu);
+        assert_eq!(children[0], text_field);
+        children[1..].to_vec()
+    };
+    assert_eq!(items(&mut gui).len(), 4);
+
+    // the items are filtered case-insensitively, and the first match is highlighted
+    type_text(&mut gui, "AP");
+    let filtered = items(&mut gui);
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(highlighted_item(&mut gui, &filtered), Some(0));
+
+    // the arrows still move the highlight, and Enter selects it
+    press_key(&mut gui, VirtualKeyCode::Down);
+    assert_eq!(highlighted_item(&mut gui, &filtered), Some(1));
+    press_key(&mut gui, VirtualKeyCode::Return);
+    assert!(!gui.get_context().is_active(menu));
+    assert_eq!(&*selected.borrow(), &[(3, "apricot".to_string())]);
+}
+
+#[test]
+fn multi_dropdown() {
+    use crate::{
+        event::SetValue,
+        graphics::{Graphic, Text},
+        style::ButtonStyle,
+        text::TextStyle,
+        widgets::MultiDropdown,
+    };
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, fonts);
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let changes = Rc::new(RefCell::new(Vec::new()));
+
+    let [dropdown, label, menu] = [(); 3].map(|_| gui.reserve_id());
+    let on_change = {
+        let changes = changes.clone();
+        move |selected: &[usize], _: Id, _: &mut Context| {
+            changes.borrow_mut().push(selected.to_vec())
+        }
+    };
+    let items = ["a", "b", "c", "d", "e"].map(String::from);
+    gui.create_control_reserved(dropdown)
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 100.0, 20.0])
+        .behaviour(MultiDropdown::new(
+            items.into(),
+            &[0],
+            menu,
+            label,
+            on_change,
+            style,
+        ))
+        .build(&mut gui);
+    let text_style = TextStyle {
+        font_id,
+        ..Default::default()
+    };
+    gui.create_control_reserved(label)
+        .parent(dropdown)
+        .graphic(Text::new(String::new(), (-1, 0), text_style))
+        .build(&mut gui);
+    build_drop_menu(&mut gui, menu, None);
+
+    let label_text = |gui: &mut Gui| match gui.get_graphic(label) {
+        Some(Graphic::Text(text)) => text.string().to_owned(),
+        _ => panic!("label is not a Text"),
+    };
+    let click = |gui: &mut Gui, y: f32| {
+        gui.mouse_moved(0, 50.0, y);
+        gui.mouse_down(0, MouseButton::Left);
+        gui.mouse_up(0, MouseButton::Left);
+    };
+    gui.get_context();
+    assert_eq!(label_text(&mut gui), "a");
+
+    // each item is 10 pixels high, starting below the dropdown
+    click(&mut gui, 10.0);
+    assert!(gui.get_context().is_active(menu));
+
+    // clicking a item toggles it, without closing the menu
+    click(&mut gui, 35.0);
+    assert!(gui.get_context().is_active(menu));
+    assert_eq!(label_text(&mut gui), "a, b");
+    click(&mut gui, 25.0);
+    assert!(gui.get_context().is_active(menu));
+    assert_eq!(label_text(&mut gui), "b");
+
+    // too many selected items are summarized by its count
+    for y in [45.0, 55.0, 65.0] {
+        click(&mut gui, y);
+    }
+    assert_eq!(label_text(&mut gui), "4 selected");
+
+    gui.send_event_to(dropdown, Box::new(SetValue(vec![0usize, 2])));
+    assert_eq!(label_text(&mut gui), "a, c");
+
+    assert_eq!(
+        &*changes.borrow(),
+        &[
+            vec![0, 1],
+            vec![1],
+            vec![1, 2],
+            vec![1, 2, 3],
+            vec![1, 2, 3, 4],
+            vec![0, 2],
+        ]
+    );
+}
+
+#[test]
+fn smooth_scroll() {
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let build = |gui: &mut Gui, smooth: bool| {
+        let [scroll_view, view, content, v_bar, v_handle] = [(); 5].map(|_| gui.reserve_id());
+        gui.create_control_reserved(scroll_view)
+            .behaviour_and_layout(
+                ScrollView::new(view, content, None, Some((v_bar, v_handle)))
+                    .with_smooth_scroll(smooth),
+            )
+            .build(gui);
+        gui.create_control_reserved(view)
+            .layout(ViewLayout::new(false, true))
+            .parent(scroll_view)
+            .build(gui);
+        gui.create_control_reserved(content)
+            .parent(view)
+            .min_size([50.0, 1000.0])
+            .build(gui);
+        gui.create_control_reserved(v_bar)
+            .parent(scroll_view)
+            .min_size([10.0, 10.0])
+            .build(gui);
+        gui.create_control_reserved(v_handle)
+            .parent(v_bar)
+            .build(gui);
+        (scroll_view, content)
+    };
+    let finish_animations = |gui: &mut Gui| {
+        drop(gui.get_render_context());
+        std::thread::sleep(Duration::from_millis(200));
+        drop(gui.get_render_context());
+        gui.get_context();
+    };
+    // the content is moved up by the scroll offset
+    let offset = |gui: &mut Gui, content: Id| -gui.get_context().get_rect(content)[1];
+
+    let (scroll_view, content) = build(&mut gui, true);
+    gui.get_context();
+    gui.mouse_moved(0, 50.0, 50.0);
+
+    // the wheel scroll is animated, and consecutive ticks accumulate
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, -1.0), TouchPhase::Moved);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, -1.0), TouchPhase::Moved);
+    assert_eq!(offset(&mut gui, content), 0.0);
+    finish_animations(&mut gui);
+    assert_eq!(offset(&mut gui, content), 200.0);
+
+    // a precise scroll is applied immediately
+    let delta = MouseScrollDelta::PixelDelta(winit::dpi::PhysicalPosition::new(0.0, -30.0));
+    gui.mouse_scroll(0, delta, TouchPhase::Moved);
+    assert_eq!(offset(&mut gui, content), 230.0);
+
+    // and so is any scroll, when smooth scrolling is disabled
+    gui.remove_control(scroll_view);
+    let (_, content) = build(&mut gui, false);
+    gui.get_context();
+    gui.mouse_moved(0, 50.0, 40.0);
+    gui.mouse_scroll(0, MouseScrollDelta::LineDelta(0.0, -1.0), TouchPhase::Moved);
+    assert_eq!(offset(&mut gui, content), 100.0);
+}
+
+#[test]
+fn scroll_to_view() {
+    use crate::widgets::ScrollToView;
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let [scroll_view, view, content, v_bar, v_handle, small, top, big] =
+        [(); 8].map(|_| gui.reserve_id());
+
+    gui.create_control_reserved(scroll_view)
+        .behaviour_and_layout(ScrollView::new(
+            view,
+            content,
+            None,
+            Some((v_bar, v_handle)),
+        ))
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ViewLayout::new(false, true))
+        .parent(scroll_view)
+        .build(&mut gui);
+    gui.create_control_reserved(content)
+        .parent(view)
+        .min_size([50.0, 1000.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(scroll_view)
+        .min_size([10.0, 10.0])
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .build(&mut gui);
+    for (id, y, height) in [
+        (small, 500.0, 20.0),
+        (top, 100.0, 10.0),
+        (big, 200.0, 200.0),
+    ] {
+        gui.create_control_reserved(id)
+            .parent(content)
+            .anchors([0.0; 4])
+            .margins([0.0, y, 50.0, y + height])
+            .build(&mut gui);
+    }
+
+    let mut scroll_to = |id: Id, margin: f32| {
+        gui.send_event_to(scroll_view, Box::new(ScrollToView { id, margin }));
+        let ctx = gui.get_context();
+        (ctx.get_rect(content)[1], ctx.get_rect(id)[1])
+    };
+
+    // a control below the view is scrolled up to the bottom of the view, plus the margin
+    assert_eq!(scroll_to(small, 10.0), (-430.0, 70.0));
+    // a control above it, to the top of the view
+    assert_eq!(scroll_to(top, 0.0), (-100.0, 0.0));
+    // a control already in view does not scroll
+    assert_eq!(scroll_to(top, 0.0), (-100.0, 0.0));
+    // a control bigger than the view keeps its start visible
+    assert_eq!(scroll_to(big, 0.0), (-200.0, 0.0));
+}
+
+#[test]
+fn list_selection() {
+    use crate::widgets::{DeselectItem, SelectItem};
+    use std::collections::BTreeSet;
+
+    init_logger();
+
+    struct MyListBuilder {
+        shown_selected: Rc<RefCell<BTreeSet<usize>>>,
+    }
+    impl ListBuilder for MyListBuilder {
+        fn item_count(&mut self, _: &mut dyn crate::BuilderContext) -> usize {
+            10
+        }
+
+        fn create_item<'a>(
+            &mut self,
+            _index: usize,
+            _list_id: Id,
+            cb: crate::ControlBuilder,
+            _ctx: &mut dyn crate::BuilderContext,
+        ) -> crate::ControlBuilder {
+            cb.min_size([15.0, 15.0])
+        }
+
+        fn set_selected(
+            &mut self,
+            index: usize,
+            _item_id: Id,
+            selected: bool,
+            _ctx: &mut dyn crate::BuilderContext,
+        ) {
+            let mut shown_selected = self.shown_selected.borrow_mut();
+            if selected {
+                shown_selected.insert(index);
+            } else {
+                shown_selected.remove(&index);
+            }
+        }
+    }
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+
+    let [list, view, h_bar, h_handle, v_bar, v_handle] = [(); 6].map(|_| gui.reserve_id());
+
+    let shown_selected = Rc::new(RefCell::new(BTreeSet::new()));
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    gui.create_control_reserved(list)
+        .behaviour_and_layout(
+            List::new(
+                10.0,
+                [10.0; 4],
+                view,
+                v_bar,
+                v_handle,
+                h_bar,
+                h_handle,
+                MyListBuilder {
+                    shown_selected: shown_selected.clone(),
+                },
+            )
+            .with_on_selection_change({
+                let changes = changes.clone();
+                move |_, _, selected: &[usize]| changes.borrow_mut().push(selected.to_vec())
+            }),
+        )
+        .build(&mut gui);
+    gui.create_control_reserved(view)
+        .layout(ListViewLayout::new(true, true))
+        .parent(list)
+        .build(&mut gui);
+    gui.create_control_reserved(h_bar)
+        .parent(list)
+        .build(&mut gui);
+    gui.create_control_reserved(h_handle)
+        .parent(h_bar)
+        .build(&mut gui);
+    gui.create_control_reserved(v_bar)
+        .parent(list)
+        .build(&mut gui);
+    gui.create_control_reserved(v_handle)
+        .parent(v_bar)
+        .build(&mut gui);
+
+    // the items are 15 pixels high, with a spacing of 10, starting at y = 10
+    let click = |gui: &mut Gui, index: usize, modifiers: ModifiersState| {
+        gui.modifiers = modifiers;
+        gui.mouse_moved(0, 50.0, 17.0 + 25.0 * index as f32);
+        gui.mouse_down(0, MouseButton::Left);
+        gui.mouse_up(0, MouseButton::Left);
+        gui.get_context();
+    };
+    gui.get_context();
+
+    // a click selects only the item
+    click(&mut gui, 1, ModifiersState::empty());
+    click(&mut gui, 2, ModifiersState::empty());
+    // Ctrl+Click toggles it
+    click(&mut gui, 3, ModifiersState::CTRL);
+    click(&mut gui, 2, ModifiersState::CTRL);
+    // and Shift+Click selects a range, from the last clicked item
+    click(&mut gui, 0, ModifiersState::SHIFT);
+    assert_eq!(*shown_selected.borrow(), [0, 1, 2].into());
+
+    gui.modifiers = ModifiersState::empty();
+    gui.send_event_to(list, Box::new(DeselectItem(1)));
+    gui.send_event_to(list, Box::new(SelectItem(3)));
+    gui.get_context();
+    assert_eq!(*shown_selected.borrow(), [0, 2, 3].into());
+
+    assert_eq!(
+        &*changes.borrow(),
+        &[
+            vec![1],
+            vec![2],
+            vec![2, 3],
+            vec![3],
+            vec![0, 1, 2],
+            vec![0, 2],
+            vec![0, 2, 3],
+        ]
+    );
+}
+
+#[test]
+fn button_long_press_and_auto_repeat() {
+    use crate::{graphics::Graphic, style::ButtonStyle, widgets::Button};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let record = |name: &'static str| {
+        let events = events.clone();
+        move |_: Id, _: &mut Context| events.borrow_mut().push(name)
+    };
+
+    let delay = Duration::from_millis(10);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 50.0, 50.0])
+        .behaviour(
+            Button::new(style.clone(), false, record("click"))
+                .with_long_press(delay, record("long")),
+        )
+        .build(&mut gui);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([50.0, 0.0, 100.0, 50.0])
+        .behaviour(Button::new(style, false, record("repeat")).with_auto_repeat(delay, delay))
+        .build(&mut gui);
+    gui.get_context();
+
+    // a quick press is a normal click
+    gui.mouse_moved(0, 25.0, 25.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(events.borrow_mut().take(), ["click"]);
+
+    // holding the button fires the long press, instead of the click
+    gui.mouse_down(0, MouseButton::Left);
+    std::thread::sleep(delay * 2);
+    gui.handle_scheduled_event();
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(events.borrow_mut().take(), ["long"]);
+
+    // exiting the button cancels it
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 25.0, 75.0);
+    std::thread::sleep(delay * 2);
+    assert_eq!(gui.handle_scheduled_event(), None);
+    gui.mouse_up(0, MouseButton::Left);
+    assert!(events.borrow().is_empty());
+
+    // a auto-repeat button clicks on press, and then repeatedly while held
+    gui.mouse_moved(0, 75.0, 25.0);
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(events.borrow_mut().take(), ["repeat"]);
+    std::thread::sleep(delay * 2);
+    gui.handle_scheduled_event();
+    assert_eq!(events.borrow_mut().take(), ["repeat"]);
+    std::thread::sleep(delay * 2);
+    gui.handle_scheduled_event();
+    assert_eq!(events.borrow_mut().take(), ["repeat"]);
+
+    // until released
+    gui.mouse_up(0, MouseButton::Left);
+    std::thread::sleep(delay * 2);
+    assert_eq!(gui.handle_scheduled_event(), None);
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn button_secondary_clicks() {
+    use crate::{graphics::Graphic, style::ButtonStyle, widgets::Button};
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let record = |name: &'static str| {
+        let events = events.clone();
+        move |_: Id, _: &mut Context| events.borrow_mut().push(name)
+    };
+
+    gui.create_control()
+        .behaviour(
+            Button::new(style, false, record("left"))
+                .with_right_click(record("right"))
+                .with_middle_click(record("middle")),
+        )
+        .build(&mut gui);
+    gui.get_context();
+
+    gui.mouse_moved(0, 50.0, 50.0);
+    for button in [MouseButton::Right, MouseButton::Left, MouseButton::Middle] {
+        gui.mouse_down(0, button);
+        gui.mouse_up(0, button);
+    }
+    assert_eq!(events.borrow_mut().take(), ["right", "left", "middle"]);
+}
+
+#[test]
+fn window_resize() {
+    use crate::widgets::Window;
+    use winit::window::CursorIcon;
+
+    init_logger();
+
+    let mut gui = Gui::new(200.0, 200.0, 1.0, Fonts::new());
+
+    let window = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([50.0, 50.0, 150.0, 150.0])
+        .min_size([20.0, 20.0])
+        .behaviour(
+            Window::new()
+                .with_min_size([40.0, 40.0])
+                .with_max_size([120.0, 120.0]),
+        )
+        .build(&mut gui);
+    gui.get_context();
+
+    // the cursor shows which edge is under the mouse
+    gui.mouse_moved(0, 148.0, 100.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::EResize));
+
+    // the size is clamped between the min and max size
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 200.0, 100.0);
+    assert_eq!(
+        gui.get_context().get_rect(window),
+        [50.0, 50.0, 170.0, 150.0]
+    );
+    gui.mouse_moved(0, 0.0, 100.0);
+    assert_eq!(
+        gui.get_context().get_rect(window),
+        [50.0, 50.0, 90.0, 150.0]
+    );
+    gui.mouse_up(0, MouseButton::Left);
+
+    // a corner resizes both axes
+    gui.mouse_moved(0, 51.0, 149.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::SwResize));
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_moved(0, 41.0, 159.0);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(
+        gui.get_context().get_rect(window),
+        [40.0, 50.0, 90.0, 160.0]
+    );
+}
+
+#[test]
+fn window_snapping() {
+    use crate::widgets::Window;
+
+    init_logger();
+
+    let mut gui = Gui::new(200.0, 200.0, 1.0, Fonts::new());
+
+    let window = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([50.0, 50.0, 100.0, 100.0])
+        .behaviour(Window::new().with_snap_distance(10.0))
+        .build(&mut gui);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([150.0, 0.0, 200.0, 50.0])
+        .build(&mut gui);
+    gui.get_context();
+
+    gui.mouse_moved(0, 75.0, 75.0);
+    gui.mouse_down(0, MouseButton::Left);
+
+    // the window snaps to the edge of its sibling
+    gui.mouse_moved(0, 118.0, 75.0);
+    assert_eq!(
+        gui.get_context().get_rect(window),
+        [100.0, 50.0, 150.0, 100.0]
+    );
+
+    // its top cannot leave the root control
+    gui.mouse_moved(0, 75.0, 1.0);
+    assert_eq!(gui.get_context().get_rect(window), [50.0, 0.0, 100.0, 50.0]);
+
+    // and a part of it is always kept inside
+    gui.mouse_moved(0, 199.0, 75.0);
+    assert_eq!(
+        gui.get_context().get_rect(window),
+        [170.0, 50.0, 220.0, 100.0]
+    );
+    gui.mouse_up(0, MouseButton::Left);
+}
+
+fn menu_style(font_id: crate::font::FontId) -> Rc<crate::style::MenuStyle> {
+    use crate::{
+        graphics::{Graphic, Texture},
+        style::{ButtonStyle, MenuStyle},
+        text::TextStyle,
+    };
+
+    Rc::new(MenuStyle {
+        button: ButtonStyle {
+            normal: Graphic::None,
+            hover: Texture::new(1, [0.0, 0.0, 1.0, 1.0]).into(),
+            pressed: Graphic::None,
+            focus: Graphic::None,
+            disabled: None,
+        },
+        separator: Graphic::None,
+        arrow: Graphic::None,
+        check: Texture::new(2, [0.0, 0.0, 1.0, 1.0]).into(),
+        text: TextStyle {
+            font_id,
+            ..Default::default()
+        },
+        shadow: None,
+    })
+}
+
+#[test]
+fn context_submenu() {
+    use crate::widgets::{ContextMenu, Item, Menu};
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(200.0, 200.0, 1.0, fonts);
+
+    let clicked = Rc::new(RefCell::new(Vec::new()));
+    let item = |name: &'static str| {
+        let clicked = clicked.clone();
+        Item::Button(
+            name.to_owned(),
+            Box::new(move |_, _| clicked.borrow_mut().push(name)),
+        )
+    };
+    let submenu = Menu::new("sub".to_owned(), vec![item("b")]);
+    let menu = Menu::new(
+        "menu".to_owned(),
+        vec![item("a"), Item::SubMenu(Rc::new(submenu))],
+    );
+    let context = gui
+        .create_control()
+        .behaviour(ContextMenu::new(menu_style(font_id), Rc::new(menu)))
+        .build(&mut gui);
+    gui.get_context();
+
+    let last_opened = |gui: &mut Gui| {
+        *gui.get_context()
+            .get_active_children(Id::ROOT_ID)
+            .last()
+            .unwrap()
+    };
+    let center = |rect: [f32; 4]| [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+    let open_submenu = |gui: &mut Gui, x: f32, y: f32| {
+        gui.mouse_moved(0, x, y);
+        gui.mouse_down(0, MouseButton::Right);
+        gui.mouse_up(0, MouseButton::Right);
+        let menu = last_opened(gui);
+        let item = gui.get_context().get_active_children(menu)[1];
+        let item_rect = gui.get_context().get_rect(item);
+        let [x, y] = center(item_rect);
+        gui.mouse_moved(0, x, y);
+        let submenu = last_opened(gui);
+        assert_ne!(submenu, menu);
+        (
+            gui.get_context().get_rect(menu),
+            item_rect,
+            gui.get_context().get_rect(submenu),
+            submenu,
+        )
+    };
+
+    // the submenu opens to the right of its item, when hovered
+    let (menu_rect, item_rect, sub_rect, submenu) = open_submenu(&mut gui, 10.0, 10.0);
+    assert_eq!(&menu_rect[..2], &[10.0, 10.0]);
+    assert_eq!(&sub_rect[..2], &[item_rect[2], item_rect[1]]);
+
+    // clicking an item of the submenu closes all menus
+    let item = gui.get_context().get_active_children(submenu)[0];
+    let [x, y] = center(gui.get_context().get_rect(item));
+    gui.mouse_moved(0, x, y);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(*clicked.borrow(), ["b"]);
+    assert_eq!(
+        gui.get_context().get_active_children(Id::ROOT_ID),
+        [context]
+    );
+
+    // near the right edge, both menus are flipped to the left
+    let (menu_rect, item_rect, sub_rect, _) = open_submenu(&mut gui, 190.0, 10.0);
+    assert_eq!(menu_rect[2], 190.0);
+    assert_eq!(&sub_rect[1..3], &[item_rect[1], item_rect[0]]);
+}
+
+#[test]
+fn checkable_and_icon_menu_items() {
+    use crate::{
+        graphics::{Graphic, Texture},
+        widgets::{ContextMenu, Item, Menu},
+    };
+    use std::cell::Cell;
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(200.0, 200.0, 1.0, fonts);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let menu = Menu::new(
+        "menu".to_owned(),
+        vec![
+            Item::Checkable("check".to_owned(), Cell::new(false), {
+                let events = events.clone();
+                Box::new(move |_, _, checked| events.borrow_mut().push(format!("{}", checked)))
+            }),
+            Item::IconButton(
+                Texture::new(3, [0.0, 0.0, 1.0, 1.0]).into(),
+                "icon".to_owned(),
+                {
+                    let events = events.clone();
+                    Box::new(move |_, _| events.borrow_mut().push("icon".to_owned()))
+                },
+            ),
+        ],
+    );
+    let context = gui
+        .create_control()
+        .behaviour(ContextMenu::new(menu_style(font_id), Rc::new(menu)))
+        .build(&mut gui);
+    gui.get_context();
+
+    let open_menu = |gui: &mut Gui| {
+        gui.mouse_moved(0, 10.0, 10.0);
+        gui.mouse_down(0, MouseButton::Right);
+        gui.mouse_up(0, MouseButton::Right);
+        let menu = *gui
+            .get_context()
+            .get_active_children(Id::ROOT_ID)
+            .last()
+            .unwrap();
+        gui.get_context().get_active_children(menu)
+    };
+    let click = |gui: &mut Gui, item: Id| {
+        let rect = gui.get_context().get_rect(item);
+        gui.mouse_moved(0, (rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0);
+        gui.mouse_down(0, MouseButton::Left);
+        gui.mouse_up(0, MouseButton::Left);
+    };
+    // the leading icon is the first child of the item
+    let icon_texture = |gui: &mut Gui, item: Id| {
+        let icon = gui.get_context().get_active_children(item)[0];
+        match gui.get_graphic(icon) {
+            Some(Graphic::Texture(texture)) => Some(texture.texture),
+            _ => None,
+        }
+    };
+
+    let items = open_menu(&mut gui);
+    assert_eq!(icon_texture(&mut gui, items[0]), None);
+    assert_eq!(icon_texture(&mut gui, items[1]), Some(3));
+
+    // a checkable item toggles its check mark, without closing the menu
+    click(&mut gui, items[0]);
+    assert_eq!(icon_texture(&mut gui, items[0]), Some(2));
+    click(&mut gui, items[0]);
+    assert_eq!(icon_texture(&mut gui, items[0]), None);
+    click(&mut gui, items[0]);
+
+    // a icon button closes it
+    click(&mut gui, items[1]);
+    assert_eq!(
+        gui.get_context().get_active_children(Id::ROOT_ID),
+        [context]
+    );
+
+    // the checked state persists when the menu is opened again
+    let items = open_menu(&mut gui);
+    assert_eq!(icon_texture(&mut gui, items[0]), Some(2));
+
+    assert_eq!(*events.borrow(), ["true", "false", "true", "icon"]);
+}
+
+/// A GuiRenderer that ignores the font texture, for tests that only inspect the sprites.
+struct NoRenderer;
+impl crate::render::GuiRenderer for NoRenderer {
+    fn update_font_texture(&mut self, _: u32, _: [u32; 4], _: &[u8]) {}
+    fn resize_font_texture(&mut self, _: u32, _: [u32; 2]) {}
+}
+
+/// The color of the topmost sprite that covers the center of the pixel at `x`, `y`.
+fn sprite_color(sprites: &[crate::graphics::Sprite], x: f32, y: f32) -> Option<crate::Color> {
+    let [x, y] = [x + 0.5, y + 0.5];
+    sprites
+        .iter()
+        .rev()
+        .find(|s| s.rect[0] <= x && x < s.rect[2] && s.rect[1] <= y && y < s.rect[3])
+        .map(|s| s.color)
+}
+
+#[test]
+fn clip_children() {
+    use crate::{graphics::Texture, Color, GuiRender};
+
+    init_logger();
+
+    let mut gui = Gui::new(4.0, 4.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 4.0, 4.0]);
+    let parent = gui
+        .create_control()
+        .anchors([0.0, 0.0, 0.5, 0.5])
+        .build(&mut gui);
+    // the child overflows its parent, covering the entire root
+    let red = Color::from_u32(0xff0000ff);
+    gui.create_control()
+        .parent(parent)
+        .margins([0.0, 0.0, 2.0, 2.0])
+        .graphic(Texture::new(1, [0.0, 0.0, 1.0, 1.0]).with_color(red))
+        .build(&mut gui);
+
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    // by default, the child is clipped by its parent
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 1.0, 1.0), Some(red));
+    assert_eq!(sprite_color(sprites, 3.0, 3.0), None);
+
+    gui.get_context()
+        .get_rect_and_graphic(parent)
+        .0
+        .set_clip_children(false);
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 1.0, 1.0), Some(red));
+    assert_eq!(sprite_color(sprites, 3.0, 3.0), Some(red));
+}
+
+#[test]
+fn round_rect() {
+    use crate::{graphics::RoundRect, Color, GuiRender};
+
+    init_logger();
+
+    let mut gui = Gui::new(20.0, 20.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 20.0, 20.0]);
+    let red = Color::from_u32(0xff0000ff);
+    let blue = Color::from_u32(0x0000ffff);
+    let control = gui
+        .create_control()
+        .graphic(RoundRect::new(red, 8.0).with_border(blue, 2.0))
+        .build(&mut gui);
+
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    // the corners are rounded
+    assert_eq!(sprite_color(sprites, 0.0, 0.0), None);
+    assert_eq!(sprite_color(sprites, 19.0, 19.0), None);
+    // the border surrounds the inside
+    assert_eq!(sprite_color(sprites, 10.0, 0.0), Some(blue));
+    assert_eq!(sprite_color(sprites, 1.0, 10.0), Some(blue));
+    assert_eq!(sprite_color(sprites, 2.0, 10.0), Some(red));
+    assert_eq!(sprite_color(sprites, 10.0, 10.0), Some(red));
+
+    // each corner can have its own radius
+    *gui.get_context().get_graphic_mut(control) = RoundRect::new(red, 8.0)
+        .with_border(blue, 2.0)
+        .with_radii([0.0, 8.0, 8.0, 8.0])
+        .into();
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 0.0, 0.0), Some(blue));
+    assert_eq!(sprite_color(sprites, 19.0, 0.0), None);
+}
+
+#[test]
+fn gradients() {
+    use crate::{
+        graphics::{LinearGradient, RadialGradient},
+        Color, GuiRender,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(10.0, 10.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 10.0, 10.0]);
+    let red = Color::from_u32(0xff0000ff);
+    let blue = Color::from_u32(0x0000ffff);
+    // a hard step from red to blue, in the middle
+    let stops = vec![(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)];
+    let control = gui
+        .create_control()
+        .graphic(LinearGradient::new(stops.clone(), 0.0))
+        .build(&mut gui);
+
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    // a gradient does not contribute to the min size
+    assert_eq!(gui.get_context().get_min_size(control), [0.0, 0.0]);
+
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 0.0, 9.0), Some(red));
+    assert_eq!(sprite_color(sprites, 9.0, 0.0), Some(blue));
+
+    // from top to bottom, and tinted by its color
+    *gui.get_context().get_graphic_mut(control) =
+        LinearGradient::new(stops.clone(), std::f32::consts::FRAC_PI_2)
+            .with_color(Color::from_u32(0xffffff80))
+            .into();
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(
+        sprite_color(sprites, 9.0, 0.0),
+        Some(Color::from_u32(0xff000080))
+    );
+    assert_eq!(
+        sprite_color(sprites, 0.0, 9.0),
+        Some(Color::from_u32(0x0000ff80))
+    );
+
+    *gui.get_context().get_graphic_mut(control) =
+        RadialGradient::new(stops, [0.5, 0.5], 0.5).into();
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 5.0, 5.0), Some(red));
+    assert_eq!(sprite_color(sprites, 0.0, 0.0), Some(blue));
+}
+
+#[test]
+fn shadow() {
+    use crate::{
+        graphics::{Shadow, Texture},
+        Color, GuiRender,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(20.0, 20.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 20.0, 20.0]);
+    let red = Color::from_u32(0xff0000ff);
+    let blue = Color::from_u32(0x0000ffff);
+    let control = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([2.0, 2.0, 8.0, 8.0])
+        .graphic(Texture::new(1, [0.0, 0.0, 1.0, 1.0]).with_color(red))
+        .shadow(Shadow::new([5.0, 5.0], 0.0, blue))
+        .build(&mut gui);
+
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    // the shadow is drawn behind the graphic, and outside of the rect of the control
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 4.0, 4.0), Some(red));
+    assert_eq!(sprite_color(sprites, 10.0, 10.0), Some(blue));
+    assert_eq!(sprite_color(sprites, 1.0, 1.0), None);
+    assert_eq!(sprite_color(sprites, 14.0, 14.0), None);
+
+    // a blurred shadow fades out at its edges
+    gui.get_context()
+        .set_shadow(control, Some(Shadow::new([5.0, 5.0], 4.0, blue)));
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(sprite_color(sprites, 10.0, 10.0), Some(blue));
+    let edge = sprite_color(sprites, 5.0, 10.0).unwrap();
+    assert_eq!([edge.r, edge.g, edge.b], [0, 0, 255]);
+    assert!(0 < edge.a && edge.a < 255);
+}
+
+#[test]
+fn opacity() {
+    use crate::{graphics::Texture, Color, GuiRender};
+
+    init_logger();
+
+    let mut gui = Gui::new(4.0, 4.0, 1.0, Fonts::new());
+    gui.set_root_rect([0.0, 0.0, 4.0, 4.0]);
+    let texture = Texture::new(1, [0.0, 0.0, 1.0, 1.0]);
+    let parent = gui.create_control().opacity(0.5).build(&mut gui);
+    let left = gui
+        .create_control()
+        .parent(parent)
+        .anchors([0.0, 0.0, 0.5, 1.0])
+        .graphic(texture.clone().with_color(Color::from_u32(0xff0000ff)))
+        .build(&mut gui);
+    gui.create_control()
+        .parent(parent)
+        .anchors([0.5, 0.0, 1.0, 1.0])
+        .opacity(0.5)
+        .graphic(texture.with_color(Color::from_u32(0x0000ffff)))
+        .build(&mut gui);
+
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+
+    // the opacity is multiplied into the descendants
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(
+        sprite_color(sprites, 0.0, 0.0),
+        Some(Color::from_u32(0xff000080))
+    );
+    assert_eq!(
+        sprite_color(sprites, 3.0, 0.0),
+        Some(Color::from_u32(0x0000ff40))
+    );
+
+    // a transparent control is not visible, but is still laid out
+    gui.get_context().set_opacity(parent, 0.0);
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert!(sprites.iter().all(|sprite| sprite.color.a == 0));
+    assert_eq!(gui.get_context().get_rect(left), [0.0, 0.0, 2.0, 4.0]);
+
+    gui.get_context().set_opacity(parent, 1.0);
+    let (sprites, _) = gui_render.render(&mut gui.get_render_context(), NoRenderer);
+    assert_eq!(
+        sprite_color(sprites, 0.0, 0.0),
+        Some(Color::from_u32(0xff0000ff))
+    );
+}
+
+#[test]
+fn hover_cursor() {
+    use crate::{graphics::Graphic, style::ButtonStyle, widgets::Button};
+    use winit::window::CursorIcon;
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, fonts);
+    let style = Rc::new(ButtonStyle {
+        normal: Graphic::None,
+        hover: Graphic::None,
+        pressed: Graphic::None,
+        focus: Graphic::None,
+        disabled: None,
+    });
+
+    let area = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 0.0, 50.0, 50.0])
+        .cursor(CursorIcon::Crosshair)
+        .build(&mut gui);
+    gui.create_control()
+        .parent(area)
+        .margins([10.0, 10.0, -10.0, -10.0])
+        .behaviour(Mousable)
+        .build(&mut gui);
+    gui.create_control()
+        .anchors([0.0; 4])
+        .margins([50.0, 0.0, 100.0, 50.0])
+        .behaviour(Button::new(style, false, |_, _| ()))
+        .build(&mut gui);
+    let container = gui
+        .create_control()
+        .anchors([0.0; 4])
+        .margins([0.0, 50.0, 50.0, 100.0])
+        .build(&mut gui);
+    let text_field = gui.reserve_id();
+    build_text_field(&mut gui, text_field, container, font_id, ());
+    gui.get_context();
+
+    // a descendant without a cursor shows the cursor of its ancestor
+    gui.mouse_moved(0, 5.0, 5.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Crosshair));
+    gui.mouse_moved(0, 25.0, 25.0);
+    assert_eq!(gui.cursor_change(), None);
+
+    // buttons and text fields have a cursor by default
+    gui.mouse_moved(0, 75.0, 25.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Hand));
+    gui.mouse_moved(0, 25.0, 75.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Text));
+
+    gui.mouse_moved(0, 75.0, 75.0);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Default));
+}
+
+#[test]
+fn button_transition() {
+    use crate::{
+        graphics::{Graphic, Texture},
+        style::ButtonStyle,
+        widgets::Button,
+        Color,
+    };
+
+    init_logger();
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, Fonts::new());
+    let texture = Texture::new(1, [0.0, 0.0, 1.0, 1.0]);
+    let normal = Color::from_u32(0xff0000ff);
+    let hover = Color::from_u32(0x0000ffff);
+    let style = Rc::new(ButtonStyle {
+        normal: texture.clone().with_color(normal).into(),
+        hover: texture.with_color(hover).into(),
+        // a different texture can only be swapped
+        pressed: Texture::new(2, [0.0, 0.0, 1.0, 1.0]).into(),
+        focus: Graphic::None,
+        disabled: None,
+    });
+    let button = gui
+        .create_control()
+        .behaviour(Button::new(style, false, |_, _| ()).with_transition(Duration::from_millis(100)))
+        .build(&mut gui);
+    gui.get_context();
+
+    let color = |gui: &mut Gui| gui.get_graphic(button).unwrap().get_color();
+    let texture = |gui: &mut Gui| match gui.get_graphic(button) {
+        Some(Graphic::Texture(x)) => x.texture,
+        _ => panic!("the graphic of the button is not a Texture"),
+    };
+    assert_eq!(color(&mut gui), normal);
+
+    // the color fades to the hover color
+    gui.mouse_moved(0, 50.0, 50.0);
+    drop(gui.get_render_context());
+    assert_eq!(color(&mut gui), normal);
+    std::thread::sleep(Duration::from_millis(150));
+    drop(gui.get_render_context());
+    assert_eq!(color(&mut gui), hover);
+
+    gui.mouse_down(0, MouseButton::Left);
+    assert_eq!(texture(&mut gui), 2);
+}
+
+#[test]
+fn caret_blink() {
+    use crate::{
+        graphics::{Graphic, Text},
+        style::{OnFocusStyle, SelectionColor, TextFieldStyle},
+        text::TextStyle,
+        widgets::TextField,
+        Color,
+    };
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, fonts);
+    let style = Rc::new(TextFieldStyle {
+        background: OnFocusStyle {
+            normal: Graphic::None,
+            focus: Graphic::None,
+        },
+        caret_color: Color::BLACK,
+        caret_width: None,
+        selection_color: SelectionColor {
+            fg: None,
+            bg: Color::BLACK,
+        },
+        inactive_selection_color: None,
+        selection_radius: 0.0,
+    });
+
+    let period = Duration::from_millis(20);
+    let [text_field, caret, label] = [(); 3].map(|_| gui.reserve_id());
+    gui.create_control_reserved(text_field)
+        .min_size([0.0, 20.0])
+        .behaviour(TextField::new(caret, label, false, style, ()).with_caret_blink(Some(period)))
+        .build(&mut gui);
+    gui.create_control_reserved(caret)
+        .parent(text_field)
+        .anchors([0.0; 4])
+        .build(&mut gui);
+    gui.create_control_reserved(label)
+        .parent(text_field)
+        .graphic(Text::new(
+            String::new(),
+            (-1, 0),
+            TextStyle {
+                font_id,
+                ..Default::default()
+            },
+        ))
+        .build(&mut gui);
+
+    let caret_visible = |gui: &mut Gui| {
+        let rect = gui.get_context().get_rect(caret);
+        rect[2] > rect[0]
+    };
+    let blink = |gui: &mut Gui| {
+        std::thread::sleep(period + period / 2);
+        gui.handle_scheduled_event();
+    };
+
+    gui.get_context().set_focus(text_field);
+    assert!(caret_visible(&mut gui));
+
+    // the caret blinks while focused
+    blink(&mut gui);
+    assert!(!caret_visible(&mut gui));
+    blink(&mut gui);
+    assert!(caret_visible(&mut gui));
+    blink(&mut gui);
+    assert!(!caret_visible(&mut gui));
+
+    // typing makes it visible again
+    type_text(&mut gui, "a");
+    assert!(caret_visible(&mut gui));
+
+    // and losing focus stops the blink
+    gui.get_context().lose_focus(text_field);
+    assert!(!caret_visible(&mut gui));
+    std::thread::sleep(period * 2);
+    assert_eq!(gui.handle_scheduled_event(), None);
+}
+
+#[test]
+fn ime_composition() {
+    use crate::{graphics::Graphic, widgets::TextFieldCallback};
+    use winit::event::{Ime, WindowEvent};
+
+    init_logger();
+
+    struct OnChange(Rc<RefCell<Vec<String>>>);
+    impl TextFieldCallback for OnChange {
+        fn on_submit(&mut self, _: Id, _: &mut Context, _: &mut String) {}
+        fn on_change(&mut self, _: Id, _: &mut Context, text: &str) {
+            self.0.borrow_mut().push(text.to_owned());
+        }
+        fn on_unfocus(&mut self, _: Id, _: &mut Context, _: &mut String) {}
+    }
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 100.0, 1.0, fonts);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let text_field = gui.reserve_id();
+    let label = build_text_field(
+        &mut gui,
+        text_field,
+        Id::ROOT_ID,
+        font_id,
+        OnChange(changes.clone()),
+    );
+
+    let label_text = |gui: &mut Gui| match gui.get_graphic(label) {
+        Some(Graphic::Text(text)) => text.string().to_owned(),
+        _ => panic!("label is not a Text"),
+    };
+    let preedit = |gui: &mut Gui, text: &str| {
+        let cursor = Some((text.len(), text.len()));
+        gui.handle_event(&WindowEvent::Ime(Ime::Preedit(text.to_owned(), cursor)));
+    };
+
+    // focusing the text field allows the IME, and places its candidate window
+    gui.get_context().set_focus(text_field);
+    gui.get_context();
+    assert_eq!(gui.ime_allowed_change(), Some(true));
+    assert!(gui.ime_position_change().is_some());
+
+    type_text(&mut gui, "ab");
+    press_key(&mut gui, VirtualKeyCode::Left);
+
+    // the preedit text is inserted at the caret, and replaced while composing
+    preedit(&mut gui, "xy");
+    assert_eq!(label_text(&mut gui), "axyb");
+    preedit(&mut gui, "x");
+    assert_eq!(label_text(&mut gui), "axb");
+
+    // and replaced by the committed text
+    gui.handle_event(&WindowEvent::Ime(Ime::Commit("z".to_owned())));
+    assert_eq!(label_text(&mut gui), "azb");
+    assert_eq!(changes.borrow().last().unwrap(), "azb");
+    type_text(&mut gui, "c");
+    assert_eq!(label_text(&mut gui), "azcb");
+
+    // disabling the IME discards the composition
+    preedit(&mut gui, "q");
+    assert_eq!(label_text(&mut gui), "azcqb");
+    gui.handle_event(&WindowEvent::Ime(Ime::Disabled));
+    assert_eq!(label_text(&mut gui), "azcb");
+}
+
+#[test]
+fn interactive_text_links() {
+    use crate::{
+        graphics::{Graphic, Text},
+        text::TextStyle,
+        widgets::InteractiveText,
+    };
+    use winit::window::CursorIcon;
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(200.0, 100.0, 1.0, fonts);
+    let clicked = Rc::new(RefCell::new(Vec::new()));
+
+    let style = TextStyle {
+        font_id,
+        ..Default::default()
+    };
+    let text = gui
+        .create_control()
+        .graphic(Text::new("see the docs".to_owned(), (-1, -1), style))
+        .behaviour(
+            InteractiveText::new(Vec::new()).with_link(8..12, "https://docs", {
+                let clicked = clicked.clone();
+                move |payload: &str, _, _| clicked.borrow_mut().push(payload.to_owned())
+            }),
+        )
+        .build(&mut gui);
+    gui.get_context();
+
+    // move the mouse over the glyph at the given byte index
+    let move_to = |gui: &mut Gui, byte_index: usize| {
+        let mut ctx = gui.get_context();
+        let fonts = ctx.get_fonts();
+        let [x, y] = match ctx.get_rect_and_graphic(text) {
+            (rect, Graphic::Text(text)) => text
+                .get_layout(fonts, rect)
+                .pixel_position_from_byte_index(byte_index)
+                .unwrap(),
+            _ => panic!("the graphic is not a Text"),
+        };
+        drop(ctx);
+        gui.mouse_moved(0, x + 1.0, y - 3.0);
+    };
+    let click = |gui: &mut Gui| {
+        gui.mouse_down(0, MouseButton::Left);
+        gui.mouse_up(0, MouseButton::Left);
+    };
+
+    // the cursor changes while hovering the link
+    move_to(&mut gui, 1);
+    gui.cursor_change();
+    move_to(&mut gui, 9);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Hand));
+
+    // and clicking on it calls the callback with its payload
+    click(&mut gui);
+    assert_eq!(*clicked.borrow(), ["https://docs"]);
+
+    move_to(&mut gui, 1);
+    assert_eq!(gui.cursor_change(), Some(CursorIcon::Default));
+    click(&mut gui);
+    assert_eq!(clicked.borrow().len(), 1);
+}
+
+#[test]
+fn text_field_caret_and_selection_style() {
+    use crate::{
+        graphics::{Graphic, Text},
+        render::software::SoftwareRenderer,
+        style::{OnFocusStyle, SelectionColor, TextFieldStyle},
+        text::TextStyle,
+        widgets::TextField,
+        Color, GuiRender,
+    };
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(100.0, 20.0, 1.0, fonts);
+    gui.set_root_rect([0.0, 0.0, 100.0, 20.0]);
+    let red = Color::from_u32(0xff0000ff);
+    let blue = Color::from_u32(0x0000ffff);
+    let style = Rc::new(TextFieldStyle {
+        background: OnFocusStyle {
+            normal: Graphic::None,
+            focus: Graphic::None,
+        },
+        caret_color: Color::BLACK,
+        caret_width: Some(3.0),
+        selection_color: SelectionColor { fg: None, bg: red },
+        inactive_selection_color: Some(SelectionColor { fg: None, bg: blue }),
+        selection_radius: 2.0,
+    });
+
+    let [text_field, caret, label] = [(); 3].map(|_| gui.reserve_id());
+    gui.create_control_reserved(text_field)
+        .behaviour(TextField::new(caret, label, false, style, ()))
+        .build(&mut gui);
+    gui.create_control_reserved(caret)
+        .parent(text_field)
+        .anchors([0.0; 4])
+        .build(&mut gui);
+    gui.create_control_reserved(label)
+        .parent(text_field)
+        .graphic(Text::new(
+            String::new(),
+            (-1, 0),
+            TextStyle {
+                font_id,
+                ..Default::default()
+            },
+        ))
+        .build(&mut gui);
+
+    gui.get_context().set_focus(text_field);
+    type_text(&mut gui, "hello");
+    let caret_rect = gui.get_context().get_rect(caret);
+    assert_eq!(caret_rect[2] - caret_rect[0], 3.0);
+
+    let mut renderer = SoftwareRenderer::new();
+    let mut gui_render = GuiRender::new(10, 11, [16, 16]);
+    // count the pixels of the image with the given color
+    let mut count = |gui: &mut Gui, color: Color| {
+        let image =
+            gui_render.render_to_image(&mut gui.get_render_context(), &mut renderer, 100, 20);
+        let color = color.to_array();
+        image.chunks(4).filter(|x| *x == color).count()
+    };
+
+    gui.modifiers = ModifiersState::SHIFT;
+    press_key(&mut gui, VirtualKeyCode::Home);
+    gui.modifiers = ModifiersState::empty();
+    assert!(count(&mut gui, red) > 0);
+    assert_eq!(count(&mut gui, blue), 0);
+
+    // the selection is kept when unfocused, but with the inactive color
+    gui.get_context().lose_focus(text_field);
+    assert_eq!(count(&mut gui, red), 0);
+    assert!(count(&mut gui, blue) > 0);
+}
+
+#[test]
+fn menu_bar_shortcut() {
+    use crate::{
+        layouts::HBoxLayout,
+        widgets::{Item, Menu, MenuBar},
+    };
+
+    init_logger();
+
+    let (fonts, font_id) = noto_sans();
+    let mut gui = Gui::new(200.0, 200.0, 1.0, fonts);
+
+    let ids = Rc::new(RefCell::new(Vec::new()));
+    let ctrl_s = Accelerator::new(ModifiersState::CTRL, VirtualKeyCode::S);
+    let save = Item::ShortcutButton("save".to_owned(), ctrl_s, {
+        let ids = ids.clone();
+        Box::new(move |id, _| ids.borrow_mut().push(id))
+    });
+    let submenu = Menu::new("sub".to_owned(), vec![save]);
+    let menu = Menu::new("file".to_owned(), vec![Item::SubMenu(Rc::new(submenu))]);
+    let blocker = gui.create_control().active(false).build(&mut gui);
+    let bar = gui
+        .create_control()
+        .anchors([0.0, 0.0, 1.0, 0.0])
+        .margins([0.0, 0.0, 0.0, 20.0])
+        .layout(HBoxLayout::new(0.0, [0.0; 4], -1))
+        .behaviour(MenuBar::new(
+            menu_style(font_id),
+            blocker,
+            vec![Rc::new(menu)],
+        ))
+        .build(&mut gui);
+    gui.get_context();
+
+    // the accelerator calls the callback with the id of the MenuBar
+    assert!(gui.call_accelerator(ctrl_s));
+    assert_eq!(*ids.borrow(), [bar]);
+
+    let last_opened = |gui: &mut Gui| {
+        *gui.get_context()
+            .get_active_children(Id::ROOT_ID)
+            .last()
+            .unwrap()
+    };
+    let center = |gui: &mut Gui, id: Id| {
+        let rect = gui.get_context().get_rect(id);
+        [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0]
+    };
+
+    // and so does clicking the item, even when it is in a submenu
+    gui.mouse_moved(0, 5.0, 5.0);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    let menu = last_opened(&mut gui);
+    let item = gui.get_context().get_active_children(menu)[0];
+    let [x, y] = center(&mut gui, item);
+    gui.mouse_moved(0, x, y);
+    let submenu = last_opened(&mut gui);
+    assert_ne!(submenu, menu);
+    let item = gui.get_context().get_active_children(submenu)[0];
+    let [x, y] = center(&mut gui, item);
+    gui.mouse_moved(0, x, y);
+    gui.mouse_down(0, MouseButton::Left);
+    gui.mouse_up(0, MouseButton::Left);
+    assert_eq!(*ids.borrow(), [bar, bar]);
+}
@@ -28,6 +28,15 @@ impl Layout for FitGraphic {
     }
 }
 
+/// Place all active children overlapping each other, filling the area of this control, minus the
+/// margins.
+///
+/// The min size of this layout is the biggest min size of its children, plus the margins. Each
+/// child is aligned inside of the area by its own [`RectFill`](crate::RectFill). Children are
+/// drawn in order, so the last child is on top.
+///
+/// This is the layout for overlays, like a badge over an icon, or a spinner centered over some
+/// content.
 pub struct MarginLayout {
     margins: [f32; 4],
 }
@@ -68,13 +77,38 @@ impl Layout for MarginLayout {
     }
 }
 
+/// Lay out its children with a width/height ratio inside of a range, filling the available space
+/// as much as possible. If the ratio of this control is out of the range, the children are
+/// letterboxed, aligned by `align`.
+///
+/// `align` is the alignment in the x and y axis: -1 to the start, 0 to the center, and 1 to the
+/// end.
 pub struct RatioLayout {
-    ratio: f32,
+    min_ratio: f32,
+    max_ratio: f32,
     align: (i8, i8),
 }
 impl RatioLayout {
+    /// Keep the children in a fixed ratio.
     pub fn new(ratio: f32, align: (i8, i8)) -> Self {
-        Self { ratio, align }
+        Self::with_range(ratio, ratio, align)
+    }
+
+    /// Keep the ratio of the children between `min_ratio` and `max_ratio`, inclusive.
+    pub fn with_range(min_ratio: f32, max_ratio: f32, align: (i8, i8)) -> Self {
+        debug_assert!(min_ratio <= max_ratio);
+        Self {
+            min_ratio,
+            max_ratio,
+            align,
+        }
+    }
+
+    fn clamp_ratio(&self, ratio: f32) -> f32 {
+        if ratio.is_nan() {
+            return self.min_ratio;
+        }
+        ratio.max(self.min_ratio).min(self.max_ratio)
     }
 }
 impl Layout for RatioLayout {
@@ -85,44 +119,31 @@ impl Layout for RatioLayout {
             min_size[0] = min_size[0].max(c_min_size[0]);
             min_size[1] = min_size[1].max(c_min_size[1]);
         }
-        if min_size[0] > min_size[1] * self.ratio {
-            [min_size[0], min_size[0] / self.ratio]
+        let ratio = self.clamp_ratio(min_size[0] / min_size[1]);
+        if min_size[0] > min_size[1] * ratio {
+            [min_size[0], min_size[0] / ratio]
         } else {
-            [min_size[1] * self.ratio, min_size[1]]
+            [min_size[1] * ratio, min_size[1]]
         }
     }
 
     fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
         let rect = ctx.get_layouting(this);
-        let mut x = rect.get_rect()[0];
-        let mut y = rect.get_rect()[1];
-        let des_rect = if rect.get_width() < rect.get_height() * self.ratio {
-            match self.align.1 {
-                -1 => {}
-                0 => y += (rect.get_height() - rect.get_width() / self.ratio) / 2.0,
-                1 => y += rect.get_height() - rect.get_width() / self.ratio,
-                _ => {}
-            }
-            [
-                x,
-                y,
-                x + rect.get_width(),
-                y + rect.get_width() / self.ratio,
-            ]
+        let (width, height) = (rect.get_width(), rect.get_height());
+        let ratio = self.clamp_ratio(width / height);
+        let size = if width < height * ratio {
+            [width, width / ratio]
         } else {
-            match self.align.0 {
-                -1 => {}
-                0 => x += (rect.get_width() - rect.get_height() * self.ratio) / 2.0,
-                1 => x += rect.get_width() - rect.get_height() * self.ratio,
-                _ => {}
-            }
-            [
-                x,
-                y,
-                x + rect.get_height() * self.ratio,
-                y + rect.get_height(),
-            ]
+            [height * ratio, height]
+        };
+        let align = |align: i8, free: f32| match align {
+            0 => free / 2.0,
+            1 => free,
+            _ => 0.0,
         };
+        let x = rect.get_rect()[0] + align(self.align.0, width - size[0]);
+        let y = rect.get_rect()[1] + align(self.align.1, height - size[1]);
+        let des_rect = [x, y, x + size[0], y + size[1]];
         for child in ctx.get_active_children(this) {
             ctx.set_designed_rect(child, des_rect);
         }
@@ -212,7 +233,10 @@ impl Layout for HBoxLayout {
                 if rect.is_expand_x() {
                     // FIXME: this implementation imply that rect with same ratio,
                     // may not have the same size when expanded
-                    let width = rect.get_min_size()[0] + free_width * rect.ratio_x / max_weight;
+                    let min_width = rect.get_min_size()[0];
+                    let width = (min_width + free_width * rect.ratio_x / max_weight)
+                        .min(rect.get_max_size()[0])
+                        .max(min_width);
                     ctx.set_designed_rect(child, [x, top, x + width, bottom]);
                     x += self.spacing + width
                 } else {
@@ -308,7 +332,10 @@ impl Layout for VBoxLayout {
                 if rect.is_expand_y() {
                     // FIXME: this implementation imply that rect with same ratio,
                     // may not have the same size when expanded
-                    let height = rect.get_min_size()[1] + free_height * rect.ratio_y / max_weight;
+                    let min_height = rect.get_min_size()[1];
+                    let height = (min_height + free_height * rect.ratio_y / max_weight)
+                        .min(rect.get_max_size()[1])
+                        .max(min_height);
                     ctx.set_designed_rect(child, [left, y, right, y + height]);
                     y += self.spacing + height;
                 } else {
@@ -321,65 +348,52 @@ impl Layout for VBoxLayout {
     }
 }
 
-pub struct GridLayout {
-    spacing: [f32; 2],
+/// Lay out its children in a row, or in a column, distributing the space by the [`Flex`] of each
+/// child.
+///
+/// Each child starts with its basis size. If there is space left, it is distributed proportionally
+/// to the grow weight of each child, but a child never grows above its max size. If there is space
+/// missing, it is removed proportionally to the shrink weight, times the basis, of each child, but
+/// a child never shrinks below its min size. In the cross axis, each child fills the layout, up to
+/// its max size.
+///
+/// [`Flex`]: crate::Flex
+pub struct FlexLayout {
+    /// If true, the children are laid out in a column, otherwise in a row.
+    vertical: bool,
+    spacing: f32,
     margins: [f32; 4],
-    columns: u32,
-    rows: u32,
-    min_sizes: Vec<f32>,
-    expand: Vec<bool>,
-    weights: Vec<f32>,
+    align: i8,
 }
-impl GridLayout {
-    pub fn new(spacing: [f32; 2], margins: [f32; 4], columns: u32) -> Self {
+impl FlexLayout {
+    pub fn new(vertical: bool, spacing: f32, margins: [f32; 4], align: i8) -> Self {
         Self {
+            vertical,
             spacing,
             margins,
-            columns,
-            rows: 0,
-            min_sizes: Vec::new(),
-            expand: Vec::new(),
-            weights: Vec::new(),
+            align,
         }
     }
 }
-impl Layout for GridLayout {
+impl Layout for FlexLayout {
     fn compute_min_size(&mut self, this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        let i = self.vertical as usize;
         let children = ctx.get_active_children(this);
-        if children.is_empty() {
-            self.rows = 0;
-            self.min_sizes.clear();
-            [
-                self.margins[0] + self.margins[2],
-                self.margins[1] + self.margins[3],
-            ]
-        } else {
-            let len = children.len();
-            self.rows = 1 + (len as u32 - 1) / self.columns;
-            let columns = self.columns.min(children.len() as u32) as usize;
-            let len = columns + self.rows as usize;
-            self.min_sizes.resize(len, 0.0);
-            self.expand.clear();
-            self.expand.resize(len, false);
-            self.weights.resize(len, 0.0);
-            for (i, child) in children.into_iter().enumerate() {
-                let rect = ctx.get_layouting(child).unwrap();
-                let col = i % columns;
-                self.min_sizes[col] = self.min_sizes[col].max(rect.get_min_size()[0]);
-                self.expand[col] |= rect.is_expand_x();
-                self.weights[col] = rect.ratio_x;
-                let row = columns + i / columns;
-                self.min_sizes[row] = self.min_sizes[row].max(rect.get_min_size()[1]);
-                self.expand[row] |= rect.is_expand_y();
-                self.weights[row] = rect.ratio_y;
-            }
-            [
-                self.min_sizes[0..columns].iter().sum::<f32>()
-                    + self.spacing[0] * self.columns.min(len as u32) as f32,
-                self.min_sizes[columns..].iter().sum::<f32>()
-                    + self.spacing[1] * (self.rows as usize - 1) as f32,
-            ]
+        let mut min_size = [
+            self.margins[0] + self.margins[2],
+            self.margins[1] + self.margins[3],
+        ];
+        let mut cross: f32 = 0.0;
+        for child in &children {
+            let child_min_size = ctx.get_min_size(*child);
+            min_size[i] += child_min_size[i];
+            cross = cross.max(child_min_size[1 - i]);
+        }
+        if !children.is_empty() {
+            min_size[i] += (children.len() - 1) as f32 * self.spacing;
         }
+        min_size[1 - i] += cross;
+        min_size
     }
 
     fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
@@ -387,86 +401,441 @@ impl Layout for GridLayout {
         if children.is_empty() {
             return;
         }
-        let columns = (self.columns as usize).min(children.len());
-        let collumn_range = 0..columns;
-        let row_range = columns..columns + self.rows as usize;
-        let mut reserved_height = self.spacing[0] * columns as f32;
-        let mut reserved_width = self.spacing[1] * self.rows as f32;
-        let mut width_weight = 0.0;
-        let mut height_weight = 0.0;
-        for i in collumn_range.clone() {
-            reserved_width += self.min_sizes[i];
-            if self.expand[i] {
-                width_weight += self.weights[i];
+        let i = self.vertical as usize;
+        let j = 1 - i;
+        let rect = *ctx.get_layouting(this).get_rect();
+        let available = rect[i + 2]
+            - rect[i]
+            - self.margins[i]
+            - self.margins[i + 2]
+            - self.spacing * (children.len() - 1) as f32;
+
+        let mins: Vec<f32> = children.iter().map(|&x| ctx.get_min_size(x)[i]).collect();
+        let maxs: Vec<f32> = children
+            .iter()
+            .zip(mins.iter())
+            .map(|(&x, min)| ctx.get_layouting(x).get_max_size()[i].max(*min))
+            .collect();
+        let flexes: Vec<_> = children
+            .iter()
+            .map(|&x| ctx.get_layouting(x).flex)
+            .collect();
+        let mut sizes: Vec<f32> = flexes
+            .iter()
+            .zip(mins.iter().zip(maxs.iter()))
+            .map(|(flex, (min, max))| flex.basis.min(*max).max(*min))
+            .collect();
+
+        let free = available - sizes.iter().sum::<f32>();
+        if free > 0.0 {
+            // grow the children that are not already at their max size, until all the free space
+            // is used, or every child is at its max size.
+            let mut frozen = vec![false; sizes.len()];
+            loop {
+                let free = available - sizes.iter().sum::<f32>();
+                let total_grow: f32 = flexes
+                    .iter()
+                    .zip(frozen.iter())
+                    .filter(|(_, frozen)| !**frozen)
+                    .map(|(flex, _)| flex.grow)
+                    .sum();
+                if free <= 0.0 || total_grow <= 0.0 {
+                    break;
+                }
+                let mut clamped = false;
+                for (k, size) in sizes.iter_mut().enumerate() {
+                    if frozen[k] {
+                        continue;
+                    }
+                    *size += free * flexes[k].grow / total_grow;
+                    if *size >= maxs[k] {
+                        *size = maxs[k];
+                        frozen[k] = true;
+                        clamped = true;
+                    }
+                }
+                if !clamped {
+                    break;
+                }
+            }
+        } else if free < 0.0 {
+            // shrink the children that are not already at their min size, until all the missing
+            // space is removed, or every child is at its min size.
+            let mut frozen = vec![false; sizes.len()];
+            loop {
+                let missing = available - sizes.iter().sum::<f32>();
+                let total_shrink: f32 = flexes
+                    .iter()
+                    .zip(frozen.iter())
+                    .filter(|(_, frozen)| !**frozen)
+                    .map(|(flex, _)| flex.shrink * flex.basis)
+                    .sum();
+                if missing >= 0.0 || total_shrink <= 0.0 {
+                    break;
+                }
+                let mut clamped = false;
+                for (k, size) in sizes.iter_mut().enumerate() {
+                    if frozen[k] {
+                        continue;
+                    }
+                    let flex = &flexes[k];
+                    *size += missing * flex.shrink * flex.basis / total_shrink;
+                    if *size <= mins[k] {
+                        *size = mins[k];
+                        frozen[k] = true;
+                        clamped = true;
+                    }
+                }
+                if !clamped {
+                    break;
+                }
             }
         }
-        for i in row_range.clone() {
-            reserved_height += self.min_sizes[i];
-            if self.expand[i] {
-                height_weight += self.weights[i];
+
+        let mut pos = rect[i] + self.margins[i];
+        let free = available - sizes.iter().sum::<f32>();
+        if free > 0.0 {
+            match self.align {
+                0 => pos += free / 2.0,
+                1 => pos += free,
+                _ => {}
             }
         }
-        let rect = ctx.get_layouting(this);
-        let width = rect.get_width() - self.margins[0] - self.margins[2];
-        let height = rect.get_height() - self.margins[1] - self.margins[3];
-        let free_width = width - reserved_width;
-        let free_height = height - reserved_height;
-        let mut positions = vec![[0.0; 2]; self.columns as usize + self.rows as usize];
-        let mut x = rect.get_rect()[0] + self.margins[0];
-        if free_width <= 0.0 || width_weight == 0.0 {
-            for i in collumn_range {
-                positions[i][0] = x;
-                positions[i][1] = x + self.min_sizes[i];
-                x += self.spacing[0] + self.min_sizes[i];
+        let cross = [rect[j] + self.margins[j], rect[j + 2] - self.margins[j + 2]];
+        for (child, size) in children.iter().zip(sizes.iter()) {
+            let mut child_rect = [0.0; 4];
+            child_rect[i] = pos;
+            child_rect[i + 2] = pos + size;
+            child_rect[j] = cross[0];
+            child_rect[j + 2] = cross[1];
+            ctx.set_designed_rect(*child, child_rect);
+            pos += size + self.spacing;
+        }
+    }
+}
+
+/// Lay out its children from left to right, wrapping to a new row when the next child would
+/// overflow the available width.
+///
+/// Each child has its min width, and the height of its row. The min width of the layout is the
+/// width of its widest child, and its min height is the height needed to fit all rows in its
+/// current width. Because of that, when the width changes, the layout is updated a second time.
+pub struct FlowLayout {
+    /// The horizontal and vertical spacing between children.
+    spacing: [f32; 2],
+    margins: [f32; 4],
+    /// The min height last computed, without the margins.
+    min_height: f32,
+}
+impl FlowLayout {
+    pub fn new(spacing: [f32; 2], margins: [f32; 4]) -> Self {
+        Self {
+            spacing,
+            margins,
+            min_height: 0.0,
+        }
+    }
+
+    /// Split the children of the given sizes in rows that fit in the given width. Return the
+    /// number of children and the height of each row.
+    fn rows(&self, sizes: &[[f32; 2]], width: f32) -> Vec<(usize, f32)> {
+        let mut rows = Vec::new();
+        let mut count = 0;
+        let mut row_width = 0.0;
+        let mut row_height: f32 = 0.0;
+        for &[w, h] in sizes {
+            if count > 0 && row_width + self.spacing[0] + w > width {
+                rows.push((count, row_height));
+                count = 0;
+                row_width = 0.0;
+                row_height = 0.0;
             }
-        } else {
-            for i in collumn_range {
-                if self.expand[i] {
-                    // FIXME: this implementation imply that rects with the same ratio
-                    // may not have the same size when expanded
-                    let width = self.min_sizes[i] + free_width * self.weights[i] / width_weight;
-                    positions[i][0] = x;
-                    positions[i][1] = x + width;
-                    x += self.spacing[0] + width;
-                } else {
-                    positions[i][0] = x;
-                    positions[i][1] = x + self.min_sizes[i];
-                    x += self.spacing[0] + self.min_sizes[i];
+            if count > 0 {
+                row_width += self.spacing[0];
+            }
+            row_width += w;
+            row_height = row_height.max(h);
+            count += 1;
+        }
+        if count > 0 {
+            rows.push((count, row_height));
+        }
+        rows
+    }
+
+    fn height(&self, rows: &[(usize, f32)]) -> f32 {
+        let spacing = self.spacing[1] * rows.len().saturating_sub(1) as f32;
+        rows.iter().map(|(_, h)| h).sum::<f32>() + spacing
+    }
+}
+impl Layout for FlowLayout {
+    fn compute_min_size(&mut self, this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        let sizes: Vec<[f32; 2]> = ctx
+            .get_active_children(this)
+            .iter()
+            .map(|&x| ctx.get_min_size(x))
+            .collect();
+        let widest = sizes.iter().map(|x| x[0]).fold(0.0, f32::max);
+        let width = ctx.get_layouting(this).map_or(0.0, |x| x.get_width())
+            - self.margins[0]
+            - self.margins[2];
+        self.min_height = self.height(&self.rows(&sizes, width.max(widest)));
+        [
+            widest + self.margins[0] + self.margins[2],
+            self.min_height + self.margins[1] + self.margins[3],
+        ]
+    }
+
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let children = ctx.get_active_children(this);
+        let sizes: Vec<[f32; 2]> = children.iter().map(|&x| ctx.get_min_size(x)).collect();
+        let rect = ctx.get_rect(this);
+        let left = rect[0] + self.margins[0];
+        let width = rect[2] - rect[0] - self.margins[0] - self.margins[2];
+
+        let rows = self.rows(&sizes, width);
+        if (self.height(&rows) - self.min_height).abs() > 0.01 {
+            // the min height was computed for a different width
+            ctx.relayout();
+        }
+
+        let mut children = children.iter().zip(sizes.iter());
+        let mut y = rect[1] + self.margins[1];
+        for (count, height) in rows {
+            let mut x = left;
+            for (&child, size) in children.by_ref().take(count) {
+                ctx.set_designed_rect(child, [x, y, x + size[0], y + height]);
+                x += size[0] + self.spacing[0];
+            }
+            y += height + self.spacing[1];
+        }
+    }
+}
+
+/// The size constraints of a column or a row of a [`GridLayout`].
+#[derive(Clone, Default)]
+struct Track {
+    min_size: f32,
+    expand: bool,
+    weight: f32,
+}
+
+/// The min size, expand and ratio of a child of a [`GridLayout`], in each axis.
+struct GridChild {
+    min_size: [f32; 2],
+    expand: [bool; 2],
+    ratio: [f32; 2],
+}
+
+/// Lay out its children in a grid with a fixed number of columns, from left to right and top to
+/// bottom.
+///
+/// A child can span multiple columns and rows, using [`ControlBuilder::span`]. It is placed in the
+/// first free cell where it fits. The width of each column is the min width of its widest child,
+/// and the height of each row is the min height of its highest child. Spanned children make the
+/// tracks they span grow equally, if they don't fit.
+///
+/// A column or row expands if any of its children expands, with the ratio of that child as
+/// weight, unless its weight was given by [`GridLayout::with_column_weights`] or
+/// [`GridLayout::with_row_weights`].
+///
+/// [`ControlBuilder::span`]: crate::ControlBuilder::span
+pub struct GridLayout {
+    spacing: [f32; 2],
+    margins: [f32; 4],
+    columns: u32,
+    /// The weights of the columns and rows given by the user.
+    weights: [Vec<f32>; 2],
+    /// The cell of each active child, as [column, row, column span, row span].
+    cells: Vec<[usize; 4]>,
+    /// The columns and the rows.
+    tracks: [Vec<Track>; 2],
+}
+impl GridLayout {
+    pub fn new(spacing: [f32; 2], margins: [f32; 4], columns: u32) -> Self {
+        Self {
+            spacing,
+            margins,
+            columns: columns.max(1),
+            weights: [Vec::new(), Vec::new()],
+            cells: Vec::new(),
+            tracks: [Vec::new(), Vec::new()],
+        }
+    }
+
+    /// Set the weight of each column, used to distribute the free width. A column with a weight
+    /// greater than zero always expands, and a column with zero weight never does. Columns
+    /// without a given weight use the weight of its children.
+    pub fn with_column_weights(mut self, weights: Vec<f32>) -> Self {
+        self.weights[0] = weights;
+        self
+    }
+
+    /// Set the weight of each row, used to distribute the free height. Works the same as
+    /// [`GridLayout::with_column_weights`].
+    pub fn with_row_weights(mut self, weights: Vec<f32>) -> Self {
+        self.weights[1] = weights;
+        self
+    }
+
+    /// Find the cell of each child, given the number of columns and rows that it spans.
+    fn place_cells(&mut self, spans: impl Iterator<Item = [u32; 2]>) {
+        let columns = self.columns as usize;
+        let mut occupied: Vec<bool> = Vec::new();
+        let is_free = |occupied: &[bool], col: usize, row: usize, w: usize, h: usize| {
+            (row..row + h).all(|r| {
+                (col..col + w).all(|c| !occupied.get(r * columns + c).copied().unwrap_or(false))
+            })
+        };
+        // all cells before this one are occupied
+        let mut first_free = 0;
+        self.cells.clear();
+        for span in spans {
+            let w = (span[0].max(1) as usize).min(columns);
+            let h = span[1].max(1) as usize;
+            let mut i = first_free;
+            while i % columns + w > columns || !is_free(&occupied, i % columns, i / columns, w, h) {
+                i += 1;
+            }
+            let (col, row) = (i % columns, i / columns);
+            if occupied.len() < (row + h) * columns {
+                occupied.resize((row + h) * columns, false);
+            }
+            for r in row..row + h {
+                for x in &mut occupied[r * columns + col..r * columns + col + w] {
+                    *x = true;
                 }
             }
+            while occupied.get(first_free) == Some(&true) {
+                first_free += 1;
+            }
+            self.cells.push([col, row, w, h]);
         }
+    }
 
-        let mut y = rect.get_rect()[1] + self.margins[1];
-        if free_height <= 0.0 || height_weight == 0.0 {
-            for i in row_range {
-                positions[i][0] = y;
-                positions[i][1] = y + self.min_sizes[i];
-                y += self.spacing[1] + self.min_sizes[i];
+    /// Compute the columns, if axis is 0, or the rows, if axis is 1.
+    fn compute_tracks(&self, axis: usize, children: &[GridChild]) -> Vec<Track> {
+        let len = self
+            .cells
+            .iter()
+            .map(|cell| cell[axis] + cell[axis + 2])
+            .max()
+            .unwrap_or(0);
+        let mut tracks = vec![Track::default(); len];
+        for (cell, child) in self.cells.iter().zip(children) {
+            if cell[axis + 2] != 1 {
+                continue;
             }
-        } else {
-            for i in row_range {
-                if self.expand[i] {
-                    // FIXME: this implementation imply that rects with the same ratio
-                    // may not have the same size when expanded
-                    let height = self.min_sizes[i] + free_height * self.weights[i] / height_weight;
-                    positions[i][0] = y;
-                    positions[i][1] = y + height;
-                    y += self.spacing[1] + height;
-                } else {
-                    positions[i][0] = y;
-                    positions[i][1] = y + self.min_sizes[i];
-                    y += self.spacing[1] + self.min_sizes[i];
+            let track = &mut tracks[cell[axis]];
+            track.min_size = track.min_size.max(child.min_size[axis]);
+            if child.expand[axis] {
+                track.expand = true;
+                track.weight = track.weight.max(child.ratio[axis]);
+            }
+        }
+        for (cell, child) in self.cells.iter().zip(children) {
+            let span = cell[axis + 2];
+            if span == 1 {
+                continue;
+            }
+            let tracks = &mut tracks[cell[axis]..cell[axis] + span];
+            let size = tracks.iter().map(|x| x.min_size).sum::<f32>()
+                + self.spacing[axis] * (span - 1) as f32;
+            let missing = child.min_size[axis] - size;
+            if missing > 0.0 {
+                for track in tracks.iter_mut() {
+                    track.min_size += missing / span as f32;
                 }
             }
+            if child.expand[axis] && !tracks.iter().any(|x| x.expand) {
+                for track in tracks.iter_mut() {
+                    track.expand = true;
+                    track.weight = child.ratio[axis];
+                }
+            }
+        }
+        for (track, &weight) in tracks.iter_mut().zip(self.weights[axis].iter()) {
+            track.expand = weight > 0.0;
+            track.weight = weight;
         }
-        for (i, child) in children.into_iter().enumerate() {
-            let col = i % self.columns as usize;
-            let row = self.columns as usize + i / self.columns as usize;
+        tracks
+    }
+
+    /// The sum of the min size of the tracks of the given axis, plus the spacing between them.
+    fn tracks_min_size(&self, axis: usize) -> f32 {
+        let tracks = &self.tracks[axis];
+        tracks.iter().map(|x| x.min_size).sum::<f32>()
+            + self.spacing[axis] * tracks.len().saturating_sub(1) as f32
+    }
+
+    /// Return the start and end of each track of the given axis, distributing the free space
+    /// between the expanding tracks.
+    fn track_positions(&self, axis: usize, start: f32, length: f32) -> Vec<[f32; 2]> {
+        let tracks = &self.tracks[axis];
+        let free = length - self.tracks_min_size(axis);
+        let weight: f32 = tracks.iter().filter(|x| x.expand).map(|x| x.weight).sum();
+        let mut x = start;
+        tracks
+            .iter()
+            .map(|track| {
+                let mut size = track.min_size;
+                if free > 0.0 && weight > 0.0 && track.expand {
+                    size += free * track.weight / weight;
+                }
+                let position = [x, x + size];
+                x += size + self.spacing[axis];
+                position
+            })
+            .collect()
+    }
+}
+impl Layout for GridLayout {
+    fn compute_min_size(&mut self, this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        let ids = ctx.get_active_children(this);
+        let mut spans = Vec::with_capacity(ids.len());
+        let mut children = Vec::with_capacity(ids.len());
+        for id in ids {
+            let rect = ctx.get_layouting(id).unwrap();
+            spans.push(rect.span);
+            children.push(GridChild {
+                min_size: rect.get_min_size(),
+                expand: [rect.is_expand_x(), rect.is_expand_y()],
+                ratio: [rect.ratio_x, rect.ratio_y],
+            });
+        }
+        self.place_cells(spans.into_iter());
+        self.tracks = [
+            self.compute_tracks(0, &children),
+            self.compute_tracks(1, &children),
+        ];
+        [
+            self.tracks_min_size(0) + self.margins[0] + self.margins[2],
+            self.tracks_min_size(1) + self.margins[1] + self.margins[3],
+        ]
+    }
+
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let children = ctx.get_active_children(this);
+        if children.len() != self.cells.len() {
+            return;
+        }
+        let rect = ctx.get_rect(this);
+        let columns = self.track_positions(
+            0,
+            rect[0] + self.margins[0],
+            rect[2] - rect[0] - self.margins[0] - self.margins[2],
+        );
+        let rows = self.track_positions(
+            1,
+            rect[1] + self.margins[1],
+            rect[3] - rect[1] - self.margins[1] - self.margins[3],
+        );
+        for (child, &[col, row, w, h]) in children.into_iter().zip(self.cells.iter()) {
             let rect = [
-                positions[col][0],
-                positions[row][0],
-                positions[col][1],
-                positions[row][1],
+                columns[col][0],
+                rows[row][0],
+                columns[col + w - 1][1],
+                rows[row + h - 1][1],
             ];
             ctx.set_designed_rect(child, rect);
         }
@@ -8,22 +8,25 @@ pub use giui_derive::*;
 pub mod font;
 pub mod text;
 mod time;
-mod util;
+pub mod util;
 
+pub mod accessibility;
 mod color;
 mod context;
 mod control;
+pub mod easing;
 pub mod graphics;
 mod gui;
 pub mod layouts;
 mod rect;
 pub mod render;
+pub mod scene;
 pub mod style;
 pub mod widgets;
 
 pub mod style_loader;
 
-pub use color::Color;
+pub use color::{Color, ParseColorError};
 pub use context::*;
 pub use control::*;
 pub use gui::*;
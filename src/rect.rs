@@ -1,6 +1,6 @@
 use crate::util::cmp_float;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum RectFill {
     Fill,
     ShrinkStart,
@@ -13,6 +13,29 @@ impl Default for RectFill {
     }
 }
 
+/// How a control grows and shrinks inside of a [`FlexLayout`](crate::layouts::FlexLayout).
+#[derive(Copy, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Flex {
+    /// The weight of the free space that is given to the control, when there is space left.
+    pub grow: f32,
+    /// The weight of the missing space that is removed from the control, when there is not
+    /// enough space. It is multiplied by the basis, so bigger controls shrink more.
+    pub shrink: f32,
+    /// The preferred size of the control, in the axis of the layout. If smaller than the min size
+    /// of the control, the min size is used instead.
+    pub basis: f32,
+}
+impl Default for Flex {
+    fn default() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 1.0,
+            basis: 0.0,
+        }
+    }
+}
+
 bitflags! {
     pub struct RenderDirtyFlags: u8 {
         /// The width of the rect has changed
@@ -65,6 +88,9 @@ pub struct Rect {
     pub margins: [f32; 4],
     pub(crate) user_min_size: [f32; 2],
     pub(crate) min_size: [f32; 2],
+    /// The max width and height of the rect. Infinite if unbounded. The min size takes precedence,
+    /// if it is bigger.
+    pub(crate) max_size: [f32; 2],
     /// A [x1, y1, x2, y2] rect.
     pub(crate) rect: [f32; 4],
     pub(crate) expand_x: bool,
@@ -73,6 +99,12 @@ pub struct Rect {
     pub(crate) fill_y: RectFill,
     pub ratio_x: f32,
     pub ratio_y: f32,
+    pub flex: Flex,
+    /// The number of columns and rows that the control spans inside of a
+    /// [`GridLayout`](crate::layouts::GridLayout).
+    pub span: [u32; 2],
+    /// If true, the graphics of the descendants of the control are clipped by its rect.
+    pub(crate) clip_children: bool,
     pub(crate) render_dirty_flags: RenderDirtyFlags,
     pub(crate) layout_dirty_flags: LayoutDirtyFlags,
 }
@@ -83,6 +115,7 @@ impl Default for Rect {
             margins: [0.0, 0.0, 0.0, 0.0],
             user_min_size: [0.0; 2],
             min_size: [0.0; 2],
+            max_size: [f32::INFINITY; 2],
             rect: [0.0; 4],
             expand_x: false,
             expand_y: false,
@@ -90,6 +123,9 @@ impl Default for Rect {
             fill_y: RectFill::default(),
             ratio_x: 1.0,
             ratio_y: 1.0,
+            flex: Flex::default(),
+            span: [1, 1],
+            clip_children: true,
             render_dirty_flags: RenderDirtyFlags::default(),
             layout_dirty_flags: LayoutDirtyFlags::default(),
         }
@@ -152,57 +188,39 @@ impl Rect {
     /// Set the designed area for this rect. This rect will decide its own size,
     /// based on its size flags and the designed area.
     pub fn set_designed_rect(&mut self, rect: [f32; 4]) {
+        let rect = self.fit_designed_rect(rect);
+        self.set_rect(rect);
+    }
+
+    /// The rect that this rect would have, if the given designed area was set.
+    pub(crate) fn fit_designed_rect(&self, rect: [f32; 4]) -> [f32; 4] {
         let mut new_rect = [0.0; 4];
-        if rect[2] - rect[0] <= self.get_min_size()[0] {
-            new_rect[0] = rect[0];
-            new_rect[2] = rect[0] + self.get_min_size()[0];
-        } else {
-            match self.fill_x {
-                RectFill::Fill => {
-                    new_rect[0] = rect[0];
-                    new_rect[2] = rect[2];
-                }
-                RectFill::ShrinkStart => {
-                    new_rect[0] = rect[0];
-                    new_rect[2] = rect[0] + self.get_min_size()[0];
-                }
-                RectFill::ShrinkCenter => {
-                    let x = (rect[2] - rect[0] - self.get_min_size()[0]) / 2.0;
-                    new_rect[0] = rect[0] + x;
-                    new_rect[2] = rect[2] - x;
-                }
-                RectFill::ShrinkEnd => {
-                    new_rect[0] = rect[2] - self.get_min_size()[0];
-                    new_rect[2] = rect[2];
-                }
+        for (i, &fill) in [self.fill_x, self.fill_y].iter().enumerate() {
+            let min = self.get_min_size()[i];
+            let start = rect[i];
+            let end = rect[i + 2];
+            if end - start <= min {
+                new_rect[i] = start;
+                new_rect[i + 2] = start + min;
+                continue;
             }
-        }
-
-        if rect[3] - rect[1] <= self.get_min_size()[1] {
-            new_rect[1] = rect[1];
-            new_rect[3] = rect[1] + self.get_min_size()[1];
-        } else {
-            match self.fill_y {
-                RectFill::Fill => {
-                    new_rect[1] = rect[1];
-                    new_rect[3] = rect[3];
-                }
-                RectFill::ShrinkStart => {
-                    new_rect[1] = rect[1];
-                    new_rect[3] = rect[1] + self.get_min_size()[1];
-                }
-                RectFill::ShrinkCenter => {
-                    let x = (rect[3] - rect[1] - self.get_min_size()[1]) / 2.0;
-                    new_rect[1] = rect[1] + x;
-                    new_rect[3] = rect[3] - x;
+            // a filling rect grows until its max size, and is centered in the remaining space.
+            let size = match fill {
+                RectFill::Fill => (end - start).min(self.get_max_size()[i]).max(min),
+                _ => min,
+            };
+            let (a, b) = match fill {
+                RectFill::ShrinkStart => (start, start + size),
+                RectFill::Fill | RectFill::ShrinkCenter => {
+                    let x = (end - start - size) / 2.0;
+                    (start + x, end - x)
                 }
-                RectFill::ShrinkEnd => {
-                    new_rect[1] = rect[3] - self.get_min_size()[1];
-                    new_rect[3] = rect[3];
-                }
-            }
+                RectFill::ShrinkEnd => (end - size, end),
+            };
+            new_rect[i] = a;
+            new_rect[i + 2] = b;
         }
-        self.set_rect(new_rect);
+        new_rect
     }
 
     pub fn set_fill_x(&mut self, fill: RectFill) {
@@ -243,6 +261,25 @@ impl Rect {
         }
     }
 
+    /// The max size of the rect. Each component is infinite, if unbounded.
+    #[inline]
+    pub fn get_max_size(&self) -> [f32; 2] {
+        self.max_size
+    }
+
+    /// Set the max size of the rect. Use [`f32::INFINITY`] to leave a component unbounded. The rect
+    /// never grows beyond its max size, unless its min size is bigger.
+    #[inline]
+    pub fn set_max_size(&mut self, max_size: [f32; 2]) {
+        self.max_size = max_size;
+        if self.get_width() > max_size[0].max(self.min_size[0]) {
+            self.set_width(max_size[0].max(self.min_size[0]));
+        }
+        if self.get_height() > max_size[1].max(self.min_size[1]) {
+            self.set_height(max_size[1].max(self.min_size[1]));
+        }
+    }
+
     /// Return true if this have the size_flag::EXPAND_X flag.
     #[inline]
     pub fn is_expand_x(&self) -> bool {
@@ -255,6 +292,20 @@ impl Rect {
         self.expand_y
     }
 
+    /// Return true if the graphics of the descendants of this are clipped by this rect.
+    #[inline]
+    pub fn is_clip_children(&self) -> bool {
+        self.clip_children
+    }
+
+    pub fn set_clip_children(&mut self, clip_children: bool) {
+        if self.clip_children != clip_children {
+            self.clip_children = clip_children;
+            // the masks of the descendants changed
+            self.render_dirty_flags |= RenderDirtyFlags::RECT;
+        }
+    }
+
     #[inline]
     pub fn get_top_left(&self) -> (f32, f32) {
         (self.rect[0], self.rect[1])
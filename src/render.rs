@@ -1,14 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 
-use ab_glyph::{Font, GlyphId};
+pub mod software;
+
+use ab_glyph::{Font, GlyphId, OutlineCurve, OutlinedGlyph, ScaleFont};
 use texture_cache::{Cached, LruTextureCache, RectEntry};
 
 // use glyph_brush_draw_cache::{CachedBy, DrawCache, DrawCacheBuilder};
 use crate::time::Instant;
 use crate::{
     context::Context,
-    font::FontId,
-    graphics::{Graphic, Sprite},
+    font::{FontId, Synthesis},
+    graphics::{Graphic, RoundRect, Sprite},
     Color, Id, RenderContext, RenderDirtyFlags,
 };
 
@@ -17,27 +20,39 @@ use crate::{
 pub struct FontGlyph {
     pub glyph: ab_glyph::Glyph,
     pub font_id: FontId,
+    pub synthesis: Synthesis,
     pub color: Color,
 }
 
 pub trait GuiRenderer {
     fn update_font_texture(&mut self, font_texture: u32, rect: [u32; 4], data: &[u8]);
     fn resize_font_texture(&mut self, font_texture: u32, new_size: [u32; 2]);
+    /// Create a new font texture of the given size, to be used as a additional page of the glyph
+    /// atlas, and return its id.
+    ///
+    /// Return `None` if the renderer does not support multiple font textures, which is the
+    /// default. In that case, the glyphs that don't fit in a single page are not rendered.
+    fn create_font_texture(&mut self, size: [u32; 2]) -> Option<u32> {
+        let _ = size;
+        None
+    }
 }
 
 #[derive(Hash, PartialEq, Eq, Clone, Copy)]
 struct GlyphKey {
     font_id: FontId,
+    synthesis: Synthesis,
     glyph: GlyphId,
     sub_pixel: (u8, u8),
     scale: (u16, u16),
 }
 impl GlyphKey {
-    fn new(f: FontId, g: &ab_glyph::Glyph) -> Self {
+    fn new(f: FontId, synthesis: Synthesis, g: &ab_glyph::Glyph) -> Self {
         const SUB_PIXEL_PRECISION: u32 = 8;
         const SCALE_PRECISION: u32 = 8;
         GlyphKey {
             font_id: f,
+            synthesis,
             glyph: g.id,
             sub_pixel: (
                 (((g.position.x * SUB_PIXEL_PRECISION as f32).round() as u32) % SUB_PIXEL_PRECISION)
@@ -53,38 +68,298 @@ impl GlyphKey {
     }
 }
 
+/// A glyph to be rasterized in the glyph atlas.
+struct RasterGlyph {
+    outline: OutlinedGlyph,
+    /// The outline is drawn again at each pixel up to this distance to the right, for a
+    /// synthetic bold.
+    bold_offset: u32,
+}
+
+/// Outline the glyph, shearing the outline for a synthetic italic.
+fn outline_glyph(
+    font: &crate::font::Font,
+    glyph: ab_glyph::Glyph,
+    synthesis: Synthesis,
+) -> Option<OutlinedGlyph> {
+    if !synthesis.italic {
+        return font.outline_glyph(glyph);
+    }
+    let mut outline = font.outline(glyph.id)?;
+    // the outline is in font units, with the y axis pointing up.
+    let shear = |p: &mut ab_glyph::Point| p.x += p.y * Synthesis::ITALIC_SHEAR;
+    for curve in &mut outline.curves {
+        match curve {
+            OutlineCurve::Line(a, b) => {
+                shear(a);
+                shear(b);
+            }
+            OutlineCurve::Quad(a, b, c) => {
+                shear(a);
+                shear(b);
+                shear(c);
+            }
+            OutlineCurve::Cubic(a, b, c, d) => {
+                shear(a);
+                shear(b);
+                shear(c);
+                shear(d);
+            }
+        }
+    }
+    outline.bounds.min.x += outline.bounds.min.y * Synthesis::ITALIC_SHEAR;
+    outline.bounds.max.x += outline.bounds.max.y * Synthesis::ITALIC_SHEAR;
+    let scale_factor = font.as_scaled(glyph.scale).scale_factor();
+    Some(OutlinedGlyph::new(glyph, outline, scale_factor))
+}
+
+type GlyphEntry = RectEntry<GlyphKey, [f32; 4], RasterGlyph>;
+
+/// A page of the glyph atlas: a font texture, and the glyphs cached in it.
+struct AtlasPage {
+    texture: u32,
+    cache: LruTextureCache<GlyphKey, [f32; 4]>,
+}
+impl AtlasPage {
+    fn new(texture: u32, size: [u32; 2]) -> Self {
+        Self {
+            texture,
+            cache: LruTextureCache::new(size[0], size[1]),
+        }
+    }
+
+    /// Rasterize the given glyphs, that were just added to this page, and upload them to the
+    /// font texture.
+    fn upload<T: GuiRenderer>(&self, entries: &[GlyphEntry], renderer: &mut T) {
+        for entry in entries {
+            let rect = self.cache.get_rect(&entry.key).unwrap();
+            let RasterGlyph {
+                outline,
+                bold_offset,
+            } = &entry.entry_data;
+            let g_width = rect.width as usize;
+            let g_height = rect.height as usize;
+            let mut pixels = vec![0; g_width * g_height];
+            for dx in 0..=*bold_offset as usize {
+                outline.draw(|x, y, c| {
+                    let i = y as usize * g_width + x as usize + dx;
+                    pixels[i] = pixels[i].max((c * 256.0) as u8);
+                });
+            }
+            renderer.update_font_texture(
+                self.texture,
+                [rect.x, rect.y, rect.x + rect.width, rect.y + rect.height],
+                &pixels,
+            )
+        }
+    }
+}
+
+/// Metrics about the usage of the glyph atlas of a [`GuiRender`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AtlasMetrics {
+    /// The number of pages in the atlas. Each page is a font texture.
+    pub pages: usize,
+    /// The number of glyphs currently cached, in all pages.
+    pub cached_glyphs: usize,
+    /// The number of distinct glyphs used in the last render.
+    pub used_glyphs: usize,
+    /// The area, in pixels, of the glyphs used in the last render.
+    pub used_area: u64,
+    /// The total area, in pixels, of all pages.
+    pub total_area: u64,
+}
+impl AtlasMetrics {
+    /// The fraction of the atlas area that is occupied by the glyphs used in the last render.
+    pub fn occupancy(&self) -> f32 {
+        if self.total_area == 0 {
+            return 0.0;
+        }
+        self.used_area as f32 / self.total_area as f32
+    }
+}
+
 pub struct GuiRender {
-    draw_cache: LruTextureCache<GlyphKey, [f32; 4]>,
-    font_texture: u32,
+    /// The pages of the glyph atlas. Only the last one receives new glyphs, evicting the least
+    /// recently used ones when full.
+    pages: Vec<AtlasPage>,
+    max_font_texture_size: u32,
+    max_atlas_pages: usize,
+    used_glyphs: usize,
+    used_area: u64,
     white_texture: u32,
     last_sprites: Vec<Sprite>,
     last_sprites_map: Vec<(Id, Range<usize>)>,
     sprites: Vec<Sprite>,
     sprites_map: Vec<(Id, Range<usize>)>,
+    /// The regions that changed in the last render.
+    damage: Vec<[f32; 4]>,
+    /// The control rendered, with its descendants. See [`GuiRender::set_root`].
+    root: Id,
     last_anim_draw: Option<Instant>,
 }
 impl GuiRender {
     pub fn new(font_texture: u32, white_texture: u32, font_texture_size: [u32; 2]) -> Self {
-        //TODO: change this to default dimensions, and allow resizing
-        let draw_cache = LruTextureCache::new(font_texture_size[0], font_texture_size[1]);
         Self {
-            draw_cache,
-            font_texture,
+            pages: vec![AtlasPage::new(font_texture, font_texture_size)],
+            max_font_texture_size: 4096,
+            max_atlas_pages: 4,
+            used_glyphs: 0,
+            used_area: 0,
             white_texture,
             last_sprites: Vec::new(),
             last_sprites_map: Vec::new(),
             sprites: Vec::new(),
             sprites_map: Vec::new(),
+            damage: Vec::new(),
+            root: Id::ROOT_ID,
             last_anim_draw: None,
         }
     }
 
     /// Replace the current font texture by the given one.
     ///
-    /// This invalidates the current glyph cache.
+    /// This invalidates the current glyph cache, and drops any additional page of the glyph
+    /// atlas.
     pub fn set_font_texture(&mut self, font_texture: u32, font_texture_size: [u32; 2]) {
-        self.font_texture = font_texture;
-        self.draw_cache = LruTextureCache::new(font_texture_size[0], font_texture_size[1]);
+        self.pages = vec![AtlasPage::new(font_texture, font_texture_size)];
+    }
+
+    /// Set the control that is rendered, together with its descendants. By default it is the
+    /// root control, which renders the main window.
+    ///
+    /// Each window added by [`Gui::add_window`](crate::Gui::add_window) must be rendered by its
+    /// own GuiRender, with the root of the window set here. The other windows are never rendered
+    /// by a GuiRender.
+    pub fn set_root(&mut self, root: Id) {
+        self.root = root;
+        self.last_sprites.clear();
+        self.last_sprites_map.clear();
+    }
+
+    /// Set the limits of the glyph atlas. A font texture is doubled in size until reaching
+    /// `max_font_texture_size`, in both dimensions. After that, new pages are created with
+    /// [`GuiRenderer::create_font_texture`], up to `max_atlas_pages` pages. The default is 4096
+    /// and 4.
+    ///
+    /// Glyphs are evicted from the atlas in a least recently used order, so these limits bound
+    /// the memory used by the glyph cache.
+    pub fn set_atlas_limits(&mut self, max_font_texture_size: u32, max_atlas_pages: usize) {
+        self.max_font_texture_size = max_font_texture_size;
+        self.max_atlas_pages = max_atlas_pages.max(1);
+    }
+
+    /// Return metrics about the glyph atlas, like the number of pages and its occupancy.
+    pub fn atlas_metrics(&self) -> AtlasMetrics {
+        AtlasMetrics {
+            pages: self.pages.len(),
+            cached_glyphs: self.pages.iter().map(|x| x.cache.len()).sum(),
+            used_glyphs: self.used_glyphs,
+            used_area: self.used_area,
+            total_area: self
+                .pages
+                .iter()
+                .map(|x| x.cache.width() as u64 * x.cache.height() as u64)
+                .sum(),
+        }
+    }
+
+    /// Add the given glyphs to the atlas, uploading the newly added ones to the font textures.
+    ///
+    /// Return false if the content of any font texture was lost, and the glyph sprites need to
+    /// be rebuilt.
+    fn cache_glyphs<T: GuiRenderer>(&mut self, queue: Vec<GlyphEntry>, renderer: &mut T) -> bool {
+        let mut keys = HashSet::new();
+        let mut queue: Vec<GlyphEntry> = queue.into_iter().filter(|x| keys.insert(x.key)).collect();
+        self.used_glyphs = queue.len();
+        self.used_area = queue.iter().map(|x| x.width as u64 * x.height as u64).sum();
+
+        // If `self.set_font_texture` was called, the cache was cleared, and the texture became
+        // invalid.
+        let mut valid = self.pages[0].cache.len() > 0;
+
+        // glyphs already cached in a older page are kept there, only marking them as used.
+        let last = self.pages.len() - 1;
+        for page in &mut self.pages[..last] {
+            let (mut cached, rest): (Vec<_>, Vec<_>) = queue
+                .into_iter()
+                .partition(|x| page.cache.get_rect(&x.key).is_some());
+            queue = rest;
+            if !cached.is_empty() {
+                let _ = page.cache.cache_rects(&mut cached);
+            }
+        }
+
+        loop {
+            let page = self.pages.last_mut().unwrap();
+            match page.cache.cache_rects(&mut queue) {
+                Ok(Cached::Added(x) | Cached::Changed(x)) => {
+                    page.upload(&queue[..x], renderer);
+                    return valid;
+                }
+                Ok(Cached::Cleared(x)) => {
+                    log::debug!("draw cache: cleared");
+                    page.upload(&queue[..x], renderer);
+                    return false;
+                }
+                Err(_) => {}
+            }
+
+            // the glyphs don't fit in the page, even after evicting all of its glyphs.
+            valid = false;
+            let width = page.cache.width();
+            let height = page.cache.height();
+            if width < self.max_font_texture_size || height < self.max_font_texture_size {
+                let width = (2 * width).min(self.max_font_texture_size.max(width));
+                let height = (2 * height).min(self.max_font_texture_size.max(height));
+                page.cache = LruTextureCache::new(width, height);
+                renderer.resize_font_texture(page.texture, [width, height]);
+                log::debug!("draw cache: rebuilded to {} x {}", width, height);
+                // retry
+                continue;
+            }
+
+            // fill the page with as many glyphs as it fits, and move the rest to a new page.
+            let mut rest = Vec::new();
+            loop {
+                let half = queue.len() / 2;
+                if half == 0 {
+                    log::warn!("draw cache: glyph is bigger than the font texture");
+                    queue.clear();
+                    break;
+                }
+                rest.splice(0..0, queue.drain(half..));
+                page.cache = LruTextureCache::new(width, height);
+                if let Ok(Cached::Added(x) | Cached::Changed(x) | Cached::Cleared(x)) =
+                    page.cache.cache_rects(&mut queue)
+                {
+                    page.upload(&queue[..x], renderer);
+                    break;
+                }
+            }
+            if rest.is_empty() {
+                return false;
+            }
+            let texture = if self.pages.len() < self.max_atlas_pages {
+                renderer.create_font_texture([width, height])
+            } else {
+                None
+            };
+            match texture {
+                Some(texture) => {
+                    log::debug!("draw cache: added page {}", self.pages.len());
+                    self.pages.push(AtlasPage::new(texture, [width, height]));
+                    queue = rest;
+                }
+                None => {
+                    log::warn!(
+                        "draw cache: atlas is full, {} glyphs will not be rendered",
+                        rest.len()
+                    );
+                    return false;
+                }
+            }
+        }
     }
 
     pub fn clear_cache(&mut self, ctx: &mut Context) {
@@ -106,6 +381,18 @@ impl GuiRender {
                 Graphic::AnimatedIcon(x) => {
                     x.color_dirty = true;
                 }
+                Graphic::RoundRect(x) => {
+                    x.color_dirty = true;
+                }
+                Graphic::LinearGradient(x) => {
+                    x.color_dirty = true;
+                }
+                Graphic::RadialGradient(x) => {
+                    x.color_dirty = true;
+                }
+                Graphic::Shimmer(x) => {
+                    x.color_dirty = true;
+                }
                 Graphic::Text(x) => x.dirty(),
                 Graphic::None => {}
             }
@@ -120,7 +407,8 @@ impl GuiRender {
     ) -> (&'a [Sprite], bool) {
         self.sprites.clear();
         self.sprites_map.clear();
-        let mut masks: Vec<(usize, [f32; 4], bool)> = Vec::new();
+        // the depth, mask, if it changed, and opacity of each ancestor of the current control.
+        let mut masks: Vec<(usize, [f32; 4], bool, f32)> = Vec::new();
 
         fn intersection(a: &[f32; 4], b: &[f32; 4]) -> Option<[f32; 4]> {
             if a[0] > b[2] || a[2] < b[0] || a[1] > b[3] || a[3] < b[1] {
@@ -140,89 +428,57 @@ impl GuiRender {
         // queue all glyphs for cache
 
         let mut queue = Vec::new();
-        let mut add_to_queue = |f: FontId, mut g: ab_glyph::Glyph| {
+        let mut add_to_queue = |f: FontId, synthesis: Synthesis, mut g: ab_glyph::Glyph| {
             g.scale.x *= scale_factor;
             g.scale.y *= scale_factor;
             g.position.x *= scale_factor;
             g.position.y *= scale_factor;
 
-            let outline = match fonts.get(f).unwrap().outline_glyph(g.clone()) {
+            let outline = match outline_glyph(fonts.get(f).unwrap(), g.clone(), synthesis) {
                 Some(x) => x,
                 None => return,
             };
+            let bold_offset = if synthesis.bold {
+                (g.scale.y * Synthesis::BOLD_OFFSET).round().max(1.0) as u32
+            } else {
+                0
+            };
             let bounds = outline.px_bounds();
-            let width = bounds.width() as u32;
+            let width = bounds.width() as u32 + bold_offset;
             let height = bounds.height() as u32;
             queue.push(RectEntry {
                 width,
                 height,
-                key: GlyphKey::new(f, &g),
+                key: GlyphKey::new(f, synthesis, &g),
                 value: [
                     bounds.min.x - g.position.x,
                     bounds.min.y - g.position.y,
-                    bounds.max.x - g.position.x,
+                    bounds.max.x - g.position.x + bold_offset as f32,
                     bounds.max.y - g.position.y,
                 ],
-                entry_data: outline,
+                entry_data: RasterGlyph {
+                    outline,
+                    bold_offset,
+                },
             })
         };
 
-        let mut parents = vec![Id::ROOT_ID];
+        let mut parents = vec![self.root];
         while let Some(parent) = parents.pop() {
-            parents.extend(ctx.get_active_children(parent).iter());
+            if !ctx.is_visible(parent) {
+                continue;
+            }
+            let children = ctx.get_active_children(parent);
+            parents.extend(children.into_iter().filter(|x| !ctx.is_window_root(*x)));
             if let (rect, Graphic::Text(text)) = ctx.get_rect_and_graphic(parent) {
                 let (glyphs, _) = text.get_glyphs_and_rects(rect, fonts);
                 for glyph in glyphs {
-                    add_to_queue(glyph.font_id, glyph.glyph.clone());
+                    add_to_queue(glyph.font_id, glyph.synthesis, glyph.glyph.clone());
                 }
             }
         }
 
-        // If `self.set_font_texture` was called, the draw_cache was cleared, and the texture
-        // became invalid.
-        let mut font_texture_valid = self.draw_cache.len() > 0;
-
-        loop {
-            // add the glyphs to the cache
-            let added = match self.draw_cache.cache_rects(&mut queue) {
-                Ok(Cached::Added(x) | Cached::Changed(x)) => x,
-                Ok(Cached::Cleared(x)) => {
-                    log::debug!("draw cache: cleared");
-                    font_texture_valid = false;
-                    x
-                }
-                Err(_) => {
-                    let width = 2 * self.draw_cache.width();
-                    let height = 2 * self.draw_cache.height();
-                    self.draw_cache = LruTextureCache::new(width, height);
-                    renderer.resize_font_texture(self.font_texture, [width, height]);
-                    log::debug!("draw cache: rebuilded to {} x {}", width, height);
-                    font_texture_valid = false;
-                    // retry
-                    continue;
-                }
-            };
-
-            // render the glyphs and upload to the texture
-            for entry in &queue[..added] {
-                let rect = self.draw_cache.get_rect(&entry.key).unwrap();
-                let outlined_glyph = &entry.entry_data;
-                let g_width = rect.width as usize;
-                let g_height = rect.height as usize;
-                let mut pixels = vec![0; g_width * g_height];
-                outlined_glyph.draw(|x, y, c| {
-                    let i = y as usize * g_width + x as usize;
-                    pixels[i] = (c * 256.0) as u8;
-                });
-                renderer.update_font_texture(
-                    self.font_texture,
-                    [rect.x, rect.y, rect.x + rect.width, rect.y + rect.height],
-                    &pixels,
-                )
-            }
-
-            break;
-        }
+        let font_texture_valid = self.cache_glyphs(queue, &mut renderer);
 
         let mut is_animating = false;
         let dt = if let Some(x) = self.last_anim_draw {
@@ -233,45 +489,73 @@ impl GuiRender {
 
         let scale_rect = |rect: [f32; 4]| rect.map(|x| x * scale_factor);
 
-        let mut parents = vec![Id::ROOT_ID];
+        let mut parents = vec![self.root];
         'tree: while let Some(parent) = parents.pop() {
-            let (mask, mask_changed) = {
+            if parent != self.root && ctx.is_window_root(parent) {
+                continue;
+            }
+            // hidden controls and its descendants are not rendered
+            if !ctx.is_visible(parent) {
+                continue;
+            }
+            let (mask, upper_mask, mask_changed, opacity) = {
+                let own_opacity = ctx.get_opacity(parent);
                 let rect = ctx.get_layouting(parent);
-                let mask = *rect.get_rect();
-                let mut mask = scale_rect([
-                    mask[0].round(),
-                    mask[1].round(),
-                    mask[2].round(),
-                    mask[3].round(),
-                ]);
+                let clip_children = rect.is_clip_children();
+                let own_rect = *rect.get_rect();
+                // round in physical pixels, to not misalign the mask at fractional scale factors
+                let own_rect = scale_rect(own_rect).map(f32::round);
                 let mut mask_changed = rect
                     .get_render_dirty_flags()
                     .contains(RenderDirtyFlags::RECT);
-                while let Some((i, upper_mask, changed)) = masks.last() {
+                // the mask of the closest ancestor that clip its children, if any.
+                let mut upper_mask = [
+                    f32::NEG_INFINITY,
+                    f32::NEG_INFINITY,
+                    f32::INFINITY,
+                    f32::INFINITY,
+                ];
+                let mut opacity = own_opacity;
+                while let Some((i, mask, changed, upper_opacity)) = masks.last() {
                     if parents.len() < *i {
                         masks.pop();
                         continue;
                     }
-
                     mask_changed |= *changed;
-
-                    if let Some(intersection) = intersection(&mask, upper_mask) {
-                        mask = intersection;
-                    } else {
+                    upper_mask = *mask;
+                    opacity *= upper_opacity;
+                    break;
+                }
+                let mask = intersection(&own_rect, &upper_mask);
+                if clip_children {
+                    match mask {
+                        Some(mask) => masks.push((parents.len(), mask, mask_changed, opacity)),
+                        None => continue 'tree,
+                    }
+                } else {
+                    masks.push((parents.len(), upper_mask, mask_changed, opacity));
+                }
+                match mask {
+                    Some(mask) => (mask, upper_mask, mask_changed, opacity),
+                    None => {
+                        // this is not visible, but its children may be.
+                        parents.extend(ctx.get_active_children(parent).iter().rev());
                         continue 'tree;
                     }
-                    break;
                 }
-                masks.push((parents.len(), mask, mask_changed));
-                (mask, mask_changed)
             };
             {
+                let shadow = ctx.get_shadow(parent);
                 let (rect, graphic) = ctx.get_rect_and_graphic(parent);
                 let mut compute_sprite = true;
+                // false if the sprites were reused, and already have the opacity applied.
+                let mut apply_opacity = true;
                 let is_text = matches!(graphic, Graphic::Text(_));
                 let graphic_is_dirty = !rect.get_render_dirty_flags().is_empty()
                     || mask_changed
                     || graphic.need_rebuild()
+                    // the shadow would be recolored with the graphic
+                    || (shadow.is_some() && graphic.is_color_dirty())
                     || (is_text && !font_texture_valid);
 
                 let len = self.sprites.len();
@@ -297,10 +581,22 @@ impl GuiRender {
                             }));
                         } else {
                             self.sprites.extend(sprites);
+                            apply_opacity = false;
                         }
                     }
                 }
                 if compute_sprite {
+                    if let Some(shadow) = shadow {
+                        // the shadow is outside of the control, so it is only clipped by its
+                        // ancestors.
+                        let rect = scale_rect(*rect.get_rect());
+                        let sprites = shadow.get_sprites(rect, scale_factor, self.white_texture);
+                        for mut sprite in sprites {
+                            if cut_sprite(&mut sprite, &upper_mask) {
+                                self.sprites.push(sprite);
+                            }
+                        }
+                    }
                     match graphic {
                         Graphic::Panel(panel) => {
                             let rect = scale_rect(*rect.get_rect());
@@ -336,17 +632,62 @@ impl GuiRender {
                                 self.sprites.push(sprite);
                             }
                         }
+                        Graphic::RoundRect(x) => {
+                            let rect = scale_rect(*rect.get_rect());
+                            let sprites = x.get_sprites(rect, scale_factor, self.white_texture);
+                            for mut sprite in sprites {
+                                if cut_sprite(&mut sprite, &mask) {
+                                    self.sprites.push(sprite);
+                                }
+                            }
+                        }
+                        Graphic::LinearGradient(x) => {
+                            let rect = scale_rect(*rect.get_rect());
+                            for mut sprite in x.get_sprites(rect, self.white_texture) {
+                                if cut_sprite(&mut sprite, &mask) {
+                                    self.sprites.push(sprite);
+                                }
+                            }
+                        }
+                        Graphic::RadialGradient(x) => {
+                            let rect = scale_rect(*rect.get_rect());
+                            for mut sprite in x.get_sprites(rect, self.white_texture) {
+                                if cut_sprite(&mut sprite, &mask) {
+                                    self.sprites.push(sprite);
+                                }
+                            }
+                        }
+                        Graphic::Shimmer(x) => {
+                            let rect = scale_rect(*rect.get_rect());
+                            for mut sprite in x.get_sprites(rect, self.white_texture) {
+                                if cut_sprite(&mut sprite, &mask) {
+                                    self.sprites.push(sprite);
+                                }
+                            }
+                        }
                         Graphic::Text(ref mut text) => {
+                            let radius = text.selection_radius();
                             let (glyphs, rects) = text.get_glyphs_and_rects(rect, fonts);
                             for rect in rects {
-                                let mut sprite = Sprite {
-                                    texture: self.white_texture,
-                                    color: rect.color,
-                                    rect: rect.rect,
-                                    uv_rect: [0.0, 0.0, 1.0, 1.0],
+                                let sprites = if rect.is_selection && radius > 0.0 {
+                                    RoundRect::new(rect.color, radius).get_sprites(
+                                        scale_rect(rect.rect),
+                                        scale_factor,
+                                        self.white_texture,
+                                    )
+                                } else {
+                                    vec![Sprite {
+                                        texture: self.white_texture,
+                                        color: rect.color,
+                                        rect: scale_rect(rect.rect),
+                                        uv_rect: [0.0, 0.0, 1.0, 1.0],
+                                        rotation: 0.0,
+                                    }]
                                 };
-                                if cut_sprite(&mut sprite, &mask) {
-                                    self.sprites.push(sprite);
+                                for mut sprite in sprites {
+                                    if cut_sprite(&mut sprite, &mask) {
+                                        self.sprites.push(sprite);
+                                    }
                                 }
                             }
                             for glyph in glyphs {
@@ -358,12 +699,13 @@ impl GuiRender {
                                     g.position.y *= scale_factor;
                                     g
                                 };
-                                if let Some(rect) =
-                                    self.draw_cache.get_rect(&GlyphKey::new(glyph.font_id, &g))
-                                {
+                                let key = GlyphKey::new(glyph.font_id, glyph.synthesis, &g);
+                                if let Some((page, rect)) = self.pages.iter().find_map(|page| {
+                                    page.cache.get_rect(&key).map(|rect| (page, rect))
+                                }) {
                                     // (tex_coords, pixel_coords)
-                                    let tex_width = self.draw_cache.width() as f32;
-                                    let tex_height = self.draw_cache.height() as f32;
+                                    let tex_width = page.cache.width() as f32;
+                                    let tex_height = page.cache.height() as f32;
                                     let tex_coords = [
                                         rect.x as f32 / tex_width,
                                         rect.y as f32 / tex_height,
@@ -389,7 +731,7 @@ impl GuiRender {
                                             pixel_coords,
                                             mask,
                                             glyph.color,
-                                            self.font_texture,
+                                            page.texture,
                                         ));
                                     }
                                 }
@@ -398,6 +740,11 @@ impl GuiRender {
                         Graphic::None => {}
                     }
                 }
+                if apply_opacity && opacity < 1.0 {
+                    for sprite in &mut self.sprites[len..] {
+                        sprite.color.a = (sprite.color.a as f32 * opacity).round() as u8;
+                    }
+                }
                 graphic.clear_dirty();
                 if len != self.sprites.len() {
                     self.sprites_map.push((parent, len..self.sprites.len()));
@@ -407,6 +754,8 @@ impl GuiRender {
             parents.extend(ctx.get_active_children(parent).iter().rev())
         }
 
+        self.compute_damage();
+
         std::mem::swap(&mut self.sprites, &mut self.last_sprites);
         std::mem::swap(&mut self.sprites_map, &mut self.last_sprites_map);
 
@@ -418,6 +767,160 @@ impl GuiRender {
 
         (&self.last_sprites, is_animating)
     }
+
+    /// The regions, in physical pixels, that changed in the last call to [`GuiRender::render`].
+    ///
+    /// A region is the bounding box of the sprites of a control whose sprites changed, before and
+    /// after the change, with overlapping regions merged. Only these regions need to be redrawn,
+    /// for example by scissoring the draw of the returned sprites to them, and keeping the rest
+    /// of the previous frame. Changes in the draw order of controls with the same sprites are not
+    /// tracked.
+    pub fn damage(&self) -> &[[f32; 4]] {
+        &self.damage
+    }
+
+    /// Compare the sprites of each control with the ones of the last render, and compute the
+    /// damaged regions.
+    fn compute_damage(&mut self) {
+        self.damage.clear();
+        let last: HashMap<Id, Range<usize>> = self.last_sprites_map.iter().cloned().collect();
+        let mut current = HashSet::new();
+        for (id, range) in &self.sprites_map {
+            current.insert(*id);
+            let sprites = &self.sprites[range.clone()];
+            match last.get(id) {
+                Some(last_range) if self.last_sprites[last_range.clone()] == *sprites => {}
+                Some(last_range) => {
+                    self.damage.extend(sprites_bounds(sprites));
+                    self.damage
+                        .extend(sprites_bounds(&self.last_sprites[last_range.clone()]));
+                }
+                None => self.damage.extend(sprites_bounds(sprites)),
+            }
+        }
+        for (id, range) in &self.last_sprites_map {
+            if !current.contains(id) {
+                self.damage
+                    .extend(sprites_bounds(&self.last_sprites[range.clone()]));
+            }
+        }
+
+        // merge overlapping regions
+        let mut i = 0;
+        while i < self.damage.len() {
+            let a = self.damage[i];
+            let other = self.damage[i + 1..]
+                .iter()
+                .position(|b| a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]);
+            match other {
+                Some(j) => {
+                    let b = self.damage.swap_remove(i + 1 + j);
+                    self.damage[i] = [
+                        a[0].min(b[0]),
+                        a[1].min(b[1]),
+                        a[2].max(b[2]),
+                        a[3].max(b[3]),
+                    ];
+                    // the merged region may overlap a previous one
+                    i = 0;
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    /// Render the Gui, and draw it into a RGBA image of the given size, in physical pixels, with
+    /// the given [`SoftwareRenderer`](software::SoftwareRenderer). Return the pixels of the
+    /// image, row by row.
+    ///
+    /// This GuiRender keeps track of the glyphs uploaded to the font texture of its renderer, so
+    /// it should only be used with this SoftwareRenderer. Use a separated GuiRender for the main
+    /// renderer.
+    pub fn render_to_image(
+        &mut self,
+        ctx: &mut RenderContext,
+        renderer: &mut software::SoftwareRenderer,
+        width: u32,
+        height: u32,
+    ) -> Vec<u8> {
+        for page in &self.pages {
+            renderer.ensure_texture(page.texture, [page.cache.width(), page.cache.height()]);
+        }
+        let (sprites, _) = self.render(ctx, &mut *renderer);
+        renderer.draw(sprites, [0.0, 0.0], width, height)
+    }
+
+    /// The same as [`GuiRender::render_to_image`], but only draw the control `id`, and its
+    /// descendants, into a image of the size of the control. Return the size of the image, and
+    /// its pixels.
+    pub fn render_control_to_image(
+        &mut self,
+        ctx: &mut RenderContext,
+        renderer: &mut software::SoftwareRenderer,
+        id: Id,
+    ) -> ([u32; 2], Vec<u8>) {
+        for page in &self.pages {
+            renderer.ensure_texture(page.texture, [page.cache.width(), page.cache.height()]);
+        }
+        self.render(ctx, &mut *renderer);
+
+        let is_in_subtree = |mut curr: Id| loop {
+            if curr == id {
+                return true;
+            }
+            match ctx.get_parent(curr) {
+                Some(parent) => curr = parent,
+                None => return false,
+            }
+        };
+        let sprites = self
+            .last_sprites_map
+            .iter()
+            .filter(|(owner, _)| is_in_subtree(*owner))
+            .flat_map(|(_, range)| self.last_sprites[range.clone()].iter().cloned())
+            .collect::<Vec<_>>();
+
+        let scale_factor = ctx.scale_factor() as f32;
+        let rect = ctx.get_rect(id).map(|x| (x * scale_factor).round());
+        let width = (rect[2] - rect[0]).max(0.0) as u32;
+        let height = (rect[3] - rect[1]).max(0.0) as u32;
+        let image = renderer.draw(&sprites, [rect[0], rect[1]], width, height);
+        ([width, height], image)
+    }
+}
+
+/// The bounding box of the given sprites, taking their rotation in account. None if there are no
+/// sprites.
+fn sprites_bounds(sprites: &[Sprite]) -> Option<[f32; 4]> {
+    sprites
+        .iter()
+        .map(|sprite| {
+            let rect = sprite.rect;
+            if sprite.rotation == 0.0 {
+                return rect;
+            }
+            let center = [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+            let (sin, cos) = sprite.rotation.sin_cos();
+            let size = [rect[2] - rect[0], rect[3] - rect[1]];
+            let half = [
+                (size[0] * cos.abs() + size[1] * sin.abs()) / 2.0,
+                (size[0] * sin.abs() + size[1] * cos.abs()) / 2.0,
+            ];
+            [
+                center[0] - half[0],
+                center[1] - half[1],
+                center[0] + half[0],
+                center[1] + half[1],
+            ]
+        })
+        .reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
 }
 
 #[inline]
@@ -463,6 +966,7 @@ pub fn to_sprite(
         color,
         rect: pixel_coords,
         uv_rect: tex_coords,
+        rotation: 0.0,
     };
 
     cut_sprite(&mut sprite, &bounds);
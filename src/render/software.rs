@@ -0,0 +1,236 @@
+//! A CPU rasterizer for the sprites generated by [`GuiRender`](super::GuiRender), to render the
+//! Gui into a image, without a GPU. Useful for visual regression tests, for example.
+
+use std::collections::HashMap;
+
+use super::GuiRenderer;
+use crate::graphics::Sprite;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+
+    fn sprite(texture: u32, color: Color, rect: [f32; 4]) -> Sprite {
+        Sprite {
+            texture,
+            color,
+            rect,
+            uv_rect: [0.0, 0.0, 1.0, 1.0],
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn draw_rects() {
+        let renderer = SoftwareRenderer::new();
+        let sprites = [
+            sprite(0, Color::from_u32(0xff0000ff), [0.0, 0.0, 2.0, 2.0]),
+            sprite(0, Color::from_u32(0x0000ff80), [1.0, 1.0, 3.0, 3.0]),
+        ];
+        let image = renderer.draw(&sprites, [0.0, 0.0], 4, 4);
+        let pixel = |x: usize, y: usize| &image[(y * 4 + x) * 4..][..4];
+        assert_eq!(pixel(0, 0), &[255, 0, 0, 255]);
+        assert_eq!(pixel(3, 3), &[0, 0, 0, 0]);
+        assert_eq!(pixel(2, 2), &[0, 0, 255, 128]);
+        // blue over red, with 50% alpha
+        assert_eq!(pixel(1, 1), &[127, 0, 128, 255]);
+    }
+
+    #[test]
+    fn draw_texture() {
+        let mut renderer = SoftwareRenderer::new();
+        // a 2x1 texture, white and transparent
+        renderer.add_texture(1, 2, 1, vec![255, 255, 255, 255, 0, 0, 0, 0]);
+        let sprites = [sprite(1, Color::from_u32(0x00ff00ff), [0.0, 0.0, 4.0, 1.0])];
+        let image = renderer.draw(&sprites, [0.0, 0.0], 4, 1);
+        assert_eq!(&image[..8], &[0, 255, 0, 255, 0, 255, 0, 255]);
+        assert_eq!(&image[8..], &[0; 8]);
+    }
+}
+
+/// The pixels of a texture, in RGBA format.
+struct RgbaTexture {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+impl RgbaTexture {
+    /// Sample the texel nearest to the given normalized coordinates.
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let x = ((u * self.width as f32) as u32).min(self.width.saturating_sub(1));
+        let y = ((v * self.height as f32) as u32).min(self.height.saturating_sub(1));
+        let i = (y * self.width + x) as usize * 4;
+        match self.data.get(i..i + 4) {
+            Some(texel) => [
+                texel[0] as f32 / 255.0,
+                texel[1] as f32 / 255.0,
+                texel[2] as f32 / 255.0,
+                texel[3] as f32 / 255.0,
+            ],
+            None => [1.0; 4],
+        }
+    }
+}
+
+/// A [`GuiRenderer`] that keeps the textures in memory, and draws the sprites into a RGBA image.
+///
+/// The textures used by the graphics of the Gui must be added with
+/// [`SoftwareRenderer::add_texture`]. Sprites of unknown textures are drawn as if the texture
+/// was white, so the white texture of the [`GuiRender`](super::GuiRender) don't need to be
+/// added. The font texture is kept updated by the `GuiRender`.
+///
+/// The sprites are sampled with nearest filtering, and blended with the usual "over" operator.
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    textures: HashMap<u32, RgbaTexture>,
+}
+impl SoftwareRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a texture, replacing any texture with the same id. `data` are the pixels of the
+    /// texture, in RGBA format, row by row.
+    pub fn add_texture(&mut self, texture: u32, width: u32, height: u32, data: Vec<u8>) {
+        assert_eq!(data.len(), (width * height * 4) as usize);
+        self.textures.insert(
+            texture,
+            RgbaTexture {
+                width,
+                height,
+                data,
+            },
+        );
+    }
+
+    /// Create a empty texture with the given size, if there is none with the given id.
+    pub(crate) fn ensure_texture(&mut self, texture: u32, [width, height]: [u32; 2]) {
+        if !self.textures.contains_key(&texture) {
+            self.add_texture(
+                texture,
+                width,
+                height,
+                vec![0; (width * height * 4) as usize],
+            );
+        }
+    }
+
+    /// Draw the sprites, in order, into a transparent image of the given size, in pixels. The
+    /// point `origin` of the sprites is drawn at the top-left corner of the image.
+    ///
+    /// Return the pixels of the image, in RGBA format, row by row.
+    pub fn draw(&self, sprites: &[Sprite], origin: [f32; 2], width: u32, height: u32) -> Vec<u8> {
+        let mut image = vec![0.0f32; (width * height * 4) as usize];
+        for sprite in sprites {
+            self.draw_sprite(&mut image, sprite, origin, width, height);
+        }
+        image
+            .iter()
+            .map(|x| (x * 255.0).round().max(0.0).min(255.0) as u8)
+            .collect()
+    }
+
+    fn draw_sprite(
+        &self,
+        image: &mut [f32],
+        sprite: &Sprite,
+        origin: [f32; 2],
+        width: u32,
+        height: u32,
+    ) {
+        let rect = [
+            sprite.rect[0] - origin[0],
+            sprite.rect[1] - origin[1],
+            sprite.rect[2] - origin[0],
+            sprite.rect[3] - origin[1],
+        ];
+        let size = [rect[2] - rect[0], rect[3] - rect[1]];
+        if size[0] <= 0.0 || size[1] <= 0.0 {
+            return;
+        }
+        let center = [(rect[0] + rect[2]) / 2.0, (rect[1] + rect[3]) / 2.0];
+        let (sin, cos) = sprite.rotation.sin_cos();
+
+        // the bounding box of the rotated rect
+        let half = [
+            (size[0] * cos.abs() + size[1] * sin.abs()) / 2.0,
+            (size[0] * sin.abs() + size[1] * cos.abs()) / 2.0,
+        ];
+        let x0 = (center[0] - half[0]).floor().max(0.0) as u32;
+        let y0 = (center[1] - half[1]).floor().max(0.0) as u32;
+        let x1 = ((center[0] + half[0]).ceil().max(0.0) as u32).min(width);
+        let y1 = ((center[1] + half[1]).ceil().max(0.0) as u32).min(height);
+
+        let texture = self.textures.get(&sprite.texture);
+        let color = sprite.color.to_array().map(|x| x as f32 / 255.0);
+        let [u, v, uv_width, uv_height] = sprite.uv_rect;
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // the position of the pixel center, in the unrotated rect, relative to its center
+                let dx = x as f32 + 0.5 - center[0];
+                let dy = y as f32 + 0.5 - center[1];
+                let local_x = cos * dx + sin * dy;
+                let local_y = -sin * dx + cos * dy;
+                let s = local_x / size[0] + 0.5;
+                let t = local_y / size[1] + 0.5;
+                if !(0.0..1.0).contains(&s) || !(0.0..1.0).contains(&t) {
+                    continue;
+                }
+                let texel = match texture {
+                    Some(texture) => texture.sample(u + s * uv_width, v + t * uv_height),
+                    None => [1.0; 4],
+                };
+                let src = [
+                    texel[0] * color[0],
+                    texel[1] * color[1],
+                    texel[2] * color[2],
+                    texel[3] * color[3],
+                ];
+                let i = ((y * width + x) * 4) as usize;
+                let dst = &mut image[i..i + 4];
+                let dst_alpha = dst[3];
+                let alpha = src[3] + dst_alpha * (1.0 - src[3]);
+                if alpha > 0.0 {
+                    for (d, s) in dst[..3].iter_mut().zip(&src[..3]) {
+                        *d = (s * src[3] + *d * dst_alpha * (1.0 - src[3])) / alpha;
+                    }
+                }
+                dst[3] = alpha;
+            }
+        }
+    }
+}
+impl GuiRenderer for &mut SoftwareRenderer {
+    fn update_font_texture(&mut self, font_texture: u32, rect: [u32; 4], data: &[u8]) {
+        let texture = match self.textures.get_mut(&font_texture) {
+            Some(x) => x,
+            None => return,
+        };
+        let width = (rect[2] - rect[0]) as usize;
+        for (i, alpha) in data.iter().enumerate() {
+            let x = rect[0] as usize + i % width;
+            let y = rect[1] as usize + i / width;
+            let j = (y * texture.width as usize + x) * 4;
+            if let Some(texel) = texture.data.get_mut(j..j + 4) {
+                texel.copy_from_slice(&[0xff, 0xff, 0xff, *alpha]);
+            }
+        }
+    }
+
+    fn resize_font_texture(&mut self, font_texture: u32, [width, height]: [u32; 2]) {
+        self.add_texture(
+            font_texture,
+            width,
+            height,
+            vec![0; (width * height * 4) as usize],
+        );
+    }
+
+    fn create_font_texture(&mut self, size: [u32; 2]) -> Option<u32> {
+        let texture = self.textures.keys().max().map_or(0, |x| x + 1);
+        self.resize_font_texture(texture, size);
+        Some(texture)
+    }
+}
@@ -0,0 +1,415 @@
+//! A serializable description of the tree of controls of a [`Gui`].
+//!
+//! A [`Scene`] only describes the presentation of the controls: their hierarchy, rects, graphics
+//! and shadows. Behaviours and layouts can't be serialized generically, so they are not restored
+//! by [`Gui::from_scene`]. The type name of the layout of each control is kept only as a
+//! description, for tooling.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graphics::{
+        AnimatedIcon, Graphic, Icon, LinearGradient, Panel, RadialGradient, RoundRect, Shadow,
+        Shimmer, Texture,
+    },
+    text::{Text, TextStyle},
+    Color, Flex, Gui, Id, RectFill,
+};
+
+/// The presentation tree of a [`Gui`]. See [`Gui::to_scene`] and [`Gui::from_scene`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Scene {
+    /// The children of the root control.
+    pub controls: Vec<SceneNode>,
+}
+
+/// The description of a control, and of its descendants, in a [`Scene`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SceneNode {
+    pub anchors: [f32; 4],
+    pub margins: [f32; 4],
+    /// The min size set by the user, not the one computed by the layout.
+    pub min_size: [f32; 2],
+    /// The max size of the control, or None if unbounded.
+    pub max_size: [Option<f32>; 2],
+    pub expand: [bool; 2],
+    pub fill: [RectFill; 2],
+    pub flex: Flex,
+    pub span: [u32; 2],
+    pub clip_children: bool,
+    pub active: bool,
+    pub visible: bool,
+    pub opacity: f32,
+    pub shadow: Option<Shadow>,
+    pub graphic: SceneGraphic,
+    /// The type name of the layout of the control, if any. Only descriptive, is ignored when
+    /// restoring the scene.
+    pub layout: Option<String>,
+    pub children: Vec<SceneNode>,
+}
+impl Default for SceneNode {
+    fn default() -> Self {
+        Self {
+            anchors: [0.0, 0.0, 1.0, 1.0],
+            margins: [0.0; 4],
+            min_size: [0.0; 2],
+            max_size: [None; 2],
+            expand: [false; 2],
+            fill: [RectFill::Fill; 2],
+            flex: Flex::default(),
+            span: [1, 1],
+            clip_children: true,
+            active: true,
+            visible: true,
+            opacity: 1.0,
+            shadow: None,
+            graphic: SceneGraphic::None,
+            layout: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// The serializable counterpart of a [`Graphic`]. Only the parameters of the graphic are kept,
+/// not its render state. A text keeps only its string and default style, without spans.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SceneGraphic {
+    None,
+    Panel {
+        texture: u32,
+        uv_rects: [[f32; 4]; 9],
+        border: [f32; 4],
+        color: Color,
+    },
+    Texture {
+        texture: u32,
+        uv_rect: [f32; 4],
+        color: Color,
+        rotation: f32,
+    },
+    Icon {
+        texture: u32,
+        uv_rect: [f32; 4],
+        size: [f32; 2],
+        color: Color,
+        rotation: f32,
+    },
+    AnimatedIcon {
+        texture: u32,
+        fps: f32,
+        frames: Vec<[f32; 4]>,
+        size: [f32; 2],
+        color: Color,
+    },
+    Text {
+        text: String,
+        align: (i8, i8),
+        font_size: f32,
+        color: Color,
+    },
+    RoundRect {
+        color: Color,
+        radius: [f32; 4],
+        border_color: Color,
+        border_width: f32,
+    },
+    LinearGradient {
+        stops: Vec<(f32, Color)>,
+        angle: f32,
+        color: Color,
+    },
+    RadialGradient {
+        stops: Vec<(f32, Color)>,
+        center: [f32; 2],
+        radius: f32,
+        color: Color,
+    },
+    Shimmer {
+        base: Color,
+        highlight: Color,
+        speed: f32,
+        band_width: f32,
+        reverse: bool,
+        color: Color,
+    },
+}
+impl Default for SceneGraphic {
+    fn default() -> Self {
+        SceneGraphic::None
+    }
+}
+impl From<&Graphic> for SceneGraphic {
+    fn from(graphic: &Graphic) -> Self {
+        match graphic {
+            Graphic::Panel(x) => SceneGraphic::Panel {
+                texture: x.texture,
+                uv_rects: x.uv_rects,
+                border: x.border,
+                color: x.color,
+            },
+            Graphic::Texture(x) => SceneGraphic::Texture {
+                texture: x.texture,
+                uv_rect: x.uv_rect,
+                color: x.color,
+                rotation: x.rotation,
+            },
+            Graphic::Icon(x) => SceneGraphic::Icon {
+                texture: x.texture,
+                uv_rect: x.uv_rect,
+                size: x.size,
+                color: x.color,
+                rotation: x.rotation,
+            },
+            Graphic::AnimatedIcon(x) => SceneGraphic::AnimatedIcon {
+                texture: x.texture,
+                fps: x.fps,
+                frames: x.frames.clone(),
+                size: x.size,
+                color: x.color,
+            },
+            Graphic::Text(x) => SceneGraphic::Text {
+                text: x.string().to_owned(),
+                align: x.align(),
+                font_size: x.default_style().font_size,
+                color: x.color(),
+            },
+            Graphic::RoundRect(x) => SceneGraphic::RoundRect {
+                color: x.color,
+                radius: x.radius,
+                border_color: x.border_color,
+                border_width: x.border_width,
+            },
+            Graphic::LinearGradient(x) => SceneGraphic::LinearGradient {
+                stops: x.stops.clone(),
+                angle: x.angle,
+                color: x.color,
+            },
+            Graphic::RadialGradient(x) => SceneGraphic::RadialGradient {
+                stops: x.stops.clone(),
+                center: x.center,
+                radius: x.radius,
+                color: x.color,
+            },
+            Graphic::Shimmer(x) => SceneGraphic::Shimmer {
+                base: x.base,
+                highlight: x.highlight,
+                speed: x.speed,
+                band_width: x.band_width,
+                reverse: x.reverse,
+                color: x.color,
+            },
+            Graphic::None => SceneGraphic::None,
+        }
+    }
+}
+impl From<SceneGraphic> for Graphic {
+    fn from(graphic: SceneGraphic) -> Self {
+        match graphic {
+            SceneGraphic::None => Graphic::None,
+            SceneGraphic::Panel {
+                texture,
+                uv_rects,
+                border,
+                color,
+            } => Panel {
+                texture,
+                uv_rects,
+                border,
+                color,
+                color_dirty: true,
+            }
+            .into(),
+            SceneGraphic::Texture {
+                texture,
+                uv_rect,
+                color,
+                rotation,
+            } => Texture::new(texture, uv_rect)
+                .with_color(color)
+                .with_rotation(rotation)
+                .into(),
+            SceneGraphic::Icon {
+                texture,
+                uv_rect,
+                size,
+                color,
+                rotation,
+            } => Icon {
+                texture,
+                uv_rect,
+                size,
+                color,
+                color_dirty: true,
+                rotation,
+            }
+            .into(),
+            SceneGraphic::AnimatedIcon {
+                texture,
+                fps,
+                frames,
+                size,
+                color,
+            } => {
+                let mut icon = AnimatedIcon::new(texture, frames, size);
+                icon.fps = fps;
+                icon.set_color(color);
+                icon.into()
+            }
+            SceneGraphic::Text {
+                text,
+                align,
+                font_size,
+                color,
+            } => Text::new(
+                text,
+                align,
+                TextStyle {
+                    color,
+                    font_size,
+                    ..Default::default()
+                },
+            )
+            .into(),
+            SceneGraphic::RoundRect {
+                color,
+                radius,
+                border_color,
+                border_width,
+            } => RoundRect::new(color, 0.0)
+                .with_radii(radius)
+                .with_border(border_color, border_width)
+                .into(),
+            SceneGraphic::LinearGradient {
+                stops,
+                angle,
+                color,
+            } => LinearGradient::new(stops, angle).with_color(color).into(),
+            SceneGraphic::RadialGradient {
+                stops,
+                center,
+                radius,
+                color,
+            } => RadialGradient::new(stops, center, radius)
+                .with_color(color)
+                .into(),
+            SceneGraphic::Shimmer {
+                base,
+                highlight,
+                speed,
+                band_width,
+                reverse,
+                color,
+            } => {
+                let mut shimmer = Shimmer::new(base, highlight)
+                    .with_speed(speed)
+                    .with_band_width(band_width)
+                    .with_color(color);
+                shimmer.reverse = reverse;
+                shimmer.into()
+            }
+        }
+    }
+}
+
+impl Gui {
+    /// Describe the presentation tree of all controls. See [`Scene`].
+    pub fn to_scene(&self) -> Scene {
+        Scene {
+            controls: self.scene_children(Id::ROOT_ID),
+        }
+    }
+
+    fn scene_children(&self, id: Id) -> Vec<SceneNode> {
+        let control = match self.controls.get(id) {
+            Some(x) => x,
+            None => return Vec::new(),
+        };
+        control
+            .children
+            .iter()
+            .filter_map(|&child| {
+                let control = self.controls.get(child)?;
+                let rect = &control.rect;
+                let layout = control
+                    .layout
+                    .as_ref()
+                    .map(|x| x.type_name())
+                    .filter(|&x| x != std::any::type_name::<()>())
+                    .map(str::to_owned);
+                Some(SceneNode {
+                    anchors: rect.anchors,
+                    margins: rect.margins,
+                    min_size: rect.user_min_size,
+                    max_size: rect.max_size.map(|x| Some(x).filter(|x| x.is_finite())),
+                    expand: [rect.expand_x, rect.expand_y],
+                    fill: [rect.fill_x, rect.fill_y],
+                    flex: rect.flex,
+                    span: rect.span,
+                    clip_children: rect.clip_children,
+                    active: control.active,
+                    visible: control.visible,
+                    opacity: control.opacity,
+                    shadow: control.shadow,
+                    graphic: (&control.graphic).into(),
+                    layout,
+                    children: self.scene_children(child),
+                })
+            })
+            .collect()
+    }
+
+    /// Create a new Gui, with the controls described by the given scene. The controls have no
+    /// behaviour or layout, besides the default one.
+    pub fn from_scene(
+        width: f32,
+        height: f32,
+        scale_factor: f64,
+        fonts: crate::font::Fonts,
+        scene: &Scene,
+    ) -> Self {
+        let mut gui = Gui::new(width, height, scale_factor, fonts);
+        gui.add_scene(Id::ROOT_ID, scene);
+        gui
+    }
+
+    /// Create the controls described by the given scene, as children of `parent`. Return the ids
+    /// of the created children of `parent`.
+    pub fn add_scene(&mut self, parent: Id, scene: &Scene) -> Vec<Id> {
+        scene
+            .controls
+            .iter()
+            .map(|node| self.add_scene_node(parent, node))
+            .collect()
+    }
+
+    fn add_scene_node(&mut self, parent: Id, node: &SceneNode) -> Id {
+        let mut builder = self
+            .create_control()
+            .parent(parent)
+            .anchors(node.anchors)
+            .margins(node.margins)
+            .min_size(node.min_size)
+            .max_size(node.max_size.map(|x| x.unwrap_or(f32::INFINITY)))
+            .expand_x(node.expand[0])
+            .expand_y(node.expand[1])
+            .fill_x(node.fill[0])
+            .fill_y(node.fill[1])
+            .flex(node.flex.grow, node.flex.shrink)
+            .flex_basis(node.flex.basis)
+            .span(node.span[0], node.span[1])
+            .clip_children(node.clip_children)
+            .active(node.active)
+            .visible(node.visible)
+            .opacity(node.opacity)
+            .graphic(Graphic::from(node.graphic.clone()));
+        if let Some(shadow) = node.shadow {
+            builder = builder.shadow(shadow);
+        }
+        let id = builder.build(self);
+        for child in &node.children {
+            self.add_scene_node(id, child);
+        }
+        id
+    }
+}
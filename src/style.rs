@@ -1,5 +1,8 @@
+use std::{any::Any, collections::HashMap, rc::Rc};
+
 use crate::{
-    graphics::{Graphic, TextStyle},
+    event::SetStyle,
+    graphics::{Graphic, Shadow, TextStyle},
     Color,
 };
 
@@ -17,6 +20,15 @@ pub struct ButtonStyle {
     pub hover: Graphic,
     pub pressed: Graphic,
     pub focus: Graphic,
+    /// The graphic of a disabled button. If None, `normal` is used.
+    #[giui(default)]
+    pub disabled: Option<Graphic>,
+}
+impl ButtonStyle {
+    /// The graphic of a disabled button.
+    pub fn disabled_graphic(&self) -> Graphic {
+        self.disabled.clone().unwrap_or_else(|| self.normal.clone())
+    }
 }
 
 #[derive(Clone, Debug, LoadStyle)]
@@ -25,13 +37,71 @@ pub struct SelectionColor {
     pub fg: Option<Color>,
     pub bg: Color,
 }
+impl SelectionColor {
+    /// The color of the selected text. If `fg` is None, this is `text_color`, if it is readable
+    /// on `bg` (has a contrast ratio of at least 4.5), otherwise is black or white, whichever is
+    /// more readable.
+    pub fn readable_fg(&self, text_color: Color) -> Color {
+        match self.fg {
+            Some(fg) => fg,
+            None if text_color.contrast_ratio(self.bg) >= 4.5 => text_color,
+            None => Color::best_on(self.bg),
+        }
+    }
+}
 
 #[derive(Clone, Debug, LoadStyle)]
 #[giui(crate = "crate")]
 pub struct TextFieldStyle {
     pub background: OnFocusStyle,
     pub caret_color: Color,
+    /// The width of the caret. If None, it is 1.0.
+    #[giui(default)]
+    pub caret_width: Option<f32>,
     pub selection_color: SelectionColor,
+    /// The color of the selection while the field is not focused. If None, `selection_color` is
+    /// used.
+    #[giui(default)]
+    pub inactive_selection_color: Option<SelectionColor>,
+    /// The corner radius of the background of the selection.
+    #[giui(default)]
+    pub selection_radius: f32,
+}
+impl TextFieldStyle {
+    /// The width of the caret.
+    pub fn caret_width(&self) -> f32 {
+        self.caret_width.unwrap_or(1.0)
+    }
+
+    /// The color of the selection, depending if the field has focus.
+    pub fn selection_color(&self, focus: bool) -> &SelectionColor {
+        match &self.inactive_selection_color {
+            Some(inactive) if !focus => inactive,
+            _ => &self.selection_color,
+        }
+    }
+}
+
+/// How the scroll bars of a [`ScrollView`](crate::widgets::ScrollView) or a
+/// [`List`](crate::widgets::List) are shown.
+#[derive(Clone, Debug, LoadStyle)]
+#[giui(crate = "crate")]
+pub struct ScrollBarStyle {
+    /// If true, the scroll bars are drawn over the content, without taking space from the view,
+    /// and are only visible while hovered or scrolling.
+    pub overlay: bool,
+    /// The duration of the fade in and out of overlay scroll bars, in milliseconds. If 0, they
+    /// appear and disappear instantly.
+    #[giui(default)]
+    pub fade_ms: u32,
+}
+impl Default for ScrollBarStyle {
+    fn default() -> Self {
+        Self {
+            overlay: false,
+            fade_ms: 150,
+        }
+    }
 }
 
 #[derive(Clone, Debug, LoadStyle)]
@@ -49,5 +119,92 @@ pub struct MenuStyle {
     pub button: ButtonStyle,
     pub separator: Graphic,
     pub arrow: Graphic,
+    /// The check mark of a checked [`Item::Checkable`](crate::widgets::Item::Checkable).
+    #[giui(default)]
+    pub check: Graphic,
     pub text: TextStyle,
+    /// The shadow of each opened menu, if any.
+    #[giui(default)]
+    pub shadow: Option<Shadow>,
+}
+
+enum ThemeEntry {
+    Style {
+        style: Rc<dyn Any>,
+        event: fn(Rc<dyn Any>) -> Box<dyn Any>,
+    },
+    Color(Color),
+}
+
+fn set_style_event<S: 'static>(style: Rc<dyn Any>) -> Box<dyn Any> {
+    Box::new(SetStyle(style.downcast::<S>().unwrap()))
+}
+
+/// A set of styles and colors, each one under a style key, that is applied to the controls
+/// tagged with the same key, by [`Gui::set_theme`](crate::Gui::set_theme).
+///
+/// A control tagged with the key of a style receives a [`SetStyle`] event with it, and a control
+/// tagged with the key of a color has the color of its graphic replaced.
+#[derive(Default)]
+pub struct GuiTheme {
+    entries: HashMap<String, ThemeEntry>,
+}
+impl GuiTheme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a style under the given key, replacing any style or color with the same key.
+    pub fn add_style<S: 'static>(&mut self, key: &str, style: Rc<S>) {
+        self.entries.insert(
+            key.to_owned(),
+            ThemeEntry::Style {
+                style,
+                event: set_style_event::<S>,
+            },
+        );
+    }
+
+    /// Add a color under the given key, replacing any style or color with the same key.
+    pub fn add_color(&mut self, key: &str, color: Color) {
+        self.entries
+            .insert(key.to_owned(), ThemeEntry::Color(color));
+    }
+
+    /// Builder version of [`GuiTheme::add_style`].
+    pub fn with_style<S: 'static>(mut self, key: &str, style: Rc<S>) -> Self {
+        self.add_style(key, style);
+        self
+    }
+
+    /// Builder version of [`GuiTheme::add_color`].
+    pub fn with_color(mut self, key: &str, color: Color) -> Self {
+        self.add_color(key, color);
+        self
+    }
+
+    /// Get the style with the given key. Return None if there is no style with this key, or if it
+    /// is not of type `S`.
+    pub fn style<S: 'static>(&self, key: &str) -> Option<Rc<S>> {
+        match self.entries.get(key) {
+            Some(ThemeEntry::Style { style, .. }) => style.clone().downcast::<S>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Get the color with the given key.
+    pub fn color(&self, key: &str) -> Option<Color> {
+        match self.entries.get(key) {
+            Some(ThemeEntry::Color(color)) => Some(*color),
+            _ => None,
+        }
+    }
+
+    /// The [`SetStyle`] event for the style with the given key.
+    pub(crate) fn style_event(&self, key: &str) -> Option<Box<dyn Any>> {
+        match self.entries.get(key) {
+            Some(ThemeEntry::Style { style, event }) => Some(event(style.clone())),
+            _ => None,
+        }
+    }
 }
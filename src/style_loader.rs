@@ -20,16 +20,22 @@ mod animated_icon;
 mod font_id;
 mod icon;
 mod panel;
+mod reload;
+mod round_rect;
 mod text;
 mod text_style;
 mod texture;
+mod theme;
 pub mod util;
 
 use animated_icon::{AnimatedIconVisitor, FIELDS as ANIMATED_ICON_FIELDS};
 use icon::{IconVisitor, FIELDS as ICON_FIELDS};
 use panel::{PanelVisitor, FIELDS as PANEL_FIELDS};
+pub use reload::{StyleSheet, StyleWatcher};
+use round_rect::{RoundRectVisitor, FIELDS as ROUND_RECT_FIELDS};
 use text::{TextVisitor, FIELDS as TEXT_FIELDS};
 use texture::{TextureVisitor, FIELDS as TEXTURE_FIELDS};
+pub use theme::{Theme, Token};
 
 use crate::Color;
 pub trait StyleLoaderCallback {
@@ -100,7 +106,15 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
     where
         D: Deserializer<'de>,
     {
-        const VARIANTS: &[&str] = &["Panel", "Texture", "Icon", "Text", "None"];
+        const VARIANTS: &[&str] = &[
+            "Panel",
+            "Texture",
+            "Icon",
+            "AnimatedIcon",
+            "Text",
+            "RoundRect",
+            "None",
+        ];
         #[allow(non_camel_case_types)]
         enum Field {
             Panel,
@@ -108,6 +122,7 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
             Icon,
             AnimatedIcon,
             Text,
+            RoundRect,
             None,
         }
 
@@ -128,9 +143,10 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
                     5u64 => Ok(Field::AnimatedIcon),
                     3u64 => Ok(Field::Text),
                     4u64 => Ok(Field::None),
+                    6u64 => Ok(Field::RoundRect),
                     _ => Err(Error::invalid_value(
                         Unexpected::Unsigned(value),
-                        &"variant index 0 <= i < 5",
+                        &"variant index 0 <= i < 7",
                     )),
                 }
             }
@@ -144,6 +160,7 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
                     "Icon" => Ok(Field::Icon),
                     "AnimatedIcon" => Ok(Field::AnimatedIcon),
                     "Text" => Ok(Field::Text),
+                    "RoundRect" => Ok(Field::RoundRect),
                     "None" => Ok(Field::None),
                     _ => Err(Error::unknown_variant(value, VARIANTS)),
                 }
@@ -158,6 +175,7 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
                     b"Icon" => Ok(Field::Icon),
                     b"AnimatedIcon" => Ok(Field::AnimatedIcon),
                     b"Text" => Ok(Field::Text),
+                    b"RoundRect" => Ok(Field::RoundRect),
                     b"None" => Ok(Field::None),
                     _ => {
                         let value = &String::from_utf8_lossy(value);
@@ -228,6 +246,10 @@ impl<'de, 'a, 'b> DeserializeSeed<'de> for GraphicLoader<'a, 'b> {
                         },
                     )
                     .map(Graphic::Text),
+                    (Field::RoundRect, variant) => {
+                        VariantAccess::struct_variant(variant, ROUND_RECT_FIELDS, RoundRectVisitor)
+                            .map(Graphic::RoundRect)
+                    }
                     (Field::None, variant) => {
                         VariantAccess::unit_variant(variant)?;
                         Ok(Graphic::None)
@@ -120,6 +120,7 @@ impl<'de, 'a, 'b: 'a> serde::de::Visitor<'de> for IconVisitor<'a, 'b> {
             size,
             color,
             color_dirty: true,
+            rotation: 0.0,
         })
     }
 }
@@ -175,62 +175,50 @@ impl<'de, 'a, 'b: 'a> serde::de::Visitor<'de> for PanelVisitor<'a, 'b> {
         let texture = texture.ok_or_else(|| de::Error::missing_field("texture"))?;
         let (texture, width, height) = self.loader.load_texture(texture);
         let border = border.ok_or_else(|| de::Error::missing_field("border"))?;
-        let uv_rects = if let Some(uv_rect) = uv_rect {
-            let w = uv_rect[2];
-            let h = uv_rect[3];
-
-            let x = [
-                uv_rect[0],
-                uv_rect[0] + border[0],
-                uv_rect[0] + w - border[2],
-            ];
-            let y = [
-                uv_rect[1],
-                uv_rect[1] + border[1],
-                uv_rect[1] + h - border[3],
-            ];
-
-            let w = [border[0], w - border[0] - border[2], border[2]];
-            let h = [border[1], h - border[1] - border[3], border[3]];
-
-            let mut uv_rects = [[0.0; 4]; 9];
-            for (i, uv_rect) in uv_rects.iter_mut().enumerate() {
-                let n = i % 3;
-                let m = i / 3;
-                *uv_rect = [
-                    x[n] as f32 / width as f32,
-                    y[m] as f32 / height as f32,
-                    w[n] as f32 / width as f32,
-                    h[m] as f32 / height as f32,
-                ];
-            }
-            uv_rects
+        let border = [
+            border[0] as f32,
+            border[1] as f32,
+            border[2] as f32,
+            border[3] as f32,
+        ];
+        let color = color.unwrap_or(Color::WHITE);
+        let mut panel = Panel {
+            texture,
+            uv_rects: [[0.0; 4]; 9],
+            border: [0.0; 4],
+            color,
+            color_dirty: true,
+        };
+        panel.set_border(border);
+        if let Some(uv_rect) = uv_rect {
+            // the texture insets are the same as the border
+            let (width, height) = (width as f32, height as f32);
+            panel.set_uv(
+                [
+                    uv_rect[0] as f32 / width,
+                    uv_rect[1] as f32 / height,
+                    uv_rect[2] as f32 / width,
+                    uv_rect[3] as f32 / height,
+                ],
+                [
+                    border[0] / width,
+                    border[1] / height,
+                    border[2] / width,
+                    border[3] / height,
+                ],
+            );
         } else {
             let uv_rects = uv_rects.ok_or_else(|| de::Error::missing_field("uv_rects"))?;
-            let mut uvs = [[0.0; 4]; 9];
             for (i, uv_rect) in uv_rects.iter().enumerate() {
-                uvs[i] = [
+                panel.uv_rects[i] = [
                     uv_rect[0] as f32 / width as f32,
                     uv_rect[1] as f32 / height as f32,
                     uv_rect[2] as f32 / width as f32,
                     uv_rect[3] as f32 / height as f32,
                 ];
             }
-            uvs
-        };
-        let color = color.unwrap_or(Color::WHITE);
-        Ok(Panel {
-            texture,
-            uv_rects,
-            border: [
-                border[0] as f32,
-                border[1] as f32,
-                border[2] as f32,
-                border[3] as f32,
-            ],
-            color,
-            color_dirty: true,
-        })
+        }
+        Ok(panel)
     }
 }
 
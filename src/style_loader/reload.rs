@@ -0,0 +1,240 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use super::Theme;
+use crate::{event::SetStyle, Behaviour, Context, Id, IntervalId};
+
+/// A named style, selected from the loaded style sheet, and the controls that use it.
+struct Binding<T> {
+    select: Box<dyn Fn(&T) -> Rc<dyn Any>>,
+    style: Rc<dyn Any>,
+    send: fn(Rc<dyn Any>, Id, &mut Context),
+    controls: Vec<Id>,
+}
+
+fn send_style<S: 'static>(style: Rc<dyn Any>, id: Id, ctx: &mut Context) {
+    if let Ok(style) = style.downcast::<S>() {
+        ctx.send_event_to(id, SetStyle(style));
+    }
+}
+
+/// A style sheet loaded from a file, that can be reloaded while the Gui is running.
+///
+/// The styles used by the controls are referenced by name. Each name is registered with
+/// [`StyleSheet::add_style`], together with a function that selects the style from the parsed
+/// sheet, and the controls that use it are registered with [`StyleSheet::bind`]. When the sheet
+/// is reloaded, each bound control receives a [`SetStyle`] event with its new style.
+pub struct StyleSheet<T> {
+    path: PathBuf,
+    parse: Box<dyn FnMut(&str) -> Result<T, String>>,
+    theme: Option<Theme>,
+    sheet: Rc<T>,
+    modified: Option<SystemTime>,
+    bindings: HashMap<String, Binding<T>>,
+}
+impl<T: 'static> StyleSheet<T> {
+    /// Read and parse the file in `path`. `parse` is used for parsing the file again in each
+    /// reload, and normally calls [`load_style`](super::load_style).
+    pub fn load<P, F>(path: P, parse: F) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str) -> Result<T, String> + 'static,
+    {
+        Self::load_inner(path.as_ref(), None, Box::new(parse))
+    }
+
+    /// Same as [`StyleSheet::load`], but the variables referenced in the file are resolved by
+    /// the given theme, before being parsed.
+    pub fn load_with_theme<P, F>(path: P, theme: Theme, parse: F) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str) -> Result<T, String> + 'static,
+    {
+        Self::load_inner(path.as_ref(), Some(theme), Box::new(parse))
+    }
+
+    fn load_inner(
+        path: &Path,
+        theme: Option<Theme>,
+        mut parse: Box<dyn FnMut(&str) -> Result<T, String>>,
+    ) -> Result<Self, String> {
+        let modified = modified_time(path);
+        let sheet = read(path, theme.as_ref(), &mut parse)?;
+        Ok(Self {
+            path: path.to_owned(),
+            parse,
+            theme,
+            sheet: Rc::new(sheet),
+            modified,
+            bindings: HashMap::new(),
+        })
+    }
+
+    /// The path of the loaded file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The theme used to resolve the variables of the file.
+    pub fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Replace the theme used to resolve the variables of the file, and reload it, so the bound
+    /// controls receive the styles with the new values.
+    ///
+    /// If the reload fails, the theme is still replaced, but the current styles are kept.
+    pub fn set_theme(&mut self, theme: Option<Theme>, ctx: &mut Context) -> Result<(), String> {
+        self.theme = theme;
+        self.reload(ctx)
+    }
+
+    /// The last successfully parsed style sheet.
+    pub fn sheet(&self) -> Rc<T> {
+        self.sheet.clone()
+    }
+
+    /// Register a style under the given name. `select` gets the style from the style sheet, and
+    /// is called again on each reload. A style registered with the same name is replaced.
+    pub fn add_style<S, F>(&mut self, name: &str, select: F)
+    where
+        S: 'static,
+        F: Fn(&T) -> Rc<S> + 'static,
+    {
+        let style = select(&self.sheet);
+        let controls = self
+            .bindings
+            .remove(name)
+            .map_or_else(Vec::new, |x| x.controls);
+        self.bindings.insert(
+            name.to_owned(),
+            Binding {
+                select: Box::new(move |sheet| select(sheet) as Rc<dyn Any>),
+                style,
+                send: send_style::<S>,
+                controls,
+            },
+        );
+    }
+
+    /// Get the current style registered with the given name. Return None if there is no style
+    /// with this name, or if it is not of type `S`.
+    pub fn get<S: 'static>(&self, name: &str) -> Option<Rc<S>> {
+        self.bindings
+            .get(name)
+            .and_then(|x| x.style.clone().downcast::<S>().ok())
+    }
+
+    /// Make the control receive a [`SetStyle`] event, with the style registered with the given
+    /// name, each time the style sheet is reloaded.
+    ///
+    /// # Panics
+    /// If there is no style registered with the given name.
+    pub fn bind(&mut self, name: &str, id: Id) {
+        let binding = self
+            .bindings
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("there is no style named '{}'", name));
+        if !binding.controls.contains(&id) {
+            binding.controls.push(id);
+        }
+    }
+
+    /// Stop sending the styles to the given control.
+    pub fn unbind(&mut self, id: Id) {
+        for binding in self.bindings.values_mut() {
+            binding.controls.retain(|&x| x != id);
+        }
+    }
+
+    /// Read and parse the file again, and send the new styles to the bound controls.
+    ///
+    /// If the file fails to be read or parsed, the error is returned, and the current styles are
+    /// kept.
+    pub fn reload(&mut self, ctx: &mut Context) -> Result<(), String> {
+        self.modified = modified_time(&self.path);
+        let sheet = read(&self.path, self.theme.as_ref(), &mut self.parse)?;
+        self.sheet = Rc::new(sheet);
+        for binding in self.bindings.values_mut() {
+            binding.style = (binding.select)(&self.sheet);
+            for &id in &binding.controls {
+                (binding.send)(binding.style.clone(), id, ctx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reload the style sheet if the file was modified since the last load. Return true if it
+    /// was reloaded.
+    pub fn reload_if_modified(&mut self, ctx: &mut Context) -> Result<bool, String> {
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return Ok(false);
+        }
+        self.reload(ctx).map(|_| true)
+    }
+}
+
+fn read<T>(
+    path: &Path,
+    theme: Option<&Theme>,
+    parse: &mut dyn FnMut(&str) -> Result<T, String>,
+) -> Result<T, String> {
+    let source = std::fs::read_to_string(path).map_err(|x| x.to_string())?;
+    match theme {
+        Some(theme) => parse(&theme.resolve(&source)?),
+        None => parse(&source),
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|x| x.modified()).ok()
+}
+
+struct CheckModified;
+
+/// A Behaviour that periodically checks if the file of a [`StyleSheet`] was modified, and
+/// reloads it.
+///
+/// The errors of a reload are logged, so a file with a error can be fixed and saved again
+/// without restarting the application.
+pub struct StyleWatcher<T> {
+    sheet: Rc<RefCell<StyleSheet<T>>>,
+    period: Duration,
+    interval: Option<IntervalId>,
+}
+impl<T: 'static> StyleWatcher<T> {
+    pub fn new(sheet: Rc<RefCell<StyleSheet<T>>>, period: Duration) -> Self {
+        Self {
+            sheet,
+            period,
+            interval: None,
+        }
+    }
+}
+impl<T: 'static> Behaviour for StyleWatcher<T> {
+    fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        self.interval = Some(ctx.schedule_interval(this, || CheckModified, self.period));
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        if let Some(interval) = self.interval.take() {
+            ctx.cancel_interval(interval);
+        }
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, _this: Id, ctx: &mut Context) {
+        if event.is::<CheckModified>() {
+            let mut sheet = self.sheet.borrow_mut();
+            if let Err(err) = sheet.reload_if_modified(ctx) {
+                log::error!("failed to reload '{}': {}", sheet.path().display(), err);
+            }
+        }
+    }
+}
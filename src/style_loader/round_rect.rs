@@ -0,0 +1,143 @@
+use super::*;
+use crate::graphics::RoundRect;
+
+pub const FIELDS: &[&str] = &["color", "radius", "border_color", "border_width"];
+#[allow(non_camel_case_types)]
+enum Field {
+    Color,
+    Radius,
+    BorderColor,
+    BorderWidth,
+}
+struct FieldVisitor;
+impl<'de> serde::de::Visitor<'de> for FieldVisitor {
+    type Value = Field;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Formatter::write_str(formatter, "field identifier")
+    }
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match value {
+            0u64 => Ok(Field::Color),
+            1u64 => Ok(Field::Radius),
+            2u64 => Ok(Field::BorderColor),
+            3u64 => Ok(Field::BorderWidth),
+            _ => Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Unsigned(value),
+                &"field index 0 <= i < 4",
+            )),
+        }
+    }
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match value {
+            "color" => Ok(Field::Color),
+            "radius" => Ok(Field::Radius),
+            "border_color" => Ok(Field::BorderColor),
+            "border_width" => Ok(Field::BorderWidth),
+            _ => Err(de::Error::unknown_field(value, FIELDS)),
+        }
+    }
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_str(&String::from_utf8_lossy(value))
+    }
+}
+impl<'de> serde::Deserialize<'de> for Field {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::Deserializer::deserialize_identifier(deserializer, FieldVisitor)
+    }
+}
+
+/// The radius can be a single value, for all corners, or one value for each corner.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Radius {
+    All(f32),
+    Each([f32; 4]),
+}
+
+pub struct RoundRectVisitor;
+impl<'de> serde::de::Visitor<'de> for RoundRectVisitor {
+    type Value = RoundRect;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Formatter::write_str(formatter, "struct RoundRect")
+    }
+    #[inline]
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut color = None;
+        let mut radius = None;
+        let mut border_color = None;
+        let mut border_width = None;
+        while let Some(key) = MapAccess::next_key::<Field>(&mut map)? {
+            match key {
+                Field::Color => {
+                    if Option::is_some(&color) {
+                        return Err(de::Error::duplicate_field("color"));
+                    }
+                    color = Some(map.next_value::<Color>()?);
+                }
+                Field::Radius => {
+                    if Option::is_some(&radius) {
+                        return Err(de::Error::duplicate_field("radius"));
+                    }
+                    radius = Some(match map.next_value::<Radius>()? {
+                        Radius::All(x) => [x; 4],
+                        Radius::Each(x) => x,
+                    });
+                }
+                Field::BorderColor => {
+                    if Option::is_some(&border_color) {
+                        return Err(de::Error::duplicate_field("border_color"));
+                    }
+                    border_color = Some(map.next_value::<Color>()?);
+                }
+                Field::BorderWidth => {
+                    if Option::is_some(&border_width) {
+                        return Err(de::Error::duplicate_field("border_width"));
+                    }
+                    border_width = Some(map.next_value::<f32>()?);
+                }
+            }
+        }
+        let color = color.unwrap_or(Color::WHITE);
+        Ok(RoundRect {
+            color,
+            radius: radius.unwrap_or([0.0; 4]),
+            border_color: border_color.unwrap_or(color),
+            border_width: border_width.unwrap_or(0.0),
+            color_dirty: true,
+        })
+    }
+}
+
+impl<'a, 'b: 'a> LoadStyle<'a, 'b> for RoundRect {
+    type Loader = RoundRectLoader;
+    fn new_loader(_loader: &'a mut StyleLoader<'b>) -> Self::Loader {
+        RoundRectLoader
+    }
+}
+
+pub struct RoundRectLoader;
+impl<'de> DeserializeSeed<'de> for RoundRectLoader {
+    type Value = RoundRect;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::Deserializer::deserialize_struct(deserializer, "RoundRect", FIELDS, RoundRectVisitor)
+    }
+}
@@ -128,6 +128,61 @@ fn deserialize_panel_b() {
     );
 }
 
+#[test]
+fn panel_insets() {
+    let my_loader = MyLoader::new(vec![("my_texture.png".into(), 256, 256)]);
+
+    // a border bigger than the uv_rect is scaled down to fit in it
+    let mut deser = ron::de::Deserializer::from_str(
+        r#"Panel(
+    texture: "my_texture.png",
+    border: (32, 16, 32, 16),
+    uv_rect: (0, 0, 32, 48),
+)
+"#,
+    )
+    .unwrap();
+
+    let mut panel = match load_style(&mut deser, my_loader).unwrap() {
+        Graphic::Panel(panel) => panel,
+        _ => panic!("expected a Panel"),
+    };
+    assert_eq!(panel.border(), [32.0, 16.0, 32.0, 16.0]);
+    assert_eq!(panel.uv_rect(), [0.0, 0.0, 0.125, 0.1875]);
+    assert_eq!(panel.uv_insets(), [0.0625, 0.0625, 0.0625, 0.0625]);
+    assert_eq!(panel.uv_rects[4], [0.0625, 0.0625, 0.0, 0.0625]);
+
+    panel.set_uv_insets([0.0, 0.0625, 0.125, 0.0]);
+    assert_eq!(panel.uv_rect(), [0.0, 0.0, 0.125, 0.1875]);
+    assert_eq!(panel.uv_rects[0], [0.0, 0.0, 0.0, 0.0625]);
+    assert_eq!(panel.uv_rects[8], [0.0, 0.1875, 0.125, 0.0]);
+
+    panel.set_uv_rect([0.5, 0.5, 0.5, 0.5]);
+    assert_eq!(panel.uv_insets(), [0.0, 0.0625, 0.125, 0.0]);
+    assert_eq!(panel.uv_rects[4], [0.5, 0.5625, 0.375, 0.4375]);
+
+    panel.set_border([4.0, -1.0, 8.0, 2.0]);
+    assert_eq!(panel.border(), [4.0, 0.0, 8.0, 2.0]);
+
+    // a flipped panel keeps its rect and insets, but mirrored
+    let mut graphic = Graphic::from(panel);
+    graphic.flip_x();
+    match graphic {
+        Graphic::Panel(panel) => {
+            assert_eq!(panel.border(), [8.0, 0.0, 4.0, 2.0]);
+            assert_eq!(panel.uv_rect(), [1.0, 0.5, -0.5, 0.5]);
+            assert_eq!(panel.uv_insets(), [0.125, 0.0625, 0.0, 0.0]);
+        }
+        _ => panic!("expected a Panel"),
+    }
+
+    // a panel created with a flipped uv_rect is sliced in equal parts too
+    let panel = Panel::new(0, [0.75, 0.0, -0.75, 0.375], [1.0; 4]);
+    assert_eq!(panel.uv_rect(), [0.75, 0.0, -0.75, 0.375]);
+    assert_eq!(panel.uv_insets(), [0.25, 0.125, 0.25, 0.125]);
+    assert_eq!(panel.uv_rects[4], [0.5, 0.125, -0.25, 0.125]);
+}
+
 #[test]
 fn deserialize_texture() {
     let my_loader = MyLoader::new(vec![("my_texture.png".into(), 256, 256)]);
@@ -368,7 +423,8 @@ fn my_style() {
                     uv_rect: [0.0, 0.0, 1.0, 1.0],
                     size: [18.0, 18.0],
                     color: [255, 255, 255, 255].into(),
-                    color_dirty: true
+                    color_dirty: true,
+                    rotation: 0.0,
                 }
                 .into(),
                 button: ButtonStyle {
@@ -443,7 +499,8 @@ fn my_style() {
                         color: [255, 255, 255, 255].into(),
                         color_dirty: true
                     }
-                    .into()
+                    .into(),
+                    disabled: None,
                 }
                 .into(),
                 on_focus: OnFocusStyle {
@@ -488,3 +545,98 @@ fn my_style() {
         )
     );
 }
+
+#[test]
+fn reload_style_sheet() {
+    use crate::{widgets::Button, Color, Gui};
+
+    fn button_style(color: &str) -> String {
+        let panel = format!(
+            r#"Panel(texture: "button.png", uv_rect: (0, 0, 30, 30), border: 10, color: {})"#,
+            color
+        );
+        format!(
+            "ButtonStyle(normal: {0}, hover: {0}, pressed: {0}, focus: {0})",
+            panel
+        )
+    }
+
+    let path = std::env::temp_dir().join(format!("giui_reload_{}.ron", std::process::id()));
+    std::fs::write(&path, button_style("(255, 0, 0, 255)")).unwrap();
+
+    let mut sheet = StyleSheet::load(&path, |source| {
+        let my_loader = MyLoader::new(vec![("button.png".into(), 60, 60)]);
+        let mut deser = ron::de::Deserializer::from_str(source).map_err(|x| x.to_string())?;
+        load_style::<ButtonStyle, _, _>(&mut deser, my_loader).map_err(|x| x.to_string())
+    })
+    .unwrap();
+    sheet.add_style("button", |style: &ButtonStyle| Rc::new(style.clone()));
+
+    let mut gui = Gui::new(100.0, 100.0, 1.0, crate::font::Fonts::new());
+    let style = sheet.get::<ButtonStyle>("button").unwrap();
+    let button = gui
+        .create_control()
+        .behaviour(Button::new(style, false, |_, _| {}))
+        .build(&mut gui);
+    sheet.bind("button", button);
+    let color = |gui: &mut Gui| gui.get_graphic(button).unwrap().get_color();
+    gui.get_context();
+    assert_eq!(color(&mut gui), Color::from_u32(0xff0000ff));
+
+    // a invalid file keeps the current style
+    std::fs::write(&path, "ButtonStyle(").unwrap();
+    assert!(sheet.reload(&mut gui.get_context()).is_err());
+    assert_eq!(color(&mut gui), Color::from_u32(0xff0000ff));
+
+    std::fs::write(&path, button_style("(0, 0, 255, 255)")).unwrap();
+    sheet.reload(&mut gui.get_context()).unwrap();
+    gui.get_context();
+    assert_eq!(color(&mut gui), Color::from_u32(0x0000ffff));
+    assert_eq!(
+        sheet
+            .get::<ButtonStyle>("button")
+            .unwrap()
+            .normal
+            .get_color(),
+        Color::from_u32(0x0000ffff)
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn theme_variables() {
+    let theme: Theme =
+        ron::de::from_str(r##"{ "primary": "#336699", "border": 10, "font": "Consolas" }"##)
+            .unwrap();
+    assert_eq!(theme.color("primary"), Some(Color::from_u32(0x336699ff)));
+    assert_eq!(theme.number("border"), Some(10.0));
+
+    let source = r#"Panel(
+    texture: "$border.png",
+    uv_rect: (0, 0, 48, 48),
+    border: $border,
+    color: $primary,
+)"#;
+    let resolved = theme.resolve(source).unwrap();
+    assert!(resolved.contains(r#"texture: "$border.png""#));
+    assert!(resolved.contains("border: 10,"));
+    assert!(resolved.contains(r##"color: "#336699ff""##));
+
+    let my_loader = MyLoader::new(vec![("$border.png".into(), 256, 256)]);
+    let mut deser = ron::de::Deserializer::from_str(&resolved).unwrap();
+    let panel: Graphic = load_style(&mut deser, my_loader).unwrap();
+    match panel {
+        Graphic::Panel(panel) => {
+            assert_eq!(panel.border, [10.0; 4]);
+            assert_eq!(panel.color, Color::from_u32(0x336699ff));
+        }
+        _ => panic!("expected a Panel"),
+    }
+
+    assert_eq!(theme.resolve("cost: $$5").unwrap(), "cost: $5");
+    assert_eq!(
+        theme.resolve("(\n  color: $secondary,\n)"),
+        Err("undefined variable '$secondary' at line 2, column 10".to_string())
+    );
+}
@@ -107,6 +107,7 @@ impl<'de, 'a, 'b: 'a> serde::de::Visitor<'de> for TextureVisitor<'a, 'b> {
             uv_rect,
             color,
             color_dirty: true,
+            rotation: 0.0,
         })
     }
 }
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// The value of a variable of a [`Theme`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Color(Color),
+    Number(f32),
+    String(String),
+}
+impl Token {
+    /// Write the token as it would be written in the style file.
+    fn write_to(&self, out: &mut String) {
+        use std::fmt::Write;
+        match self {
+            Token::Color(color) => {
+                let _ = write!(out, "\"{}\"", color.to_hex_string());
+            }
+            // integers are written without a decimal point, so they can be used in integer fields,
+            // like the border of a Panel.
+            Token::Number(x) if x.fract() == 0.0 && x.abs() < i32::MAX as f32 => {
+                let _ = write!(out, "{}", *x as i32);
+            }
+            Token::Number(x) => {
+                let _ = write!(out, "{:?}", x);
+            }
+            Token::String(x) => {
+                let _ = write!(out, "{:?}", x);
+            }
+        }
+    }
+}
+impl From<Color> for Token {
+    fn from(x: Color) -> Self {
+        Token::Color(x)
+    }
+}
+impl From<f32> for Token {
+    fn from(x: f32) -> Self {
+        Token::Number(x)
+    }
+}
+impl From<&str> for Token {
+    fn from(x: &str) -> Self {
+        Token::String(x.to_owned())
+    }
+}
+
+struct TokenVisitor;
+impl<'de> Visitor<'de> for TokenVisitor {
+    type Value = Token;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Formatter::write_str(formatter, "a color, a number or a string")
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Token::Number(v as f32))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Token::Number(v as f32))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(Token::Number(v as f32))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if v.starts_with('#') {
+            Color::from_hex(v)
+                .map(Token::Color)
+                .map_err(de::Error::custom)
+        } else {
+            Ok(Token::String(v.to_owned()))
+        }
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        Color::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Token::Color)
+    }
+}
+impl<'de> Deserialize<'de> for Token {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(TokenVisitor)
+    }
+}
+
+/// A table of named variables, like colors, spacings and font sizes, that can be referenced by
+/// the styles.
+///
+/// A style source references a variable by writing its name prefixed by `$`, like `$primary`,
+/// anywhere a value is expected, and [`Theme::resolve`] replaces each reference by the value of
+/// the variable. `$$` is replaced by a single `$`, and the content of string literals is left
+/// untouched. Swapping the theme and loading the styles again, changes every style that
+/// references the variables.
+///
+/// A Theme can be deserialized from a map, like
+/// `{ "primary": "#336699", "spacing": 4, "font": "Consolas" }`. Strings starting with `#` are
+/// parsed as colors.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    tokens: HashMap<String, Token>,
+}
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of a variable, replacing any previous value.
+    pub fn set(&mut self, name: &str, token: impl Into<Token>) {
+        self.tokens.insert(name.to_owned(), token.into());
+    }
+
+    /// Builder version of [`Theme::set`].
+    pub fn with(mut self, name: &str, token: impl Into<Token>) -> Self {
+        self.set(name, token);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Token> {
+        self.tokens.get(name)
+    }
+
+    /// Get the value of a variable, if it is a color.
+    pub fn color(&self, name: &str) -> Option<Color> {
+        match self.tokens.get(name) {
+            Some(Token::Color(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a variable, if it is a number.
+    pub fn number(&self, name: &str) -> Option<f32> {
+        match self.tokens.get(name) {
+            Some(Token::Number(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Replace each variable reference in `source` by its value.
+    ///
+    /// Return a error with the line and column of the first reference to a undefined variable.
+    pub fn resolve(&self, source: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(source.len());
+        let mut chars = source.char_indices().peekable();
+        let mut in_string = false;
+        while let Some((i, c)) = chars.next() {
+            if in_string {
+                out.push(c);
+                match c {
+                    '\\' => {
+                        if let Some((_, c)) = chars.next() {
+                            out.push(c);
+                        }
+                    }
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => {
+                    in_string = true;
+                    out.push(c);
+                }
+                '$' if matches!(chars.peek(), Some((_, '$'))) => {
+                    chars.next();
+                    out.push('$');
+                }
+                '$' => {
+                    let start = i + 1;
+                    let mut end = start;
+                    while let Some(&(j, c)) = chars.peek() {
+                        if !(c.is_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        end = j + c.len_utf8();
+                        chars.next();
+                    }
+                    let name = &source[start..end];
+                    match self.tokens.get(name) {
+                        Some(token) => token.write_to(&mut out),
+                        None => {
+                            let line = source[..i].matches('\n').count() + 1;
+                            let column = i - source[..i].rfind('\n').map_or(0, |x| x + 1) + 1;
+                            return Err(format!(
+                                "undefined variable '${}' at line {}, column {}",
+                                name, line, column
+                            ));
+                        }
+                    }
+                }
+                _ => out.push(c),
+            }
+        }
+        Ok(out)
+    }
+}
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HashMap::<String, Token>::deserialize(deserializer).map(|tokens| Theme { tokens })
+    }
+}
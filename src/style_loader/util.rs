@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use super::*;
+use crate::graphics::Shadow;
 
 struct ColorVisitor;
 impl<'de> Visitor<'de> for ColorVisitor {
@@ -13,27 +14,7 @@ impl<'de> Visitor<'de> for ColorVisitor {
     where
         E: Error,
     {
-        if v.starts_with('#') {
-            let v = v.trim_start_matches('#');
-            let num = u32::from_str_radix(v, 16).map_err(|_| {
-                de::Error::invalid_value(de::Unexpected::Str(v), &"hexadecimal color")
-            })?;
-            if v.len() == 6 {
-                Ok(Color::from_u32(num << 8 | 0xFF))
-            } else if v.len() == 8 {
-                Ok(Color::from_u32(num))
-            } else {
-                Err(de::Error::invalid_value(
-                    de::Unexpected::Str(v),
-                    &"RGB or RGBA hexadecimal color",
-                ))
-            }
-        } else {
-            Err(de::Error::invalid_value(
-                de::Unexpected::Str(v),
-                &"hexadecimal color, starting with #",
-            ))
-        }
+        Color::from_hex(v).map_err(de::Error::custom)
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -66,6 +47,15 @@ impl<'de> Deserialize<'de> for Color {
         deserializer.deserialize_any(ColorVisitor)
     }
 }
+/// Serialize as a "#rrggbbaa" string, that is accepted back by the Deserialize implementation.
+impl serde::Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_hex_string())
+    }
+}
 
 impl<'a, 'b: 'a> LoadStyle<'a, 'b> for Color {
     type Loader = ColorLoader;
@@ -86,6 +76,25 @@ impl<'de> DeserializeSeed<'de> for ColorLoader {
     }
 }
 
+impl<'a, 'b: 'a> LoadStyle<'a, 'b> for Shadow {
+    type Loader = ShadowLoader;
+    fn new_loader(_: &'a mut StyleLoader<'b>) -> Self::Loader {
+        ShadowLoader
+    }
+}
+
+pub struct ShadowLoader;
+impl<'de> DeserializeSeed<'de> for ShadowLoader {
+    type Value = Shadow;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Shadow as Deserialize>::deserialize(deserializer)
+    }
+}
+
 impl<'a, 'b: 'a, T: 'a> LoadStyle<'a, 'b> for Rc<T>
 where
     T: LoadStyle<'a, 'b>,
@@ -149,3 +158,16 @@ where
             .map(Some)
     }
 }
+
+/// Implement LoadStyle for types that are loaded by their Deserialize implementation.
+macro_rules! load_style_by_deserialize {
+    ($($ty:ty),*) => {$(
+        impl<'a, 'b: 'a> LoadStyle<'a, 'b> for $ty {
+            type Loader = PhantomData<$ty>;
+            fn new_loader(_: &'a mut StyleLoader<'b>) -> Self::Loader {
+                PhantomData
+            }
+        }
+    )*};
+}
+load_style_by_deserialize!(bool, u32, f32);
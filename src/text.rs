@@ -4,7 +4,7 @@ use self::layout::ColorRect;
 use crate::{
     font::{FontId, Fonts},
     render::FontGlyph,
-    text::layout::{LayoutSettings, TextLayout},
+    text::layout::{Direction, LayoutSettings, TextLayout, WrapMode},
     Color, Rect, RenderDirtyFlags,
 };
 
@@ -14,8 +14,11 @@ mod shaping;
 
 #[cfg(test)]
 mod test {
-    use super::{ShapeSpan, Span, SpannedString};
-    use crate::{font::FontId, Color};
+    use super::{RichText, ShapeSpan, Span, SpannedString, Text, TextStyle};
+    use crate::{
+        font::{Font, FontId, Fonts},
+        Color, Rect,
+    };
 
     #[rustfmt::skip]
     #[test]
@@ -27,7 +30,7 @@ mod test {
         spanned.add_span( 3..6, Span::FontId(a), );
         spanned.add_span( 1..4, Span::FontId(b),);
         spanned.add_span( 2..5, Span::FontId(c),);
-        let default = ShapeSpan { byte_range: 0..0, font_size: 16.0, font_id: FontId::new(0) };
+        let default = ShapeSpan { byte_range: 0..0, font_size: 16.0, font_id: FontId::new(0), bold: false, italic: false };
         assert_eq!(
             spanned.get_shape_spans(),
             vec![
@@ -72,6 +75,8 @@ mod test {
                 byte_range: 0..2,
                 font_size: 16.0,
                 font_id: FontId::new(0),
+                bold: false,
+                italic: false,
             }]
         );
     }
@@ -88,16 +93,22 @@ mod test {
                     byte_range: 0..8,
                     font_size: 16.0,
                     font_id: FontId::new(0),
+                    bold: false,
+                    italic: false,
                 },
                 ShapeSpan {
                     byte_range: 8..9,
                     font_size: 0.1,
                     font_id: FontId::new(0),
+                    bold: false,
+                    italic: false,
                 },
                 ShapeSpan {
                     byte_range: 9..10,
                     font_size: 16.0,
                     font_id: FontId::new(0),
+                    bold: false,
+                    italic: false,
                 },
             ]
         );
@@ -113,6 +124,8 @@ mod test {
                 byte_range: 0..4,
                 font_size: 16.0,
                 font_id: FontId::new(0),
+                bold: false,
+                italic: false,
             }]
         );
         spanned.replace_range(1..2, "_");
@@ -122,6 +135,8 @@ mod test {
                 byte_range: 0..4,
                 font_size: 16.0,
                 font_id: FontId::new(0),
+                bold: false,
+                italic: false,
             }]
         );
     }
@@ -135,6 +150,8 @@ mod test {
                 byte_range: 0..4,
                 font_size: 16.0,
                 font_id: FontId::new(0),
+                bold: false,
+                italic: false,
             }]
         );
         spanned.add_span(
@@ -151,6 +168,8 @@ mod test {
                 byte_range: 0..1,
                 font_size: 16.0,
                 font_id: FontId::new(0),
+                bold: false,
+                italic: false,
             }]
         );
     }
@@ -168,6 +187,8 @@ mod test {
             byte_range: 0..10,
             font_size: 16.0,
             font_id: FontId::new(0),
+            bold: false,
+            italic: false,
         };
         assert_eq!(
             spanned.get_shape_spans(),
@@ -191,6 +212,131 @@ mod test {
             ]
         );
     }
+    #[test]
+    fn rich_text() {
+        let a = FontId::new(1);
+        let mut spanned = RichText::new()
+            .text("Hello ")
+            .color(Color::WHITE)
+            .font_id(a)
+            .text("wörld")
+            .reset()
+            .font_size(10.0)
+            .text("!")
+            .build();
+        assert_eq!(spanned.string(), "Hello wörld!");
+        let spans: Vec<_> = spanned
+            .spans
+            .iter()
+            .map(|x| (x.byte_range.clone(), x.span_type))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (6..12, Span::FontId(a)),
+                (6..12, Span::Color(Color::WHITE)),
+                (12..13, Span::FontSize(10.0)),
+            ]
+        );
+        let default = ShapeSpan {
+            byte_range: 0..0,
+            font_size: 16.0,
+            font_id: FontId::new(0),
+            bold: false,
+            italic: false,
+        };
+        assert_eq!(
+            spanned.get_shape_spans(),
+            vec![
+                ShapeSpan {
+                    byte_range: 0..6,
+                    ..default.clone()
+                },
+                ShapeSpan {
+                    byte_range: 6..12,
+                    font_id: a,
+                    ..default.clone()
+                },
+                ShapeSpan {
+                    byte_range: 12..13,
+                    font_size: 10.0,
+                    ..default.clone()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn highlights() {
+        let mut fonts = Fonts::new();
+        let font_id = fonts.add(Font::new(include_bytes!(
+            "../examples/NotoSans-Regular.ttf"
+        )));
+        let style = TextStyle {
+            color: Color::WHITE,
+            font_size: 16.0,
+            font_id,
+        };
+        let mut text = Text::new("one two one".to_string(), (-1, -1), style);
+        let mut rect = Rect::default();
+        rect.set_rect([0.0, 0.0, 200.0, 50.0]);
+        assert!(text.get_glyphs_and_rects(&rect, &fonts).1.is_empty());
+
+        let ranges = text.find("one");
+        assert_eq!(ranges, vec![0..3, 8..11]);
+        let red = Color::from_u32(0xff0000ff);
+        text.set_highlights(ranges, red);
+        assert!(text.rects_dirty);
+
+        let (glyphs, rects) = text.get_glyphs_and_rects(&rect, &fonts);
+        assert_eq!(rects.len(), 2);
+        assert!(rects.iter().all(|x| x.color == red && x.is_selection));
+        assert_eq!(rects[0].rect[0], glyphs[0].glyph.position.x);
+        assert_eq!(rects[1].rect[0], glyphs[8].glyph.position.x);
+        assert!(!text.rects_dirty);
+    }
+
+    #[test]
+    fn layout_cache() {
+        let mut fonts = Fonts::new();
+        let font_id = fonts.add(Font::new(include_bytes!(
+            "../examples/NotoSans-Regular.ttf"
+        )));
+        let style = TextStyle {
+            color: Color::WHITE,
+            font_size: 16.0,
+            font_id,
+        };
+        let mut text = Text::new("one two".to_string(), (-1, -1), style);
+        let mut rect = Rect::default();
+        rect.set_rect([0.0, 0.0, 200.0, 50.0]);
+
+        let min_size = text.compute_min_size(&fonts).unwrap();
+        assert!(!text.get_glyphs_and_rects(&rect, &fonts).0.is_empty());
+
+        // the cached values are only replaced when the text is shaped again, so tamper them to
+        // detect that.
+        let tamper = |text: &mut Text| {
+            text.min_size = Some([1.0, 1.0]);
+            text.glyphs.clear();
+        };
+
+        tamper(&mut text);
+        assert_eq!(text.compute_min_size(&fonts), Some([1.0, 1.0]));
+        assert_eq!(text.compute_min_size(&fonts), Some([1.0, 1.0]));
+        assert!(text.get_glyphs_and_rects(&rect, &fonts).0.is_empty());
+
+        fonts.invalidate();
+        assert_eq!(text.compute_min_size(&fonts), Some(min_size));
+        assert!(!text.get_glyphs_and_rects(&rect, &fonts).0.is_empty());
+
+        tamper(&mut text);
+        fonts.add(Font::new(include_bytes!(
+            "../examples/NotoSans-Regular.ttf"
+        )));
+        assert!(!text.get_glyphs_and_rects(&rect, &fonts).0.is_empty());
+        assert_eq!(text.compute_min_size(&fonts), Some(min_size));
+    }
 }
 
 /// A span of text of certain shape. This contains all information necessary for text shaping.
@@ -199,22 +345,25 @@ pub(crate) struct ShapeSpan {
     pub byte_range: Range<usize>,
     pub font_size: f32,
     pub font_id: FontId,
+    pub bold: bool,
+    pub italic: bool,
     // diretion
     // language
     // script
 }
 impl PartialEq for ShapeSpan {
     fn eq(&self, other: &Self) -> bool {
-        self.byte_range == other.byte_range
-            && self.font_size == other.font_size
-            && self.font_id == other.font_id
+        self.byte_range == other.byte_range && self.cmp_shape(other)
     }
 }
 impl Eq for ShapeSpan {}
 impl ShapeSpan {
     /// Check if the two ShapeSpan are equal, disregarding the range.
     fn cmp_shape(&self, other: &Self) -> bool {
-        self.font_size == other.font_size && self.font_id == other.font_id
+        self.font_size == other.font_size
+            && self.font_id == other.font_id
+            && self.bold == other.bold
+            && self.italic == other.italic
     }
 }
 
@@ -232,13 +381,23 @@ pub enum Span {
     FontSize(f32),
     FontId(FontId),
     Color(Color),
-    Selection { bg: Color, fg: Option<Color> },
+    Selection {
+        bg: Color,
+        fg: Option<Color>,
+    },
     Underline(Option<Color>),
+    /// Use the bold face of the font, or synthesize it. See [`Fonts::resolve_style`].
+    Bold,
+    /// Use the italic face of the font, or synthesize it. See [`Fonts::resolve_style`].
+    Italic,
 }
 impl Span {
     /// Tells if this span changes the shape of the text (like the font or text direction)
     fn is_shape_span(&self) -> bool {
-        matches!(self, Self::FontSize(_) | Self::FontId(_))
+        matches!(
+            self,
+            Self::FontSize(_) | Self::FontId(_) | Self::Bold | Self::Italic
+        )
     }
 }
 
@@ -323,6 +482,8 @@ impl SpannedString {
                 byte_range: 0..self.string.len(),
                 font_size: self.default_style.font_size,
                 font_id: self.default_style.font_id,
+                bold: false,
+                italic: false,
             });
             let spans = std::mem::take(&mut self.spans);
             for span in &spans {
@@ -342,6 +503,8 @@ impl SpannedString {
                 byte_range: 0..string.len(),
                 font_size: style.font_size,
                 font_id: style.font_id,
+                bold: false,
+                italic: false,
             }],
             spans: Vec::new(),
             default_style: style,
@@ -519,10 +682,119 @@ impl SpannedString {
     }
 }
 
+/// A builder of [`SpannedString`], that appends pieces of text with different styles, and keeps
+/// track of the byte range of each span.
+///
+/// The style methods set the style of the text appended after them, until they are set again,
+/// or [`RichText::reset`] is called:
+///
+/// ```
+/// # use giui::{text::RichText, Color};
+/// let spanned = RichText::new()
+///     .text("Hello ")
+///     .color(Color::WHITE)
+///     .font_size(20.0)
+///     .text("world")
+///     .build();
+/// assert_eq!(spanned.string(), "Hello world");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    spanned: SpannedString,
+    font_id: Option<FontId>,
+    font_size: Option<f32>,
+    color: Option<Color>,
+    underline: Option<Option<Color>>,
+    bold: bool,
+    italic: bool,
+}
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder where the text without spans has the given style.
+    pub fn with_style(default_style: TextStyle) -> Self {
+        Self {
+            spanned: SpannedString::new(default_style),
+            ..Self::default()
+        }
+    }
+
+    /// Append a piece of text with the current style.
+    pub fn text(mut self, text: &str) -> Self {
+        if text.is_empty() {
+            return self;
+        }
+        let start = self.spanned.string.len();
+        self.spanned.string.push_str(text);
+        let range = start..self.spanned.string.len();
+        let spans = [
+            self.font_id.map(Span::FontId),
+            self.font_size.map(Span::FontSize),
+            self.color.map(Span::Color),
+            self.underline.map(Span::Underline),
+            self.bold.then(|| Span::Bold),
+            self.italic.then(|| Span::Italic),
+        ];
+        // the shape spans are recomputed lazily, after adding the text.
+        self.spanned.clear_shape_spans();
+        for span in spans.iter().flatten() {
+            self.spanned.add_span(range.clone(), *span);
+        }
+        self
+    }
+
+    pub fn font_id(mut self, font_id: FontId) -> Self {
+        self.font_id = Some(font_id);
+        self
+    }
+
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Underline the following text, with the given color, or the color of the text if None.
+    pub fn underline(mut self, color: Option<Color>) -> Self {
+        self.underline = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Go back to the default style, for the following text.
+    pub fn reset(self) -> Self {
+        Self {
+            spanned: self.spanned,
+            ..Self::default()
+        }
+    }
+
+    pub fn build(self) -> SpannedString {
+        self.spanned
+    }
+}
+
 fn merge_shape_span(a: &mut ShapeSpan, b: &Span) {
     match b {
         Span::FontSize(x) => a.font_size = *x,
         Span::FontId(x) => a.font_id = *x,
+        Span::Bold => a.bold = true,
+        Span::Italic => a.italic = true,
         _ => unreachable!("the span b is not type shape"),
     }
 }
@@ -576,7 +848,21 @@ impl InnerText {
         *self = Self::TextLayout(text_layout);
     }
 
+    /// Return true if the text needs to be shaped again, because it changed, or the fonts changed
+    /// since it was shaped.
+    fn is_stale(&self, fonts: &Fonts) -> bool {
+        match self {
+            InnerText::TextLayout(x) => !x.is_up_to_date(fonts),
+            _ => true,
+        }
+    }
+
+    /// Convert the text to a TextLayout with the given settings. A current layout is reused if it
+    /// has the same settings and is not stale.
     fn to_layout(&mut self, settings: &LayoutSettings, fonts: &Fonts) -> &mut TextLayout {
+        if !self.is_stale(fonts) && self.as_layout().settings() == settings {
+            return self.as_layout();
+        }
         let x = match std::mem::take(self) {
             InnerText::SpannedString(x) => x,
             InnerText::TextLayout(x) => x.to_spanned(),
@@ -599,9 +885,17 @@ pub struct Text {
     min_size: Option<[f32; 2]>,
     last_pos: [f32; 2],
     align: (i8, i8),
-    wrap_line: bool,
+    wrap_mode: WrapMode,
+    direction: Direction,
     glyphs: Vec<FontGlyph>,
     rects: Vec<ColorRect>,
+    /// The corner radius of the selection rects.
+    selection_radius: f32,
+    /// The byte ranges of the text that have a highlight rect, and the color of the rects.
+    highlights: Vec<Range<usize>>,
+    highlight_color: Color,
+    /// The highlights changed since the last update, so the rects must be computed again.
+    pub(crate) rects_dirty: bool,
     pub(crate) color_dirty: bool,
 }
 impl Clone for Text {
@@ -609,7 +903,12 @@ impl Clone for Text {
         Self {
             text: self.text.clone(),
             align: self.align,
-            wrap_line: true,
+            wrap_mode: self.wrap_mode,
+            direction: self.direction,
+            selection_radius: self.selection_radius,
+            highlights: self.highlights.clone(),
+            highlight_color: self.highlight_color,
+            rects_dirty: true,
             color_dirty: true,
             text_dirty: true,
             last_pos: Default::default(),
@@ -624,7 +923,12 @@ impl Text {
         Self {
             text: InnerText::SpannedString(SpannedString::from_string(text, style.clone())),
             align,
-            wrap_line: true,
+            wrap_mode: Default::default(),
+            direction: Default::default(),
+            selection_radius: 0.0,
+            highlights: Vec::new(),
+            highlight_color: Color::WHITE,
+            rects_dirty: true,
             color_dirty: true,
             text_dirty: true,
             last_pos: Default::default(),
@@ -638,7 +942,12 @@ impl Text {
         Self {
             text: InnerText::SpannedString(text),
             align,
-            wrap_line: true,
+            wrap_mode: Default::default(),
+            direction: Default::default(),
+            selection_radius: 0.0,
+            highlights: Vec::new(),
+            highlight_color: Color::WHITE,
+            rects_dirty: true,
             color_dirty: true,
             text_dirty: true,
             last_pos: Default::default(),
@@ -682,6 +991,16 @@ impl Text {
         self.dirty();
     }
 
+    /// The alignment of the text, in each axis: -1 for start, 0 for center and 1 for end.
+    pub fn align(&self) -> (i8, i8) {
+        self.align
+    }
+
+    /// The style of the parts of the text that are not affected by any span.
+    pub fn default_style(&self) -> &TextStyle {
+        &self.text.as_spanned().default_style
+    }
+
     /// Return the length of the underlining string
     pub fn len(&self) -> usize {
         self.text.as_spanned().string().len()
@@ -700,9 +1019,15 @@ impl Text {
         self.dirty();
     }
 
+    /// Enable or disable the wrapping of lines that exceed the width of the rect. This is the
+    /// same as setting the wrap mode to [`WrapMode::WordBoundary`] or [`WrapMode::NoWrap`].
     pub fn set_wrap(&mut self, wrap: bool) {
-        self.wrap_line = wrap;
-        self.dirty();
+        let wrap_mode = if wrap {
+            WrapMode::WordBoundary
+        } else {
+            WrapMode::NoWrap
+        };
+        self.set_wrap_mode(wrap_mode);
     }
 
     pub fn with_wrap(mut self, wrap: bool) -> Self {
@@ -710,6 +1035,71 @@ impl Text {
         self
     }
 
+    /// Set how the lines that exceed the width of the rect are broken.
+    pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode) {
+        self.wrap_mode = wrap_mode;
+        self.dirty();
+    }
+
+    pub fn with_wrap_mode(mut self, wrap_mode: WrapMode) -> Self {
+        self.set_wrap_mode(wrap_mode);
+        self
+    }
+
+    /// Set the base direction of the paragraphs of the text.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+        self.dirty();
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.set_direction(direction);
+        self
+    }
+
+    /// The corner radius of the background of the selections, added with [`Span::Selection`].
+    pub fn selection_radius(&self) -> f32 {
+        self.selection_radius
+    }
+
+    /// Set the corner radius of the background of the selections. Zero by default.
+    pub fn set_selection_radius(&mut self, radius: f32) {
+        #[allow(clippy::float_cmp)]
+        if self.selection_radius != radius {
+            self.selection_radius = radius;
+            self.dirty();
+        }
+    }
+
+    /// Add a background rect, like the one of a selection, to each of the given byte ranges of
+    /// the text, replacing any previous highlights. The highlights don't change the spans of the
+    /// text, so only the rects are computed again.
+    pub fn set_highlights(&mut self, ranges: Vec<Range<usize>>, color: Color) {
+        self.highlights = ranges;
+        self.highlight_color = color;
+        self.rects_dirty = true;
+    }
+
+    /// The byte ranges currently highlighted. See [`Text::set_highlights`].
+    pub fn highlights(&self) -> &[Range<usize>] {
+        &self.highlights
+    }
+
+    /// Return the byte ranges of all non-overlapping occurrences of `query` in the text, to be
+    /// used with [`Text::set_highlights`], for example.
+    pub fn find(&self, query: &str) -> Vec<Range<usize>> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let text = match &self.text {
+            InnerText::TextLayout(x) => x.text(),
+            x => x.as_spanned().string(),
+        };
+        text.match_indices(query)
+            .map(|(i, x)| i..i + x.len())
+            .collect()
+    }
+
     pub fn set_text_layout(&mut self, text: TextLayout) {
         self.text.set_layout(text);
         self.dirty();
@@ -737,9 +1127,11 @@ impl Text {
         let rect = rect.get_rect();
         let layout = self.text.to_layout(
             &LayoutSettings {
-                max_width: self.wrap_line.then(|| rect[2] - rect[0]),
+                max_width: (self.wrap_mode != WrapMode::NoWrap).then(|| rect[2] - rect[0]),
+                wrap_mode: self.wrap_mode,
                 horizontal_align: [Start, Center, End][(self.align.0 + 1) as usize],
                 vertical_align: [Start, Center, End][(self.align.1 + 1) as usize],
+                base_direction: self.direction,
             },
             fonts,
         );
@@ -753,22 +1145,42 @@ impl Text {
                 FontGlyph {
                     glyph,
                     font_id: x.font_id,
+                    synthesis: x.synthesis,
                     color: x.color,
                 }
             })
             .collect();
-        self.rects = layout
-            .rects()
+        self.update_rects();
+    }
+
+    /// Compute the rects of the text, from its layout and its highlights.
+    fn update_rects(&mut self) {
+        self.rects_dirty = false;
+        let anchor_pos = self.last_pos;
+        let offset = |mut rect: [f32; 4]| {
+            rect[0] += anchor_pos[0];
+            rect[1] += anchor_pos[1];
+            rect[2] += anchor_pos[0];
+            rect[3] += anchor_pos[1];
+            rect
+        };
+        let layout = &*self.text.as_layout();
+        let color = self.highlight_color;
+        // the highlights are drawn below the rects of the spans, like selections.
+        let highlights = self
+            .highlights
             .iter()
-            .cloned()
-            .map(|mut x| {
-                x.rect[0] += anchor_pos[0];
-                x.rect[1] += anchor_pos[1];
-                x.rect[2] += anchor_pos[0];
-                x.rect[3] += anchor_pos[1];
-                x
-            })
-            .collect();
+            .flat_map(|range| layout.selection_rects(range.clone()))
+            .map(|rect| ColorRect {
+                rect: offset(rect),
+                color,
+                is_selection: true,
+            });
+        let span_rects = layout.rects().iter().map(|x| ColorRect {
+            rect: offset(x.rect),
+            ..x.clone()
+        });
+        self.rects = highlights.chain(span_rects).collect();
     }
 
     pub fn get_layout(&mut self, fonts: &Fonts, rect: &mut Rect) -> &mut TextLayout {
@@ -783,35 +1195,50 @@ impl Text {
         rect: &Rect,
         fonts: &Fonts,
     ) -> (&[FontGlyph], &[ColorRect]) {
+        let stale = self.text.is_stale(fonts);
+        if stale {
+            // the min size was computed with the old fonts
+            self.min_size = None;
+        }
         let dirty_flags = rect.get_render_dirty_flags();
         let width_change = dirty_flags.contains(RenderDirtyFlags::WIDTH)
             && self.min_size.map_or(true, |x| rect.get_width() < x[0]);
-        if self.text.is_spanned() || self.text_dirty || width_change {
+        if stale || self.text_dirty || width_change {
             self.text_dirty = false;
             self.update_glyphs(rect, fonts);
-        } else if dirty_flags.contains(RenderDirtyFlags::RECT) {
-            let rect = *rect.get_rect();
-            let anchor = self.get_align_anchor(rect);
-            let delta = [anchor[0] - self.last_pos[0], anchor[1] - self.last_pos[1]];
-            self.last_pos = anchor;
-
-            for glyph in &mut self.glyphs {
-                glyph.glyph.position.x += delta[0];
-                glyph.glyph.position.y += delta[1];
+        } else {
+            if dirty_flags.contains(RenderDirtyFlags::RECT) {
+                let rect = *rect.get_rect();
+                let anchor = self.get_align_anchor(rect);
+                let delta = [anchor[0] - self.last_pos[0], anchor[1] - self.last_pos[1]];
+                self.last_pos = anchor;
+
+                for glyph in &mut self.glyphs {
+                    glyph.glyph.position.x += delta[0];
+                    glyph.glyph.position.y += delta[1];
+                }
+                for rect in &mut self.rects {
+                    rect.rect[0] += delta[0];
+                    rect.rect[1] += delta[1];
+                    rect.rect[2] += delta[0];
+                    rect.rect[3] += delta[1];
+                }
             }
-            for rect in &mut self.rects {
-                rect.rect[0] += delta[0];
-                rect.rect[1] += delta[1];
-                rect.rect[2] += delta[0];
-                rect.rect[3] += delta[1];
+            if self.rects_dirty {
+                self.update_rects();
             }
         }
         (&self.glyphs, &self.rects)
     }
 
     pub fn compute_min_size(&mut self, fonts: &Fonts) -> Option<[f32; 2]> {
-        if self.min_size.is_none() {
+        // the min size does not depend on the layout settings, so any up-to-date layout is
+        // reused.
+        if self.text.is_stale(fonts) {
             self.text.to_layout(&Default::default(), fonts);
+            self.min_size = None;
+        }
+        if self.min_size.is_none() {
             self.min_size = Some(self.text.as_layout().min_size());
         }
         self.min_size
@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{font::Fonts, text::layout::TextLayout, util::cmp_range};
+use crate::{font::Fonts, text::layout::TextLayout};
 
 /// Represents a position in a text.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -108,10 +108,7 @@ impl TextEditor {
     /// the last line index is returned.
     #[must_use]
     fn get_line_from_byte_index(&self, byte_index: usize, text_layout: &TextLayout) -> usize {
-        let lines = text_layout.lines();
-        lines
-            .binary_search_by(|x| cmp_range(byte_index, x.byte_range.clone()))
-            .unwrap_or(lines.len() - 1)
+        text_layout.line_index_at(byte_index)
     }
 
     /// Get the position, given the byte index in the text string.
@@ -200,35 +197,15 @@ impl TextEditor {
                 }
             }
             HorizontalMotion::Words(delta_x) => {
-                if delta_x == 0 {
-                    return position;
-                }
-                let byte_index = position.0;
-                if delta_x > 0 {
-                    let n = delta_x as usize;
-                    let offset = text_layout.text()[byte_index..]
-                        .unicode_word_indices()
-                        .skip(1)
-                        .take(n)
-                        .map(|x| x.0)
-                        .last()
-                        .unwrap_or(text_layout.text().len() - byte_index);
-                    let target = byte_index + offset;
-                    ByteIndex(target)
-                } else {
-                    let n = (-delta_x) as usize;
-                    let offset = text_layout.text()[..byte_index]
-                        .unicode_word_indices()
-                        .rev()
-                        .take(n)
-                        .map(|x| x.0)
-                        .last();
-                    if let Some(offset) = offset {
-                        ByteIndex(offset)
+                let mut byte_index = position.0;
+                for _ in 0..delta_x.unsigned_abs() {
+                    byte_index = if delta_x > 0 {
+                        text_layout.next_word_boundary(byte_index)
                     } else {
-                        position
-                    }
+                        text_layout.prev_word_boundary(byte_index)
+                    };
                 }
+                ByteIndex(byte_index)
             }
         }
     }
@@ -268,33 +245,15 @@ impl TextEditor {
 
     /// Return the byte_range of the word that cotains the given byte_index. If the line that
     /// contains that byte_index contains no word, return byte_index..byte_index.
+    ///
+    /// The same as [`TextLayout::word_range_at`].
     #[must_use]
     pub fn word_range_at_byte_index(
         &self,
         byte_index: usize,
         text_layout: &TextLayout,
     ) -> Range<usize> {
-        let line = self.get_line_from_byte_index(byte_index, text_layout);
-        let line_range = text_layout.lines()[line].byte_range.clone();
-        let text = &text_layout.text()[line_range.clone()];
-        if byte_index >= line_range.end {
-            return text
-                .split_word_bound_indices()
-                .rev()
-                .next()
-                .map(|(i, s)| line_range.start + i..line_range.start + i + s.len())
-                .unwrap_or(byte_index..byte_index);
-        }
-        text.split_word_bound_indices()
-            .find_map(|(i, s)| {
-                let range = line_range.start + i..line_range.start + i + s.len();
-                if range.contains(&byte_index) {
-                    Some(range)
-                } else {
-                    None
-                }
-            })
-            .unwrap()
+        text_layout.word_range_at(byte_index)
     }
 
     /// Select the words that contain part of the given byte range. The given byte range can have
@@ -318,6 +277,22 @@ impl TextEditor {
         self.selection.anchor = ByteIndex(range.end);
     }
 
+    /// Select the paragraphs that contain part of the given byte range. The given byte range can
+    /// have the start and end swapped, for convenience.
+    pub fn select_lines_at_byte_range(
+        &mut self,
+        mut byte_range: Range<usize>,
+        text_layout: &TextLayout,
+    ) {
+        if byte_range.start > byte_range.end {
+            std::mem::swap(&mut byte_range.start, &mut byte_range.end);
+        }
+        let start = text_layout.line_range_at(byte_range.start).start;
+        let end = text_layout.line_range_at(byte_range.end).end;
+        self.selection.cursor = ByteIndex(start);
+        self.selection.anchor = ByteIndex(end);
+    }
+
     /// Select the entire text.
     pub fn select_all(&mut self, text_layout: &TextLayout) {
         let len = text_layout.text().len();
@@ -356,8 +331,7 @@ impl TextEditor {
     /// Move the cursor to the start of the currently line. If expand_selection is true, the anchor
     /// of the selection will be preserved. Otherwise, the selection is clear.
     pub fn move_cursor_line_start(&mut self, expand_selection: bool, text_layout: &TextLayout) {
-        let line = self.get_line_from_byte_index(self.selection.cursor.0, text_layout);
-        let cursor = ByteIndex(text_layout.lines()[line].byte_range.start);
+        let cursor = ByteIndex(text_layout.line_start(self.selection.cursor.0));
         if expand_selection {
             self.selection.cursor = cursor;
         } else {
@@ -369,12 +343,7 @@ impl TextEditor {
     /// Move the cursor to the end of the currently line. If expand_selection is true, the anchor
     /// of the selection will be preserved. Otherwise, the selection is clear.
     pub fn move_cursor_line_end(&mut self, expand_selection: bool, text_layout: &TextLayout) {
-        let line = self.get_line_from_byte_index(self.selection.cursor.0, text_layout);
-        let mut cursor = ByteIndex(text_layout.lines()[line].byte_range.end);
-        if line != text_layout.lines().len() - 1 {
-            // The end byte of a line, is the start byte of the next line, so offset it by -1.
-            cursor = self.offset_byte_index(cursor, HorizontalMotion::Cluster(-1), text_layout);
-        }
+        let cursor = ByteIndex(text_layout.line_end(self.selection.cursor.0));
         if expand_selection {
             self.selection.cursor = cursor;
         } else {
@@ -400,21 +369,15 @@ impl TextEditor {
         expand_selection: bool,
         text_layout: &TextLayout,
     ) {
-        let curr_line = self.get_line_from_byte_index(self.selection.cursor.0, text_layout);
-        let target = curr_line as isize + lines as isize;
-        let lines = text_layout.lines();
-        let cursor = if target < 0 {
-            // if move to a negative line, go to the start of the text.
-            ByteIndex(0)
-        } else if target >= lines.len() as isize {
-            // if move to a out of bounds line, go to the end of the text.
-            ByteIndex(lines.last().unwrap().byte_range.end)
-        } else {
-            let index = text_layout
-                .byte_index_from_x_position(target as usize, self.selection.cursor_x)
-                .unwrap_or_else(|x| x);
-            ByteIndex(index)
-        };
+        let mut byte_index = self.selection.cursor.0;
+        for _ in 0..lines.unsigned_abs() {
+            byte_index = if lines > 0 {
+                text_layout.next_line(byte_index, self.selection.cursor_x)
+            } else {
+                text_layout.prev_line(byte_index, self.selection.cursor_x)
+            };
+        }
+        let cursor = ByteIndex(byte_index);
 
         if expand_selection {
             self.selection.cursor = cursor;
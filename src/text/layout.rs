@@ -1,10 +1,11 @@
 use std::{cmp::Ordering, collections::VecDeque, ops::Range};
 
 use ab_glyph::{Font, Glyph, ScaleFont};
+use unicode_segmentation::UnicodeSegmentation;
 
 use super::{InnerSpan, ShapeSpan, Span};
 use crate::{
-    font::{FontId, Fonts},
+    font::{FontId, Fonts, Synthesis},
     text::SpannedString,
     util::cmp_range,
     Color,
@@ -12,10 +13,12 @@ use crate::{
 
 #[cfg(test)]
 mod test {
+    use std::ops::Range;
+
     use crate::{
-        font::{Font, FontId, Fonts},
+        font::{Font, FontId, Fonts, Synthesis},
         text::{
-            layout::{LayoutSettings, TextLayout},
+            layout::{Alignment, Direction, LayoutSettings, TextLayout, WrapMode},
             Span, SpannedString, TextStyle,
         },
         Color,
@@ -48,8 +51,10 @@ mod test {
         );
         let settings = LayoutSettings {
             max_width: None,
+            wrap_mode: Default::default(),
             horizontal_align: Default::default(),
             vertical_align: Default::default(),
+            base_direction: Default::default(),
         };
         let _text_layout = TextLayout::new(text, settings, &fonts);
     }
@@ -68,8 +73,10 @@ mod test {
         );
         let settings = LayoutSettings {
             max_width: None,
+            wrap_mode: Default::default(),
             horizontal_align: Default::default(),
             vertical_align: Default::default(),
+            base_direction: Default::default(),
         };
         let mut text_layout = TextLayout::new(text, settings, &fonts);
 
@@ -99,6 +106,61 @@ mod test {
         assert_eq!(min_size, text_layout.min_size);
     }
 
+    #[test]
+    fn partial_relayout() {
+        let (fonts, font_ids) = fonts();
+        let mut text = SpannedString::from_string(
+            "first line\nsecond \u{5d0}\u{5d1} line\nthird\n".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        text.add_span(0..5, Span::Color(Color::from_u32(0xff0000ff)));
+        let settings = LayoutSettings {
+            max_width: Some(60.0),
+            wrap_mode: Default::default(),
+            horizontal_align: Alignment::Center,
+            vertical_align: Alignment::Center,
+            base_direction: Default::default(),
+        };
+        let mut text_layout = TextLayout::new(text, settings.clone(), &fonts);
+
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        let edits: &[(Range<usize>, &str)] = &[
+            (2..2, "xx"),
+            (12..13, ""),
+            (31..31, "a\nb"),
+            (13..14, "\r"),
+            (14..14, "\n"),
+            (38..38, "last"),
+            (0..42, ""),
+        ];
+        for (range, text) in edits {
+            text_layout.replace_range(range.clone(), text, &fonts);
+            let expected =
+                TextLayout::new(text_layout.clone().to_spanned(), settings.clone(), &fonts);
+
+            assert_eq!(text_layout.paragraphs, expected.paragraphs);
+            assert_eq!(text_layout.min_size, expected.min_size);
+            assert_eq!(text_layout.lines.len(), expected.lines.len());
+            for (a, b) in text_layout.lines.iter().zip(expected.lines.iter()) {
+                assert_eq!(a.byte_range, b.byte_range);
+                assert_eq!(a.glyph_range, b.glyph_range);
+                assert!(close(a.x, b.x) && close(a.y, b.y) && close(a.width, b.width));
+            }
+            assert_eq!(text_layout.glyphs.len(), expected.glyphs.len());
+            for (a, b) in text_layout.glyphs.iter().zip(expected.glyphs.iter()) {
+                assert_eq!(a.byte_range, b.byte_range);
+                assert_eq!(a.bidi_level, b.bidi_level);
+                assert_eq!(a.color, b.color);
+                assert!(close(a.glyph.position.x, b.glyph.position.x));
+                assert!(close(a.glyph.position.y, b.glyph.position.y));
+            }
+        }
+    }
+
     #[test]
     fn zero_width() {
         let (fonts, font_ids) = fonts();
@@ -115,12 +177,92 @@ mod test {
 
         let settings = LayoutSettings {
             max_width: Some(0.0),
+            wrap_mode: Default::default(),
             horizontal_align: Default::default(),
             vertical_align: Default::default(),
+            base_direction: Default::default(),
         };
         let _text_layout = TextLayout::new(text, settings, &fonts);
     }
 
+    #[test]
+    fn bold_italic() {
+        let data = include_bytes!("..\\..\\examples\\CascadiaCode.ttf");
+        let mut fonts = Fonts::new();
+        let regular = fonts.add(Font::new(data).with_bold(FontId::new(1)));
+        let bold = fonts.add(Font::new(data));
+
+        // a registered face is preferred, and the missing one is synthesized
+        let synthesis = |bold, italic| Synthesis { bold, italic };
+        assert_eq!(
+            fonts.resolve_style(regular, true, false),
+            (bold, synthesis(false, false))
+        );
+        assert_eq!(
+            fonts.resolve_style(regular, true, true),
+            (bold, synthesis(false, true))
+        );
+        assert_eq!(
+            fonts.resolve_style(bold, true, false),
+            (bold, synthesis(true, false))
+        );
+
+        let mut text = SpannedString::from_string(
+            "00".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 24.0,
+                font_id: bold,
+            },
+        );
+        text.add_span(0..1, Span::Bold);
+        let settings = LayoutSettings {
+            max_width: None,
+            wrap_mode: Default::default(),
+            horizontal_align: Default::default(),
+            vertical_align: Default::default(),
+            base_direction: Default::default(),
+        };
+        let text_layout = TextLayout::new(text, settings, &fonts);
+        let glyphs = text_layout.glyphs();
+        assert_eq!(glyphs[0].synthesis, synthesis(true, false));
+        assert_eq!(glyphs[1].synthesis, synthesis(false, false));
+        // a synthetic bold glyph is 1 pixel wider, for each 24 pixels of font size
+        assert!((glyphs[0].width - glyphs[1].width - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let (fonts, font_ids) = fonts();
+        let text = SpannedString::from_string(
+            "aaaa bbbbbbbb".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        let width = TextLayout::new(text.clone(), Default::default(), &fonts).glyphs()[0].width;
+
+        let line_ranges = |wrap_mode: WrapMode| {
+            let settings = LayoutSettings {
+                max_width: Some(width * 6.5),
+                wrap_mode,
+                ..Default::default()
+            };
+            let text_layout = TextLayout::new(text.clone(), settings, &fonts);
+            text_layout
+                .lines()
+                .iter()
+                .map(|x| x.byte_range.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(line_ranges(WrapMode::WordBoundary), [0..5, 5..11, 11..13]);
+        assert_eq!(line_ranges(WrapMode::BreakAnywhere), [0..6, 6..12, 12..13]);
+        assert_eq!(line_ranges(WrapMode::NoWrap), [0..13]);
+    }
+
     #[test]
     fn multi_style() {
         let (fonts, font_ids) = fonts();
@@ -136,8 +278,10 @@ mod test {
 
         let settings = LayoutSettings {
             max_width: Some(20.0),
+            wrap_mode: Default::default(),
             horizontal_align: Default::default(),
             vertical_align: Default::default(),
+            base_direction: Default::default(),
         };
         let text_layout = TextLayout::new(text.clone(), settings.clone(), &fonts);
 
@@ -151,15 +295,229 @@ mod test {
 
         assert_eq!(text_layout.lines(), text_layout2.lines());
     }
+
+    #[test]
+    fn word_and_line_range() {
+        let (fonts, font_ids) = fonts();
+        let text = SpannedString::from_string(
+            "Hello world!\nSecond line".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        let text_layout = TextLayout::new(text, Default::default(), &fonts);
+
+        assert_eq!(text_layout.word_range_at(0), 0..5);
+        assert_eq!(text_layout.word_range_at(7), 6..11);
+        assert_eq!(text_layout.word_range_at(5), 5..6);
+        assert_eq!(text_layout.word_range_at(15), 13..19);
+        assert_eq!(text_layout.word_range_at(24), 20..24);
+
+        assert_eq!(text_layout.line_range_at(3), 0..12);
+        assert_eq!(text_layout.line_range_at(12), 0..12);
+        assert_eq!(text_layout.line_range_at(13), 13..24);
+        assert_eq!(text_layout.line_range_at(24), 13..24);
+    }
+
+    #[test]
+    fn selection_rects() {
+        let (fonts, font_ids) = fonts();
+        let mut text = SpannedString::from_string(
+            "Hello world!\nSecond line".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        let text_layout = TextLayout::new(text.clone(), Default::default(), &fonts);
+        let glyphs = text_layout.glyphs();
+
+        let rects = text_layout.selection_rects(3..16);
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0][0], glyphs[3].glyph.position.x);
+        assert_eq!(rects[0][2], glyphs[12].right());
+        assert_eq!(rects[1][0], glyphs[13].glyph.position.x);
+        assert_eq!(rects[1][2], glyphs[15].right());
+        assert!(text_layout.selection_rects(5..5).is_empty());
+
+        // the same rects of a selection span
+        text.add_span(
+            3..16,
+            Span::Selection {
+                bg: Color::WHITE,
+                fg: None,
+            },
+        );
+        let text_layout = TextLayout::new(text, Default::default(), &fonts);
+        let span_rects: Vec<_> = text_layout.rects().iter().map(|x| x.rect).collect();
+        assert_eq!(span_rects, rects);
+    }
+
+    #[test]
+    fn caret_navigation() {
+        let (fonts, font_ids) = fonts();
+        let text = SpannedString::from_string(
+            "Hello, wörld!\nSecond line".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        let text_layout = TextLayout::new(text, Default::default(), &fonts);
+
+        assert_eq!(text_layout.next_word_boundary(0), 7);
+        assert_eq!(text_layout.next_word_boundary(2), 7);
+        assert_eq!(text_layout.next_word_boundary(7), 15);
+        assert_eq!(text_layout.next_word_boundary(22), 26);
+        assert_eq!(text_layout.prev_word_boundary(26), 22);
+        assert_eq!(text_layout.prev_word_boundary(15), 7);
+        assert_eq!(text_layout.prev_word_boundary(11), 7);
+        assert_eq!(text_layout.prev_word_boundary(3), 0);
+
+        assert_eq!(text_layout.line_start(11), 0);
+        assert_eq!(text_layout.line_start(20), 15);
+        assert_eq!(text_layout.line_end(3), 14);
+        assert_eq!(text_layout.line_end(15), 26);
+
+        let [x, _] = text_layout.pixel_position_from_byte_index(2).unwrap();
+        assert_eq!(text_layout.next_line(2, x), 17);
+        assert_eq!(text_layout.prev_line(17, x), 2);
+        assert_eq!(text_layout.next_line(17, x), 26);
+        assert_eq!(text_layout.prev_line(2, x), 0);
+    }
+
+    #[test]
+    fn right_to_left() {
+        let (fonts, font_ids) = fonts();
+        // "abc אבג", where the hebrew letters have 2 bytes each.
+        let text = SpannedString::from_string(
+            "abc \u{5d0}\u{5d1}\u{5d2}".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        let text_layout = TextLayout::new(text, Default::default(), &fonts);
+        let glyphs = text_layout.glyphs();
+
+        assert!(glyphs[..4].iter().all(|x| !x.is_rtl()));
+        assert!(glyphs[4..].iter().all(|x| x.is_rtl()));
+        // the first hebrew letter is the rightmost one
+        assert!(glyphs[4].glyph.position.x > glyphs[5].glyph.position.x);
+        assert!(glyphs[5].glyph.position.x > glyphs[6].glyph.position.x);
+        assert!(glyphs[6].glyph.position.x >= glyphs[3].right());
+
+        let x = glyphs[4].glyph.position.x + glyphs[4].width * 0.75;
+        assert_eq!(text_layout.byte_index_from_x_position(0, x), Ok(4));
+        let x = glyphs[4].glyph.position.x + glyphs[4].width * 0.25;
+        assert_eq!(text_layout.byte_index_from_x_position(0, x), Ok(6));
+    }
+
+    #[test]
+    fn right_to_left_alignment() {
+        let (fonts, font_ids) = fonts();
+        let layout = |string: &str, align: Alignment, base_direction: Direction| {
+            let text = SpannedString::from_string(
+                string.to_string(),
+                TextStyle {
+                    color: Color::WHITE,
+                    font_size: 16.0,
+                    font_id: font_ids[0],
+                },
+            );
+            let settings = LayoutSettings {
+                horizontal_align: align,
+                base_direction,
+                ..Default::default()
+            };
+            TextLayout::new(text, settings, &fonts)
+        };
+        let is_left_aligned = |layout: &TextLayout| {
+            let mut glyphs = layout.glyphs().iter().filter(|x| !x.is_whitespace);
+            glyphs.all(|x| x.glyph.position.x >= 0.0)
+        };
+        let is_right_aligned = |layout: &TextLayout| {
+            let mut glyphs = layout.glyphs().iter().filter(|x| !x.is_whitespace);
+            glyphs.all(|x| x.right() <= 0.01)
+        };
+
+        let hebrew = "\u{5d0}\u{5d1}\u{5d2}";
+        assert!(is_right_aligned(&layout(
+            hebrew,
+            Alignment::Start,
+            Direction::Auto
+        )));
+        assert!(is_left_aligned(&layout(
+            hebrew,
+            Alignment::End,
+            Direction::Auto
+        )));
+        assert!(is_left_aligned(&layout(
+            hebrew,
+            Alignment::Start,
+            Direction::Ltr
+        )));
+        assert!(is_right_aligned(&layout(
+            "abc",
+            Alignment::Start,
+            Direction::Rtl
+        )));
+        assert!(is_left_aligned(&layout(
+            "abc",
+            Alignment::Start,
+            Direction::Auto
+        )));
+
+        // each paragraph is aligned by its own direction
+        let text_layout = layout("abc\n\u{5d0}\u{5d1}", Alignment::Start, Direction::Auto);
+        let glyphs = text_layout.glyphs();
+        assert_eq!(glyphs[0].glyph.position.x, 0.0);
+        assert!(glyphs[4].right() <= 0.01);
+    }
+
+    #[test]
+    fn set_span_color() {
+        let (fonts, font_ids) = fonts();
+        let red = Color::from_u32(0xff0000ff);
+        let blue = Color::from_u32(0x0000ffff);
+        let mut text = SpannedString::from_string(
+            "hello world".to_string(),
+            TextStyle {
+                color: Color::WHITE,
+                font_size: 16.0,
+                font_id: font_ids[0],
+            },
+        );
+        text.add_span(6..11, Span::Color(red));
+        let mut text_layout = TextLayout::new(text, Default::default(), &fonts);
+        let color_at = |layout: &TextLayout, i: usize| layout.glyphs()[i].color;
+
+        text_layout.set_span_color(3..8, Some(blue));
+        assert_eq!(color_at(&text_layout, 2), Color::WHITE);
+        assert_eq!(color_at(&text_layout, 3), blue);
+        assert_eq!(color_at(&text_layout, 7), blue);
+        assert_eq!(color_at(&text_layout, 8), red);
+
+        text_layout.set_span_color(0..11, None);
+        assert_eq!(color_at(&text_layout, 3), Color::WHITE);
+        assert_eq!(color_at(&text_layout, 7), red);
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Alignment {
-    /// If the alignment is horizontal, align to the left. If is vertical, to the top.
+    /// If the alignment is horizontal, align to the left, or to the right in a right-to-left
+    /// paragraph. If is vertical, to the top.
     Start,
     /// Align to the center.
     Center,
-    /// If the alignment is horizontal, align to the right. If is vertical, to the bottom.
+    /// If the alignment is horizontal, align to the right, or to the left in a right-to-left
+    /// paragraph. If is vertical, to the bottom.
     End,
 }
 impl Default for Alignment {
@@ -168,20 +526,58 @@ impl Default for Alignment {
     }
 }
 
+/// The base direction of a paragraph, as defined in UAX #9.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// The paragraph is left-to-right.
+    Ltr,
+    /// The paragraph is right-to-left.
+    Rtl,
+    /// The direction is given by the first strong directional character of each paragraph. If
+    /// there is none, the paragraph is left-to-right.
+    Auto,
+}
+impl Default for Direction {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// How the lines that exceed the max width of a layout are broken.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WrapMode {
+    /// Break the line at the last break opportunity, as specified in UAX #14. If the line don't
+    /// have a break opportunity, the line is broken at the first glyph to overflow.
+    WordBoundary,
+    /// Break the line at the first glyph to overflow, ignoring the break opportunities. Useful for
+    /// code and URLs.
+    BreakAnywhere,
+    /// Never break the line. Lines that exceed the max width overflow.
+    NoWrap,
+}
+impl Default for WrapMode {
+    fn default() -> Self {
+        Self::WordBoundary
+    }
+}
+
 /// The settings of the text layout.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct LayoutSettings {
     /// The max width of the text layout. Any line of text that exceeds this width suffers a line
-    /// break at the last break opportunity, as specified in UAX #14. If the line don't have a
-    /// break opportunity, the line will be break at the first glyph to overflow. If there is only
-    /// one glyph, the line will overflow.
+    /// break, as specified by `wrap_mode`. If there is only one glyph, the line will overflow.
     pub max_width: Option<f32>,
+    /// How the lines that exceed the max width are broken.
+    pub wrap_mode: WrapMode,
     /// The horizontal alignment of the text. The text is aligned towards the origin, (0, 0). If
     /// it have right alignment, for example, all glyphs will have a negative x position.
     pub horizontal_align: Alignment,
     /// The vertical alignment of the text. The text is aligned towards the origin, (0, 0). If it
     /// have bottom alignment, for example, all glyphs will have a negative y position.
     pub vertical_align: Alignment,
+    /// The base direction of each paragraph of the text. Each line is split in directional runs,
+    /// following the Unicode Bidirectional Algorithm, which are reordered visually.
+    pub base_direction: Direction,
 }
 
 #[derive(Default, Clone, Debug, PartialEq)]
@@ -238,6 +634,21 @@ impl Line {
     }
 }
 
+/// A section of the text delimited by mandatory line breaks, and the lines it was broken in.
+#[derive(Clone, Debug, PartialEq)]
+struct Paragraph {
+    /// The byte range of the paragraph, including its line break.
+    byte_range: Range<usize>,
+    /// The range of the lines of this paragraph in `TextLayout::lines`.
+    line_range: Range<usize>,
+    /// The width of the paragraph before being broken in lines, in pixels.
+    width: f32,
+    /// The height of the paragraph before being broken in lines, in pixels.
+    height: f32,
+    /// The line gap of the paragraph, in pixels.
+    line_gap: f32,
+}
+
 /// A positioned scaled glyph.
 #[derive(Debug, Clone, PartialEq)]
 pub struct GlyphPosition {
@@ -245,6 +656,8 @@ pub struct GlyphPosition {
     pub glyph: Glyph,
     /// The index of the font of this glyph
     pub font_id: FontId,
+    /// The styles that are synthesized when drawing this glyph.
+    pub synthesis: Synthesis,
     /// The byte range of text string represented by this glyph.
     pub byte_range: Range<usize>,
     /// The width of this glyph.
@@ -253,8 +666,16 @@ pub struct GlyphPosition {
     pub color: Color,
     /// If this glyph represents a whitespace char.
     pub is_whitespace: bool,
+    /// The resolved embedding level of this glyph, as defined in UAX #9. Odd levels are
+    /// right-to-left.
+    pub bidi_level: u8,
 }
 impl GlyphPosition {
+    /// Returns true if this glyph is part of a right-to-left run.
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
+
     /// The position of the right edge of this glyph. Equal to position.x + width.
     pub fn right(&self) -> f32 {
         self.glyph.position.x + self.width
@@ -268,6 +689,10 @@ pub struct ColorRect {
     pub rect: [f32; 4],
     /// The color of the rect.
     pub color: Color,
+    /// If this rect is the background of a selection. See [`Text::set_selection_radius`].
+    ///
+    /// [`Text::set_selection_radius`]: crate::text::Text::set_selection_radius
+    pub is_selection: bool,
 }
 
 /// Performs the shaping and layout of a SpannedString, producing glyphs for rendering. The
@@ -281,12 +706,16 @@ pub struct TextLayout {
     settings: LayoutSettings,
     /// The lines of the layout.
     lines: Vec<Line>,
+    /// The paragraphs of the text, and the lines they were broken in.
+    paragraphs: Vec<Paragraph>,
     /// The glyphs of the layout.
     glyphs: Vec<GlyphPosition>,
     /// Rects that add others drawings such as underlines, and selections.
     rects: Vec<ColorRect>,
     /// The minimum width and height required so that there is no line wrap or overflow
     min_size: [f32; 2],
+    /// The revision of the fonts used in the last layout.
+    font_revision: u64,
 }
 impl TextLayout {
     /// Create a new TextLayout from the given SpannedString.
@@ -309,15 +738,19 @@ impl TextLayout {
             byte_range: len..len + 1,
             font_size: last_font_size,
             font_id: Default::default(),
+            bold: false,
+            italic: false,
         });
 
         let mut this = Self {
             text,
             settings,
             lines: Vec::new(),
+            paragraphs: Vec::new(),
             glyphs: Vec::new(),
             rects: Vec::new(),
             min_size: [0.0, 0.0],
+            font_revision: fonts.revision(),
         };
         this.layout(fonts);
 
@@ -409,8 +842,13 @@ impl TextLayout {
             .binary_search_by(|x| cmp_range(byte_index, x.byte_range.clone()))
             .ok()?;
         let glyph = &self.glyphs[x];
-        let pos = [glyph.glyph.position.x, glyph.glyph.position.y];
-        Some(pos)
+        // the caret before a right-to-left glyph is on its right side.
+        let x = if glyph.is_rtl() {
+            glyph.right()
+        } else {
+            glyph.glyph.position.x
+        };
+        Some([x, glyph.glyph.position.y])
     }
 
     /// Return the index of the line that contains the given y_position, in pixels, wrapped in
@@ -450,33 +888,38 @@ impl TextLayout {
             line.glyph_range.clone()
         };
         let glyphs = &self.glyphs[glyph_range];
-        let g = glyphs.binary_search_by(|g| {
+        // The glyphs are in logical order, but a line with right-to-left runs is not ordered by x
+        // position, so search for the closest glyph.
+        let distance = |g: &GlyphPosition| {
             if x_position < g.glyph.position.x {
-                Ordering::Greater
+                g.glyph.position.x - x_position
             } else if x_position > g.right() {
-                Ordering::Less
+                x_position - g.right()
             } else {
-                Ordering::Equal
-            }
-        });
-        match g {
-            Ok(i) => {
-                // round to nearest
-                let middle = {
-                    let glyph = &glyphs[i];
-                    glyph.glyph.position.x + glyph.width / 2.0
-                };
-                let i = if i < glyphs.len() - 1 && x_position > middle {
-                    i + 1
-                } else {
-                    i
-                };
-                Ok(glyphs[i].byte_range.start)
+                0.0
             }
-            Err(0) => Err(line.byte_range.start),
-            Err(i) if i == glyphs.len() => Err(glyphs.last().unwrap().byte_range.start),
-            _ => unreachable!("Unreachable, as long as there is no gap beetween glyphs"),
+        };
+        let (i, glyph) = glyphs
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .unwrap();
+        if distance(glyph) > 0.0 {
+            return Err(glyph.byte_range.start);
         }
+        // round to nearest
+        let middle = glyph.glyph.position.x + glyph.width / 2.0;
+        let after = if glyph.is_rtl() {
+            x_position < middle
+        } else {
+            x_position > middle
+        };
+        let i = if i < glyphs.len() - 1 && after {
+            i + 1
+        } else {
+            i
+        };
+        Ok(glyphs[i].byte_range.start)
     }
 
     /// Return the byte index for the closest caret to the given horizontal
@@ -497,6 +940,115 @@ impl TextLayout {
         }
     }
 
+    /// Return the byte range of the word that contains the given byte index, as defined by the
+    /// word boundaries of UAX #29. If the byte index is at the end of a line, the last word of that
+    /// line is returned. The range never crosses a mandatory line break.
+    pub fn word_range_at(&self, byte_index: usize) -> Range<usize> {
+        let line_range = self.line_range_at(byte_index);
+        let text = &self.text()[line_range.clone()];
+        let byte_index = byte_index.min(line_range.end);
+        let mut last = byte_index..byte_index;
+        for (i, s) in text.split_word_bound_indices() {
+            let range = line_range.start + i..line_range.start + i + s.len();
+            if range.contains(&byte_index) {
+                return range;
+            }
+            last = range;
+        }
+        last
+    }
+
+    /// Return the byte range of the paragraph that contains the given byte index. A paragraph is a
+    /// section of text delimited by mandatory line breaks. The returned range doesn't include the
+    /// line break itself.
+    pub fn line_range_at(&self, byte_index: usize) -> Range<usize> {
+        let text = self.text();
+        let byte_index = byte_index.min(text.len());
+        let start = text[..byte_index].rfind('\n').map_or(0, |x| x + 1);
+        let end = text[byte_index..]
+            .find('\n')
+            .map_or(text.len(), |x| x + byte_index);
+        start..end
+    }
+
+    /// Return the index of the line that contains the given byte index. If there is no such line,
+    /// the last line index is returned.
+    pub fn line_index_at(&self, byte_index: usize) -> usize {
+        self.lines
+            .binary_search_by(|x| cmp_range(byte_index, x.byte_range.clone()))
+            .unwrap_or(self.lines.len() - 1)
+    }
+
+    /// Return the byte index of the start of the next word after the given byte index, as defined
+    /// by the word boundaries of UAX #29. If there is no next word, return the end of the text.
+    pub fn next_word_boundary(&self, byte_index: usize) -> usize {
+        let text = self.text();
+        let byte_index = byte_index.min(text.len());
+        text[byte_index..]
+            .unicode_word_indices()
+            .map(|(i, _)| byte_index + i)
+            .find(|&i| i > byte_index)
+            .unwrap_or(text.len())
+    }
+
+    /// Return the byte index of the start of the word before the given byte index, or of the word
+    /// that contains it, as defined by the word boundaries of UAX #29. If there is no previous
+    /// word, return 0.
+    pub fn prev_word_boundary(&self, byte_index: usize) -> usize {
+        let text = self.text();
+        let byte_index = byte_index.min(text.len());
+        text[..byte_index]
+            .unicode_word_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Return the byte index of the start of the visual line that contains the given byte index.
+    pub fn line_start(&self, byte_index: usize) -> usize {
+        let line = self.line_index_at(byte_index);
+        self.lines[line].byte_range.start
+    }
+
+    /// Return the byte index of the end of the visual line that contains the given byte index. The
+    /// returned index is before the line break, or before the whitespace where the line was
+    /// wrapped, so the caret stays in the same line.
+    pub fn line_end(&self, byte_index: usize) -> usize {
+        let line = self.line_index_at(byte_index);
+        let byte_range = self.lines[line].byte_range.clone();
+        if line == self.lines.len() - 1 {
+            return byte_range.end;
+        }
+        // The end byte of a line is the start byte of the next line, so move back one cluster.
+        self.text()[byte_range.clone()]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(byte_range.start, |(i, _)| byte_range.start + i)
+    }
+
+    /// Return the byte index of the caret in the line below the one that contains the given byte
+    /// index, closest to the horizontal position `desired_x`, in pixels. If it is already in the
+    /// last line, return the end of the text.
+    pub fn next_line(&self, byte_index: usize, desired_x: f32) -> usize {
+        let line = self.line_index_at(byte_index);
+        if line + 1 >= self.lines.len() {
+            return self.text().len();
+        }
+        self.byte_index_from_x_position(line + 1, desired_x)
+            .unwrap_or_else(|x| x)
+    }
+
+    /// Return the byte index of the caret in the line above the one that contains the given byte
+    /// index, closest to the horizontal position `desired_x`, in pixels. If it is already in the
+    /// first line, return 0.
+    pub fn prev_line(&self, byte_index: usize, desired_x: f32) -> usize {
+        let line = self.line_index_at(byte_index);
+        if line == 0 {
+            return 0;
+        }
+        self.byte_index_from_x_position(line - 1, desired_x)
+            .unwrap_or_else(|x| x)
+    }
+
     /// Append the given string to the alrleady existing text.
     ///
     /// This recompute the layout.
@@ -507,8 +1059,8 @@ impl TextLayout {
 
     /// Removes the specified range in the string, and replaces it with the given string.
     ///
-    /// This recompute the layout. The given string doesn’t need to be the same length as the
-    /// range.
+    /// This recompute the layout of the paragraphs that contains the range, and only moves the
+    /// lines of the others. The given string doesn’t need to be the same length as the range.
     pub fn replace_range(&mut self, range: Range<usize>, text: &str, fonts: &Fonts) {
         // the string has a extra char, so check for out of bounds for len() - 1.
         assert!(
@@ -517,16 +1069,54 @@ impl TextLayout {
             range.end,
             self.text.string.len() - 1
         );
-        self.text.replace_range(range, text);
+        if !self.is_up_to_date(fonts) {
+            self.text.replace_range(range, text);
+            self.layout(fonts);
+            return;
+        }
 
-        // clear everthing
-        self.glyphs.clear();
-        self.rects.clear();
-        self.lines.clear();
-        self.min_size = [0.0, 0.0];
+        let paragraph_at = |byte_index: usize| {
+            self.paragraphs
+                .binary_search_by(|x| cmp_range(byte_index, x.byte_range.clone()))
+                .unwrap()
+        };
+        // A edit at the start of a paragraph can change the line break of the previous one, like
+        // a "\n" inserted after a "\r", so it is laid out again too.
+        let mut first = paragraph_at(range.start);
+        if first > 0 && self.paragraphs[first].byte_range.start == range.start {
+            first -= 1;
+        }
+        let last = paragraph_at(range.end);
 
-        // recompute everthing
-        self.layout(fonts);
+        let old_len = self.text.string.len();
+        self.text.replace_range(range, text);
+        let new_len = self.text.string.len();
+
+        // shift everything after the edited paragraphs.
+        self.restore_extra_glyph();
+        let byte_end = self.paragraphs[last].byte_range.end;
+        let line_end = self.paragraphs[last].line_range.end;
+        let glyph_end = self.lines[line_end - 1].glyph_range.end;
+        let shift = |x: &mut Range<usize>| {
+            x.start = x.start + new_len - old_len;
+            x.end = x.end + new_len - old_len;
+        };
+        self.paragraphs[last + 1..]
+            .iter_mut()
+            .for_each(|x| shift(&mut x.byte_range));
+        self.lines[line_end..]
+            .iter_mut()
+            .for_each(|x| shift(&mut x.byte_range));
+        self.glyphs[glyph_end..]
+            .iter_mut()
+            .for_each(|x| shift(&mut x.byte_range));
+
+        let byte_start = self.paragraphs[first].byte_range.start;
+        self.relayout(
+            first..last + 1,
+            byte_start..byte_end + new_len - old_len,
+            fonts,
+        );
     }
 
     /// Destroys self, returning the inner SpannedString.
@@ -542,27 +1132,136 @@ impl TextLayout {
         &self.text
     }
 
+    /// The settings used in this layout.
+    pub fn settings(&self) -> &LayoutSettings {
+        &self.settings
+    }
+
+    /// Change the color of the glyphs in the given byte range, without shaping or laying out the
+    /// text again. If `color` is None, the glyphs get back the color given by the text style and
+    /// its spans.
+    ///
+    /// This is meant for transient changes, like highlighting a hovered link. The change is lost
+    /// when the text is laid out again.
+    pub fn set_span_color(&mut self, range: Range<usize>, color: Option<Color>) {
+        let text = &self.text;
+        // the color given by the spans, where the last one takes precedence, like in
+        // apply_styles.
+        let style_color = |byte_index: usize| {
+            text.spans
+                .iter()
+                .filter(|x| x.byte_range.contains(&byte_index))
+                .filter_map(|x| match x.span_type {
+                    Span::Color(color)
+                    | Span::Selection {
+                        fg: Some(color), ..
+                    } => Some(color),
+                    _ => None,
+                })
+                .last()
+                .unwrap_or(text.default_style.color)
+        };
+        for glyph in &mut self.glyphs {
+            if !range.contains(&glyph.byte_range.start) {
+                continue;
+            }
+            glyph.color = color.unwrap_or_else(|| style_color(glyph.byte_range.start));
+        }
+    }
+
+    /// Return true if this layout was computed with the current revision of the fonts. See
+    /// [`Fonts::invalidate`].
+    pub fn is_up_to_date(&self, fonts: &Fonts) -> bool {
+        self.font_revision == fonts.revision()
+    }
+
     fn layout(&mut self, fonts: &Fonts) {
+        self.lines.clear();
+        self.paragraphs.clear();
+        self.glyphs.clear();
+        let len = self.text.string.len();
+        self.relayout(0..0, 0..len, fonts);
+    }
+
+    /// Layout the paragraphs in the given byte range of the text, replacing the given range of
+    /// paragraphs, and its lines and glyphs. The lines of the others paragraphs are only moved.
+    fn relayout(&mut self, paragraphs: Range<usize>, byte_range: Range<usize>, fonts: &Fonts) {
+        self.font_revision = fonts.revision();
         use unicode_linebreak::{linebreaks, BreakOpportunity::*};
+        let start = byte_range.start;
         let (allowed_breaks, mandatory_breaks) = {
             let mut left: Vec<usize> = Vec::new();
             let mut right: Vec<usize> = Vec::new();
 
-            for x in linebreaks(&self.text.string) {
+            for x in linebreaks(&self.text.string[byte_range.clone()]) {
                 if x.1 == Allowed {
-                    left.push(x.0);
+                    left.push(start + x.0);
                 } else {
-                    right.push(x.0);
+                    right.push(start + x.0);
                 }
             }
 
             (left, right)
         };
 
-        let lines = self.layout_paragraphs(fonts, mandatory_breaks);
+        let levels = self.bidi_levels(byte_range);
+        let line_layouts = self.layout_paragraphs(fonts, start, mandatory_breaks, &levels);
+        let (lines, mut glyphs, new_paragraphs) = self.break_lines(line_layouts, allowed_breaks);
+        for glyph in &mut glyphs {
+            glyph.bidi_level = levels
+                .get(glyph.byte_range.start - start)
+                .copied()
+                .unwrap_or(0);
+        }
+
+        // replace the lines and glyphs of the old paragraphs
+        let line_start = paragraphs
+            .start
+            .checked_sub(1)
+            .map_or(0, |x| self.paragraphs[x].line_range.end);
+        let line_end = if paragraphs.is_empty() {
+            line_start
+        } else {
+            self.paragraphs[paragraphs.end - 1].line_range.end
+        };
+        let glyph_start = self
+            .lines
+            .get(line_start)
+            .map_or(self.glyphs.len(), |x| x.glyph_range.start);
+        let glyph_end = if line_end > line_start {
+            self.lines[line_end - 1].glyph_range.end
+        } else {
+            glyph_start
+        };
+
+        let (new_lines, new_glyphs) = (lines.len(), glyphs.len());
+        for line in &mut self.lines[line_end..] {
+            line.glyph_range.start = line.glyph_range.start - glyph_end + glyph_start + new_glyphs;
+            line.glyph_range.end = line.glyph_range.end - glyph_end + glyph_start + new_glyphs;
+        }
+        for paragraph in &mut self.paragraphs[paragraphs.end..] {
+            let line_range = &mut paragraph.line_range;
+            line_range.start = line_range.start - line_end + line_start + new_lines;
+            line_range.end = line_range.end - line_end + line_start + new_lines;
+        }
+        self.glyphs.splice(glyph_start..glyph_end, glyphs);
+        self.lines.splice(
+            line_start..line_end,
+            lines.into_iter().map(|mut x| {
+                x.glyph_range.start += glyph_start;
+                x.glyph_range.end += glyph_start;
+                x
+            }),
+        );
+        self.paragraphs.splice(
+            paragraphs,
+            new_paragraphs.into_iter().map(|mut x| {
+                x.line_range.start += line_start;
+                x.line_range.end += line_start;
+                x
+            }),
+        );
 
-        self.compute_min_size(&lines);
-        self.break_lines(lines, allowed_breaks);
         assert_eq!(self.lines[0].glyph_range.start, 0);
         assert_eq!(
             self.lines.last().unwrap().glyph_range.end,
@@ -573,7 +1272,9 @@ impl TextLayout {
             self.lines.last().unwrap().byte_range.end,
             self.text.string.len()
         );
-        self.position_lines();
+
+        self.compute_min_size();
+        self.position_lines(line_start..line_start + new_lines);
 
         // remove extra glyph from the last line
         let last_line = self.lines.last_mut().unwrap();
@@ -581,22 +1282,62 @@ impl TextLayout {
         last_line.glyph_range.end -= 1;
         last_line.byte_range.end -= 1;
 
+        let color = self.text.default_style.color;
+        self.glyphs.iter_mut().for_each(|x| x.color = color);
+        self.rects.clear();
         self.apply_styles(fonts);
     }
 
+    /// Undo the removal of the extra glyph from the last line, done at the end of the layout, so
+    /// the layout can be partially recomputed.
+    fn restore_extra_glyph(&mut self) {
+        let last_line = self.lines.last_mut().unwrap();
+        let extra_glyph = &self.glyphs[last_line.glyph_range.end];
+        if extra_glyph.is_whitespace {
+            last_line.width += extra_glyph.width;
+        }
+        last_line.glyph_range.end += 1;
+        last_line.byte_range.end += 1;
+    }
+
+    /// Resolve the embedding level of each byte of the given range of the text, following the
+    /// Unicode Bidirectional Algorithm. The range must start and end at paragraph boundaries.
+    fn bidi_levels(&self, byte_range: Range<usize>) -> Vec<u8> {
+        use unicode_bidi::{BidiInfo, Level};
+        let para_level = match self.settings.base_direction {
+            Direction::Ltr => Some(Level::ltr()),
+            Direction::Rtl => Some(Level::rtl()),
+            Direction::Auto => None,
+        };
+        let text = &self.text.string[byte_range];
+        let info = BidiInfo::new(text, para_level);
+        if !info.has_rtl() {
+            return vec![0; text.len()];
+        }
+        info.levels.iter().map(|x| x.number()).collect()
+    }
+
     /// Layout it paragraph in a LineLayout. Each paragraph is section of the text, separated by
-    /// mandatory breaklines.
+    /// mandatory breaklines. `start` is the byte index of the first paragraph, and of the first
+    /// level in `levels`.
     fn layout_paragraphs(
         &mut self,
         fonts: &Fonts,
+        start: usize,
         mandatory_breaks: Vec<usize>,
+        levels: &[u8],
     ) -> Vec<LineLayout> {
         // split the text in paragraphs
+        self.text.split_shape_span(start);
         for i in &mandatory_breaks {
             self.text.split_shape_span(*i);
         }
 
-        let mut span_start = 0;
+        let mut span_start = self
+            .text
+            .get_shape_spans()
+            .binary_search_by(|x| cmp_range(start, x.byte_range.clone()))
+            .unwrap();
         let mut lines = Vec::new();
         for next_break in mandatory_breaks.into_iter() {
             let shape_spans = self.text.get_shape_spans();
@@ -605,7 +1346,7 @@ impl TextLayout {
                 .skip(span_start)
                 .position(|x| x.byte_range.start == next_break)
                 .map_or(shape_spans.len(), |x| x + span_start);
-            let line = LineLayout::new(&self.text, span_start..span_end, fonts);
+            let line = LineLayout::new(&self.text, span_start..span_end, levels, start, fonts);
             lines.push(line);
             span_start = span_end;
         }
@@ -615,12 +1356,13 @@ impl TextLayout {
 
     /// Compute the minimum size for the bound rect of this layout required so that there is no
     /// line wrap or overflow. Stores it in self.min_size
-    fn compute_min_size(&mut self, lines: &[LineLayout]) {
+    fn compute_min_size(&mut self) {
+        let paragraphs = &self.paragraphs;
         let height = {
-            let sum: f32 = lines.iter().map(|x| x.height + x.line_gap).sum();
-            sum - lines.last().map_or(0.0, |x| x.line_gap)
+            let sum: f32 = paragraphs.iter().map(|x| x.height + x.line_gap).sum();
+            sum - paragraphs.last().map_or(0.0, |x| x.line_gap)
         };
-        let width = lines
+        let width = paragraphs
             .iter()
             .map(|x| x.width)
             .max_by(|a, b| a.partial_cmp(b).unwrap())
@@ -628,11 +1370,23 @@ impl TextLayout {
         self.min_size = [width, height];
     }
 
-    /// If there is a max_width, break the given LineLayouts in multiple lines. All lines and
-    /// glyphs are moved to self.lines and self.glyphs.
-    fn break_lines(&mut self, mut lines: Vec<LineLayout>, allowed_breaks: Vec<usize>) {
-        if let Some(max_width) = self.settings.max_width {
-            let mut breaklines = allowed_breaks.into();
+    /// If there is a max_width, break the given LineLayouts in multiple lines, following the wrap
+    /// mode. Return the lines and glyphs of all of them, and the paragraph of each one.
+    fn break_lines(
+        &self,
+        mut lines: Vec<LineLayout>,
+        allowed_breaks: Vec<usize>,
+    ) -> (Vec<Line>, Vec<GlyphPosition>, Vec<Paragraph>) {
+        let max_width = self
+            .settings
+            .max_width
+            .filter(|_| self.settings.wrap_mode != WrapMode::NoWrap);
+        if let Some(max_width) = max_width {
+            // without break opportunities, the lines are broken at the first glyph to overflow.
+            let mut breaklines = match self.settings.wrap_mode {
+                WrapMode::BreakAnywhere => VecDeque::new(),
+                _ => allowed_breaks.into(),
+            };
             for line in &mut lines {
                 line.break_lines(max_width, &mut breaklines);
             }
@@ -643,24 +1397,32 @@ impl TextLayout {
             }
         }
 
-        assert_eq!(self.glyphs.len(), 0);
+        let mut glyphs = Vec::new();
+        let mut paragraphs = Vec::new();
+        let mut broken_lines = Vec::new();
         for line in lines {
-            let start_glyph = self.glyphs.len();
-            self.lines.extend(line.lines.into_iter().map(|mut x| {
+            let start_glyph = glyphs.len();
+            paragraphs.push(Paragraph {
+                byte_range: line.lines[0].byte_range.start
+                    ..line.lines.last().unwrap().byte_range.end,
+                line_range: broken_lines.len()..broken_lines.len() + line.lines.len(),
+                width: line.width,
+                height: line.height,
+                line_gap: line.line_gap,
+            });
+            broken_lines.extend(line.lines.into_iter().map(|mut x| {
                 x.glyph_range.start += start_glyph;
                 x.glyph_range.end += start_glyph;
                 x
             }));
-            let color = self.text.default_style.color;
-            self.glyphs.extend(line.glyphs.into_iter().map(|mut x| {
-                x.color = color;
-                x
-            }));
+            glyphs.extend(line.glyphs);
         }
+        (broken_lines, glyphs, paragraphs)
     }
 
-    /// Move all lines to the right position.
-    fn position_lines(&mut self) {
+    /// Move all lines to the right position. The lines in `new_lines` were just laid out, and are
+    /// also reordered visually.
+    fn position_lines(&mut self, new_lines: Range<usize>) {
         // right after calling this function, the extra glyph in the last line is removed.
         // make sure this is always called before that
         debug_assert!(
@@ -677,9 +1439,22 @@ impl TextLayout {
             Alignment::Center => -height / 2.0,
             Alignment::End => -height,
         };
-        for line in &mut self.lines {
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            if new_lines.contains(&i) {
+                reorder_line(line, &mut self.glyphs);
+            }
             y += line.ascent;
-            let x = match self.settings.horizontal_align {
+            let rtl = is_rtl_paragraph(
+                &self.text.string,
+                self.settings.base_direction,
+                line.byte_range.start,
+            );
+            let align = match (self.settings.horizontal_align, rtl) {
+                (Alignment::Start, true) => Alignment::End,
+                (Alignment::End, true) => Alignment::Start,
+                (align, _) => align,
+            };
+            let x = match align {
                 Alignment::Start => 0.0,
                 Alignment::Center => -line.visible_width(&self.glyphs) / 2.0,
                 Alignment::End => -line.visible_width(&self.glyphs),
@@ -725,70 +1500,18 @@ impl TextLayout {
                     .for_each(move |x| x.color = color),
                 Span::Selection { .. } => {}
                 Span::Underline(_) => {}
-                Span::FontSize(_) | Span::FontId(_) => {}
+                Span::FontSize(_) | Span::FontId(_) | Span::Bold | Span::Italic => {}
             }
             // create rects
             match kind {
                 Span::Color(_) => {}
                 &Span::Selection { bg: color, .. } => {
-                    let first_line = self
-                        .lines
-                        .binary_search_by(|x| cmp_range(range.start, x.byte_range.clone()))
-                        .unwrap();
-                    let glyphs = &self.glyphs;
-                    let glyph_pos = |glyph_index: usize| {
-                        let glyph = &glyphs[glyph_index];
-                        [glyph.glyph.position.x, glyph.glyph.position.y]
-                    };
-                    let glyph_pos_end = |glyph_index: usize| {
-                        let glyph = &glyphs[glyph_index];
-                        [glyph.right(), glyph.glyph.position.y]
-                    };
-                    let start_pos = glyph_pos(glyph_range.start);
-                    let end_pos = glyph_pos_end(glyph_range.end - 1);
-                    let line = &self.lines[first_line];
-                    if line.glyph_range.end > glyph_range.end {
-                        let rect = [
-                            start_pos[0],
-                            start_pos[1] - line.ascent,
-                            end_pos[0],
-                            end_pos[1] - line.descent,
-                        ];
-                        self.rects.push(ColorRect { rect, color });
-                    } else {
-                        {
-                            let end_pos = glyph_pos_end(line.glyph_range.end - 1);
-                            let rect = [
-                                start_pos[0],
-                                start_pos[1] - line.ascent,
-                                end_pos[0],
-                                end_pos[1] - line.descent,
-                            ];
-                            self.rects.push(ColorRect { rect, color });
-                        }
-                        for line in self.lines[first_line..].iter().skip(1) {
-                            let start_pos = glyph_pos(line.glyph_range.start);
-                            if line.glyph_range.end > glyph_range.end {
-                                let rect = [
-                                    start_pos[0],
-                                    start_pos[1] - line.ascent,
-                                    end_pos[0],
-                                    end_pos[1] - line.descent,
-                                ];
-                                self.rects.push(ColorRect { rect, color });
-                                break;
-                            } else {
-                                let end_pos = glyph_pos_end(line.glyph_range.end - 1);
-                                let rect = [
-                                    start_pos[0],
-                                    start_pos[1] - line.ascent,
-                                    end_pos[0],
-                                    end_pos[1] - line.descent,
-                                ];
-                                self.rects.push(ColorRect { rect, color });
-                            };
-                        }
-                    }
+                    let rects = self.selection_rects(range.clone());
+                    self.rects.extend(rects.into_iter().map(|rect| ColorRect {
+                        rect,
+                        color,
+                        is_selection: true,
+                    }));
                 }
                 Span::Underline(color) => {
                     // TODO: this should have a different thickness for each different font size,
@@ -796,69 +1519,168 @@ impl TextLayout {
                     // TODO: query font for underline position and thickness (not possible with
                     // ab_glyph currently)
                     let color = color.unwrap_or_else(|| self.text.default_style.color);
-                    let first_line = self
-                        .lines
-                        .binary_search_by(|x| cmp_range(range.start, x.byte_range.clone()))
-                        .unwrap();
-                    let glyphs = &self.glyphs;
-                    let glyph_pos = |glyph_index: usize| {
-                        let glyph = &glyphs[glyph_index];
-                        [glyph.glyph.position.x, glyph.glyph.position.y]
-                    };
-                    let glyph_pos_end = |glyph_index: usize| {
-                        let glyph = &glyphs[glyph_index];
-                        [glyph.right(), glyph.glyph.position.y]
-                    };
-                    let start_pos = glyph_pos(glyph_range.start);
-                    let end_pos = glyph_pos_end(glyph_range.end - 1);
-                    let line = &self.lines[first_line];
-                    if line.glyph_range.end > glyph_range.end {
+                    for (start_pos, end_pos, line) in self.line_segments(range.clone()) {
+                        let line = &self.lines[line];
                         let rect = [
                             start_pos[0],
                             start_pos[1] - line.descent - 2.0,
                             end_pos[0],
                             end_pos[1] - line.descent,
                         ];
-                        self.rects.push(ColorRect { rect, color });
-                    } else {
-                        {
-                            let end_pos = glyph_pos_end(line.glyph_range.end - 1);
-                            let rect = [
-                                start_pos[0],
-                                start_pos[1] - line.descent - 2.0,
-                                end_pos[0],
-                                end_pos[1] - line.descent,
-                            ];
-                            self.rects.push(ColorRect { rect, color });
-                        }
-                        for line in self.lines[first_line..].iter().skip(1) {
-                            let start_pos = glyph_pos(line.glyph_range.start);
-                            if line.glyph_range.end > glyph_range.end {
-                                let rect = [
-                                    start_pos[0],
-                                    start_pos[1] - line.descent - 2.0,
-                                    end_pos[0],
-                                    end_pos[1] - line.descent,
-                                ];
-                                self.rects.push(ColorRect { rect, color });
-                                break;
-                            } else {
-                                let end_pos = glyph_pos_end(line.glyph_range.end - 1);
-                                let rect = [
-                                    start_pos[0],
-                                    start_pos[1] - line.descent - 2.0,
-                                    end_pos[0],
-                                    end_pos[1] - line.descent,
-                                ];
-                                self.rects.push(ColorRect { rect, color });
-                            };
-                        }
+                        self.rects.push(ColorRect {
+                            rect,
+                            color,
+                            is_selection: false,
+                        });
                     }
                 }
-                Span::FontSize(_) | Span::FontId(_) => {}
+                Span::FontSize(_) | Span::FontId(_) | Span::Bold | Span::Italic => {}
             }
         }
     }
+
+    /// Return the rects, in the form [x1, y1, x2, y2], that cover the text in the given byte
+    /// range, one for each line that the range spans. Each rect has the height of its line, like
+    /// the background of a [`Span::Selection`]. The rects are in pixels, relative to the alignment
+    /// anchor.
+    ///
+    /// This can be used to highlight any range of the text, like the results of a search.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<[f32; 4]> {
+        self.line_segments(range)
+            .into_iter()
+            .map(|(start_pos, end_pos, line)| {
+                let line = &self.lines[line];
+                [
+                    start_pos[0],
+                    start_pos[1] - line.ascent,
+                    end_pos[0],
+                    end_pos[1] - line.descent,
+                ]
+            })
+            .collect()
+    }
+
+    /// Split the glyphs in the given byte range by the lines that contains them. Return, for each
+    /// line, the position of the first glyph, the position of the right side of the last glyph,
+    /// and the index of the line.
+    fn line_segments(&self, range: Range<usize>) -> Vec<([f32; 2], [f32; 2], usize)> {
+        let len = self.text().len();
+        let range = range.start.min(len)..range.end.min(len);
+        let glyph_range = {
+            let glyph_at = |byte_index: usize| {
+                self.glyphs
+                    .binary_search_by(|x| cmp_range(byte_index, x.byte_range.clone()))
+                    .unwrap()
+            };
+            glyph_at(range.start)..glyph_at(range.end)
+        };
+        if glyph_range.is_empty() {
+            return Vec::new();
+        }
+
+        let glyph_pos = |glyph_index: usize| {
+            let glyph = &self.glyphs[glyph_index];
+            [glyph.glyph.position.x, glyph.glyph.position.y]
+        };
+        let glyph_pos_end = |glyph_index: usize| {
+            let glyph = &self.glyphs[glyph_index];
+            [glyph.right(), glyph.glyph.position.y]
+        };
+
+        let first_line = self.line_index_at(range.start);
+        let mut segments = Vec::new();
+        for (l, line) in self.lines.iter().enumerate().skip(first_line) {
+            if line.glyph_range.start >= glyph_range.end {
+                break;
+            }
+            let start_pos = if l == first_line {
+                glyph_pos(glyph_range.start)
+            } else {
+                glyph_pos(line.glyph_range.start)
+            };
+            if line.glyph_range.end > glyph_range.end {
+                segments.push((start_pos, glyph_pos_end(glyph_range.end - 1), l));
+                break;
+            }
+            segments.push((start_pos, glyph_pos_end(line.glyph_range.end - 1), l));
+        }
+        segments
+    }
+}
+
+/// Return true if the paragraph that contains the byte `index` of `text` has a right-to-left base
+/// direction. With [`Direction::Auto`], that is given by its first strong directional character.
+fn is_rtl_paragraph(text: &str, direction: Direction, index: usize) -> bool {
+    use unicode_bidi::{bidi_class, BidiClass};
+    match direction {
+        Direction::Ltr => false,
+        Direction::Rtl => true,
+        Direction::Auto => {
+            let index = index.min(text.len());
+            let start = text[..index].rfind('\n').map_or(0, |x| x + 1);
+            text[start..]
+                .chars()
+                .take_while(|&c| c != '\n')
+                .find_map(|c| match bidi_class(c) {
+                    BidiClass::L => Some(false),
+                    BidiClass::R | BidiClass::AL => Some(true),
+                    _ => None,
+                })
+                .unwrap_or(false)
+        }
+    }
+}
+
+/// Reorder the glyphs of the line visually, following the rule L2 of UAX #9. The glyphs keep their
+/// logical order in the slice, only their horizontal position is changed.
+fn reorder_line(line: &Line, glyphs: &mut [GlyphPosition]) {
+    let glyphs = &mut glyphs[line.glyph_range.clone()];
+    if !glyphs.iter().any(|x| x.is_rtl()) {
+        return;
+    }
+
+    let mut visual: Vec<usize> = (0..glyphs.len()).collect();
+    let max_level = glyphs.iter().map(|x| x.bidi_level).max().unwrap_or(0);
+    let min_odd_level = glyphs
+        .iter()
+        .map(|x| x.bidi_level)
+        .filter(|x| x % 2 == 1)
+        .min()
+        .unwrap_or(1);
+    // from the highest level to the lowest odd level, reverse any contiguous sequence of glyphs
+    // that are at that level or higher.
+    for level in (min_odd_level..=max_level).rev() {
+        let mut i = 0;
+        while i < visual.len() {
+            if glyphs[visual[i]].bidi_level < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < visual.len() && glyphs[visual[i]].bidi_level >= level {
+                i += 1;
+            }
+            visual[start..i].reverse();
+        }
+    }
+
+    // The offset of each glyph in relation to its logical pen position (as positioned by the
+    // shaping) must be preserved.
+    let mut pen = line.x;
+    let offsets: Vec<f32> = glyphs
+        .iter()
+        .map(|g| {
+            let offset = g.glyph.position.x - pen;
+            pen += g.width;
+            offset
+        })
+        .collect();
+    let mut pen = line.x;
+    for i in visual {
+        let glyph = &mut glyphs[i];
+        glyph.glyph.position.x = pen + offsets[i];
+        pen += glyph.width;
+    }
 }
 
 /// The layout of a single line of text. This can be break in multiple line later.
@@ -877,8 +1699,16 @@ struct LineLayout {
     line_gap: f32,
 }
 impl LineLayout {
-    /// Create a new layout for the given range of the given text.
-    fn new(text: &SpannedString, span_range: Range<usize>, fonts: &Fonts) -> Self {
+    /// Create a new layout for the given range of the given text. Each shape span is split in runs
+    /// of same embedding level, given by the levels of each byte, where the first level is of the
+    /// byte `levels_start`.
+    fn new(
+        text: &SpannedString,
+        span_range: Range<usize>,
+        levels: &[u8],
+        levels_start: usize,
+        fonts: &Fonts,
+    ) -> Self {
         let shape_spans = &text.shape_spans;
         // assert that the given SpannedString has its shape_spans already computed
         assert!(!shape_spans.is_empty());
@@ -891,8 +1721,20 @@ impl LineLayout {
         };
 
         for shape_span in &shape_spans[span_range] {
-            let text = &text.string[shape_span.byte_range.clone()];
-            this.append_run(fonts, shape_span, text, shape_span.byte_range.clone());
+            let mut start = shape_span.byte_range.start;
+            while start < shape_span.byte_range.end {
+                let level = levels[start - levels_start];
+                let end = (start..shape_span.byte_range.end)
+                    .find(|&i| levels[i - levels_start] != level)
+                    .unwrap_or(shape_span.byte_range.end);
+                let run = ShapeSpan {
+                    byte_range: start..end,
+                    ..shape_span.clone()
+                };
+                let rtl = level % 2 == 1;
+                this.append_run(fonts, &run, &text.string[start..end], start..end, rtl);
+                start = end;
+            }
         }
 
         let last_glyph = this.glyphs.last().unwrap();
@@ -907,6 +1749,7 @@ impl LineLayout {
         shape: &ShapeSpan,
         text: &str,
         byte_range: Range<usize>,
+        rtl: bool,
     ) {
         if shape.byte_range.is_empty() {
             return;
@@ -969,7 +1812,7 @@ impl LineLayout {
         let start_x = current_line.x + current_line.width;
         let start_y = current_line.y;
 
-        let glyphs = super::shaping::shape(fonts, &text, shape);
+        let glyphs = super::shaping::shape(fonts, &text, shape, rtl);
         for mut glyph in glyphs {
             glyph.glyph.position.x += start_x;
             glyph.glyph.position.y += start_y;
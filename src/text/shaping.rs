@@ -1,15 +1,29 @@
 use crate::{
-    font::Fonts,
+    font::{Fonts, Synthesis},
     text::{layout::GlyphPosition, ShapeSpan},
     Color,
 };
 
+/// The increase in the advance of each glyph, for a synthetic bold text.
+fn synthetic_bold_offset(font_size: f32, synthesis: Synthesis) -> f32 {
+    if synthesis.bold {
+        font_size * Synthesis::BOLD_OFFSET
+    } else {
+        0.0
+    }
+}
+
+/// Shape the given text. If `rtl` is true, the text is a right-to-left run. In both cases, the
+/// returned glyphs are in logical order, and positioned as if the run was left-to-right. The visual
+/// reordering is done later by the layout.
 #[cfg(feature = "complex_shaping")]
-pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPosition> {
+pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan, rtl: bool) -> Vec<GlyphPosition> {
     use ab_glyph::{point, Glyph, GlyphId};
-    use harfbuzz_rs::{shape as hb_shape, Face, Font as HbFont, UnicodeBuffer};
+    use harfbuzz_rs::{shape as hb_shape, Direction, Face, Font as HbFont, UnicodeBuffer};
 
-    let bytes = &fonts.get(style.font_id).unwrap().data;
+    let (font_id, synthesis) = fonts.resolve_style(style.font_id, style.bold, style.italic);
+    let bold_offset = synthetic_bold_offset(style.font_size, synthesis);
+    let bytes = &fonts.get(font_id).unwrap().data;
     let face = Face::from_bytes(bytes, 0);
     let font = HbFont::new(face);
     let scale = {
@@ -21,7 +35,10 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
     let cleanup_text = text.replace(|x: char| x.is_control(), " ");
     debug_assert_eq!(cleanup_text.len(), text.len());
     // let scale = style.px / ppem;
-    let buffer = UnicodeBuffer::new().add_str(&cleanup_text);
+    let direction = if rtl { Direction::Rtl } else { Direction::Ltr };
+    let buffer = UnicodeBuffer::new()
+        .add_str(&cleanup_text)
+        .set_direction(direction);
     let output = hb_shape(&font, buffer, &[]);
 
     let positions = output.get_glyph_positions();
@@ -30,12 +47,18 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
     let mut glyphs: Vec<GlyphPosition> = Vec::with_capacity(positions.len());
     let mut x = 0.0;
 
-    for (position, info) in positions.iter().zip(infos) {
+    // harfbuzz outputs right-to-left runs in visual order, so reverse them back to logical order.
+    let mut output: Vec<_> = positions.iter().zip(infos).collect();
+    if rtl {
+        output.reverse();
+    }
+
+    for (position, info) in output {
         let gid = info.codepoint;
         let cluster = info.cluster as usize;
         let x_offset = position.x_offset as f32 * scale;
         let y_offset = position.y_offset as f32 * scale;
-        let x_advance = position.x_advance as f32 * scale;
+        let x_advance = position.x_advance as f32 * scale + bold_offset;
 
         if let Some((mut last, split)) = glyphs.split_last_mut() {
             if last.byte_range.start != cluster {
@@ -62,11 +85,13 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
                 scale: style.font_size.into(),
                 position: point(x + x_offset, y_offset),
             },
-            font_id: style.font_id,
+            font_id,
+            synthesis,
             byte_range: cluster..text.len(),
             width: x_advance,
             color: Color::WHITE,
             is_whitespace,
+            bidi_level: 0,
         });
         x += x_advance;
     }
@@ -89,10 +114,17 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
 }
 
 #[cfg(not(feature = "complex_shaping"))]
-pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPosition> {
+pub(crate) fn shape(
+    fonts: &Fonts,
+    text: &str,
+    style: &ShapeSpan,
+    _rtl: bool,
+) -> Vec<GlyphPosition> {
     use ab_glyph::{point, Font as _, Glyph, ScaleFont as _};
+    let (font_id, synthesis) = fonts.resolve_style(style.font_id, style.bold, style.italic);
+    let bold_offset = synthetic_bold_offset(style.font_size, synthesis);
     let font = fonts
-        .get(style.font_id)
+        .get(font_id)
         .expect("FontId is out of bounds")
         .as_scaled(style.font_size);
 
@@ -115,7 +147,7 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
             }
         }
 
-        let advance = font.h_advance(glyph.id);
+        let advance = font.h_advance(glyph.id) + bold_offset;
         if !glyphs.is_empty() {
             let last = glyphs.last_mut().unwrap();
             last.width += font.kern(last.glyph.id, glyph.id);
@@ -133,10 +165,12 @@ pub(crate) fn shape(fonts: &Fonts, text: &str, style: &ShapeSpan) -> Vec<GlyphPo
                 position: point(x, 0.0),
             },
             font_id: font.font.id(),
+            synthesis,
             byte_range: byte_offset..byte_offset + c.len_utf8(),
             width: advance,
             color: Color::WHITE,
             is_whitespace,
+            bidi_level: 0,
         });
 
         x += advance;
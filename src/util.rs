@@ -1,5 +1,9 @@
+//! Small utilities, used by the crate, and useful for its users.
+
 use std::{cmp::Ordering, ops::Range};
 
+use crate::Color;
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -20,13 +24,45 @@ mod test {
         v.binary_search_by(|x| super::cmp_range(15, x.clone()))
             .unwrap_err();
     }
+
+    #[test]
+    fn rng() {
+        use super::Rng;
+
+        let a: Vec<u64> = (0..8)
+            .scan(Rng::new(42), |r, _| Some(r.next_u64()))
+            .collect();
+        let b: Vec<u64> = (0..8)
+            .scan(Rng::new(42), |r, _| Some(r.next_u64()))
+            .collect();
+        let c: Vec<u64> = (0..8)
+            .scan(Rng::new(43), |r, _| Some(r.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        // a zero seed still produces numbers
+        let mut rng = Rng::new(0);
+        assert!((0..8).any(|_| rng.next_u64() != 0));
+
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let x = rng.next_f32();
+            assert!((0.0..1.0).contains(&x));
+            let x = rng.range(3..10);
+            assert!((3..10).contains(&x));
+            let x = rng.range_f32(-1.0..1.0);
+            assert!((-1.0..1.0).contains(&x));
+            assert_eq!(rng.color().a, 255);
+        }
+    }
 }
 
-pub fn cmp_float(a: f32, b: f32) -> bool {
+pub(crate) fn cmp_float(a: f32, b: f32) -> bool {
     (a - b).abs() <= f32::EPSILON * a.abs().max(b.abs())
 }
 
-pub fn cmp_range(v: usize, range: Range<usize>) -> Ordering {
+pub(crate) fn cmp_range(v: usize, range: Range<usize>) -> Ordering {
     if v < range.start {
         Ordering::Greater
     } else if v < range.end {
@@ -36,7 +72,7 @@ pub fn cmp_range(v: usize, range: Range<usize>) -> Ordering {
     }
 }
 
-pub struct WithPriority<P: Ord, Item> {
+pub(crate) struct WithPriority<P: Ord, Item> {
     priority: P,
     pub item: Item,
 }
@@ -66,3 +102,72 @@ impl<P: Ord, Item> PartialEq for WithPriority<P, Item> {
         self.priority == other.priority
     }
 }
+
+/// A small seeded pseudo-random number generator, using the xorshift64* algorithm.
+///
+/// The same seed always produces the same sequence of numbers, so it can be used to generate
+/// reproducible placeholder content, like the colors of items in a demo, or the data of a test.
+/// It is not suitable for cryptography.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+impl Rng {
+    /// Create a new Rng with the given seed. Any seed is valid, including zero.
+    pub fn new(seed: u64) -> Self {
+        // scramble the seed with a step of splitmix64, so close seeds produce unrelated sequences.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        // a xorshift state of zero would only produce zeros
+        Self {
+            state: if z == 0 { 0x9e37_79b9_7f4a_7c15 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        // the higher bits have better quality
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// A number in the range `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// A number in the given range. The distribution is only approximately uniform.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn range(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "empty range");
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as u32
+    }
+
+    /// A number in the given range.
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        let x = range.start + (range.end - range.start) * self.next_f32();
+        // rounding may reach the end of the range
+        if x < range.end {
+            x
+        } else {
+            range.start
+        }
+    }
+
+    /// A fully opaque color, with random red, green and blue components.
+    pub fn color(&mut self) -> Color {
+        Color::from_u32(self.next_u32() | 0xff)
+    }
+}
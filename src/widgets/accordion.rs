@@ -0,0 +1,260 @@
+use std::any::Any;
+
+use crate::{
+    easing, Animation, AnimationId, Behaviour, Context, Id, Layout, LayoutContext, MinSizeContext,
+};
+
+/// Toggle the section with the given index of a [`Accordion`] between open and closed.
+pub struct ToggleSection(pub usize);
+
+/// Open, if true, or close, if false, the section with the given index of a [`Accordion`].
+pub struct SetSectionOpen(pub usize, pub bool);
+
+/// Sent by the animation of a section, with its current openness.
+struct SectionStep {
+    index: usize,
+    openness: f32,
+}
+
+struct SectionAnim {
+    accordion: Id,
+    index: usize,
+    from: f32,
+    to: f32,
+}
+impl Animation for SectionAnim {
+    fn on_update(&mut self, t: f32, _: f32, _: f32, ctx: &mut Context) {
+        let openness = if t == 1.0 {
+            self.to
+        } else {
+            self.from + (self.to - self.from) * easing::ease_out(t)
+        };
+        ctx.send_event_to(
+            self.accordion,
+            SectionStep {
+                index: self.index,
+                openness,
+            },
+        );
+    }
+}
+
+/// A section of a [`Accordion`].
+pub struct AccordionSection {
+    /// The header of the section. Normally a [`Button`](super::Button) that sends
+    /// [`ToggleSection`] to the Accordion.
+    pub header: Id,
+    /// The control that clips the content, while the section is opening or closing. Must have a
+    /// [`AccordionViewLayout`].
+    pub view: Id,
+    /// The content of the section. Always has the height of its min size.
+    pub content: Id,
+}
+
+struct SectionState {
+    section: AccordionSection,
+    open: bool,
+    /// Goes from 0.0, when closed, to 1.0, when open.
+    openness: f32,
+    animation: Option<AnimationId>,
+}
+
+/// A vertical stack of titled sections, where each section can be expanded and collapsed.
+///
+/// The hiearchy of controls must be the following, for each section:
+///
+/// ```text
+/// accordion : Accordion
+/// ├─ header
+/// └─ view : AccordionViewLayout
+///    └─ content
+/// ```
+///
+/// Each header is followed by the view of its section. While the section opens or closes, the
+/// height of the view is animated between 0 and the min height of the content, and the content is
+/// clipped by the view. A closed section has its view deactivated.
+pub struct Accordion {
+    sections: Vec<SectionState>,
+    /// If false, opening a section closes all other ones.
+    multiple_open: bool,
+}
+impl Accordion {
+    /// The duration, in seconds, to fully open or close a section.
+    const DURATION: f32 = 0.2;
+
+    pub fn new(sections: Vec<AccordionSection>, multiple_open: bool) -> Self {
+        Self {
+            sections: sections
+                .into_iter()
+                .map(|section| SectionState {
+                    section,
+                    open: false,
+                    openness: 0.0,
+                    animation: None,
+                })
+                .collect(),
+            multiple_open,
+        }
+    }
+
+    /// Make the section with the given index start open.
+    pub fn with_open(mut self, index: usize) -> Self {
+        if !self.multiple_open {
+            for state in &mut self.sections {
+                state.open = false;
+                state.openness = 0.0;
+            }
+        }
+        if let Some(state) = self.sections.get_mut(index) {
+            state.open = true;
+            state.openness = 1.0;
+        }
+        self
+    }
+
+    /// If true, more than one section can be open at the same time.
+    pub fn multiple_open(&self) -> bool {
+        self.multiple_open
+    }
+
+    /// Return true if the section with the given index is open, or opening.
+    pub fn is_open(&self, index: usize) -> bool {
+        self.sections.get(index).map_or(false, |x| x.open)
+    }
+
+    fn set_open(&mut self, index: usize, open: bool, this: Id, ctx: &mut Context) {
+        if index >= self.sections.len() {
+            return;
+        }
+        if open && !self.multiple_open {
+            for i in 0..self.sections.len() {
+                if i != index {
+                    self.animate(i, false, this, ctx);
+                }
+            }
+        }
+        self.animate(index, open, this, ctx);
+    }
+
+    fn animate(&mut self, index: usize, open: bool, this: Id, ctx: &mut Context) {
+        let state = &mut self.sections[index];
+        if state.open == open {
+            return;
+        }
+        state.open = open;
+        if let Some(id) = state.animation.take() {
+            ctx.remove_animation(id);
+        }
+        if open {
+            ctx.active(state.section.view);
+        }
+        let to = if open { 1.0 } else { 0.0 };
+        let length = Self::DURATION * (to - state.openness).abs();
+        if length == 0.0 {
+            return;
+        }
+        let id = ctx.add_animation(
+            length,
+            SectionAnim {
+                accordion: this,
+                index,
+                from: state.openness,
+                to,
+            },
+        );
+        state.animation = Some(id);
+    }
+}
+impl Behaviour for Accordion {
+    fn on_start(&mut self, _this: Id, ctx: &mut Context) {
+        for state in &self.sections {
+            if !state.open {
+                ctx.deactive(state.section.view);
+            }
+        }
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        for state in &mut self.sections {
+            if let Some(id) = state.animation.take() {
+                ctx.remove_animation(id);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(ToggleSection(index)) = event.downcast_ref::<ToggleSection>() {
+            let open = !self.is_open(*index);
+            self.set_open(*index, open, this, ctx);
+        } else if let Some(SetSectionOpen(index, open)) = event.downcast_ref::<SetSectionOpen>() {
+            self.set_open(*index, *open, this, ctx);
+        } else if let Some(&SectionStep { index, openness }) = event.downcast_ref::<SectionStep>() {
+            let state = match self.sections.get_mut(index) {
+                Some(x) => x,
+                None => return,
+            };
+            state.openness = openness;
+            if openness == if state.open { 1.0 } else { 0.0 } {
+                state.animation = None;
+                if !state.open {
+                    ctx.deactive(state.section.view);
+                }
+            }
+            ctx.dirty_layout(this);
+        }
+    }
+}
+impl Layout for Accordion {
+    fn compute_min_size(&mut self, _this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        let mut min_size = [0.0f32; 2];
+        for state in &self.sections {
+            let header = ctx.get_min_size(state.section.header);
+            let content = ctx.get_min_size(state.section.view);
+            min_size[0] = min_size[0].max(header[0]).max(content[0]);
+            min_size[1] += header[1] + content[1] * state.openness;
+        }
+        min_size
+    }
+
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let rect = ctx.get_rect(this);
+        let mut y = rect[1];
+        for state in &self.sections {
+            let header_height = ctx.get_min_size(state.section.header)[1];
+            ctx.set_designed_rect(
+                state.section.header,
+                [rect[0], y, rect[2], y + header_height],
+            );
+            y += header_height;
+
+            // the min height of the view is the full height of the content, but the view is smaller
+            // while the section is not fully open.
+            let content_height = ctx.get_min_size(state.section.view)[1];
+            let view_height = content_height * state.openness;
+            ctx.set_rect(state.section.view, [rect[0], y, rect[2], y + view_height]);
+            y += view_height;
+        }
+    }
+}
+
+/// The layout of the view of a [`AccordionSection`]. Its min size is the min size of the content,
+/// and the content is laid out at the top of the view, with the height of its min size, even if
+/// the view is smaller.
+#[derive(Default)]
+pub struct AccordionViewLayout;
+impl Layout for AccordionViewLayout {
+    fn compute_min_size(&mut self, this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        match ctx.get_active_children(this).get(0) {
+            Some(&content) => ctx.get_min_size(content),
+            None => [0.0; 2],
+        }
+    }
+
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let rect = ctx.get_rect(this);
+        for content in ctx.get_active_children(this) {
+            let height = ctx.get_min_size(content)[1];
+            ctx.set_designed_rect(content, [rect[0], rect[1], rect[2], rect[1] + height]);
+        }
+    }
+}
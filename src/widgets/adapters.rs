@@ -1,4 +1,9 @@
-use crate::{Behaviour, Context, Id, KeyboardEvent};
+use std::{any::Any, path::Path};
+
+use crate::{
+    accessibility::{AccessValue, Role},
+    Behaviour, Context, Id, ImeEvent, KeyboardEvent,
+};
 
 pub struct OnKeyboardEvent<F, B: Behaviour>
 where
@@ -55,8 +60,12 @@ where
         self.extends.on_event(event, this, ctx)
     }
 
-    fn on_scroll_event(&mut self, delta: [f32; 2], this: Id, ctx: &mut Context) {
-        self.extends.on_scroll_event(delta, this, ctx)
+    fn on_scroll_event(&mut self, scroll: crate::ScrollInfo, this: Id, ctx: &mut Context) {
+        self.extends.on_scroll_event(scroll, this, ctx)
+    }
+
+    fn on_gesture(&mut self, gesture: crate::GestureInfo, this: Id, ctx: &mut Context) {
+        self.extends.on_gesture(gesture, this, ctx)
     }
 
     fn on_mouse_event(&mut self, mouse: crate::MouseInfo, this: Id, ctx: &mut Context) {
@@ -70,4 +79,50 @@ where
     fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
         self.extends.on_keyboard_event(event, this, ctx) || (self.on_keyboard)(event, this, ctx)
     }
+
+    fn on_ime_event(&mut self, event: ImeEvent, this: Id, ctx: &mut Context) -> bool {
+        self.extends.on_ime_event(event, this, ctx)
+    }
+
+    fn on_drag_over(
+        &mut self,
+        payload: &dyn Any,
+        position: [f32; 2],
+        this: Id,
+        ctx: &mut Context,
+    ) -> bool {
+        self.extends.on_drag_over(payload, position, this, ctx)
+    }
+
+    fn on_drag_exit(&mut self, this: Id, ctx: &mut Context) {
+        self.extends.on_drag_exit(this, ctx)
+    }
+
+    fn on_drop(&mut self, payload: Box<dyn Any>, position: [f32; 2], this: Id, ctx: &mut Context) {
+        self.extends.on_drop(payload, position, this, ctx)
+    }
+
+    fn on_drag_end(&mut self, dropped: bool, this: Id, ctx: &mut Context) {
+        self.extends.on_drag_end(dropped, this, ctx)
+    }
+
+    fn on_file_hover(&mut self, path: &Path, this: Id, ctx: &mut Context) -> bool {
+        self.extends.on_file_hover(path, this, ctx)
+    }
+
+    fn on_file_hover_exit(&mut self, this: Id, ctx: &mut Context) {
+        self.extends.on_file_hover_exit(this, ctx)
+    }
+
+    fn on_file_drop(&mut self, path: &Path, this: Id, ctx: &mut Context) {
+        self.extends.on_file_drop(path, this, ctx)
+    }
+
+    fn accessibility_role(&self) -> Role {
+        self.extends.accessibility_role()
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        self.extends.accessibility_value()
+    }
 }
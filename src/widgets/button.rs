@@ -1,15 +1,62 @@
-use std::rc::Rc;
+use std::{any::Any, rc::Rc, time::Duration};
+
+use winit::window::CursorIcon;
 
 use crate::{
-    style::ButtonStyle, Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    accessibility::Role,
+    event::{SetEnabled, SetStyle},
+    graphics::Graphic,
+    style::ButtonStyle,
+    time::Instant,
+    Animation, AnimationId, Behaviour, Color, Context, Id, InputFlags, MouseButton, MouseEvent,
+    MouseInfo,
 };
 
+/// Scheduled when a [`Button`] is pressed, to detect a long press.
+struct LongPress;
+
+/// Scheduled while a [`Button`] with auto-repeat is held.
+struct RepeatClick;
+
+/// Interpolate the color of the graphic of a [`Button`], when changing between states.
+struct TintTransition {
+    id: Id,
+    from: Color,
+    to: Color,
+}
+impl Animation for TintTransition {
+    fn on_update(&mut self, t: f32, _: f32, _: f32, ctx: &mut Context) {
+        let color = if t == 1.0 {
+            self.to
+        } else {
+            self.from.lerp(self.to, t)
+        };
+        ctx.get_graphic_mut(self.id).set_color(color);
+    }
+}
+
 pub struct Button<F: FnMut(Id, &mut Context)> {
     normal: bool,
     focusable: bool,
+    disabled: bool,
     focus: bool,
     on_click: F,
     style: Rc<ButtonStyle>,
+    /// The time the button must be held to be a long press, and its callback.
+    long_press: Option<(Duration, Box<dyn FnMut(Id, &mut Context)>)>,
+    /// The delay before the auto-repeat starts, and the interval between each click.
+    auto_repeat: Option<(Duration, Duration)>,
+    /// true if the current press already fired a long press or a auto-repeat click, so the release
+    /// must not fire a click.
+    consumed: bool,
+    /// The event_id of the scheduled LongPress or RepeatClick event, if any.
+    scheduled: Option<u64>,
+    on_right_click: Option<Box<dyn FnMut(Id, &mut Context)>>,
+    on_middle_click: Option<Box<dyn FnMut(Id, &mut Context)>>,
+    /// The duration, in seconds, of the cross-fade between the graphics of each state, if any.
+    transition: Option<f32>,
+    /// The currently running TintTransition, if any.
+    animation: Option<AnimationId>,
 }
 impl<F: FnMut(Id, &mut Context)> Button<F> {
     pub fn new(style: Rc<ButtonStyle>, focusable: bool, on_click: F) -> Self {
@@ -17,17 +64,179 @@ impl<F: FnMut(Id, &mut Context)> Button<F> {
             normal: true,
             focus: false,
             focusable,
+            disabled: false,
             on_click,
             style,
+            long_press: None,
+            auto_repeat: None,
+            consumed: false,
+            scheduled: None,
+            on_right_click: None,
+            on_middle_click: None,
+            transition: None,
+            animation: None,
+        }
+    }
+
+    /// Call `on_right_click` when the button is clicked with the right mouse button. Useful for
+    /// context actions.
+    pub fn with_right_click<R: FnMut(Id, &mut Context) + 'static>(
+        mut self,
+        on_right_click: R,
+    ) -> Self {
+        self.on_right_click = Some(Box::new(on_right_click));
+        self
+    }
+
+    /// Call `on_middle_click` when the button is clicked with the middle mouse button.
+    pub fn with_middle_click<M: FnMut(Id, &mut Context) + 'static>(
+        mut self,
+        on_middle_click: M,
+    ) -> Self {
+        self.on_middle_click = Some(Box::new(on_middle_click));
+        self
+    }
+
+    /// Call `on_long_press` when the button is held for the given duration. A long press does
+    /// not fire a click when released.
+    pub fn with_long_press<L: FnMut(Id, &mut Context) + 'static>(
+        mut self,
+        duration: Duration,
+        on_long_press: L,
+    ) -> Self {
+        self.long_press = Some((duration, Box::new(on_long_press)));
+        self
+    }
+
+    /// Fire the click callback as soon as the button is pressed, and then repeatedly, every
+    /// `interval`, while it is held, starting after `delay`. The release does not fire a click.
+    ///
+    /// This takes precedence over [`Button::with_long_press`].
+    pub fn with_auto_repeat(mut self, delay: Duration, interval: Duration) -> Self {
+        self.auto_repeat = Some((delay, interval));
+        self
+    }
+
+    /// Cross-fade the color of the graphic, over the given duration, when changing between the
+    /// states of the [`ButtonStyle`].
+    ///
+    /// This only happens when the current and the new graphic only differ by their color (see
+    /// [`Graphic::same_shape`]), like tinted textures or panels. Otherwise, the graphic is swapped
+    /// instantly.
+    pub fn with_transition(mut self, duration: Duration) -> Self {
+        self.transition = Some(duration.as_secs_f32());
+        self
+    }
+
+    fn set_graphic(&mut self, graphic: Graphic, this: Id, ctx: &mut Context) {
+        self.cancel_transition(ctx);
+        let length = match self.transition {
+            Some(x) if x > 0.0 => x,
+            _ => {
+                ctx.set_graphic(this, graphic);
+                return;
+            }
+        };
+        let current = ctx.get_graphic_mut(this);
+        if !current.same_shape(&graphic) {
+            ctx.set_graphic(this, graphic);
+            return;
+        }
+        let from = current.get_color();
+        let to = graphic.get_color();
+        if from == to {
+            return;
+        }
+        let id = ctx.add_animation(length, TintTransition { id: this, from, to });
+        self.animation = Some(id);
+    }
+
+    fn cancel_transition(&mut self, ctx: &mut Context) {
+        if let Some(id) = self.animation.take() {
+            ctx.remove_animation(id);
+        }
+    }
+
+    fn cancel_scheduled(&mut self, ctx: &mut Context) {
+        if let Some(event_id) = self.scheduled.take() {
+            ctx.cancel_scheduled_event(event_id);
         }
     }
 }
 impl<F: FnMut(Id, &mut Context)> Behaviour for Button<F> {
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        if ctx.get_hover_cursor(this).is_none() {
+            ctx.set_hover_cursor(this, Some(CursorIcon::Hand));
+        }
+    }
+
     fn on_active(&mut self, this: Id, ctx: &mut Context) {
-        ctx.set_graphic(this, self.style.normal.clone());
+        self.cancel_transition(ctx);
+        if self.disabled {
+            ctx.set_graphic(this, self.style.disabled_graphic());
+        } else {
+            ctx.set_graphic(this, self.style.normal.clone());
+        }
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.cancel_scheduled(ctx);
+        self.cancel_transition(ctx);
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.cancel_scheduled(ctx);
+        self.cancel_transition(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetEnabled(enabled)) = event.downcast_ref() {
+            self.disabled = !enabled;
+            self.normal = true;
+            self.cancel_scheduled(ctx);
+            if self.disabled {
+                ctx.lose_focus(this);
+                self.set_graphic(self.style.disabled_graphic(), this, ctx);
+            } else if self.focus {
+                self.set_graphic(self.style.focus.clone(), this, ctx);
+            } else {
+                self.set_graphic(self.style.normal.clone(), this, ctx);
+            }
+        } else if let Some(SetStyle(style)) = event.downcast_ref::<SetStyle<ButtonStyle>>() {
+            self.style = style.clone();
+            self.cancel_transition(ctx);
+            if self.disabled {
+                ctx.set_graphic(this, self.style.disabled_graphic());
+            } else if self.focus {
+                ctx.set_graphic(this, self.style.focus.clone());
+            } else {
+                ctx.set_graphic(this, self.style.normal.clone());
+            }
+        } else if event.is::<LongPress>() {
+            self.scheduled = None;
+            self.consumed = true;
+            if let Some((_, on_long_press)) = &mut self.long_press {
+                on_long_press(this, ctx);
+            }
+        } else if event.is::<RepeatClick>() {
+            self.scheduled = None;
+            if let Some((_, interval)) = self.auto_repeat {
+                (self.on_click)(this, ctx);
+                let event_id =
+                    ctx.send_event_to_scheduled(this, RepeatClick, Instant::now() + interval);
+                self.scheduled = Some(event_id);
+            }
+        }
     }
 
     fn input_flags(&self) -> InputFlags {
+        if self.disabled {
+            return InputFlags::empty();
+        }
         let mut flags = InputFlags::MOUSE;
         if self.focusable {
             flags |= InputFlags::FOCUS
@@ -37,27 +246,52 @@ impl<F: FnMut(Id, &mut Context)> Behaviour for Button<F> {
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
         use MouseButton::*;
-        if mouse.click() {
+        if mouse.click() && !self.consumed {
             (self.on_click)(this, ctx);
         }
+        if mouse.right_click() {
+            if let Some(on_right_click) = &mut self.on_right_click {
+                on_right_click(this, ctx);
+            }
+        }
+        if mouse.middle_click() {
+            if let Some(on_middle_click) = &mut self.on_middle_click {
+                on_middle_click(this, ctx);
+            }
+        }
         match mouse.event {
             MouseEvent::Enter => {
                 self.normal = false;
-                ctx.set_graphic(this, self.style.hover.clone());
+                self.set_graphic(self.style.hover.clone(), this, ctx);
             }
             MouseEvent::Exit => {
                 self.normal = true;
+                self.cancel_scheduled(ctx);
                 if self.focus {
-                    ctx.set_graphic(this, self.style.focus.clone());
+                    self.set_graphic(self.style.focus.clone(), this, ctx);
                 } else {
-                    ctx.set_graphic(this, self.style.normal.clone());
+                    self.set_graphic(self.style.normal.clone(), this, ctx);
                 }
             }
             MouseEvent::Down(Left) => {
-                ctx.set_graphic(this, self.style.pressed.clone());
+                self.set_graphic(self.style.pressed.clone(), this, ctx);
+                self.cancel_scheduled(ctx);
+                self.consumed = false;
+                if let Some((delay, _)) = self.auto_repeat {
+                    self.consumed = true;
+                    (self.on_click)(this, ctx);
+                    let event_id =
+                        ctx.send_event_to_scheduled(this, RepeatClick, Instant::now() + delay);
+                    self.scheduled = Some(event_id);
+                } else if let Some((duration, _)) = self.long_press {
+                    let event_id =
+                        ctx.send_event_to_scheduled(this, LongPress, Instant::now() + duration);
+                    self.scheduled = Some(event_id);
+                }
             }
             MouseEvent::Up(Left) => {
-                ctx.set_graphic(this, self.style.hover.clone());
+                self.cancel_scheduled(ctx);
+                self.set_graphic(self.style.hover.clone(), this, ctx);
             }
             _ => {}
         }
@@ -65,11 +299,11 @@ impl<F: FnMut(Id, &mut Context)> Behaviour for Button<F> {
 
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
         self.focus = focus;
-        if self.normal {
+        if self.normal && !self.disabled {
             if focus {
-                ctx.set_graphic(this, self.style.focus.clone());
+                self.set_graphic(self.style.focus.clone(), this, ctx);
             } else {
-                ctx.set_graphic(this, self.style.normal.clone());
+                self.set_graphic(self.style.normal.clone(), this, ctx);
             }
         }
     }
@@ -3,12 +3,14 @@ use std::{any::Any, rc::Rc};
 use crate::{
     layouts::VBoxLayout,
     style::MenuStyle,
-    widgets::{Blocker, CloseMenu, ItemClicked, Menu, MenuBehaviour},
+    widgets::{menu::PlaceMenu, Blocker, CloseMenu, ItemClicked, Menu, MenuBehaviour},
     Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
 };
 
-struct Repos;
-
+/// Open a [`Menu`] at the mouse position, when the control is right clicked.
+///
+/// Items of type [`Item::SubMenu`](super::Item::SubMenu) open their menu to the right of the item,
+/// when hovered. Any menu that would overflow the root control is flipped to the other side.
 pub struct ContextMenu {
     menu: Rc<Menu>,
     open: Option<Id>,
@@ -43,25 +45,6 @@ impl Behaviour for ContextMenu {
                 ctx.remove(menu);
                 ctx.deactive(self.blocker.unwrap());
             }
-        } else if event.is::<Repos>() {
-            if let Some(menu) = self.open {
-                let desktop = ctx.get_rect(ctx.get_parent(menu).unwrap());
-
-                let menu_rect = ctx.get_rect(menu);
-                let width = menu_rect[2] - menu_rect[0];
-                let height = menu_rect[3] - menu_rect[1];
-
-                let mut margins = ctx.get_margins(menu);
-                if menu_rect[2] > desktop[2] && menu_rect[0] - width > 0.0 {
-                    margins[0] -= width;
-                    margins[2] = margins[0];
-                }
-                if menu_rect[3] > desktop[3] && menu_rect[1] - height > 0.0 {
-                    margins[1] -= height;
-                    margins[3] = margins[1];
-                }
-                ctx.set_margins(menu, margins);
-            }
         }
     }
 
@@ -76,6 +59,9 @@ impl Behaviour for ContextMenu {
             MouseEvent::Up(Right) => {
                 if self.open.is_none() {
                     let [x, y] = mouse.pos;
+                    // the blocker must be behind the menu and its submenus.
+                    ctx.move_to_front(self.blocker.unwrap());
+                    ctx.active(self.blocker.unwrap());
 
                     let menu = ctx
                         .create_control()
@@ -90,10 +76,10 @@ impl Behaviour for ContextMenu {
                         .layout(VBoxLayout::new(0.0, [0.0, 0.0, 0.0, 0.0], -1))
                         .build(ctx);
                     self.open = Some(menu);
-                    // when 'this' receive the event 'Repos', the 'menu' will already have its size defined.
-                    ctx.send_event_to(this, Repos);
-                    ctx.move_to_front(self.blocker.unwrap());
-                    ctx.active(self.blocker.unwrap());
+                    // when 'menu' receive this event, it will already have its size defined.
+                    let root = ctx.get_rect(Id::ROOT_ID);
+                    let from = [x - root[0], y - root[1], x - root[0], y - root[1]];
+                    ctx.send_event_to(menu, PlaceMenu { from, side: true });
                 }
             }
             _ => {}
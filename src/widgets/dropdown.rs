@@ -1,7 +1,11 @@
 use std::{any::Any, rc::Rc};
 
+use winit::event::VirtualKeyCode;
+
+use super::{ScrollToView, TextFieldCallback};
 use crate::{
-    style::ButtonStyle, Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    accessibility::Role, event::SetValue, style::ButtonStyle, Behaviour, Context, Id, InputFlags,
+    KeyboardEvent, MouseButton, MouseEvent, MouseInfo,
 };
 
 pub struct SetSelected(pub usize);
@@ -9,13 +13,31 @@ struct SetIndex(usize);
 // struct SetOwner(Id);
 // struct SetItens<T: 'static + Clone>(Vec<T>);
 // struct SetFocus(usize);
-struct ShowMenu<T: 'static + Clone>(pub Id, pub Option<usize>, pub Vec<T>);
+/// Sent by a [`Dropdown`] or [`MultiDropdown`] to open the [`DropMenu`].
+struct ShowMenu<T: 'static + Clone> {
+    owner: Id,
+    selected: Option<usize>,
+    itens: Vec<T>,
+    /// If true, the items can be filtered by typing.
+    filtering: bool,
+    /// The checked state of each item, if the menu is multi-select.
+    checked: Option<Vec<bool>>,
+}
 pub struct CloseMenu;
 #[derive(Clone, Copy)]
 struct ItemClicked {
     pub index: usize,
 }
 struct MenuClosed;
+/// Sent by the [`DropMenu`] to a [`MenuItem`] when it is highlighted, or unhighlighted, by the
+/// keyboard.
+struct SetHighlight(bool);
+/// Sent by the [`DropMenuFilter`] when the filter query changes.
+struct SetFilter(String);
+/// Select the highlighted item of the [`DropMenu`].
+struct SubmitFilter;
+/// Sent by the [`DropMenu`] to a [`MenuItem`] to set its checkbox, in a multi-select menu.
+struct SetChecked(bool);
 
 pub struct MenuItem {
     index: usize,
@@ -23,6 +45,9 @@ pub struct MenuItem {
     menu: Id,
     style: Rc<ButtonStyle>,
     focus: bool,
+    highlight: bool,
+    /// The check mark of the item, in a multi-select menu.
+    check: Option<Id>,
 }
 impl MenuItem {
     pub fn new(menu: Id, style: Rc<ButtonStyle>) -> Self {
@@ -32,17 +57,54 @@ impl MenuItem {
             menu,
             style,
             focus: false,
+            highlight: false,
+            check: None,
+        }
+    }
+
+    /// Set the control that marks the item as checked, in a multi-select [`DropMenu`]. It is
+    /// deactivated while the item is unchecked.
+    pub fn with_check(mut self, check: Id) -> Self {
+        self.check = Some(check);
+        self
+    }
+
+    fn set_rest_graphic(&self, this: Id, ctx: &mut Context) {
+        if self.highlight {
+            ctx.set_graphic(this, self.style.hover.clone());
+        } else if self.focus {
+            ctx.set_graphic(this, self.style.focus.clone());
+        } else {
+            ctx.set_graphic(this, self.style.normal.clone());
         }
     }
 }
 impl Behaviour for MenuItem {
+    fn accessibility_role(&self) -> Role {
+        Role::MenuItem
+    }
+
     fn on_active(&mut self, this: Id, ctx: &mut Context) {
-        ctx.set_graphic(this, self.style.normal.clone());
+        // the item may already be highlighted, or focused, when the menu is opened
+        self.set_rest_graphic(this, ctx);
     }
 
-    fn on_event(&mut self, event: Box<dyn Any>, _this: Id, _ctx: &mut Context) {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
         if let Some(SetIndex(index)) = event.downcast_ref() {
             self.index = *index;
+        } else if let Some(SetHighlight(highlight)) = event.downcast_ref() {
+            self.highlight = *highlight;
+            if self.state == 0 {
+                self.set_rest_graphic(this, ctx);
+            }
+        } else if let Some(SetChecked(checked)) = event.downcast_ref() {
+            if let Some(check) = self.check {
+                if *checked {
+                    ctx.active(check);
+                } else {
+                    ctx.deactive(check);
+                }
+            }
         }
     }
 
@@ -59,11 +121,7 @@ impl Behaviour for MenuItem {
             }
             MouseEvent::Exit => {
                 self.state = 0;
-                if self.focus {
-                    ctx.set_graphic(this, self.style.focus.clone());
-                } else {
-                    ctx.set_graphic(this, self.style.normal.clone());
-                }
+                self.set_rest_graphic(this, ctx);
             }
             MouseEvent::Down(Left) => {
                 self.state = 2;
@@ -83,11 +141,7 @@ impl Behaviour for MenuItem {
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
         self.focus = focus;
         if self.state == 0 {
-            if focus {
-                ctx.set_graphic(this, self.style.focus.clone());
-            } else {
-                ctx.set_graphic(this, self.style.normal.clone());
-            }
+            self.set_rest_graphic(this, ctx);
         }
     }
 }
@@ -112,6 +166,29 @@ impl<F: FnMut(Id, &mut Context)> Behaviour for Blocker<F> {
     }
 }
 
+/// The [`TextFieldCallback`] of the filter text field of a [`DropMenu`].
+///
+/// Each change of the text filters the items of the menu, and Enter selects the highlighted item.
+pub struct DropMenuFilter {
+    menu: Id,
+}
+impl DropMenuFilter {
+    pub fn new(menu: Id) -> Self {
+        Self { menu }
+    }
+}
+impl TextFieldCallback for DropMenuFilter {
+    fn on_submit(&mut self, _this: Id, ctx: &mut Context, _text: &mut String) {
+        ctx.send_event_to(self.menu, SubmitFilter);
+    }
+
+    fn on_change(&mut self, _this: Id, ctx: &mut Context, text: &str) {
+        ctx.send_event_to(self.menu, SetFilter(text.to_owned()));
+    }
+
+    fn on_unfocus(&mut self, _: Id, _: &mut Context, _: &mut String) {}
+}
+
 pub struct DropMenu<T, F>
 where
     T: 'static + Clone,
@@ -119,6 +196,21 @@ where
 {
     blocker: Id,
     list: Vec<T>,
+    /// The index in `list` of each visible item, in order.
+    visible: Vec<usize>,
+    /// The controls of each visible item, in order.
+    items: Vec<Id>,
+    /// The index in `items` of the item highlighted by the keyboard.
+    highlighted: Option<usize>,
+    /// A ScrollView that contains the items, if any.
+    scroll_view: Option<Id>,
+    /// The text field used for filtering the items, and the function that tests if a item
+    /// matches the query.
+    filter: Option<(Id, Box<dyn Fn(&T, &str) -> bool>)>,
+    /// The current filter query, in lowercase. Empty if the items are not being filtered.
+    query: String,
+    /// The checked state of each item in `list`, if the menu is multi-select.
+    checked: Option<Vec<bool>>,
     create_item: F,
     owner: Id,
 }
@@ -131,11 +223,74 @@ where
         Self {
             blocker,
             list: Vec::new(),
+            visible: Vec::new(),
+            items: Vec::new(),
+            highlighted: None,
+            scroll_view: None,
+            filter: None,
+            query: String::new(),
+            checked: None,
             create_item,
             owner: crate::Id::ROOT_ID,
         }
     }
 
+    /// Set the ScrollView that contains the items, to scroll the highlighted item into view.
+    pub fn with_scroll_view(mut self, scroll_view: Id) -> Self {
+        self.scroll_view = Some(scroll_view);
+        self
+    }
+
+    /// Set a text field for filtering the items, when opened by a [`Dropdown`] with filtering
+    /// enabled. Only the items whose text contains the text of the field, case-insensitively,
+    /// are shown.
+    ///
+    /// `text_field` must be a `TextField<DropMenuFilter>` descendant of this DropMenu, and it is
+    /// deactivated when not filtering.
+    pub fn with_filter(mut self, text_field: Id) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        let matches = |item: &T, query: &str| item.to_string().to_lowercase().contains(query);
+        self.filter = Some((text_field, Box::new(matches)));
+        self
+    }
+
+    fn set_highlighted(&mut self, index: Option<usize>, ctx: &mut Context) {
+        if let Some(&id) = self.highlighted.and_then(|i| self.items.get(i)) {
+            ctx.send_event_to(id, SetHighlight(false));
+        }
+        self.highlighted = index;
+        if let Some(&id) = index.and_then(|i| self.items.get(i)) {
+            ctx.send_event_to(id, SetHighlight(true));
+            if let Some(scroll_view) = self.scroll_view {
+                ctx.send_event_to(scroll_view, ScrollToView { id, margin: 0.0 });
+            }
+        }
+    }
+
+    /// Recreate the controls of the items that match the current query.
+    fn rebuild_items(&mut self, this: Id, ctx: &mut Context) {
+        for item in self.items.drain(..) {
+            ctx.remove(item);
+        }
+        self.highlighted = None;
+        self.visible = match &self.filter {
+            Some((_, matches)) if !self.query.is_empty() => (0..self.list.len())
+                .filter(|&i| matches(&self.list[i], &self.query))
+                .collect(),
+            _ => (0..self.list.len()).collect(),
+        };
+        for &i in &self.visible {
+            let id = (self.create_item)(&self.list[i], this, ctx);
+            ctx.send_event_to(id, SetIndex(i));
+            if let Some(checked) = &self.checked {
+                ctx.send_event_to(id, SetChecked(checked[i]));
+            }
+            self.items.push(id);
+        }
+    }
+
     pub fn close(&self, this: Id, ctx: &mut Context) {
         ctx.deactive(this);
         ctx.deactive(self.blocker);
@@ -148,41 +303,120 @@ where
     F: Fn(&T, Id, &mut Context) -> Id,
 {
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
-        if let Some(ShowMenu(owner, selected, itens)) = event.downcast_ref() {
+        if let Some(ShowMenu {
+            owner,
+            selected,
+            itens,
+            filtering,
+            checked,
+        }) = event.downcast_ref()
+        {
             // set owner
             self.owner = *owner;
-            // set focus
-            if selected.is_none() {
-                ctx.set_focus(this);
-            }
             // set itens
-            for child in ctx.get_active_children(this) {
-                ctx.remove(child);
-            }
-            for (i, item) in itens.iter().enumerate() {
-                let id = (self.create_item)(item, this, ctx);
-                ctx.send_event_to(id, SetIndex(i));
-                // set focus
-                if let Some(index) = selected {
-                    if *index == i {
-                        ctx.set_focus(id);
-                    }
-                }
-            }
             self.list = itens.clone();
-            // active blocker
+            self.checked = checked.clone();
+            self.query.clear();
+            self.rebuild_items(this, ctx);
+            // the menu must be active before focusing it, or one of its items
             ctx.active(this);
             ctx.active(self.blocker);
             ctx.move_to_front(self.blocker);
             ctx.move_to_front(this);
+            match &self.filter {
+                Some(&(text_field, _)) if *filtering => {
+                    ctx.active(text_field);
+                    ctx.send_event_to(text_field, SetValue(String::new()));
+                    ctx.set_focus(text_field);
+                }
+                filter => {
+                    if let Some(&(text_field, _)) = filter {
+                        ctx.deactive(text_field);
+                    }
+                    // set focus
+                    match selected.and_then(|i| self.items.get(i)) {
+                        Some(&id) => ctx.set_focus(id),
+                        None => ctx.set_focus(this),
+                    }
+                }
+            }
+            self.set_highlighted(*selected, ctx);
+        } else if let Some(SetFilter(query)) = event.downcast_ref::<SetFilter>() {
+            self.query = query.to_lowercase();
+            self.rebuild_items(this, ctx);
+            let first = if self.items.is_empty() { None } else { Some(0) };
+            self.set_highlighted(first, ctx);
+        } else if event.is::<SubmitFilter>() {
+            if let Some(&index) = self.highlighted.and_then(|i| self.visible.get(i)) {
+                ctx.send_event_to(this, ItemClicked { index });
+            }
         } else if event.is::<CloseMenu>() {
             self.close(this, ctx);
         } else if let Some(x) = event.downcast_ref::<ItemClicked>() {
             ctx.send_event_to(self.owner, *x);
-            self.close(this, ctx);
+            match &mut self.checked {
+                // in a multi-select menu, clicking a item only toggles it.
+                Some(checked) => {
+                    checked[x.index] = !checked[x.index];
+                    let pos = self.visible.iter().position(|&i| i == x.index);
+                    if let Some(&id) = pos.and_then(|i| self.items.get(i)) {
+                        ctx.send_event_to(id, SetChecked(checked[x.index]));
+                    }
+                }
+                None => self.close(this, ctx),
+            }
         } else {
         }
     }
+
+    fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
+        let len = self.items.len();
+        match event {
+            KeyboardEvent::Pressed(VirtualKeyCode::Up) if len > 0 => {
+                let index = match self.highlighted {
+                    Some(i) if i > 0 => i - 1,
+                    Some(i) => i,
+                    None => len - 1,
+                };
+                self.set_highlighted(Some(index), ctx);
+                true
+            }
+            KeyboardEvent::Pressed(VirtualKeyCode::Down) if len > 0 => {
+                let index = match self.highlighted {
+                    Some(i) => (i + 1).min(len - 1),
+                    None => 0,
+                };
+                self.set_highlighted(Some(index), ctx);
+                true
+            }
+            KeyboardEvent::Pressed(VirtualKeyCode::Return) => {
+                ctx.send_event_to(this, SubmitFilter);
+                true
+            }
+            KeyboardEvent::Pressed(VirtualKeyCode::Escape) => {
+                ctx.send_event_to(this, CloseMenu);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Place the menu below its owner, and open it.
+fn show_menu<T: 'static + Clone>(menu: Id, show: ShowMenu<T>, ctx: &mut Context) {
+    let size = {
+        let root = ctx.get_rect(Id::ROOT_ID);
+        let rect = ctx.get_rect(show.owner);
+        [
+            rect[0] - root[0],
+            rect[1] - root[1],
+            rect[2] - root[0],
+            rect[3] - root[1],
+        ]
+    };
+    ctx.set_anchors(menu, [0.0, 0.0, 0.0, 0.0]);
+    ctx.set_margins(menu, [size[0], size[3], size[2], size[3]]);
+    ctx.send_event_to(menu, show);
 }
 
 pub struct Dropdown<T, F>
@@ -198,6 +432,8 @@ where
     focus: bool,
     on_select: F,
     opened: bool,
+    /// If true, the items of the menu can be filtered by typing.
+    filtering: bool,
 }
 impl<T, F> Dropdown<T, F>
 where
@@ -220,14 +456,26 @@ where
             focus: false,
             on_select,
             opened: false,
+            filtering: false,
         }
     }
+
+    /// If true, opening the dropdown also reveals the filter text field of the [`DropMenu`], and
+    /// typing filters its items. The default is false.
+    pub fn with_filtering(mut self, filtering: bool) -> Self {
+        self.filtering = filtering;
+        self
+    }
 }
 impl<T, F> Behaviour for Dropdown<T, F>
 where
     T: 'static + Clone,
     F: Fn((usize, T), Id, &mut Context),
 {
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
     fn on_active(&mut self, this: Id, ctx: &mut Context) {
         ctx.set_graphic(this, self.style.normal.clone());
     }
@@ -272,22 +520,201 @@ where
                 if self.state == 2 {
                     if !self.opened {
                         self.opened = true;
-                        // ctx.active(self.menu);
-                        let size = {
-                            let root = ctx.get_rect(Id::ROOT_ID);
-                            let rect = ctx.get_rect(this);
-                            [
-                                rect[0] - root[0],
-                                rect[1] - root[1],
-                                rect[2] - root[0],
-                                rect[3] - root[1],
-                            ]
-                        };
-                        ctx.set_anchors(self.menu, [0.0, 0.0, 0.0, 0.0]);
-                        ctx.set_margins(self.menu, [size[0], size[3], size[2], size[3]]);
-                        ctx.send_event_to(
+                        show_menu(
+                            self.menu,
+                            ShowMenu {
+                                owner: this,
+                                selected: self.selected,
+                                itens: self.itens.clone(),
+                                filtering: self.filtering,
+                                checked: None,
+                            },
+                            ctx,
+                        );
+                    } else {
+                        self.opened = false;
+                        ctx.deactive(self.menu);
+                    }
+                }
+                self.state = 1;
+                ctx.set_graphic(this, self.style.hover.clone());
+            }
+            _ => {}
+        }
+    }
+
+    fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
+        self.focus = focus;
+        if self.state == 0 {
+            if focus {
+                ctx.set_graphic(this, self.style.focus.clone());
+            } else {
+                ctx.set_graphic(this, self.style.normal.clone());
+            }
+        }
+    }
+}
+
+/// A dropdown where any number of items can be selected.
+///
+/// Clicking a item of the [`DropMenu`] toggles it, without closing the menu. The items should be
+/// [`MenuItem`]s with a check mark, see [`MenuItem::with_check`]. The `label` control must have a
+/// Text graphic, that is set to a summary of the selected items: the items separated by commas, or
+/// "N selected", if there are too many. Use [`SetValue<Vec<usize>>`](SetValue) to set the
+/// selected items.
+pub struct MultiDropdown<T, F>
+where
+    T: 'static + Clone + std::fmt::Display,
+    F: Fn(&[usize], Id, &mut Context),
+{
+    itens: Vec<T>,
+    checked: Vec<bool>,
+    menu: Id,
+    label: Id,
+    state: u8,
+    style: Rc<ButtonStyle>,
+    focus: bool,
+    on_change: F,
+    opened: bool,
+    filtering: bool,
+}
+impl<T, F> MultiDropdown<T, F>
+where
+    T: 'static + Clone + std::fmt::Display,
+    F: Fn(&[usize], Id, &mut Context),
+{
+    /// The maximum number of items that are listed in the label.
+    const MAX_SUMMARY_ITEMS: usize = 3;
+
+    pub fn new(
+        itens: Vec<T>,
+        initial_selected: &[usize],
+        menu: Id,
+        label: Id,
+        on_change: F,
+        style: Rc<ButtonStyle>,
+    ) -> Self {
+        let mut checked = vec![false; itens.len()];
+        for &i in initial_selected {
+            if let Some(x) = checked.get_mut(i) {
+                *x = true;
+            }
+        }
+        Self {
+            itens,
+            checked,
+            menu,
+            label,
+            state: 0,
+            style,
+            focus: false,
+            on_change,
+            opened: false,
+            filtering: false,
+        }
+    }
+
+    /// If true, opening the dropdown also reveals the filter text field of the [`DropMenu`], and
+    /// typing filters its items. The default is false.
+    pub fn with_filtering(mut self, filtering: bool) -> Self {
+        self.filtering = filtering;
+        self
+    }
+
+    /// The indices of the selected items, in increasing order.
+    pub fn selected(&self) -> Vec<usize> {
+        (0..self.checked.len())
+            .filter(|&i| self.checked[i])
+            .collect()
+    }
+
+    fn update_label(&self, ctx: &mut Context) {
+        let selected = self.selected();
+        let text = if selected.len() > Self::MAX_SUMMARY_ITEMS {
+            format!("{} selected", selected.len())
+        } else {
+            selected
+                .iter()
+                .map(|&i| self.itens[i].to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        ctx.get_graphic_mut(self.label).set_text(&text);
+    }
+}
+impl<T, F> Behaviour for MultiDropdown<T, F>
+where
+    T: 'static + Clone + std::fmt::Display,
+    F: Fn(&[usize], Id, &mut Context),
+{
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn on_start(&mut self, _this: Id, ctx: &mut Context) {
+        self.update_label(ctx);
+    }
+
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        ctx.set_graphic(this, self.style.normal.clone());
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(x) = event.downcast_ref::<ItemClicked>() {
+            self.checked[x.index] = !self.checked[x.index];
+            self.update_label(ctx);
+            (self.on_change)(&self.selected(), this, ctx);
+        } else if event.is::<MenuClosed>() {
+            self.opened = false;
+        } else if let Some(SetValue(selected)) = event.downcast_ref::<SetValue<Vec<usize>>>() {
+            self.checked.iter_mut().for_each(|x| *x = false);
+            for &i in selected {
+                if let Some(x) = self.checked.get_mut(i) {
+                    *x = true;
+                }
+            }
+            self.update_label(ctx);
+            (self.on_change)(&self.selected(), this, ctx);
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE | InputFlags::FOCUS
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        use MouseButton::*;
+        match mouse.event {
+            MouseEvent::Enter => {
+                self.state = 1;
+                ctx.set_graphic(this, self.style.hover.clone());
+            }
+            MouseEvent::Exit => {
+                self.state = 0;
+                if self.focus {
+                    ctx.set_graphic(this, self.style.focus.clone());
+                } else {
+                    ctx.set_graphic(this, self.style.normal.clone());
+                }
+            }
+            MouseEvent::Down(Left) => {
+                self.state = 2;
+                ctx.set_graphic(this, self.style.pressed.clone());
+            }
+            MouseEvent::Up(Left) => {
+                if self.state == 2 {
+                    if !self.opened {
+                        self.opened = true;
+                        show_menu(
                             self.menu,
-                            ShowMenu(this, self.selected, self.itens.clone()),
+                            ShowMenu {
+                                owner: this,
+                                selected: None,
+                                itens: self.itens.clone(),
+                                filtering: self.filtering,
+                                checked: Some(self.checked.clone()),
+                            },
+                            ctx,
                         );
                     } else {
                         self.opened = false;
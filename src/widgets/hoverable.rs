@@ -1,10 +1,21 @@
-use crate::{Behaviour, Context, Id, InputFlags, MouseEvent, MouseInfo};
+use std::{any::Any, time::Duration};
+
+use crate::{time::Instant, Behaviour, Context, Id, InputFlags, MouseEvent, MouseInfo};
+
+/// Scheduled when the mouse enter the control, to show the hover after the delay.
+struct ShowHover;
 
 pub struct Hoverable {
     is_over: bool,
     text: String,
     hover: Id,
     label: Id,
+    delay: Duration,
+    follow_cursor: bool,
+    /// The id of the scheduled ShowHover event, if any.
+    scheduled: Option<u64>,
+    is_shown: bool,
+    mouse_pos: [f32; 2],
 }
 impl Hoverable {
     pub fn new(hover: Id, label: Id, text: String) -> Self {
@@ -13,43 +24,112 @@ impl Hoverable {
             text,
             hover,
             label,
+            delay: Duration::ZERO,
+            follow_cursor: true,
+            scheduled: None,
+            is_shown: false,
+            mouse_pos: [0.0; 2],
         }
     }
+
+    /// Set the time the mouse must be over the control before the hover is shown. The default
+    /// is to show it immediately.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// If true, the default, the hover follows the cursor while it moves over the control.
+    /// Otherwise, it stays where it was shown.
+    pub fn with_follow_cursor(mut self, follow_cursor: bool) -> Self {
+        self.follow_cursor = follow_cursor;
+        self
+    }
+
+    fn show(&mut self, ctx: &mut Context) {
+        self.is_shown = true;
+        ctx.active(self.hover);
+        ctx.move_to_front(self.hover);
+        self.set_position(ctx);
+    }
+
+    fn hide(&mut self, ctx: &mut Context) {
+        if let Some(event_id) = self.scheduled.take() {
+            ctx.cancel_scheduled_event(event_id);
+        }
+        if self.is_shown {
+            ctx.deactive(self.hover);
+            self.is_shown = false;
+        }
+    }
+
+    /// Anchor the hover at the cursor, but moving it to never go outside of the root control.
+    fn set_position(&mut self, ctx: &mut Context) {
+        let root = ctx.get_rect(Id::ROOT_ID);
+        let [root_width, root_height] = [root[2] - root[0], root[3] - root[1]];
+        let [width, height] = ctx.get_min_size(self.hover);
+        let margins = ctx.get_margins(self.hover);
+
+        let x = self.mouse_pos[0] - root[0] + margins[0];
+        let y = self.mouse_pos[1] - root[1] + margins[1];
+        let x = x.min(root_width - width).max(0.0) - margins[0];
+        let y = y.min(root_height - height).max(0.0) - margins[1];
+
+        let x = x / root_width;
+        let y = y / root_height;
+        ctx.set_anchors(self.hover, [x, y, x, y]);
+    }
 }
 impl Behaviour for Hoverable {
     fn on_start(&mut self, _this: Id, ctx: &mut Context) {
         ctx.deactive(self.hover);
     }
 
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.is_over = false;
+        self.hide(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, _this: Id, ctx: &mut Context) {
+        if event.is::<ShowHover>() {
+            self.scheduled = None;
+            if self.is_over {
+                self.show(ctx);
+            }
+        }
+    }
+
     fn input_flags(&self) -> InputFlags {
         InputFlags::MOUSE
     }
 
-    fn on_mouse_event(&mut self, mouse: MouseInfo, _this: Id, ctx: &mut Context) {
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
         match mouse.event {
             MouseEvent::Enter => {
-                ctx.active(self.hover);
+                self.is_over = true;
+                self.mouse_pos = mouse.pos;
+                self.hide(ctx);
+                // the text is set before the delay, so the hover has its final size when shown.
                 ctx.get_graphic_mut(self.label).set_text(&self.text);
                 ctx.dirty_layout(self.label);
-                ctx.move_to_front(self.hover);
-                self.is_over = true;
+                if self.delay == Duration::ZERO {
+                    self.show(ctx);
+                } else {
+                    let event_id =
+                        ctx.send_event_to_scheduled(this, ShowHover, Instant::now() + self.delay);
+                    self.scheduled = Some(event_id);
+                }
             }
             MouseEvent::Exit => {
-                ctx.deactive(self.hover);
                 self.is_over = false;
+                self.hide(ctx);
             }
             MouseEvent::Down(_) => {}
             MouseEvent::Up(_) => {}
             MouseEvent::Moved => {
-                let [x, y] = {
-                    let root = ctx.get_rect(Id::ROOT_ID);
-                    [mouse.pos[0] - root[0], mouse.pos[1] - root[1]]
-                };
-                if self.is_over {
-                    let [width, heigth] = ctx.get_size(crate::Id::ROOT_ID);
-                    let x = x / width;
-                    let y = y / heigth;
-                    ctx.set_anchors(self.hover, [x, y, x, y]);
+                self.mouse_pos = mouse.pos;
+                if self.is_shown && self.follow_cursor {
+                    self.set_position(ctx);
                 }
             }
             MouseEvent::None => {}
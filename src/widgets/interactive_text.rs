@@ -1,6 +1,13 @@
 use std::ops::Range;
 
-use crate::{graphics::Graphic, Behaviour, Context, Id, InputFlags, MouseEvent, MouseInfo};
+use winit::window::CursorIcon;
+
+use crate::{
+    graphics::Graphic,
+    style::SelectionColor,
+    text::{editor::TextEditor, Key, Span},
+    Behaviour, Color, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+};
 
 pub trait InteractiveTextCallback {
     /// Similar to [`Behaviour::on_mouse_event`], but limited to the bounds of the text span.
@@ -12,8 +19,33 @@ impl<T: FnMut(MouseInfo, Id, &mut Context)> InteractiveTextCallback for T {
     }
 }
 
+/// A clickable range of the text. See [`InteractiveText::with_link`].
+struct Link {
+    range: Range<usize>,
+    payload: String,
+    on_click: Box<dyn FnMut(&str, Id, &mut Context)>,
+    /// The underline span of the link, after the control has started.
+    span: Option<Key>,
+}
+
 pub struct InteractiveText {
     actions: Vec<(Range<usize>, Box<dyn InteractiveTextCallback>, bool)>,
+    links: Vec<Link>,
+    /// The index of the link under the mouse, if any.
+    hover_link: Option<usize>,
+    /// The index of the link where the left mouse button was pressed, if any.
+    pressed_link: Option<usize>,
+    /// The color of a link while hovered, if any. See [`InteractiveText::with_link_colors`].
+    link_hover_color: Option<Color>,
+    /// The color of a link while pressed, if any.
+    link_pressed_color: Option<Color>,
+    /// The color of the selected text. If None, the text is not selectable.
+    selection_color: Option<SelectionColor>,
+    editor: TextEditor,
+    selection_span: Option<Key>,
+    /// If it is non zero, the mouse is being dragged. 1 for single click, 2 for double click, etc...
+    mouse_down: u8,
+    drag_start: usize,
 }
 impl InteractiveText {
     pub fn new(actions: Vec<(Range<usize>, Box<dyn InteractiveTextCallback>)>) -> Self {
@@ -22,21 +54,214 @@ impl InteractiveText {
                 .into_iter()
                 .map(|(range, cb)| (range, cb, false))
                 .collect(),
+            links: Vec::new(),
+            hover_link: None,
+            pressed_link: None,
+            link_hover_color: None,
+            link_pressed_color: None,
+            selection_color: None,
+            editor: TextEditor::new(),
+            selection_span: None,
+            mouse_down: 0,
+            drag_start: 0,
+        }
+    }
+
+    /// Allow the text to be selected with the mouse, using the given color for the selection.
+    ///
+    /// A double click selects a word, and a triple click selects a paragraph. Dragging after that
+    /// extends the selection by whole words or paragraphs, respectively.
+    pub fn with_selection(mut self, selection_color: SelectionColor) -> Self {
+        self.selection_color = Some(selection_color);
+        self
+    }
+
+    /// Mark the given byte range of the text as a link. The link is underlined, the cursor changes
+    /// to a hand while hovering it, and clicking on it calls `on_click` with the given payload,
+    /// like a URL.
+    pub fn with_link<F: FnMut(&str, Id, &mut Context) + 'static>(
+        mut self,
+        range: Range<usize>,
+        payload: impl Into<String>,
+        on_click: F,
+    ) -> Self {
+        self.links.push(Link {
+            range,
+            payload: payload.into(),
+            on_click: Box::new(on_click),
+            span: None,
+        });
+        self
+    }
+
+    /// Recolor the links while they are hovered, and while they are pressed. Only the color of the
+    /// glyphs is changed, so the text is not laid out again.
+    pub fn with_link_colors(mut self, hover: Color, pressed: Color) -> Self {
+        self.link_hover_color = Some(hover);
+        self.link_pressed_color = Some(pressed);
+        self
+    }
+
+    /// Change the color of the given link, or restore its color if None.
+    fn set_link_color(&self, link: usize, color: Option<Color>, this: Id, ctx: &mut Context) {
+        let fonts = ctx.get_fonts();
+        let (rect, text) = match ctx.get_rect_and_graphic(this) {
+            (a, Graphic::Text(b)) => (a, b),
+            _ => return,
+        };
+        let range = self.links[link].range.clone();
+        text.get_layout(fonts, rect).set_span_color(range, color);
+    }
+
+    /// Update the link under the mouse, changing the cursor and its color if needed.
+    fn set_hover_link(&mut self, link: Option<usize>, this: Id, ctx: &mut Context) {
+        if link == self.hover_link {
+            return;
+        }
+        if let Some(prev) = self.hover_link {
+            if self.link_hover_color.is_some() {
+                self.set_link_color(prev, None, this, ctx);
+            }
+        }
+        if let Some(link) = link {
+            if self.link_hover_color.is_some() {
+                self.set_link_color(link, self.link_hover_color, this, ctx);
+            }
+        }
+        self.hover_link = link;
+        let cursor = match link {
+            Some(_) => CursorIcon::Hand,
+            None => ctx.get_hover_cursor(this).unwrap_or(CursorIcon::Default),
+        };
+        ctx.set_cursor(cursor);
+    }
+
+    /// Return the byte range of the currently selected text.
+    pub fn selection_range(&self) -> Range<usize> {
+        self.editor.selection_range()
+    }
+
+    fn byte_index_at(
+        &self,
+        mouse: &MouseInfo,
+        this: Id,
+        ctx: &mut Context,
+    ) -> Option<Result<usize, usize>> {
+        let fonts = ctx.get_fonts();
+        let (rect, text) = match ctx.get_rect_and_graphic(this) {
+            (a, Graphic::Text(b)) => (a, b),
+            _ => return None,
+        };
+        let anchor = text.get_align_anchor(rect.rect);
+        let text_layout = text.get_layout(fonts, rect);
+        let x = mouse.pos[0] - anchor[0];
+        let y = mouse.pos[1] - anchor[1];
+        Some(text_layout.byte_index_from_position(x, y))
+    }
+
+    /// Select the text from drag_start to the given byte_index, with the granularity given by
+    /// self.mouse_down.
+    fn select_to(&mut self, byte_index: usize, this: Id, ctx: &mut Context) {
+        let selection_color = match &self.selection_color {
+            Some(x) => x.clone(),
+            None => return,
+        };
+        let fonts = ctx.get_fonts();
+        let (rect, text) = match ctx.get_rect_and_graphic(this) {
+            (a, Graphic::Text(b)) => (a, b),
+            _ => return,
+        };
+        let text_layout = text.get_layout(fonts, rect);
+        match self.mouse_down {
+            0 => return,
+            1 => {
+                self.editor
+                    .move_cursor_to_byte_index(self.drag_start, false, text_layout);
+                self.editor
+                    .move_cursor_to_byte_index(byte_index, true, text_layout);
+            }
+            2 => self
+                .editor
+                .select_words_at_byte_range(self.drag_start..byte_index, text_layout),
+            3..=u8::MAX => self
+                .editor
+                .select_lines_at_byte_range(self.drag_start..byte_index, text_layout),
+        }
+
+        let selection_range = self.editor.selection_range();
+        self.selection_span.take().map(|x| text.remove_span(x));
+        if !selection_range.is_empty() {
+            self.selection_span = Some(text.add_span(
+                selection_range,
+                Span::Selection {
+                    bg: selection_color.bg,
+                    fg: selection_color.fg,
+                },
+            ));
         }
     }
 }
 impl Behaviour for InteractiveText {
+    fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        let text = match ctx.get_graphic_mut(this) {
+            Graphic::Text(x) => x,
+            _ => return,
+        };
+        for link in &mut self.links {
+            link.span = Some(text.add_span(link.range.clone(), Span::Underline(None)));
+        }
+    }
+
     fn input_flags(&self) -> InputFlags {
         InputFlags::MOUSE
     }
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
-        let fonts = ctx.get_fonts();
-        let (rect, text) = match ctx.get_rect_and_graphic(this) {
-            (a, Graphic::Text(b)) => (a, b),
-            _ => return,
+        let byte_index = match mouse.event {
+            MouseEvent::Down(_) | MouseEvent::Moved => {
+                match self.byte_index_at(&mouse, this, ctx) {
+                    Some(x) => x,
+                    None => return,
+                }
+            }
+            _ => Err(0),
         };
 
+        match mouse.event {
+            MouseEvent::Moved => {
+                let link = self
+                    .links
+                    .iter()
+                    .position(|x| byte_index.map_or(false, |i| x.range.contains(&i)));
+                if self.mouse_down == 0 {
+                    self.set_hover_link(link, this, ctx);
+                }
+            }
+            MouseEvent::Exit => self.set_hover_link(None, this, ctx),
+            MouseEvent::Down(MouseButton::Left) => {
+                self.pressed_link = self.hover_link;
+                if let (Some(link), Some(_)) = (self.pressed_link, self.link_pressed_color) {
+                    self.set_link_color(link, self.link_pressed_color, this, ctx);
+                }
+            }
+            MouseEvent::Up(MouseButton::Left) => {
+                let pressed_link = self.pressed_link.take();
+                if let (Some(link), Some(_)) = (pressed_link, self.link_pressed_color) {
+                    let color = if self.hover_link == Some(link) {
+                        self.link_hover_color
+                    } else {
+                        None
+                    };
+                    self.set_link_color(link, color, this, ctx);
+                }
+                if mouse.click() && pressed_link.is_some() && pressed_link == self.hover_link {
+                    let link = &mut self.links[pressed_link.unwrap()];
+                    (link.on_click)(&link.payload, this, ctx);
+                }
+            }
+            _ => {}
+        }
+
         // TODO: the rules for mouse.click_count are not being respected when calling
         // action.on_mouse_event.
         match mouse.event {
@@ -50,18 +275,39 @@ impl Behaviour for InteractiveText {
                 }
             }
             MouseEvent::Down(_) | MouseEvent::Up(_) => {
+                let mut on_action = false;
                 for (_, action, is_on) in self.actions.iter_mut() {
                     if *is_on {
+                        on_action = true;
                         action.on_mouse_event(mouse.clone(), this, ctx);
                     }
                 }
+                match mouse.event {
+                    MouseEvent::Down(MouseButton::Left)
+                        if !on_action
+                            && self.hover_link.is_none()
+                            && self.selection_color.is_some() =>
+                    {
+                        let byte_index = byte_index.unwrap_or_else(|x| x);
+                        self.mouse_down = mouse.click_count.max(1);
+                        if self.mouse_down == 1 {
+                            self.drag_start = byte_index;
+                        }
+                        self.select_to(byte_index, this, ctx);
+                        ctx.lock_cursor(true, mouse.id);
+                    }
+                    MouseEvent::Up(MouseButton::Left) if self.mouse_down != 0 => {
+                        self.mouse_down = 0;
+                        ctx.lock_cursor(false, mouse.id);
+                    }
+                    _ => {}
+                }
             }
             MouseEvent::Moved => {
-                let anchor = text.get_align_anchor(rect.rect);
-                let text_layout = text.get_layout(fonts, rect);
-                let x = mouse.pos[0] - anchor[0];
-                let y = mouse.pos[1] - anchor[1];
-                let byte_index = text_layout.byte_index_from_position(x, y);
+                if self.mouse_down != 0 {
+                    self.select_to(byte_index.unwrap_or_else(|x| x), this, ctx);
+                    return;
+                }
                 let contains = |r: &Range<usize>| byte_index.map_or(false, |x| r.contains(&x));
                 for (r, action, was_on) in self.actions.iter_mut() {
                     let is_on = contains(r);
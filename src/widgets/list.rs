@@ -1,17 +1,27 @@
 // TODO: call just once builder.item_count() per layout
 
-use std::{any::Any, collections::BTreeMap};
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+};
 
 use winit::event::VirtualKeyCode;
 
-use super::{FinishScrollMomentum, ScrollBar, ScrollDelta, ScrollMomentum};
+use super::{
+    FinishScrollMomentum, OverlayScrollBars, ScrollBar, ScrollDelta, ScrollMomentum, ScrollPage,
+};
 use crate::MouseInfo;
 use crate::{
-    util::cmp_float, widgets::SetScrollPosition, Behaviour, BuilderContext, Context,
-    ControlBuilder, Id, InputFlags, KeyboardEvent, Layout, LayoutContext, MinSizeContext,
+    accessibility::Role, style::ScrollBarStyle, util::cmp_float, widgets::SetScrollPosition,
+    Behaviour, BuilderContext, Context, ControlBuilder, Id, InputFlags, KeyboardEvent, Layout,
+    LayoutContext, MinSizeContext, ScrollInfo,
 };
 
 pub struct UpdateItems;
+/// When send to the behaviour [List], add the item with the given index to the selection.
+pub struct SelectItem(pub usize);
+/// When send to the behaviour [List], remove the item with the given index from the selection.
+pub struct DeselectItem(pub usize);
 /// When send to the behaviour [List], will bring a item to inside the view.
 ///
 /// If the item is outside of the view, the item will be centered on the screen, otherwise the view
@@ -112,6 +122,19 @@ pub trait ListBuilder {
         true
     }
 
+    /// Used to show if a item is selected or not.
+    ///
+    /// Called after a item is created or updated, and every time the selection of a created item
+    /// changes.
+    fn set_selected(
+        &mut self,
+        index: usize,
+        item_id: Id,
+        selected: bool,
+        ctx: &mut dyn BuilderContext,
+    ) {
+    }
+
     /// Called after all items has been updated.
     ///
     /// In the case where the items need to be updated sometimes, this can be used to mark all
@@ -162,8 +185,14 @@ pub struct List<C: ListBuilder> {
     // CreatedItem is in last_created_items?
     focused: Option<CreatedItem>,
     builder: C,
+    /// The indices of the selected items.
+    selected: BTreeSet<usize>,
+    /// The item from where a Shift+Click range selection starts.
+    selection_anchor: Option<usize>,
+    on_selection_change: Option<Box<dyn FnMut(Id, &mut Context, &[usize])>>,
 
     momentum_scroll: ScrollMomentum,
+    overlay_bars: OverlayScrollBars,
 }
 impl<C: ListBuilder> List<C> {
     /// Create a new List.
@@ -228,8 +257,72 @@ impl<C: ListBuilder> List<C> {
             last_created_items: BTreeMap::new(),
             created_items: BTreeMap::new(),
             builder,
+            selected: BTreeSet::new(),
+            selection_anchor: None,
+            on_selection_change: None,
             momentum_scroll: ScrollMomentum::default(),
+            overlay_bars: OverlayScrollBars::new(vec![h_scroll_bar, v_scroll_bar]),
+        }
+    }
+
+    /// Set how the scroll bars are shown. Overlay scroll bars are drawn over the items, and fade
+    /// out when idle.
+    pub fn with_scroll_bar_style(mut self, style: ScrollBarStyle) -> Self {
+        self.overlay_bars.set_style(style);
+        self
+    }
+
+    /// Set a callback that is called every time the selection changes, with the indices of the
+    /// selected items.
+    ///
+    /// A click on a item selects it. Ctrl+Click toggles the item, and Shift+Click selects all
+    /// items from the last clicked item.
+    pub fn with_on_selection_change<F: FnMut(Id, &mut Context, &[usize]) + 'static>(
+        mut self,
+        on_selection_change: F,
+    ) -> Self {
+        self.on_selection_change = Some(Box::new(on_selection_change));
+        self
+    }
+
+    /// The indices of the selected items, in increasing order.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.selected.iter().copied().collect()
+    }
+
+    fn set_selection(&mut self, selected: BTreeSet<usize>, this: Id, ctx: &mut Context) {
+        if selected == self.selected {
+            return;
+        }
+        let old = std::mem::replace(&mut self.selected, selected);
+        for (&i, item) in &self.created_items {
+            let is_selected = self.selected.contains(&i);
+            if is_selected != old.contains(&i) {
+                self.builder.set_selected(i, item.id, is_selected, ctx);
+            }
+        }
+        if let Some(on_selection_change) = &mut self.on_selection_change {
+            on_selection_change(this, ctx, &self.selected_indices());
+        }
+    }
+
+    /// Update the selection after a click on the item with the given index.
+    fn click_item(&mut self, index: usize, this: Id, ctx: &mut Context) {
+        let modifiers = ctx.modifiers();
+        let mut selected = self.selected.clone();
+        if modifiers.ctrl() {
+            if !selected.remove(&index) {
+                selected.insert(index);
+            }
+            self.selection_anchor = Some(index);
+        } else if let (true, Some(anchor)) = (modifiers.shift(), self.selection_anchor) {
+            selected = (anchor.min(index)..=anchor.max(index)).collect();
+        } else {
+            selected.clear();
+            selected.insert(index);
+            self.selection_anchor = Some(index);
         }
+        self.set_selection(selected, this, ctx);
     }
 
     fn create_item_generic(
@@ -301,6 +394,8 @@ impl<C: ListBuilder> List<C> {
         }
 
         let id = x.id;
+        self.builder
+            .set_selected(i, id, self.selected.contains(&i), ctx);
 
         let top_margin = if i == 0 { self.margins[1] } else { 0.0 };
         let bottom_margin = if i + 1 == self.builder.item_count(ctx) {
@@ -635,9 +730,18 @@ impl<C: ListBuilder> List<C> {
     }
 }
 impl<C: ListBuilder> Behaviour for List<C> {
+    fn accessibility_role(&self) -> Role {
+        Role::List
+    }
+
     fn on_start(&mut self, _this: Id, ctx: &mut Context) {
         ctx.move_to_front(self.h_scroll_bar);
         ctx.move_to_front(self.v_scroll_bar);
+        self.overlay_bars.on_start(ctx);
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.overlay_bars.on_remove(ctx);
     }
 
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
@@ -702,7 +806,11 @@ impl<C: ListBuilder> Behaviour for List<C> {
     }
 
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if self.overlay_bars.on_event(&*event, this, ctx) {
+            return;
+        }
         if let Some(event) = event.downcast_ref::<SetScrollPosition>() {
+            self.overlay_bars.show(this, ctx);
             self.momentum_scroll.cancel_scroll(ctx);
             if !event.vertical {
                 let total_size = self.content_width - ctx.get_size(self.view)[0];
@@ -716,8 +824,23 @@ impl<C: ListBuilder> Behaviour for List<C> {
             ctx.dirty_layout(this);
         } else if let Some(event) = event.downcast_ref::<ScrollDelta>() {
             self.add_delta(event.delta, ctx)
+        } else if let Some(&ScrollPage { vertical, pages }) = event.downcast_ref() {
+            self.overlay_bars.show(this, ctx);
+            self.momentum_scroll.cancel_scroll(ctx);
+            let i = vertical as usize;
+            let mut delta = [0.0; 2];
+            delta[i] = -pages * ctx.get_size(self.view)[i];
+            self.add_delta(delta, ctx)
         } else if event.is::<FinishScrollMomentum>() {
             self.momentum_scroll.is_scrolling = false;
+        } else if let Some(&SelectItem(index)) = event.downcast_ref::<SelectItem>() {
+            let mut selected = self.selected.clone();
+            selected.insert(index);
+            self.set_selection(selected, this, ctx);
+        } else if let Some(&DeselectItem(index)) = event.downcast_ref::<DeselectItem>() {
+            let mut selected = self.selected.clone();
+            selected.remove(&index);
+            self.set_selection(selected, this, ctx);
         } else if event.is::<UpdateItems>() {
             // TODO: I add this set_y here, to force a update, but i don't know if this will go
             // wrong!!
@@ -791,12 +914,23 @@ impl<C: ListBuilder> Behaviour for List<C> {
     }
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        if mouse.click() && !mouse.is_dragging() {
+            let [x, y] = mouse.pos;
+            let clicked = self.created_items.iter().find(|(_, item)| {
+                let rect = ctx.get_rect(item.id);
+                rect[0] <= x && x < rect[2] && rect[1] <= y && y < rect[3]
+            });
+            if let Some((&index, _)) = clicked {
+                self.click_item(index, this, ctx);
+            }
+        }
         self.momentum_scroll.on_mouse_event(mouse, this, ctx)
     }
 
-    fn on_scroll_event(&mut self, delta: [f32; 2], _this: Id, ctx: &mut Context) {
+    fn on_scroll_event(&mut self, scroll: ScrollInfo, this: Id, ctx: &mut Context) {
+        self.overlay_bars.show(this, ctx);
         self.momentum_scroll.cancel_scroll(ctx);
-        self.add_delta(delta, ctx);
+        self.add_delta(scroll.delta, ctx);
     }
 
     fn on_keyboard_event(&mut self, event: KeyboardEvent, _this: Id, ctx: &mut Context) -> bool {
@@ -860,8 +994,10 @@ impl<C: ListBuilder> Layout for List<C> {
         min_size[0] = min_size[0].max(h_scroll_bar_size[0]);
         min_size[1] = min_size[1].max(v_scroll_bar_size[1]);
 
-        min_size[0] += v_scroll_bar_size[0];
-        min_size[1] += h_scroll_bar_size[1];
+        if !self.overlay_bars.is_overlay() {
+            min_size[0] += v_scroll_bar_size[0];
+            min_size[1] += h_scroll_bar_size[1];
+        }
 
         min_size
     }
@@ -872,25 +1008,29 @@ impl<C: ListBuilder> Layout for List<C> {
         let this_rect = ctx.get_rect(this);
 
         let this_width = this_rect[2] - this_rect[0];
+        // overlay scroll bars are drawn over the view, and don't take space from it.
+        let overlay = self.overlay_bars.is_overlay();
 
         // assume that the vertical bar will be used
         let mut v_scroll_bar_size = ctx.get_min_size(self.v_scroll_bar)[0];
+        let v_reserved = if overlay { 0.0 } else { v_scroll_bar_size };
 
         // check if the horizontal bar is need
         let mut h_active;
         let mut h_scroll_bar_size;
-        h_active = this_width - v_scroll_bar_size < self.content_width;
+        h_active = this_width - v_reserved < self.content_width;
         h_scroll_bar_size = if h_active {
             ctx.get_min_size(self.h_scroll_bar)[1]
         } else {
             0.0
         };
+        let h_reserved = if overlay { 0.0 } else { h_scroll_bar_size };
 
         let mut view_rect = [
             this_rect[0],
             this_rect[1],
-            this_rect[2] - v_scroll_bar_size,
-            this_rect[3] - h_scroll_bar_size,
+            this_rect[2] - v_reserved,
+            this_rect[3] - h_reserved,
         ];
 
         // clamp delta_x
@@ -932,6 +1072,8 @@ impl<C: ListBuilder> Layout for List<C> {
 
         if !v_active {
             v_scroll_bar_size = 0.0;
+        }
+        if !v_active && !overlay {
             view_rect[2] = this_rect[2];
             // the first layout has assumed that the vertical bar exist. redo if it not exist.
             self.create_items_from_top(view_rect, this, ctx);
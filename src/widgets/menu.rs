@@ -1,17 +1,32 @@
-use std::{any::Any, rc::Rc};
+use std::{any::Any, cell::Cell, rc::Rc};
 
 use crate::{
-    graphics::Text,
+    accessibility::Role,
+    graphics::{Graphic, Text},
     layouts::{FitGraphic, HBoxLayout, MarginLayout, VBoxLayout},
     style::MenuStyle,
     widgets::CloseMenu,
-    Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo, RectFill,
+    Accelerator, Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo, RectFill,
 };
 
 pub enum Item {
     Separator,
     Button(String, Box<dyn Fn(Id, &mut Context)>),
     SubMenu(Rc<Menu>),
+    /// A item that can be checked on and off. It shows the check mark of the
+    /// [`MenuStyle`](crate::style::MenuStyle) when on. Clicking it toggles its state, calls the
+    /// callback with the new state, and does not close the menu.
+    ///
+    /// The state is kept in a Cell, so it persists between each time the menu is opened.
+    Checkable(String, Cell<bool>, Box<dyn Fn(Id, &mut Context, bool)>),
+    /// A Button with a leading icon.
+    IconButton(Graphic, String, Box<dyn Fn(Id, &mut Context)>),
+    /// A Button that shows its accelerator right-aligned. When the Menu is part of a [`MenuBar`],
+    /// pressing the accelerator calls the callback even if the menu is closed.
+    ///
+    /// Unlike the other items, the callback receives the id of the control that owns the menu,
+    /// like the MenuBar, both when the item is clicked and when the accelerator is pressed.
+    ShortcutButton(String, Accelerator, Box<dyn Fn(Id, &mut Context)>),
 }
 
 pub struct Menu {
@@ -26,6 +41,44 @@ impl Menu {
 
 pub struct ItemClicked;
 
+/// Sent to a menu after it is created, to place it beside the rect it was opened from, once its
+/// size is known.
+pub(crate) struct PlaceMenu {
+    /// The rect the menu was opened from, relative to the root control.
+    pub(crate) from: [f32; 4],
+    /// If true, the menu opens to the right of the rect, otherwise it opens below it.
+    pub(crate) side: bool,
+}
+
+/// Place the menu beside, or below, the given rect. If the menu would overflow the root control,
+/// it is flipped to the other side of the rect.
+fn place_menu(menu: Id, place: &PlaceMenu, ctx: &mut Context) {
+    let root = ctx.get_rect(Id::ROOT_ID);
+    let rect = ctx.get_rect(menu);
+    let width = rect[2] - rect[0];
+    let height = rect[3] - rect[1];
+    let from = place.from;
+
+    let (mut x, mut y) = if place.side {
+        (from[2], from[1])
+    } else {
+        (from[0], from[3])
+    };
+    if root[0] + x + width > root[2] {
+        let flipped = if place.side { from[0] } else { from[2] } - width;
+        if flipped >= 0.0 {
+            x = flipped;
+        }
+    }
+    if root[1] + y + height > root[3] {
+        let flipped = if place.side { from[3] } else { from[1] } - height;
+        if flipped >= 0.0 {
+            y = flipped;
+        }
+    }
+    ctx.set_margins(menu, [x, y, x, y]);
+}
+
 pub struct MenuBehaviour {
     menu: Rc<Menu>,
     over: Option<usize>,
@@ -34,6 +87,8 @@ pub struct MenuBehaviour {
     click: bool,
     style: Rc<MenuStyle>,
     owner: Id,
+    /// The control that owns the outermost menu, like a MenuBar.
+    root: Id,
 }
 impl MenuBehaviour {
     pub fn new(menu: Rc<Menu>, style: Rc<MenuStyle>, owner: Id) -> Self {
@@ -45,6 +100,7 @@ impl MenuBehaviour {
             click: false,
             style,
             owner,
+            root: owner,
         }
     }
 
@@ -58,7 +114,10 @@ impl MenuBehaviour {
         self.close_menu(ctx);
         match &self.menu.itens[i] {
             Item::Separator => {}
-            Item::Button(_, _) => {}
+            Item::Button(_, _)
+            | Item::Checkable(..)
+            | Item::IconButton(..)
+            | Item::ShortcutButton(..) => {}
             Item::SubMenu(menu) => {
                 let child = ctx.get_active_children(this)[i];
                 let rect = ctx.get_rect(child);
@@ -68,24 +127,64 @@ impl MenuBehaviour {
                     (rect[0], rect[1])
                 };
 
-                let x = rect[2] - root_x;
-                let y = rect[1] - root_y;
+                let from = [
+                    rect[0] - root_x,
+                    rect[1] - root_y,
+                    rect[2] - root_x,
+                    rect[3] - root_y,
+                ];
+                let [x, y] = [from[2], from[1]];
 
                 let menu = ctx
                     .create_control()
                     .anchors([0.0, 0.0, 0.0, 0.0])
                     .margins([x, y, x, y])
-                    .behaviour(MenuBehaviour::new(menu.clone(), self.style.clone(), this))
+                    .behaviour(MenuBehaviour {
+                        root: self.root,
+                        ..MenuBehaviour::new(menu.clone(), self.style.clone(), this)
+                    })
                     .graphic(self.style.button.normal.clone())
                     .layout(VBoxLayout::new(0.0, [0.0, 0.0, 0.0, 0.0], -1))
                     .build(ctx);
+                // when 'menu' receive this event, it will already have its size defined.
+                ctx.send_event_to(menu, PlaceMenu { from, side: true });
                 self.open = Some(menu);
             }
         }
     }
+
+    /// Create a item with a leading icon, that is the first child of the item.
+    fn create_icon_item(&self, icon: Graphic, text: &str, this: Id, ctx: &mut Context) {
+        let item = ctx
+            .create_control()
+            .parent(this)
+            .layout(HBoxLayout::new(0.0, [2.0, 2.0, 18.0, 2.0], -1))
+            .build(ctx);
+        let _icon = ctx
+            .create_control()
+            .min_size([16.0, 16.0])
+            .fill_y(RectFill::ShrinkCenter)
+            .graphic(icon)
+            .parent(item)
+            .build(ctx);
+        let _text = ctx
+            .create_control()
+            .parent(item)
+            .graphic(Text::new(text.to_owned(), (-1, 0), self.style.text.clone()))
+            .layout(FitGraphic)
+            .expand_x(true)
+            .build(ctx);
+    }
 }
 impl Behaviour for MenuBehaviour {
+    fn accessibility_role(&self) -> Role {
+        Role::Menu
+    }
+
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        if let Some(shadow) = self.style.shadow {
+            ctx.set_shadow(this, Some(shadow));
+        }
         for item in self.menu.itens.iter() {
             match item {
                 Item::Separator => {
@@ -114,6 +213,41 @@ impl Behaviour for MenuBehaviour {
                         .layout(FitGraphic)
                         .build(ctx);
                 }
+                Item::Checkable(text, checked, _) => {
+                    let graphic = if checked.get() {
+                        self.style.check.clone()
+                    } else {
+                        Graphic::None
+                    };
+                    self.create_icon_item(graphic, text, this, ctx);
+                }
+                Item::IconButton(icon, text, _) => {
+                    self.create_icon_item(icon.clone(), text, this, ctx);
+                }
+                Item::ShortcutButton(text, accelerator, _) => {
+                    let item = ctx
+                        .create_control()
+                        .parent(this)
+                        .layout(HBoxLayout::new(8.0, [18.0, 2.0, 18.0, 2.0], -1))
+                        .build(ctx);
+                    let _text = ctx
+                        .create_control()
+                        .parent(item)
+                        .graphic(Text::new(text.clone(), (-1, 0), self.style.text.clone()))
+                        .layout(FitGraphic)
+                        .expand_x(true)
+                        .build(ctx);
+                    let _shortcut = ctx
+                        .create_control()
+                        .parent(item)
+                        .graphic(Text::new(
+                            accelerator.to_string(),
+                            (1, 0),
+                            self.style.text.clone(),
+                        ))
+                        .layout(FitGraphic)
+                        .build(ctx);
+                }
                 Item::SubMenu(menu) => {
                     let item = ctx
                         .create_control()
@@ -151,10 +285,12 @@ impl Behaviour for MenuBehaviour {
         self.close_menu(ctx);
     }
 
-    fn on_event(&mut self, event: Box<dyn Any>, _: Id, ctx: &mut Context) {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
         if event.is::<ItemClicked>() {
             self.close_menu(ctx);
             ctx.send_event_to(self.owner, ItemClicked);
+        } else if let Some(place) = event.downcast_ref::<PlaceMenu>() {
+            place_menu(this, place, ctx);
         }
     }
 
@@ -174,10 +310,27 @@ impl Behaviour for MenuBehaviour {
                     let i = self.over.unwrap();
                     match &self.menu.itens[i] {
                         Item::Separator => {}
-                        Item::Button(_, call) => {
+                        Item::Button(_, call) | Item::IconButton(_, _, call) => {
                             (call)(this, ctx);
                             ctx.send_event_to(self.owner, ItemClicked);
                         }
+                        Item::ShortcutButton(_, _, call) => {
+                            (call)(self.root, ctx);
+                            ctx.send_event_to(self.owner, ItemClicked);
+                        }
+                        Item::Checkable(_, checked, call) => {
+                            let value = !checked.get();
+                            checked.set(value);
+                            let item = ctx.get_active_children(this)[i];
+                            let check = ctx.get_active_children(item)[0];
+                            let graphic = if value {
+                                self.style.check.clone()
+                            } else {
+                                Graphic::None
+                            };
+                            ctx.set_graphic(check, graphic);
+                            (call)(this, ctx, value);
+                        }
                         Item::SubMenu(_) => {}
                     }
                 }
@@ -196,7 +349,11 @@ impl Behaviour for MenuBehaviour {
                             }
                             use Item::*;
                             match self.menu.itens[i] {
-                                Button(_, _) | SubMenu(_) => {
+                                Button(_, _)
+                                | SubMenu(_)
+                                | Checkable(..)
+                                | IconButton(..)
+                                | ShortcutButton(..) => {
                                     ctx.set_graphic(*child, self.style.button.hover.clone());
                                 }
                                 Separator => {}
@@ -263,8 +420,13 @@ impl MenuBar {
             (rect[0], rect[1])
         };
 
-        let x = rect[0] - root_x;
-        let y = rect[3] - root_y;
+        let from = [
+            rect[0] - root_x,
+            rect[1] - root_y,
+            rect[2] - root_x,
+            rect[3] - root_y,
+        ];
+        let [x, y] = [from[0], from[3]];
 
         let menu = ctx
             .create_control()
@@ -278,10 +440,35 @@ impl MenuBar {
             .graphic(self.style.button.normal.clone())
             .layout(VBoxLayout::new(0.0, [0.0, 0.0, 0.0, 0.0], -1))
             .build(ctx);
+        ctx.send_event_to(menu, PlaceMenu { from, side: false });
         self.open = Some(menu);
     }
+
+    /// Call `f` for each ShortcutButton in the menu and its submenus, with the menu and the index
+    /// of the item.
+    fn for_each_shortcut(menu: &Rc<Menu>, f: &mut dyn FnMut(&Rc<Menu>, usize, Accelerator)) {
+        for (i, item) in menu.itens.iter().enumerate() {
+            match item {
+                Item::ShortcutButton(_, accelerator, _) => f(menu, i, *accelerator),
+                Item::SubMenu(submenu) => Self::for_each_shortcut(submenu, f),
+                _ => {}
+            }
+        }
+    }
+
+    fn remove_accelerators(&self, ctx: &mut Context) {
+        for menu in &self.menus {
+            Self::for_each_shortcut(menu, &mut |_, _, accelerator| {
+                ctx.remove_accelerator(accelerator)
+            });
+        }
+    }
 }
 impl Behaviour for MenuBar {
+    fn accessibility_role(&self) -> Role {
+        Role::MenuBar
+    }
+
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
         for menu in self.menus.iter() {
             let item = ctx
@@ -302,6 +489,27 @@ impl Behaviour for MenuBar {
         }
     }
 
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        for menu in &self.menus {
+            Self::for_each_shortcut(menu, &mut |menu, i, accelerator| {
+                let menu = menu.clone();
+                ctx.add_accelerator(accelerator, move |ctx| {
+                    if let Item::ShortcutButton(_, _, call) = &menu.itens[i] {
+                        (call)(this, ctx);
+                    }
+                });
+            });
+        }
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.remove_accelerators(ctx);
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.remove_accelerators(ctx);
+    }
+
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
         if event.is::<ItemClicked>() || event.is::<CloseMenu>() {
             self.close_menu(ctx);
@@ -39,3 +39,24 @@ pub use adapters::*;
 
 mod interactive_text;
 pub use interactive_text::*;
+
+mod progress_bar;
+pub use progress_bar::*;
+
+mod spinner;
+pub use spinner::*;
+
+mod tooltip;
+pub use tooltip::*;
+
+mod stepper;
+pub use stepper::*;
+
+mod split_pane;
+pub use split_pane::*;
+
+mod tree_view;
+pub use tree_view::*;
+
+mod accordion;
+pub use accordion::*;
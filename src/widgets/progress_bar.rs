@@ -0,0 +1,142 @@
+use std::any::Any;
+
+use crate::{
+    accessibility::{AccessValue, Role},
+    event::SetValue,
+    AnimationId, Behaviour, Context, Id, Layout, LayoutContext,
+};
+
+/// Enable or disable the indeterminate mode of a [`ProgressBar`].
+pub struct SetIndeterminate(pub bool);
+
+/// Sent by the indeterminate animation, with the current time of the animation.
+struct IndeterminateStep(f32);
+
+/// A read-only progress indicator.
+///
+/// The `fill` control, a child of the progress bar, has its right anchor set to the current
+/// value. In indeterminate mode, `fill` is instead a chunk that moves from left to right
+/// repeatedly.
+pub struct ProgressBar {
+    fill: Id,
+    /// A value between 0.0 and 1.0.
+    value: f32,
+    indeterminate: bool,
+    /// The position of the chunk in the indeterminate mode, between 0.0 and 1.0.
+    phase: f32,
+    animation: Option<AnimationId>,
+}
+impl ProgressBar {
+    /// The duration, in seconds, of one passage of the chunk in the indeterminate mode.
+    const PERIOD: f32 = 1.5;
+    /// The width of the chunk in the indeterminate mode, relative to the progress bar width.
+    const CHUNK_WIDTH: f32 = 0.3;
+
+    pub fn new(fill: Id, value: f32) -> Self {
+        Self {
+            fill,
+            value: value.max(0.0).min(1.0),
+            indeterminate: false,
+            phase: 0.0,
+            animation: None,
+        }
+    }
+
+    pub fn with_indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
+    fn start_animation(&mut self, this: Id, ctx: &mut Context) {
+        self.stop_animation(ctx);
+        let id = ctx.add_animation(Self::PERIOD, move |t, _, _, ctx: &mut Context| {
+            ctx.send_event_to(this, IndeterminateStep(t))
+        });
+        self.animation = Some(id);
+    }
+
+    fn stop_animation(&mut self, ctx: &mut Context) {
+        if let Some(id) = self.animation.take() {
+            ctx.remove_animation(id);
+        }
+    }
+}
+impl Behaviour for ProgressBar {
+    fn accessibility_role(&self) -> Role {
+        Role::ProgressBar
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        if self.indeterminate {
+            return None;
+        }
+        Some(AccessValue::Numeric {
+            value: self.value as f64,
+            min: 0.0,
+            max: 1.0,
+        })
+    }
+
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        if self.indeterminate {
+            self.start_animation(this, ctx);
+        }
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.stop_animation(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetValue(x)) = event.downcast_ref::<SetValue<f32>>() {
+            self.value = x.max(0.0).min(1.0);
+            ctx.dirty_layout(this);
+            ctx.dirty_accessibility();
+        } else if let Some(SetIndeterminate(x)) = event.downcast_ref::<SetIndeterminate>() {
+            self.indeterminate = *x;
+            ctx.dirty_accessibility();
+            if self.indeterminate {
+                self.start_animation(this, ctx);
+            } else {
+                self.stop_animation(ctx);
+            }
+            ctx.dirty_layout(this);
+        } else if let Some(IndeterminateStep(t)) = event.downcast_ref::<IndeterminateStep>() {
+            if !self.indeterminate {
+                return;
+            }
+            self.phase = *t;
+            // restart the animation, so it loops
+            if *t == 1.0 {
+                self.start_animation(this, ctx);
+            }
+            ctx.dirty_layout(this);
+        }
+    }
+}
+impl Layout for ProgressBar {
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let (left, right) = if self.indeterminate {
+            let left = -Self::CHUNK_WIDTH + (1.0 + Self::CHUNK_WIDTH) * self.phase;
+            (left.max(0.0), (left + Self::CHUNK_WIDTH).min(1.0))
+        } else {
+            (0.0, self.value)
+        };
+        let fill = ctx.get_layouting(self.fill);
+        fill.anchors[0] = left;
+        fill.anchors[2] = right;
+
+        // layout the children by its anchors and margins
+        let rect = ctx.get_rect(this);
+        let size = [rect[2] - rect[0], rect[3] - rect[1]];
+        let pos: [f32; 2] = [rect[0], rect[1]];
+        for child in ctx.get_active_children(this) {
+            let rect = &mut ctx.get_layouting(child);
+            let mut new_rect = [0.0; 4];
+            for (i, x) in new_rect.iter_mut().enumerate() {
+                *x = pos[i % 2] + size[i % 2] * rect.anchors[i] + rect.margins[i];
+            }
+            ctx.set_designed_rect(child, new_rect);
+        }
+    }
+}
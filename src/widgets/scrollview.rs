@@ -5,10 +5,12 @@ use instant::{Duration, Instant};
 use winit::event::VirtualKeyCode;
 
 use crate::{
-    style::ButtonStyle, Behaviour, Context, Id, InputFlags, KeyboardEvent, Layout, LayoutContext,
-    MinSizeContext, MouseButton, MouseEvent, MouseInfo,
+    accessibility::Role,
+    style::{ButtonStyle, ScrollBarStyle},
+    Behaviour, Context, Id, InputFlags, KeyboardEvent, Layout, LayoutContext, MinSizeContext,
+    MouseButton, MouseEvent, MouseInfo, ScrollInfo,
 };
-use crate::{Animation, AnimationId};
+use crate::{Animation, AnimationGroup, AnimationId, FadeAnimation};
 
 pub struct SetScrollPosition {
     /// If true, it is setting the vertical scroll. Otherwise the horizontal.
@@ -21,6 +23,41 @@ pub struct ScrollDelta {
     pub delta: [f32; 2],
 }
 
+/// When send to a [ScrollView] or a [List](super::List), scroll by the given number of pages,
+/// where a page is the size of the view. Negative values scroll toward the start.
+pub struct ScrollPage {
+    /// If true, it is scrolling vertically. Otherwise horizontally.
+    pub vertical: bool,
+    pub pages: f32,
+}
+
+/// Scheduled while the track of a [ScrollBar] is pressed, to keep paging toward the mouse.
+struct RepeatPage;
+
+/// Sent by a [ScrollBar] to its scroll view while hovered, to keep overlay scroll bars visible.
+struct ShowScrollBars;
+
+/// Scheduled to hide the overlay scroll bars of a scroll view, after some time idle.
+struct HideScrollBars;
+
+/// The time the overlay scroll bars stay visible after the last scroll or hover.
+const OVERLAY_HIDE_DELAY: Duration = Duration::from_millis(1000);
+
+/// The delay before the paging starts to repeat, while the track is held.
+const PAGE_REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// The interval between each page, while the track is held.
+const PAGE_REPEAT_INTERVAL: Duration = Duration::from_millis(80);
+
+/// When send to a [ScrollView], scroll the minimum necessary to bring the given control, a
+/// descendant of the content, fully inside the view.
+pub struct ScrollToView {
+    /// The control to be brought into view.
+    pub id: Id,
+    /// The space that should be visible around the control. If the control plus the margin does
+    /// not fit in the view, the start of the control is kept visible.
+    pub margin: f32,
+}
+
 pub struct ScrollBar {
     handle: Id,
     scroll_view: Id,
@@ -30,6 +67,10 @@ pub struct ScrollBar {
     curr_value: f32,
     vertical: bool,
     style: Rc<ButtonStyle>,
+    /// The direction of the paging, -1.0 or 1.0, while the track is pressed.
+    paging: Option<f32>,
+    /// The scheduled RepeatPage event.
+    scheduled: Option<u64>,
 }
 impl ScrollBar {
     pub fn new(handle: Id, scroll_view: Id, vertical: bool, style: Rc<ButtonStyle>) -> Self {
@@ -42,6 +83,53 @@ impl ScrollBar {
             curr_value: 0.0,
             vertical,
             style,
+            paging: None,
+            scheduled: None,
+        }
+    }
+
+    /// The direction, -1.0 or 1.0, from the handle toward the mouse, or 0.0 if the mouse is over
+    /// the handle.
+    fn direction_to_mouse(&self, ctx: &mut Context) -> f32 {
+        let handle_rect = ctx.get_rect(self.handle);
+        let (start, end) = if self.vertical {
+            (handle_rect[1], handle_rect[3])
+        } else {
+            (handle_rect[0], handle_rect[2])
+        };
+        if self.mouse_pos < start {
+            -1.0
+        } else if self.mouse_pos > end {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Scroll a page toward the mouse, if the handle is not yet under it, and schedule the next
+    /// page.
+    fn page(&mut self, this: Id, delay: Duration, ctx: &mut Context) {
+        let direction = match self.paging {
+            Some(x) => x,
+            None => return,
+        };
+        if self.direction_to_mouse(ctx) == direction {
+            ctx.send_event_to(
+                self.scroll_view,
+                ScrollPage {
+                    vertical: self.vertical,
+                    pages: direction,
+                },
+            );
+        }
+        let event_id = ctx.send_event_to_scheduled(this, RepeatPage, Instant::now() + delay);
+        self.scheduled = Some(event_id);
+    }
+
+    fn stop_paging(&mut self, ctx: &mut Context) {
+        self.paging = None;
+        if let Some(event_id) = self.scheduled.take() {
+            ctx.cancel_scheduled_event(event_id);
         }
     }
 
@@ -72,6 +160,10 @@ impl ScrollBar {
     }
 }
 impl Behaviour for ScrollBar {
+    fn accessibility_role(&self) -> Role {
+        Role::ScrollBar
+    }
+
     fn on_active(&mut self, _this: Id, ctx: &mut Context) {
         ctx.set_graphic(self.handle, self.style.normal.clone());
     }
@@ -80,58 +172,60 @@ impl Behaviour for ScrollBar {
         InputFlags::MOUSE
     }
 
-    fn on_mouse_event(&mut self, mouse: MouseInfo, _this: Id, ctx: &mut Context) {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if event.is::<RepeatPage>() {
+            self.scheduled = None;
+            self.page(this, PAGE_REPEAT_INTERVAL, ctx);
+        }
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.stop_paging(ctx);
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
         use MouseButton::*;
+        if let MouseEvent::Enter | MouseEvent::Moved = mouse.event {
+            ctx.send_event_to(self.scroll_view, ShowScrollBars);
+        }
         match mouse.event {
             MouseEvent::Enter => {}
             MouseEvent::Exit => {
                 ctx.set_graphic(self.handle, self.style.normal.clone());
             }
             MouseEvent::Down(Left) => {
-                self.dragging = true;
                 ctx.set_graphic(self.handle, self.style.pressed.clone());
                 ctx.lock_cursor(true, mouse.id);
+                let direction = self.direction_to_mouse(ctx);
+                if direction != 0.0 {
+                    // a click in the track pages toward the click, repeating while held
+                    self.paging = Some(direction);
+                    self.page(this, PAGE_REPEAT_DELAY, ctx);
+                    return;
+                }
+                self.dragging = true;
                 let handle_rect = ctx.get_rect(self.handle);
                 let area = ctx
                     .get_parent(self.handle)
                     .expect("the handle of the scrollbar must have a parent");
                 let area_rect = ctx.get_rect(area);
                 self.drag_start = self.mouse_pos;
-                if !self.vertical {
+                self.curr_value = if !self.vertical {
                     let handle_size = handle_rect[2] - handle_rect[0];
                     let area_size = area_rect[2] - area_rect[0] - handle_size;
-                    if self.mouse_pos < handle_rect[0] || self.mouse_pos > handle_rect[2] {
-                        self.curr_value =
-                            (self.mouse_pos - (area_rect[0] + handle_size / 2.0)) / area_size;
-                        ctx.send_event_to(
-                            self.scroll_view,
-                            SetScrollPosition {
-                                vertical: false,
-                                value: self.curr_value,
-                            },
-                        )
-                    } else {
-                        self.curr_value = (handle_rect[0] - area_rect[0]) / area_size;
-                    }
+                    (handle_rect[0] - area_rect[0]) / area_size
                 } else {
                     let handle_size = handle_rect[3] - handle_rect[1];
                     let area_size = area_rect[3] - area_rect[1] - handle_size;
-                    if self.mouse_pos < handle_rect[1] || self.mouse_pos > handle_rect[3] {
-                        self.curr_value =
-                            (self.mouse_pos - (area_rect[1] + handle_size / 2.0)) / area_size;
-                        ctx.send_event_to(
-                            self.scroll_view,
-                            SetScrollPosition {
-                                vertical: true,
-                                value: self.curr_value,
-                            },
-                        )
-                    } else {
-                        self.curr_value = (handle_rect[1] - area_rect[1]) / area_size;
-                    }
-                }
+                    (handle_rect[1] - area_rect[1]) / area_size
+                };
             }
             MouseEvent::Up(Left) => {
+                if self.paging.is_some() {
+                    self.stop_paging(ctx);
+                    ctx.lock_cursor(false, mouse.id);
+                    ctx.set_graphic(self.handle, self.style.normal.clone());
+                }
                 if self.dragging {
                     self.dragging = false;
                     ctx.lock_cursor(false, mouse.id);
@@ -231,6 +325,9 @@ const MEAN_SIZE: usize = 5;
 
 pub struct FinishScrollMomentum;
 
+/// Sent by the smooth scroll animation, with the fraction of the remaining scroll to be applied.
+struct SmoothScrollStep(f32);
+
 /// Encapsulate the behaviour of dragging a scroll container that preserves the drag momentum.
 ///
 /// Is used by composition. The owner Behaviour must have `InputFlags::DRAG`, delegate the
@@ -361,6 +458,110 @@ impl ScrollMomentum {
     }
 }
 
+/// Encapsulate the fading of overlay scroll bars, that are only visible while hovered or
+/// scrolling.
+///
+/// Is used by composition. The owner Behaviour must call `on_start` and `on_remove`, forward its
+/// events to `on_event`, and call `show` each time it is scrolled.
+pub(super) struct OverlayScrollBars {
+    style: ScrollBarStyle,
+    bars: Vec<Id>,
+    /// If the scroll bars are visible, or fading in.
+    visible: bool,
+    fade_anim: Option<AnimationId>,
+    /// The scheduled HideScrollBars event.
+    hide_event: Option<u64>,
+}
+impl OverlayScrollBars {
+    pub(super) fn new(bars: Vec<Id>) -> Self {
+        Self {
+            style: ScrollBarStyle::default(),
+            bars,
+            visible: true,
+            fade_anim: None,
+            hide_event: None,
+        }
+    }
+
+    pub(super) fn set_style(&mut self, style: ScrollBarStyle) {
+        self.visible = !style.overlay;
+        self.style = style;
+    }
+
+    /// If the scroll bars are drawn over the view, without taking space from it.
+    pub(super) fn is_overlay(&self) -> bool {
+        self.style.overlay
+    }
+
+    /// Fade the scroll bars in or out.
+    fn fade(&mut self, visible: bool, ctx: &mut Context) {
+        if self.visible == visible {
+            return;
+        }
+        self.visible = visible;
+        if let Some(id) = self.fade_anim.take() {
+            ctx.remove_animation(id);
+        }
+        let to = if visible { 1.0 } else { 0.0 };
+        let length = self.style.fade_ms as f32 / 1000.0;
+        if length <= 0.0 {
+            for &bar in &self.bars {
+                ctx.set_opacity(bar, to);
+            }
+            return;
+        }
+        let mut group = AnimationGroup::new();
+        for &id in &self.bars {
+            let from = ctx.get_opacity(id);
+            group = group.with(length, FadeAnimation { id, from, to });
+        }
+        let id = ctx.add_animation(length, group);
+        self.fade_anim = Some(id);
+    }
+
+    /// Show the overlay scroll bars, and hide them again after some time idle.
+    pub(super) fn show(&mut self, this: Id, ctx: &mut Context) {
+        if !self.style.overlay {
+            return;
+        }
+        self.fade(true, ctx);
+        if let Some(event_id) = self.hide_event.take() {
+            ctx.cancel_scheduled_event(event_id);
+        }
+        let event_id =
+            ctx.send_event_to_scheduled(this, HideScrollBars, Instant::now() + OVERLAY_HIDE_DELAY);
+        self.hide_event = Some(event_id);
+    }
+
+    pub(super) fn on_start(&mut self, ctx: &mut Context) {
+        if self.style.overlay {
+            for &bar in &self.bars {
+                ctx.set_opacity(bar, 0.0);
+            }
+        }
+    }
+
+    pub(super) fn on_remove(&mut self, ctx: &mut Context) {
+        if let Some(event_id) = self.hide_event.take() {
+            ctx.cancel_scheduled_event(event_id);
+        }
+    }
+
+    /// Handle the [ShowScrollBars] and [HideScrollBars] events. Return false if the event is
+    /// not one of them.
+    pub(super) fn on_event(&mut self, event: &dyn Any, this: Id, ctx: &mut Context) -> bool {
+        if event.is::<ShowScrollBars>() {
+            self.show(this, ctx);
+        } else if event.is::<HideScrollBars>() {
+            self.hide_event = None;
+            self.fade(false, ctx);
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
 pub struct ScrollView {
     pub delta_x: f32,
     pub delta_y: f32,
@@ -370,6 +571,18 @@ pub struct ScrollView {
     v_scroll_bar_and_handle: Option<(Id, Id)>,
 
     momentum_scroll: ScrollMomentum,
+    /// If true, scroll events are animated.
+    smooth: bool,
+    /// The scroll delta that was not yet applied by the smooth scroll animation.
+    smooth_remaining: [f32; 2],
+    smooth_anim: Option<AnimationId>,
+
+    overlay_bars: OverlayScrollBars,
+
+    /// The min size of the content, in the last layout.
+    content_size: [f32; 2],
+    /// The size of the view, in the last layout.
+    view_size: [f32; 2],
 }
 impl ScrollView {
     /// Create a new ScrollView.
@@ -411,9 +624,64 @@ impl ScrollView {
             h_scroll_bar_and_handle,
             v_scroll_bar_and_handle,
             momentum_scroll: ScrollMomentum::default(),
+            smooth: true,
+            smooth_remaining: [0.0; 2],
+            smooth_anim: None,
+            overlay_bars: OverlayScrollBars::new(
+                h_scroll_bar_and_handle
+                    .into_iter()
+                    .chain(v_scroll_bar_and_handle)
+                    .map(|(bar, _)| bar)
+                    .collect(),
+            ),
+            content_size: [0.0; 2],
+            view_size: [0.0; 2],
         }
     }
 
+    /// How far the content is scrolled, horizontally and vertically, in pixels.
+    ///
+    /// To query it while the ScrollView is in the Gui, keep it in a `Rc<RefCell<ScrollView>>`,
+    /// which also implements Behaviour and Layout. The offset can be restored later by sending a
+    /// [`ScrollDelta`] with the negated offset, or a [`SetScrollPosition`] for each axis.
+    pub fn scroll_offset(&self) -> [f32; 2] {
+        [self.delta_x, self.delta_y]
+    }
+
+    /// The size of the scrolled content, as of the last layout.
+    pub fn content_size(&self) -> [f32; 2] {
+        self.content_size
+    }
+
+    /// The size of the visible area of the content, as of the last layout.
+    pub fn viewport_size(&self) -> [f32; 2] {
+        self.view_size
+    }
+
+    /// Set how the scroll bars are shown. Overlay scroll bars are drawn over the content, and
+    /// fade out when idle.
+    pub fn with_scroll_bar_style(mut self, style: ScrollBarStyle) -> Self {
+        self.overlay_bars.set_style(style);
+        self
+    }
+
+    /// The duration, in seconds, of the animation of a scroll event.
+    const SMOOTH_SCROLL_LENGTH: f32 = 0.15;
+
+    /// If true, scroll events, like the mouse wheel, are animated over a short time, instead of
+    /// being applied instantly. Subsequent scroll events accumulate. The default is true.
+    pub fn with_smooth_scroll(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        self
+    }
+
+    fn cancel_smooth_scroll(&mut self, ctx: &mut Context) {
+        if let Some(id) = self.smooth_anim.take() {
+            ctx.remove_animation(id);
+        }
+        self.smooth_remaining = [0.0; 2];
+    }
+
     fn add_delta(&mut self, delta: [f32; 2], ctx: &mut Context) {
         self.delta_x -= delta[0];
         self.delta_y -= delta[1];
@@ -422,6 +690,10 @@ impl ScrollView {
 }
 
 impl Behaviour for ScrollView {
+    fn accessibility_role(&self) -> Role {
+        Role::ScrollView
+    }
+
     fn on_start(&mut self, _this: Id, ctx: &mut Context) {
         debug_assert!({
             let children = ctx.get_all_children(_this);
@@ -455,6 +727,11 @@ impl Behaviour for ScrollView {
         if let Some((v_scroll_bar, _)) = self.v_scroll_bar_and_handle {
             ctx.move_to_front(v_scroll_bar);
         }
+        self.overlay_bars.on_start(ctx);
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.overlay_bars.on_remove(ctx);
     }
 
     fn on_active(&mut self, _: Id, ctx: &mut Context) {
@@ -482,9 +759,14 @@ impl Behaviour for ScrollView {
         }
     }
 
-    fn on_event(&mut self, event: Box<dyn Any>, _: Id, ctx: &mut Context) {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if self.overlay_bars.on_event(&*event, this, ctx) {
+            return;
+        }
         if let Some(event) = event.downcast_ref::<SetScrollPosition>() {
+            self.overlay_bars.show(this, ctx);
             self.momentum_scroll.cancel_scroll(ctx);
+            self.cancel_smooth_scroll(ctx);
             if !event.vertical {
                 let total_size = ctx.get_size(self.content)[0] - ctx.get_size(self.view)[0];
                 self.delta_x = event.value * total_size;
@@ -495,8 +777,54 @@ impl Behaviour for ScrollView {
             ctx.dirty_layout(self.view);
         } else if let Some(event) = event.downcast_ref::<ScrollDelta>() {
             self.add_delta(event.delta, ctx);
+        } else if let Some(&ScrollPage { vertical, pages }) = event.downcast_ref() {
+            self.overlay_bars.show(this, ctx);
+            self.momentum_scroll.cancel_scroll(ctx);
+            self.cancel_smooth_scroll(ctx);
+            let i = vertical as usize;
+            let mut delta = [0.0; 2];
+            delta[i] = -pages * ctx.get_size(self.view)[i];
+            self.add_delta(delta, ctx);
         } else if event.is::<FinishScrollMomentum>() {
             self.momentum_scroll.is_scrolling = false;
+        } else if let Some(&SmoothScrollStep(fraction)) = event.downcast_ref::<SmoothScrollStep>() {
+            if self.smooth_anim.is_none() {
+                return;
+            }
+            let delta = [
+                self.smooth_remaining[0] * fraction,
+                self.smooth_remaining[1] * fraction,
+            ];
+            self.smooth_remaining[0] -= delta[0];
+            self.smooth_remaining[1] -= delta[1];
+            if fraction == 1.0 {
+                self.smooth_anim = None;
+                self.smooth_remaining = [0.0; 2];
+            }
+            self.add_delta(delta, ctx);
+        } else if let Some(&ScrollToView { id, margin }) = event.downcast_ref::<ScrollToView>() {
+            self.momentum_scroll.cancel_scroll(ctx);
+            self.cancel_smooth_scroll(ctx);
+            let view_size = ctx.get_size(self.view);
+            let content_rect = ctx.get_rect(self.content);
+            let rect = ctx.get_rect(id);
+            // the new scroll offset in the given axis
+            let scroll_to = |i: usize, offset: f32| {
+                // the rect of the control, relative to the content, plus the margin.
+                let start = rect[i] - content_rect[i] - margin;
+                let end = rect[i + 2] - content_rect[i] + margin;
+                if start < offset {
+                    start
+                } else if end > offset + view_size[i] {
+                    // but never hide the start of the control
+                    (end - view_size[i]).min(start)
+                } else {
+                    offset
+                }
+            };
+            self.delta_x = scroll_to(0, self.delta_x);
+            self.delta_y = scroll_to(1, self.delta_y);
+            ctx.dirty_layout(self.view);
         }
     }
 
@@ -509,13 +837,39 @@ impl Behaviour for ScrollView {
     }
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        if let MouseEvent::Down(_) = mouse.event {
+            self.cancel_smooth_scroll(ctx);
+        }
         self.momentum_scroll.on_mouse_event(mouse, this, ctx)
     }
 
-    fn on_scroll_event(&mut self, delta: [f32; 2], _: Id, ctx: &mut Context) {
+    fn on_scroll_event(&mut self, scroll: ScrollInfo, this: Id, ctx: &mut Context) {
+        self.overlay_bars.show(this, ctx);
         self.momentum_scroll.cancel_scroll(ctx);
 
-        self.add_delta(delta, ctx);
+        let delta = scroll.delta;
+        // a precise scroll is already smooth, and may carry the momentum of the system
+        if !self.smooth || scroll.is_precise {
+            self.cancel_smooth_scroll(ctx);
+            self.add_delta(delta, ctx);
+            return;
+        }
+
+        self.smooth_remaining[0] += delta[0];
+        self.smooth_remaining[1] += delta[1];
+        // restart the animation, so the accumulated delta is applied over the full length.
+        if let Some(id) = self.smooth_anim.take() {
+            ctx.remove_animation(id);
+        }
+        let id = ctx.add_animation(
+            Self::SMOOTH_SCROLL_LENGTH,
+            move |t: f32, dt: f32, _, ctx: &mut Context| {
+                // the fraction of the remaining delta that corresponds to this step
+                let fraction = if t == 1.0 { 1.0 } else { dt / (1.0 - t + dt) };
+                ctx.send_event_to(this, SmoothScrollStep(fraction));
+            },
+        );
+        self.smooth_anim = Some(id);
     }
 
     fn on_keyboard_event(&mut self, event: KeyboardEvent, _this: Id, ctx: &mut Context) -> bool {
@@ -590,8 +944,10 @@ impl Layout for ScrollView {
         min_size[0] = min_size[0].max(h_scroll_bar_size[0]);
         min_size[1] = min_size[1].max(v_scroll_bar_size[1]);
 
-        min_size[0] += v_scroll_bar_size[0];
-        min_size[1] += h_scroll_bar_size[1];
+        if !self.overlay_bars.is_overlay() {
+            min_size[0] += v_scroll_bar_size[0];
+            min_size[1] += h_scroll_bar_size[1];
+        }
 
         min_size
     }
@@ -601,6 +957,8 @@ impl Layout for ScrollView {
         let content_size = ctx.get_min_size(self.content);
         let this_width = this_rect[2] - this_rect[0];
         let this_height = this_rect[3] - this_rect[1];
+        // overlay scroll bars are drawn over the view, and don't take space from it.
+        let overlay = self.overlay_bars.is_overlay();
 
         let mut h_active;
         let mut h_scroll_bar_size;
@@ -624,7 +982,8 @@ impl Layout for ScrollView {
         let v_scroll_bar;
         if let Some((_v_scroll_bar, _)) = self.v_scroll_bar_and_handle {
             v_scroll_bar = _v_scroll_bar;
-            v_active = this_height - h_scroll_bar_size < content_size[1];
+            let h_reserved = if overlay { 0.0 } else { h_scroll_bar_size };
+            v_active = this_height - h_reserved < content_size[1];
             v_scroll_bar_size = if v_active {
                 ctx.get_min_size(v_scroll_bar)[0]
             } else {
@@ -637,7 +996,8 @@ impl Layout for ScrollView {
         }
 
         if let Some((_h_scroll_bar, _)) = self.h_scroll_bar_and_handle {
-            if !h_active && this_width - v_scroll_bar_size < content_size[0] {
+            let v_reserved = if overlay { 0.0 } else { v_scroll_bar_size };
+            if !h_active && this_width - v_reserved < content_size[0] {
                 h_active = true;
                 h_scroll_bar = _h_scroll_bar;
                 h_scroll_bar_size = ctx.get_min_size(h_scroll_bar)[1];
@@ -688,6 +1048,12 @@ impl Layout for ScrollView {
             );
         }
 
+        let (h_scroll_bar_size, v_scroll_bar_size) = if overlay {
+            (0.0, 0.0)
+        } else {
+            (h_scroll_bar_size, v_scroll_bar_size)
+        };
+
         ctx.set_designed_rect(
             self.view,
             [
@@ -747,5 +1113,8 @@ impl Layout for ScrollView {
         }
 
         ctx.set_designed_rect(self.content, content_rect);
+
+        self.content_size = content_size;
+        self.view_size = [view_width, view_height];
     }
 }
@@ -1,10 +1,14 @@
-use std::{any::Any, rc::Rc};
+use std::{any::Any, cmp::Ordering, rc::Rc};
 
-use event::SetValue;
+use event::{SetEnabled, SetValue};
+
+use winit::event::VirtualKeyCode;
 
 use crate::{
-    event, style::OnFocusStyle, Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent,
-    MouseInfo,
+    accessibility::{AccessValue, Role},
+    event,
+    style::OnFocusStyle,
+    Behaviour, Context, Id, InputFlags, KeyboardEvent, MouseButton, MouseEvent, MouseInfo,
 };
 
 pub struct SetMinValue(pub i32);
@@ -25,14 +29,40 @@ impl SliderCallback for () {
     fn on_release(&mut self, _: Id, _: &mut Context, _: i32) {}
 }
 
+/// Return the anchor, relative to `this_rect`, of the point at `rel` (a value between 0 and 1) of
+/// the `area_rect`, in the given axis (0 for x, 1 for y).
+fn anchor_in_area(this_rect: &[f32; 4], area_rect: &[f32; 4], rel: f32, axis: usize) -> f32 {
+    let i = axis;
+    let size = this_rect[i + 2] - this_rect[i];
+    let margin_start = (area_rect[i] - this_rect[i]) / size;
+    let margin_end = (this_rect[i + 2] - area_rect[i + 2]) / size;
+    margin_start + (1.0 - margin_start - margin_end) * rel
+}
+
+/// Return the position of `value` in the range from `min` to `max`, between 0 and 1. If the range
+/// is empty, return 0.
+fn rel_in_range(value: i32, min: i32, max: i32) -> f32 {
+    if max <= min {
+        return 0.0;
+    }
+    let rel = (value - min) as f32 / (max - min) as f32;
+    rel.max(0.0).min(1.0)
+}
+
 pub struct Slider<C: SliderCallback> {
     handle: Id,
     slide_area: Id, //TODO: I should remove this slide_area
     dragging: bool,
-    mouse_x: f32,
+    disabled: bool,
+    /// The position of the mouse, in the axis of the slider.
+    mouse_pos: f32,
+    /// If true, the handle moves along the y axis, with the bottom being the min value.
+    vertical: bool,
     min: i32,
     max: i32,
     value: i32,
+    /// If non zero, the value is snapped to the nearest `min + k * step`.
+    step: i32,
     style: Rc<OnFocusStyle>,
     callback: C,
 }
@@ -50,52 +80,153 @@ impl<C: SliderCallback> Slider<C> {
             handle,
             slide_area,
             dragging: false,
-            mouse_x: 0.0,
+            disabled: false,
+            mouse_pos: 0.0,
+            vertical: false,
             max,
             min,
             value: start_value,
+            step: 0,
             style,
             callback,
         }
     }
 
-    fn update_value(&mut self, ctx: &mut Context) {
+    /// Create a vertical slider. The handle moves along the y axis, with the bottom of the
+    /// slide_area mapping to `min`, and the top to `max`.
+    pub fn new_vertical(
+        handle: Id,
+        slide_area: Id,
+        min: i32,
+        max: i32,
+        start_value: i32,
+        style: Rc<OnFocusStyle>,
+        callback: C,
+    ) -> Self {
+        Self {
+            vertical: true,
+            ..Self::new(handle, slide_area, min, max, start_value, style, callback)
+        }
+    }
+
+    /// Snap the value to the nearest `min + k * step`, for a integer `k`, that is between min and
+    /// max. If `step` is 0, the value change continuously.
+    pub fn with_step(mut self, step: i32) -> Self {
+        self.step = step.abs();
+        self.value = self.snap(self.value);
+        self
+    }
+
+    /// Round the value to the nearest `min + k * step`, that is between min and max.
+    fn snap(&self, value: i32) -> i32 {
+        let value = value.max(self.min).min(self.max);
+        if self.step == 0 {
+            return value;
+        }
+        let steps = ((value - self.min) as f32 / self.step as f32).round() as i32;
+        let mut snapped = self.min + steps * self.step;
+        // if the range is not a multiple of step, rounding up may go past max
+        if snapped > self.max {
+            snapped -= self.step;
+        }
+        snapped.max(self.min).min(self.max)
+    }
+
+    /// Snap the current value again, after the range has changed, calling the callback if the
+    /// value has changed.
+    fn resnap(&mut self, this: Id, ctx: &mut Context) {
+        let value = self.snap(self.value);
+        let changed = value != self.value;
+        self.value = value;
+        self.set_handle_pos(this, ctx);
+        if changed {
+            self.callback.on_change(this, ctx, value);
+        }
+    }
+
+    /// Update the value based on the mouse position. Return true if the value has changed.
+    fn update_value(&mut self, ctx: &mut Context) -> bool {
         let area_rect = ctx.get_rect(self.slide_area);
-        let mut rel_x = (self.mouse_x - area_rect[0]) / (area_rect[2] - area_rect[0]);
-        rel_x = rel_x.max(0.0).min(1.0);
-        self.value = (rel_x * (self.max - self.min) as f32).round() as i32 + self.min;
+        let i = self.vertical as usize;
+        let mut rel = (self.mouse_pos - area_rect[i]) / (area_rect[i + 2] - area_rect[i]);
+        rel = rel.max(0.0).min(1.0);
+        if self.vertical {
+            rel = 1.0 - rel;
+        }
+        let value = (rel * (self.max - self.min) as f32).round() as i32 + self.min;
+        let value = self.snap(value);
+        let changed = value != self.value;
+        self.value = value;
+        changed
     }
 
     fn set_handle_pos(&mut self, this: Id, ctx: &mut Context) {
+        // the handle is moved every time the value changes
+        ctx.dirty_accessibility();
         let this_rect = ctx.get_rect(this);
         let area_rect = ctx.get_rect(self.slide_area);
+        let i = self.vertical as usize;
 
-        let mut rel_x = (self.value - self.min) as f32 / (self.max - self.min) as f32;
-        rel_x = rel_x.max(0.0).min(1.0);
+        let mut rel = rel_in_range(self.value, self.min, self.max);
+        if self.vertical {
+            rel = 1.0 - rel;
+        }
 
-        let margin_left = (area_rect[0] - this_rect[0]) / (this_rect[2] - this_rect[0]);
-        let margin_right = (this_rect[2] - area_rect[2]) / (this_rect[2] - this_rect[0]);
-        let x = margin_left + (1.0 - margin_left - margin_right) * rel_x;
+        let x = anchor_in_area(&this_rect, &area_rect, rel, i);
 
-        ctx.set_anchor_left(self.handle, x);
-        ctx.set_anchor_right(self.handle, x);
+        if self.vertical {
+            ctx.set_anchor_top(self.handle, x);
+            ctx.set_anchor_bottom(self.handle, x);
+        } else {
+            ctx.set_anchor_left(self.handle, x);
+            ctx.set_anchor_right(self.handle, x);
+        }
+    }
+
+    /// Set the value, snapped and clamped between min and max, calling the callback if it has
+    /// changed.
+    fn change_value(&mut self, value: i32, this: Id, ctx: &mut Context) {
+        let value = self.snap(value);
+        if value != self.value {
+            self.value = value;
+            self.set_handle_pos(this, ctx);
+            self.callback.on_change(this, ctx, value);
+        }
     }
 }
 impl<C: SliderCallback> Behaviour for Slider<C> {
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        Some(AccessValue::Numeric {
+            value: self.value as f64,
+            min: self.min as f64,
+            max: self.max as f64,
+        })
+    }
+
     fn on_active(&mut self, this: Id, ctx: &mut Context) {
         self.set_handle_pos(this, ctx);
         ctx.set_graphic(this, self.style.normal.clone());
     }
 
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
-        if let Some(SetMaxValue(x)) = event.downcast_ref::<SetMaxValue>() {
+        if let Some(SetEnabled(enabled)) = event.downcast_ref() {
+            self.disabled = !enabled;
+            self.dragging = false;
+            if self.disabled {
+                ctx.lose_focus(this);
+            }
+        } else if let Some(SetMaxValue(x)) = event.downcast_ref::<SetMaxValue>() {
             self.max = *x;
-            self.set_handle_pos(this, ctx);
+            self.resnap(this, ctx);
         } else if let Some(SetMinValue(x)) = event.downcast_ref::<SetMinValue>() {
             self.min = *x;
-            self.set_handle_pos(this, ctx);
+            self.resnap(this, ctx);
         } else if let Some(SetValue(x)) = event.downcast_ref::<SetValue<i32>>() {
-            self.value = *x;
+            self.value = self.snap(*x);
             self.set_handle_pos(this, ctx);
             self.callback.on_change(this, ctx, self.value);
         }
@@ -110,6 +241,9 @@ impl<C: SliderCallback> Behaviour for Slider<C> {
     }
 
     fn input_flags(&self) -> InputFlags {
+        if self.disabled {
+            return InputFlags::empty();
+        }
         InputFlags::MOUSE | InputFlags::FOCUS
     }
 
@@ -121,10 +255,12 @@ impl<C: SliderCallback> Behaviour for Slider<C> {
             MouseEvent::Down(Left) => {
                 self.dragging = true;
                 ctx.lock_cursor(true, mouse.id);
-                self.update_value(ctx);
+                let changed = self.update_value(ctx);
                 self.set_handle_pos(this, ctx);
-                let value = self.value;
-                self.callback.on_change(this, ctx, value);
+                if changed {
+                    let value = self.value;
+                    self.callback.on_change(this, ctx, value);
+                }
             }
             MouseEvent::Up(Left) => {
                 self.dragging = false;
@@ -134,10 +270,8 @@ impl<C: SliderCallback> Behaviour for Slider<C> {
                 ctx.lock_cursor(false, mouse.id);
             }
             MouseEvent::Moved => {
-                let [x, _] = mouse.pos;
-                self.mouse_x = x;
-                if self.dragging {
-                    self.update_value(ctx);
+                self.mouse_pos = mouse.pos[self.vertical as usize];
+                if self.dragging && self.update_value(ctx) {
                     self.set_handle_pos(this, ctx);
                     let value = self.value;
                     self.callback.on_change(this, ctx, value);
@@ -148,4 +282,255 @@ impl<C: SliderCallback> Behaviour for Slider<C> {
             MouseEvent::None => {}
         }
     }
+
+    fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let step = self.step.max(1);
+        // a page is 10% of the range, but at least one step
+        let page = ((self.max - self.min) / 10).max(step);
+        let value = match event {
+            KeyboardEvent::Pressed(key) => match key {
+                VirtualKeyCode::Right | VirtualKeyCode::Up => self.value + step,
+                VirtualKeyCode::Left | VirtualKeyCode::Down => self.value - step,
+                VirtualKeyCode::PageUp => self.value + page,
+                VirtualKeyCode::PageDown => self.value - page,
+                VirtualKeyCode::Home => self.min,
+                VirtualKeyCode::End => self.max,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        self.change_value(value, this, ctx);
+        true
+    }
+}
+
+pub trait RangeSliderCallback {
+    fn on_change(&mut self, this: Id, ctx: &mut Context, value: (i32, i32));
+    fn on_release(&mut self, this: Id, ctx: &mut Context, value: (i32, i32));
+}
+impl<F: Fn(Id, &mut Context, (i32, i32))> RangeSliderCallback for F {
+    fn on_change(&mut self, this: Id, ctx: &mut Context, value: (i32, i32)) {
+        self(this, ctx, value)
+    }
+    fn on_release(&mut self, _this: Id, _ctx: &mut Context, _value: (i32, i32)) {}
+}
+impl RangeSliderCallback for () {
+    fn on_change(&mut self, _: Id, _: &mut Context, _: (i32, i32)) {}
+    fn on_release(&mut self, _: Id, _: &mut Context, _: (i32, i32)) {}
+}
+
+/// The behaviour of each handle of a [`RangeSlider`]. It only makes the handle focusable, and
+/// draw its focus graphic. Keyboard events are handled by the parent [`RangeSlider`].
+pub struct RangeSliderHandle {
+    style: Rc<OnFocusStyle>,
+}
+impl RangeSliderHandle {
+    pub fn new(style: Rc<OnFocusStyle>) -> Self {
+        Self { style }
+    }
+}
+impl Behaviour for RangeSliderHandle {
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        ctx.set_graphic(this, self.style.normal.clone());
+    }
+
+    fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
+        if focus {
+            ctx.set_graphic(this, self.style.focus.clone());
+        } else {
+            ctx.set_graphic(this, self.style.normal.clone());
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::FOCUS
+    }
+}
+
+/// A slider with two handles, for selecting a range of values between min and max.
+///
+/// Each handle should be a child control with a [`RangeSliderHandle`] behaviour, so they can be
+/// focused independently. The handles never cross each other. The `fill` control is anchored
+/// between the two handles.
+pub struct RangeSlider<C: RangeSliderCallback> {
+    handles: [Id; 2],
+    fill: Id,
+    slide_area: Id,
+    /// The index of the handle being dragged, if any.
+    dragging: Option<usize>,
+    min: i32,
+    max: i32,
+    values: [i32; 2],
+    callback: C,
+}
+impl<C: RangeSliderCallback> RangeSlider<C> {
+    pub fn new(
+        handles: [Id; 2],
+        fill: Id,
+        slide_area: Id,
+        min: i32,
+        max: i32,
+        start_value: (i32, i32),
+        callback: C,
+    ) -> Self {
+        let (start, end) = start_value;
+        Self {
+            handles,
+            fill,
+            slide_area,
+            dragging: None,
+            min,
+            max,
+            values: [start.min(end), start.max(end)],
+            callback,
+        }
+    }
+
+    fn value(&self) -> (i32, i32) {
+        (self.values[0], self.values[1])
+    }
+
+    /// Return the value at the given x position.
+    fn value_at(&self, x: f32, ctx: &mut Context) -> i32 {
+        let area_rect = ctx.get_rect(self.slide_area);
+        let mut rel = (x - area_rect[0]) / (area_rect[2] - area_rect[0]);
+        rel = rel.max(0.0).min(1.0);
+        (rel * (self.max - self.min) as f32).round() as i32 + self.min
+    }
+
+    fn set_handles_pos(&mut self, this: Id, ctx: &mut Context) {
+        let this_rect = ctx.get_rect(this);
+        let area_rect = ctx.get_rect(self.slide_area);
+
+        let anchors: Vec<f32> = self
+            .values
+            .iter()
+            .map(|&value| {
+                let rel = rel_in_range(value, self.min, self.max);
+                anchor_in_area(&this_rect, &area_rect, rel, 0)
+            })
+            .collect();
+        for (&handle, &x) in self.handles.iter().zip(anchors.iter()) {
+            ctx.set_anchor_left(handle, x);
+            ctx.set_anchor_right(handle, x);
+        }
+        ctx.set_anchor_left(self.fill, anchors[0]);
+        ctx.set_anchor_right(self.fill, anchors[1]);
+    }
+
+    /// Clamp the values of both handles to the range, after it or the values have changed, calling
+    /// the callback if `changed` is true or if the values have changed.
+    fn clamp_values(&mut self, changed: bool, this: Id, ctx: &mut Context) {
+        let old = self.values;
+        for value in self.values.iter_mut() {
+            *value = (*value).max(self.min).min(self.max);
+        }
+        self.set_handles_pos(this, ctx);
+        if changed || self.values != old {
+            let value = self.value();
+            self.callback.on_change(this, ctx, value);
+        }
+    }
+
+    /// Set the value of the given handle, clamped so that the handles don't cross, calling the
+    /// callback if it has changed.
+    fn change_value(&mut self, handle: usize, value: i32, this: Id, ctx: &mut Context) {
+        let value = if handle == 0 {
+            value.max(self.min).min(self.values[1])
+        } else {
+            value.max(self.values[0]).min(self.max)
+        };
+        if value != self.values[handle] {
+            self.values[handle] = value;
+            self.set_handles_pos(this, ctx);
+            let value = self.value();
+            self.callback.on_change(this, ctx, value);
+        }
+    }
+}
+impl<C: RangeSliderCallback> Behaviour for RangeSlider<C> {
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        self.set_handles_pos(this, ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetMaxValue(x)) = event.downcast_ref::<SetMaxValue>() {
+            self.max = *x;
+            self.clamp_values(false, this, ctx);
+        } else if let Some(SetMinValue(x)) = event.downcast_ref::<SetMinValue>() {
+            self.min = *x;
+            self.clamp_values(false, this, ctx);
+        } else if let Some(SetValue((a, b))) = event.downcast_ref::<SetValue<(i32, i32)>>() {
+            self.values = [*a.min(b), *a.max(b)];
+            self.clamp_values(true, this, ctx);
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        use MouseButton::*;
+        match mouse.event {
+            MouseEvent::Down(Left) => {
+                let value = self.value_at(mouse.pos[0], ctx);
+                // drag the closest handle. If both are at the same value, pick the one that can
+                // move in the direction of the mouse.
+                let [start, end] = self.values;
+                let handle = match (value - start).abs().cmp(&(value - end).abs()) {
+                    Ordering::Less => 0,
+                    Ordering::Greater => 1,
+                    Ordering::Equal if value < start => 0,
+                    Ordering::Equal => 1,
+                };
+                self.dragging = Some(handle);
+                ctx.set_focus(self.handles[handle]);
+                ctx.lock_cursor(true, mouse.id);
+                self.change_value(handle, value, this, ctx);
+            }
+            MouseEvent::Up(Left) => {
+                if self.dragging.take().is_some() {
+                    let value = self.value();
+                    self.callback.on_release(this, ctx, value);
+                    ctx.lock_cursor(false, mouse.id);
+                }
+            }
+            MouseEvent::Moved => {
+                if let Some(handle) = self.dragging {
+                    let value = self.value_at(mouse.pos[0], ctx);
+                    self.change_value(handle, value, this, ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
+        let focus = ctx.get_focus();
+        let handle = match self.handles.iter().position(|x| Some(*x) == focus) {
+            Some(x) => x,
+            None => return false,
+        };
+        let current = self.values[handle];
+        // a page is 10% of the range
+        let page = ((self.max - self.min) / 10).max(1);
+        let value = match event {
+            KeyboardEvent::Pressed(key) => match key {
+                VirtualKeyCode::Right | VirtualKeyCode::Up => current + 1,
+                VirtualKeyCode::Left | VirtualKeyCode::Down => current - 1,
+                VirtualKeyCode::PageUp => current + page,
+                VirtualKeyCode::PageDown => current - page,
+                VirtualKeyCode::Home => self.min,
+                VirtualKeyCode::End => self.max,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        self.change_value(handle, value, this, ctx);
+        true
+    }
 }
@@ -0,0 +1,70 @@
+use std::any::Any;
+
+use crate::{AnimationId, Behaviour, Context, Id};
+
+/// Sent by the spinner animation, with the current time of the animation.
+struct SpinnerStep(f32);
+
+/// A loading indicator, that continuously rotates the Icon or Texture graphic of its control.
+///
+/// The animation only runs while the control is active.
+pub struct Spinner {
+    /// The speed of the rotation, in revolutions per second. A negative value rotates
+    /// counter-clockwise.
+    speed: f32,
+    animation: Option<AnimationId>,
+}
+impl Spinner {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            animation: None,
+        }
+    }
+
+    fn start_animation(&mut self, this: Id, ctx: &mut Context) {
+        self.stop_animation(ctx);
+        if self.speed == 0.0 {
+            return;
+        }
+        // each animation is a single revolution
+        let length = 1.0 / self.speed.abs();
+        let id = ctx.add_animation(length, move |t, _, _, ctx: &mut Context| {
+            ctx.send_event_to(this, SpinnerStep(t))
+        });
+        self.animation = Some(id);
+    }
+
+    fn stop_animation(&mut self, ctx: &mut Context) {
+        if let Some(id) = self.animation.take() {
+            ctx.remove_animation(id);
+        }
+    }
+}
+impl Behaviour for Spinner {
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        self.start_animation(this, ctx);
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.stop_animation(ctx);
+    }
+
+    fn on_remove(&mut self, _this: Id, ctx: &mut Context) {
+        self.stop_animation(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SpinnerStep(t)) = event.downcast_ref::<SpinnerStep>() {
+            if self.animation.is_none() {
+                return;
+            }
+            let angle = self.speed.signum() * t * std::f32::consts::TAU;
+            ctx.get_graphic_mut(this).set_rotation(angle);
+            // restart the animation, so it loops
+            if *t == 1.0 {
+                self.start_animation(this, ctx);
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::any::Any;
+
+use winit::window::CursorIcon;
+
+use crate::{
+    accessibility::Role, event::SetValue, Behaviour, Context, Id, InputFlags, Layout,
+    LayoutContext, MinSizeContext, MouseButton, MouseEvent, MouseInfo,
+};
+
+/// Sent by the [`SplitHandle`] to its [`SplitPane`] while being dragged.
+enum SplitDrag {
+    Start,
+    /// The offset of the mouse, in pixels, since the start of the drag.
+    Move([f32; 2]),
+}
+
+/// Lays out two children side by side, with a draggable handle between them.
+///
+/// The hiearchy of controls must be the following:
+///
+/// ```text
+/// split_pane : SplitPane
+/// ├─ first
+/// ├─ handle : SplitHandle
+/// └─ second
+/// ```
+///
+/// The size of the handle is its min size, in the axis of the split. The remaining space is
+/// divided between `first` and `second` by the split ratio, but never smaller than their min
+/// size. Use [`SetValue<f32>`](SetValue) to set the ratio.
+pub struct SplitPane {
+    first: Id,
+    handle: Id,
+    second: Id,
+    /// If true, `first` is above `second`. Otherwise, `first` is on the left.
+    vertical: bool,
+    /// The fraction of the available space that is given to `first`.
+    ratio: f32,
+    /// The ratio at the start of the current drag.
+    drag_start_ratio: f32,
+    on_change: Option<Box<dyn FnMut(Id, &mut Context, f32)>>,
+}
+impl SplitPane {
+    pub fn new(first: Id, handle: Id, second: Id, vertical: bool, ratio: f32) -> Self {
+        Self {
+            first,
+            handle,
+            second,
+            vertical,
+            ratio: ratio.max(0.0).min(1.0),
+            drag_start_ratio: 0.0,
+            on_change: None,
+        }
+    }
+
+    /// Set a callback that is called every time the split ratio changes by dragging the handle.
+    pub fn with_callback<F: FnMut(Id, &mut Context, f32) + 'static>(
+        mut self,
+        on_change: F,
+    ) -> Self {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    /// The fraction of the available space that is given to the first child.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(0.0).min(1.0);
+    }
+}
+impl Behaviour for SplitPane {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetValue(x)) = event.downcast_ref::<SetValue<f32>>() {
+            self.set_ratio(*x);
+            ctx.dirty_layout(this);
+        } else if let Some(drag) = event.downcast_ref::<SplitDrag>() {
+            match drag {
+                SplitDrag::Start => self.drag_start_ratio = self.ratio,
+                SplitDrag::Move(offset) => {
+                    let i = self.vertical as usize;
+                    let size = ctx.get_size(this)[i] - ctx.get_min_size(self.handle)[i];
+                    if size <= 0.0 {
+                        return;
+                    }
+                    let min = ctx.get_min_size(self.first)[i] / size;
+                    let max = 1.0 - ctx.get_min_size(self.second)[i] / size;
+                    let ratio = (self.drag_start_ratio + offset[i] / size)
+                        .min(max)
+                        .max(min)
+                        .max(0.0)
+                        .min(1.0);
+                    if ratio != self.ratio {
+                        self.ratio = ratio;
+                        ctx.dirty_layout(this);
+                        if let Some(on_change) = &mut self.on_change {
+                            on_change(this, ctx, ratio);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+impl Layout for SplitPane {
+    fn compute_min_size(&mut self, _this: Id, ctx: &mut MinSizeContext) -> [f32; 2] {
+        let i = self.vertical as usize;
+        let mut min_size = [0.0f32; 2];
+        for child in [self.first, self.handle, self.second] {
+            let child_min_size = ctx.get_min_size(child);
+            min_size[i] += child_min_size[i];
+            min_size[1 - i] = min_size[1 - i].max(child_min_size[1 - i]);
+        }
+        min_size
+    }
+
+    fn update_layouts(&mut self, this: Id, ctx: &mut LayoutContext) {
+        let rect = ctx.get_rect(this);
+        let i = self.vertical as usize;
+        let handle_size = ctx.get_min_size(self.handle)[i];
+        let first_min = ctx.get_min_size(self.first)[i];
+        let second_min = ctx.get_min_size(self.second)[i];
+
+        let available = (rect[i + 2] - rect[i] - handle_size).max(0.0);
+        let first_size = (available * self.ratio)
+            .min(available - second_min)
+            .max(first_min);
+
+        let start = rect[i];
+        let splits = [
+            start,
+            start + first_size,
+            start + first_size + handle_size,
+            rect[i + 2],
+        ];
+        for (j, child) in [self.first, self.handle, self.second].iter().enumerate() {
+            let mut child_rect = rect;
+            child_rect[i] = splits[j].min(rect[i + 2]);
+            child_rect[i + 2] = splits[j + 1].max(child_rect[i]);
+            ctx.set_designed_rect(*child, child_rect);
+        }
+    }
+}
+
+/// The draggable handle of a [`SplitPane`]. Must be a direct child of the SplitPane.
+pub struct SplitHandle {
+    vertical: bool,
+    dragging: bool,
+    drag_start: [f32; 2],
+}
+impl SplitHandle {
+    /// `vertical` must be the same as the one of the parent [`SplitPane`].
+    pub fn new(vertical: bool) -> Self {
+        Self {
+            vertical,
+            dragging: false,
+            drag_start: [0.0; 2],
+        }
+    }
+}
+impl Behaviour for SplitHandle {
+    fn accessibility_role(&self) -> Role {
+        Role::Splitter
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        let split_pane = match ctx.get_parent(this) {
+            Some(x) => x,
+            None => return,
+        };
+        match mouse.event {
+            MouseEvent::Enter => {
+                ctx.set_cursor(if self.vertical {
+                    CursorIcon::NsResize
+                } else {
+                    CursorIcon::EwResize
+                });
+            }
+            MouseEvent::Exit => {
+                if !self.dragging {
+                    ctx.set_cursor(CursorIcon::Default);
+                }
+            }
+            MouseEvent::Down(MouseButton::Left) => {
+                self.dragging = true;
+                self.drag_start = mouse.pos;
+                ctx.lock_cursor(true, mouse.id);
+                ctx.send_event_to(split_pane, SplitDrag::Start);
+            }
+            MouseEvent::Up(MouseButton::Left) => {
+                self.dragging = false;
+                ctx.lock_cursor(false, mouse.id);
+            }
+            MouseEvent::Moved => {
+                if self.dragging {
+                    let offset = [
+                        mouse.pos[0] - self.drag_start[0],
+                        mouse.pos[1] - self.drag_start[1],
+                    ];
+                    ctx.send_event_to(split_pane, SplitDrag::Move(offset));
+                }
+            }
+            _ => {}
+        }
+    }
+}
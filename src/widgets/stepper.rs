@@ -0,0 +1,238 @@
+use std::{any::Any, rc::Rc, time::Duration};
+
+use winit::event::VirtualKeyCode;
+
+use crate::{
+    accessibility::{AccessValue, Role},
+    event::SetValue,
+    style::ButtonStyle,
+    time::Instant,
+    Behaviour, Context, Id, InputFlags, KeyboardEvent, MouseButton, MouseEvent, MouseInfo,
+};
+
+use super::TextFieldCallback;
+
+/// Increment the value of a [`Stepper`] by the given number of steps. Negative values decrement
+/// it.
+pub struct StepBy(pub i32);
+
+/// Sent to the [`Stepper`] to make it rewrite its value to its text field.
+struct RefreshText;
+
+/// Scheduled while a [`StepperButton`] is held, for auto-repeat.
+struct RepeatStep;
+
+/// A numeric input, combining a [`TextField`](super::TextField) with up/down buttons.
+///
+/// The hiearchy of controls must be the following:
+///
+/// ```text
+/// stepper : Stepper
+/// ├─ text_field : TextField<StepperField>
+/// ├─ up : StepperButton
+/// └─ down : StepperButton
+/// ```
+///
+/// The value is always clamped between min and max. The callback is called every time the value
+/// changes.
+pub struct Stepper<F: FnMut(Id, &mut Context, i32)> {
+    text_field: Id,
+    min: i32,
+    max: i32,
+    step: i32,
+    value: i32,
+    on_change: F,
+}
+impl<F: FnMut(Id, &mut Context, i32)> Stepper<F> {
+    pub fn new(text_field: Id, min: i32, max: i32, start_value: i32, on_change: F) -> Self {
+        Self {
+            text_field,
+            min,
+            max,
+            step: 1,
+            value: start_value.max(min).min(max),
+            on_change,
+        }
+    }
+
+    /// The amount the value is incremented or decremented by each step. The default is 1.
+    pub fn with_step(mut self, step: i32) -> Self {
+        self.step = step;
+        self
+    }
+
+    fn set_value(&mut self, value: i32, this: Id, ctx: &mut Context) {
+        let value = value.max(self.min).min(self.max);
+        ctx.send_event_to(self.text_field, SetValue(value.to_string()));
+        if value != self.value {
+            self.value = value;
+            ctx.dirty_accessibility();
+            (self.on_change)(this, ctx, value);
+        }
+    }
+}
+impl<F: FnMut(Id, &mut Context, i32)> Behaviour for Stepper<F> {
+    fn accessibility_role(&self) -> Role {
+        Role::SpinButton
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        Some(AccessValue::Numeric {
+            value: self.value as f64,
+            min: self.min as f64,
+            max: self.max as f64,
+        })
+    }
+
+    fn on_start(&mut self, _this: Id, ctx: &mut Context) {
+        ctx.send_event_to(self.text_field, SetValue(self.value.to_string()));
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetValue(x)) = event.downcast_ref::<SetValue<i32>>() {
+            self.set_value(*x, this, ctx);
+        } else if let Some(StepBy(x)) = event.downcast_ref::<StepBy>() {
+            let value = self.value.saturating_add(x.saturating_mul(self.step));
+            self.set_value(value, this, ctx);
+        } else if event.is::<RefreshText>() {
+            ctx.send_event_to(self.text_field, SetValue(self.value.to_string()));
+        }
+    }
+}
+
+/// The [`TextFieldCallback`] of the text field of a [`Stepper`].
+///
+/// The text is parsed when submitted or unfocused. If it is not a valid integer, the text is
+/// restored to the previous value. The Up and Down keys increment and decrement the value.
+pub struct StepperField {
+    stepper: Id,
+}
+impl StepperField {
+    pub fn new(stepper: Id) -> Self {
+        Self { stepper }
+    }
+}
+impl TextFieldCallback for StepperField {
+    fn on_submit(&mut self, _this: Id, ctx: &mut Context, text: &mut String) {
+        match text.trim().parse::<i32>() {
+            Ok(x) => ctx.send_event_to(self.stepper, SetValue(x)),
+            Err(_) => ctx.send_event_to(self.stepper, RefreshText),
+        }
+    }
+
+    fn on_change(&mut self, _: Id, _: &mut Context, _: &str) {}
+
+    fn on_unfocus(&mut self, this: Id, ctx: &mut Context, text: &mut String) {
+        self.on_submit(this, ctx, text)
+    }
+
+    fn on_keyboard_event(&mut self, event: KeyboardEvent, _this: Id, ctx: &mut Context) -> bool {
+        match event {
+            KeyboardEvent::Pressed(VirtualKeyCode::Up) => {
+                ctx.send_event_to(self.stepper, StepBy(1));
+                true
+            }
+            KeyboardEvent::Pressed(VirtualKeyCode::Down) => {
+                ctx.send_event_to(self.stepper, StepBy(-1));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A up or down button of a [`Stepper`]. While held, it repeatedly steps the value.
+pub struct StepperButton {
+    stepper: Id,
+    /// 1 for the up button, -1 for the down button.
+    direction: i32,
+    pressed: bool,
+    /// The event_id of the scheduled RepeatStep event, if any.
+    repeat_event: Option<u64>,
+    style: Rc<ButtonStyle>,
+}
+impl StepperButton {
+    /// The delay before the auto-repeat starts.
+    const REPEAT_DELAY: Duration = Duration::from_millis(400);
+    /// The interval between each step, while auto-repeating.
+    const REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+    pub fn new(stepper: Id, up: bool, style: Rc<ButtonStyle>) -> Self {
+        Self {
+            stepper,
+            direction: if up { 1 } else { -1 },
+            pressed: false,
+            repeat_event: None,
+            style,
+        }
+    }
+
+    fn stop_repeat(&mut self, ctx: &mut Context) {
+        self.pressed = false;
+        if let Some(event_id) = self.repeat_event.take() {
+            ctx.cancel_scheduled_event(event_id);
+        }
+    }
+}
+impl Behaviour for StepperButton {
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn on_active(&mut self, this: Id, ctx: &mut Context) {
+        ctx.set_graphic(this, self.style.normal.clone());
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.stop_repeat(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if event.is::<RepeatStep>() {
+            self.repeat_event = None;
+            if self.pressed {
+                ctx.send_event_to(self.stepper, StepBy(self.direction));
+                let event_id = ctx.send_event_to_scheduled(
+                    this,
+                    RepeatStep,
+                    Instant::now() + Self::REPEAT_INTERVAL,
+                );
+                self.repeat_event = Some(event_id);
+            }
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        use MouseButton::*;
+        match mouse.event {
+            MouseEvent::Enter => {
+                ctx.set_graphic(this, self.style.hover.clone());
+            }
+            MouseEvent::Exit => {
+                self.stop_repeat(ctx);
+                ctx.set_graphic(this, self.style.normal.clone());
+            }
+            MouseEvent::Down(Left) => {
+                ctx.set_graphic(this, self.style.pressed.clone());
+                ctx.send_event_to(self.stepper, StepBy(self.direction));
+                self.stop_repeat(ctx);
+                self.pressed = true;
+                let event_id = ctx.send_event_to_scheduled(
+                    this,
+                    RepeatStep,
+                    Instant::now() + Self::REPEAT_DELAY,
+                );
+                self.repeat_event = Some(event_id);
+            }
+            MouseEvent::Up(Left) => {
+                self.stop_repeat(ctx);
+                ctx.set_graphic(this, self.style.hover.clone());
+            }
+            _ => {}
+        }
+    }
+}
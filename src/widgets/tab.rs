@@ -1,34 +1,70 @@
 use std::{any::Any, cell::RefCell, rc::Rc};
 
 use crate::{
-    style::TabStyle, Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    accessibility::Role, style::TabStyle, Behaviour, Context, Id, InputFlags, MouseButton,
+    MouseEvent, MouseInfo,
 };
 
 struct Unselected;
 pub struct Select;
 
+/// Sent to the parent of a [`TabButton`], the tab strip, when the close button of the tab is
+/// clicked. The owner is expected to remove the tab and its page.
+///
+/// If the closed tab is selected, the next tab in the strip is selected before this is sent,
+/// or the previous one if it is the last tab. If there is no other tab, none is left selected,
+/// and `on_change` of the group is only called if it is
+/// [deselectable](ButtonGroup::new_deselectable).
+pub struct TabClosed {
+    /// The id of the TabButton.
+    pub id: Id,
+}
+
 struct ButtonGroupInner {
     selected: Option<Id>,
-    on_change: Box<dyn Fn(Id, &mut Context)>,
+    allow_none: bool,
+    on_change: Box<dyn Fn(Option<Id>, &mut Context)>,
 }
 
 #[derive(Clone)]
 /// It is basically a Rc<RefCell<Option<Id>>>.
 pub struct ButtonGroup(Rc<RefCell<ButtonGroupInner>>);
 impl ButtonGroup {
+    /// Create a group where exactly one button is selected. Clicking the selected button does
+    /// nothing.
     pub fn new<F: Fn(Id, &mut Context) + 'static>(on_change: F) -> Self {
         Self(Rc::new(RefCell::new(ButtonGroupInner {
             selected: None,
+            allow_none: false,
+            on_change: Box::new(move |selected, ctx| {
+                on_change(selected.expect("None selected is not allowed"), ctx)
+            }),
+        })))
+    }
+
+    /// Create a group where at most one button is selected. Clicking the selected button
+    /// deselects it, leaving none selected, and `on_change` is called with None.
+    pub fn new_deselectable<F: Fn(Option<Id>, &mut Context) + 'static>(on_change: F) -> Self {
+        Self(Rc::new(RefCell::new(ButtonGroupInner {
+            selected: None,
+            allow_none: true,
             on_change: Box::new(on_change),
         })))
     }
+
     pub fn selected(&self) -> Option<Id> {
         self.0.borrow().selected
     }
+
+    /// If the selected button can be deselected. See [`ButtonGroup::new_deselectable`].
+    pub fn allow_none(&self) -> bool {
+        self.0.borrow().allow_none
+    }
+
     pub fn set_selected(&mut self, selected: Option<Id>, ctx: &mut Context) {
         let mut this = self.0.borrow_mut();
         this.selected = selected;
-        (this.on_change)(selected.expect("None selected is not implemented"), ctx);
+        (this.on_change)(selected, ctx);
     }
 }
 
@@ -38,6 +74,14 @@ pub struct TabButton {
     selected: bool,
     click: bool,
     style: Rc<TabStyle>,
+    /// A descendant of this tab, that sends a TabClosed when clicked.
+    close_button: Option<Id>,
+    /// If the close button was pressed.
+    closing: bool,
+    /// If the tab can be dragged to reorder it between its siblings.
+    reorderable: bool,
+    /// If the tab was dragged since the last mouse down.
+    dragged: bool,
 }
 impl TabButton {
     pub fn new(tab_group: ButtonGroup, page: Id, selected: bool, style: Rc<TabStyle>) -> Self {
@@ -47,6 +91,72 @@ impl TabButton {
             selected,
             click: false,
             style,
+            close_button: None,
+            closing: false,
+            reorderable: false,
+            dragged: false,
+        }
+    }
+
+    /// Make the tab closable. `close_button` must be a descendant of the tab, and clicking it
+    /// sends a [`TabClosed`] to the parent of the tab, instead of selecting it. Closing the
+    /// selected tab moves the selection to a neighbour tab, see [`TabClosed`].
+    pub fn with_close_button(mut self, close_button: Id) -> Self {
+        self.close_button = Some(close_button);
+        self
+    }
+
+    /// If true, the tab can be dragged along the tab strip, swapping places with its siblings.
+    pub fn with_reordering(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    fn is_over_close_button(&self, pos: [f32; 2], ctx: &mut Context) -> bool {
+        match self.close_button {
+            Some(close_button) => {
+                let rect = ctx.get_rect(close_button);
+                pos[0] >= rect[0] && pos[0] < rect[2] && pos[1] >= rect[1] && pos[1] < rect[3]
+            }
+            None => false,
+        }
+    }
+
+    /// Move the tab to the place of the sibling under the mouse, once the mouse passes the
+    /// middle of the sibling.
+    fn drag(&mut self, pos: [f32; 2], this: Id, ctx: &mut Context) {
+        self.dragged = true;
+        let strip = match ctx.get_parent(this) {
+            Some(x) => x,
+            None => return,
+        };
+        let this_rect = ctx.get_rect(this);
+        let children = ctx.get_all_children(strip);
+        let this_index = children.iter().position(|&x| x == this).unwrap();
+        let target = children.iter().position(|&x| {
+            if x == this || !ctx.is_active(x) {
+                return false;
+            }
+            let rect = ctx.get_rect(x);
+            if pos[0] < rect[0] || pos[0] >= rect[2] || pos[1] < rect[1] || pos[1] >= rect[3] {
+                return false;
+            }
+            // the axis in which the tabs are side by side
+            let i = if (rect[0] - this_rect[0]).abs() >= (rect[1] - this_rect[1]).abs() {
+                0
+            } else {
+                1
+            };
+            let middle = (rect[i] + rect[i + 2]) / 2.0;
+            if rect[i] > this_rect[i] {
+                pos[i] > middle
+            } else {
+                pos[i] < middle
+            }
+        });
+        if let Some(target) = target {
+            log::trace!("moving tab {} from {} to {}", this, this_index, target);
+            ctx.move_to_index(this, target);
         }
     }
 
@@ -63,6 +173,41 @@ impl TabButton {
         ctx.set_graphic(this, self.style.selected.clone());
     }
 
+    /// Unselect this button, leaving none selected in the group.
+    fn deselect(&mut self, this: Id, ctx: &mut Context) {
+        self.unselect(this, ctx);
+        self.tab_group.set_selected(None, ctx);
+    }
+
+    /// Move the selection away from this tab, and tell the tab strip to remove it.
+    fn close(&mut self, this: Id, ctx: &mut Context) {
+        let strip = match ctx.get_parent(this) {
+            Some(x) => x,
+            None => return,
+        };
+        if self.selected {
+            let tabs = ctx.get_active_children(strip);
+            let neighbour = tabs.iter().position(|&x| x == this).and_then(|i| {
+                tabs.get(i + 1)
+                    .or_else(|| i.checked_sub(1).and_then(|i| tabs.get(i)))
+                    .copied()
+            });
+            self.unselect(this, ctx);
+            match neighbour {
+                Some(neighbour) => {
+                    // clear the selection first, so the neighbour doesn't send Unselected to a
+                    // removed tab
+                    self.tab_group.0.borrow_mut().selected = None;
+                    ctx.send_event_to(neighbour, Select);
+                }
+                None if self.tab_group.allow_none() => self.tab_group.set_selected(None, ctx),
+                // on_change of a non deselectable group can't be called with None
+                None => self.tab_group.0.borrow_mut().selected = None,
+            }
+        }
+        ctx.send_event_to(strip, TabClosed { id: this });
+    }
+
     fn unselect(&mut self, this: Id, ctx: &mut Context) {
         ctx.deactive(self.page);
         self.selected = false;
@@ -70,6 +215,10 @@ impl TabButton {
     }
 }
 impl Behaviour for TabButton {
+    fn accessibility_role(&self) -> Role {
+        Role::Tab
+    }
+
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
         if self.selected {
             self.select(this, ctx);
@@ -87,7 +236,11 @@ impl Behaviour for TabButton {
     }
 
     fn input_flags(&self) -> InputFlags {
-        InputFlags::MOUSE
+        if self.reorderable {
+            InputFlags::MOUSE | InputFlags::DRAG
+        } else {
+            InputFlags::MOUSE
+        }
     }
 
     fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
@@ -102,26 +255,51 @@ impl Behaviour for TabButton {
             MouseEvent::Exit => {
                 if !self.selected {
                     ctx.set_graphic(this, self.style.unselected.clone());
+                } else if self.click {
+                    ctx.set_graphic(this, self.style.selected.clone());
                 }
             }
             MouseEvent::Down(Left) => {
-                if !self.selected {
+                self.dragged = false;
+                if self.is_over_close_button(mouse.pos, ctx) {
+                    self.closing = true;
+                } else if !self.selected || self.tab_group.allow_none() {
                     self.click = true;
                     ctx.set_graphic(this, self.style.pressed.clone());
                 }
             }
             MouseEvent::Up(Left) => {
-                if !self.selected {
-                    if self.click {
+                if self.closing {
+                    self.closing = false;
+                    if self.is_over_close_button(mouse.pos, ctx) {
+                        self.close(this, ctx);
+                    }
+                } else if self.dragged {
+                    // a dragged tab is selected, but never deselected
+                    self.click = false;
+                    if self.selected {
+                        ctx.set_graphic(this, self.style.selected.clone());
+                    } else {
                         self.select(this, ctx);
+                    }
+                } else if self.click {
+                    self.click = false;
+                    if self.selected {
+                        self.deselect(this, ctx);
                     } else {
-                        ctx.set_graphic(this, self.style.unselected.clone());
+                        self.select(this, ctx);
                     }
+                } else if !self.selected {
+                    ctx.set_graphic(this, self.style.unselected.clone());
                 }
             }
             MouseEvent::Up(_) => {}
             MouseEvent::Down(_) => {}
-            MouseEvent::Moved => {}
+            MouseEvent::Moved => {
+                if self.reorderable && mouse.is_dragging() && !self.closing {
+                    self.drag(mouse.pos, this, ctx);
+                }
+            }
             MouseEvent::None => {}
         }
     }
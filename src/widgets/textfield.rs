@@ -1,15 +1,16 @@
-use std::{any::Any, rc::Rc, time::Duration};
+use std::{any::Any, ops::Range, rc::Rc, time::Duration};
 
 use copypasta::{ClipboardContext, ClipboardProvider};
 use winit::{event::VirtualKeyCode, window::CursorIcon};
 
 use crate::{
-    event::SetValue,
+    accessibility::Role,
+    event::{SetEnabled, SetStyle, SetValue},
     graphics::Graphic,
     style::TextFieldStyle,
     text::{editor::TextEditor, layout::TextLayout, Span},
-    time::Instant,
-    Behaviour, Context, Id, InputFlags, KeyboardEvent, MouseEvent, MouseInfo,
+    Behaviour, Context, Id, ImeEvent, InputFlags, IntervalId, KeyboardEvent, KeyboardKind,
+    MouseEvent, MouseInfo, ScrollInfo,
 };
 
 /// The callback that handle the events dispatched by the TextField.
@@ -44,6 +45,9 @@ impl TextFieldCallback for () {
 
 struct BlinkCaret;
 
+/// The default period of the caret blink.
+const BLINK_PERIOD: Duration = Duration::from_millis(530);
+
 const SIDE_MARGIN: f32 = 5.0;
 const TOP_MARGIN: f32 = 5.0;
 
@@ -64,6 +68,7 @@ pub struct TextField<C: TextFieldCallback> {
     y_scroll: f32,
     /// If this is false, the TextField will always contain a sigle line.
     multiline: bool,
+    disabled: bool,
     on_focus: bool,
     /// If it is non zero, the mouse is being dragged. 1 for single click, 2 for double click, etc...
     mouse_down: u8,
@@ -71,8 +76,15 @@ pub struct TextField<C: TextFieldCallback> {
     style: Rc<TextFieldStyle>,
     selection_span: Option<crate::text::Key>,
     blink: bool,
-    /// event_id of the last scheduled BlinkCaret event
-    blink_event: Option<u64>,
+    /// The interval that sends BlinkCaret events, while focused.
+    blink_interval: Option<IntervalId>,
+    /// The time the caret stays visible, or hidden, while blinking. None disables the blink.
+    blink_period: Option<Duration>,
+    /// The byte range of the IME preedit text, that is inserted in the text while composing,
+    /// and its underline span.
+    preedit: Option<(Range<usize>, crate::text::Key)>,
+    /// The layout of the virtual keyboard requested while focused.
+    keyboard_kind: KeyboardKind,
 }
 impl<C: TextFieldCallback> TextField<C> {
     pub fn new(
@@ -94,16 +106,34 @@ impl<C: TextFieldCallback> TextField<C> {
             x_scroll: 0.0,
             y_scroll: 0.0,
             multiline,
+            disabled: false,
             on_focus: false,
             mouse_down: 0,
             drag_start: 0,
             style,
             selection_span: None,
             blink: false,
-            blink_event: None,
+            blink_interval: None,
+            blink_period: Some(BLINK_PERIOD),
+            preedit: None,
+            keyboard_kind: KeyboardKind::Text,
         }
     }
 
+    /// Set the time the caret stays visible, or hidden, while blinking. The default is 530ms.
+    /// If None, the caret does not blink.
+    pub fn with_caret_blink(mut self, period: Option<Duration>) -> Self {
+        self.blink_period = period;
+        self
+    }
+
+    /// Set the kind of text expected by this field, used for choosing the layout of the virtual
+    /// keyboard requested when it gains focus. The default is [`KeyboardKind::Text`].
+    pub fn with_keyboard_kind(mut self, kind: KeyboardKind) -> Self {
+        self.keyboard_kind = kind;
+        self
+    }
+
     fn update_text(&mut self, this: Id, ctx: &mut Context) {
         let fonts = ctx.get_fonts();
         if let (rect, Graphic::Text(text)) = ctx.get_rect_and_graphic(self.label) {
@@ -139,11 +169,10 @@ impl<C: TextFieldCallback> TextField<C> {
     }
 
     fn update_carret(&mut self, this: Id, ctx: &mut Context, focus_caret: bool) {
-        // Cancel the scheduled blink event. The event will rescheduled later in the function if
-        // necessary.
-        if let Some(event_id) = self.blink_event {
-            ctx.cancel_scheduled_event(event_id);
-        } else {
+        if focus_caret {
+            // the caret was moved, so make it visible, and restart the blink
+            self.stop_blink(ctx);
+        } else if self.blink_interval.is_none() {
             self.blink = false;
         }
 
@@ -221,16 +250,20 @@ impl<C: TextFieldCallback> TextField<C> {
         // event if necessary.
         let selection_range = self.editor.selection_range();
         if selection_range.len() > 0 {
+            self.stop_blink(ctx);
             ctx.set_margins(self.caret, [0.0; 4]);
             if let Graphic::Text(text) = ctx.get_graphic_mut(self.label) {
+                let selection_color = self.style.selection_color(self.on_focus);
+                let fg = selection_color.readable_fg(text.color());
                 self.selection_span.take().map(|x| text.remove_span(x));
                 self.selection_span = Some(text.add_span(
                     selection_range,
                     Span::Selection {
-                        bg: self.style.selection_color.bg,
-                        fg: self.style.selection_color.fg,
+                        bg: selection_color.bg,
+                        fg: Some(fg),
                     },
                 ));
+                text.set_selection_radius(self.style.selection_radius);
             }
         } else {
             if let Graphic::Text(text) = ctx.get_graphic_mut(self.label) {
@@ -239,13 +272,17 @@ impl<C: TextFieldCallback> TextField<C> {
             ctx.get_graphic_mut(self.caret)
                 .set_color(self.style.caret_color);
 
+            if self.on_focus {
+                // place the IME candidate window just below the caret
+                ctx.set_ime_position([this_rect[0] + caret_pos[0], this_rect[1] + caret_pos[1]]);
+            }
             if self.on_focus && !self.blink {
                 ctx.set_margins(
                     self.caret,
                     [
                         caret_pos[0],
                         caret_pos[1] - caret_pos[2],
-                        caret_pos[0] + 1.0,
+                        caret_pos[0] + self.style.caret_width(),
                         caret_pos[1],
                     ],
                 );
@@ -253,17 +290,73 @@ impl<C: TextFieldCallback> TextField<C> {
                 ctx.set_margins(self.caret, [0.0, 0.0, 0.0, 0.0]);
             }
 
-            if self.on_focus {
-                self.blink_event = Some(ctx.send_event_to_scheduled(
-                    this,
-                    BlinkCaret,
-                    Instant::now() + Duration::from_millis(500),
-                ));
+            match self.blink_period {
+                Some(period) if self.on_focus => {
+                    if self.blink_interval.is_none() {
+                        self.blink_interval =
+                            Some(ctx.schedule_interval(this, || BlinkCaret, period));
+                    }
+                }
+                _ => self.stop_blink(ctx),
             }
         }
     }
+
+    /// Replace the current IME preedit text, if any, by the given one, underlined, and move the
+    /// caret to the end of the preedit `cursor`. A empty `preedit` only removes the current one.
+    fn set_preedit(
+        &mut self,
+        preedit: &str,
+        cursor: Option<(usize, usize)>,
+        this: Id,
+        ctx: &mut Context,
+    ) {
+        let fonts = ctx.get_fonts();
+        let previous = self.preedit.take();
+        if previous.is_none() && preedit.is_empty() {
+            return;
+        }
+        if let Some((range, span)) = previous {
+            if let Graphic::Text(text) = ctx.get_graphic_mut(self.label) {
+                text.remove_span(span);
+            }
+            let text_layout = self.get_layout(ctx);
+            self.editor
+                .move_cursor_to_byte_index(range.start, false, text_layout);
+            self.editor
+                .move_cursor_to_byte_index(range.end, true, text_layout);
+        }
+        let text_layout = self.get_layout(ctx);
+        let start = self.editor.selection_range().start;
+        self.editor.insert_text(preedit, fonts, text_layout);
+        if !preedit.is_empty() {
+            let range = start..start + preedit.len();
+            if let Some((_, end)) = cursor {
+                self.editor
+                    .move_cursor_to_byte_index(start + end, false, text_layout);
+            }
+            if let Graphic::Text(text) = ctx.get_graphic_mut(self.label) {
+                let span = text.add_span(range.clone(), Span::Underline(None));
+                self.preedit = Some((range, span));
+            }
+        }
+        self.update_text(this, ctx);
+    }
+
+    /// Stop the caret blink, making the caret visible. It restarts in the next `update_carret`,
+    /// if focused.
+    fn stop_blink(&mut self, ctx: &mut Context) {
+        if let Some(interval_id) = self.blink_interval.take() {
+            ctx.cancel_interval(interval_id);
+        }
+        self.blink = false;
+    }
 }
 impl<C: TextFieldCallback> Behaviour for TextField<C> {
+    fn accessibility_role(&self) -> Role {
+        Role::TextInput
+    }
+
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
         let fonts = ctx.get_fonts();
         if let (rect, Graphic::Text(text)) = ctx.get_rect_and_graphic(self.label) {
@@ -285,6 +378,9 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
             }
             ctx.move_to_front(self.label);
             ctx.set_graphic(this, self.style.background.normal.clone());
+            if ctx.get_hover_cursor(this).is_none() {
+                ctx.set_hover_cursor(this, Some(CursorIcon::Text));
+            }
         } else {
             panic!("TextField label graphic is not Text");
         }
@@ -302,6 +398,20 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
             self.editor.insert_text(&text, fonts, text_layout);
             self.update_text(this, ctx);
             self.callback.on_change(this, ctx, &text);
+        } else if let Some(SetEnabled(enabled)) = event.downcast_ref() {
+            self.disabled = !enabled;
+            self.mouse_down = 0;
+            if self.disabled {
+                ctx.lose_focus(this);
+            }
+        } else if let Some(SetStyle(style)) = event.downcast_ref::<SetStyle<TextFieldStyle>>() {
+            self.style = style.clone();
+            if self.on_focus {
+                ctx.set_graphic(this, self.style.background.focus.clone());
+            } else {
+                ctx.set_graphic(this, self.style.background.normal.clone());
+            }
+            self.update_carret(this, ctx, false);
         } else if event.is::<BlinkCaret>() {
             self.blink = !self.blink;
             self.update_carret(this, ctx, false);
@@ -309,7 +419,11 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
     }
 
     fn input_flags(&self) -> InputFlags {
-        let mut flags = InputFlags::MOUSE | InputFlags::FOCUS;
+        let mut flags = if self.disabled {
+            InputFlags::empty()
+        } else {
+            InputFlags::MOUSE | InputFlags::FOCUS
+        };
 
         if !self.multiline && self.text_width > self.this_width
             || self.multiline && self.text_height > self.this_height
@@ -319,7 +433,8 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
         flags
     }
 
-    fn on_scroll_event(&mut self, mut delta: [f32; 2], this: Id, ctx: &mut Context) {
+    fn on_scroll_event(&mut self, scroll: ScrollInfo, this: Id, ctx: &mut Context) {
+        let mut delta = scroll.delta;
         // allow scrolling in a text field with the mouse weel.
         if !self.multiline && delta[0].abs() == 0.0 {
             delta[0] = delta[1];
@@ -339,12 +454,6 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
         };
         let text_layout = self.get_layout(ctx);
         match mouse.event {
-            MouseEvent::Enter => {
-                ctx.set_cursor(CursorIcon::Text);
-            }
-            MouseEvent::Exit => {
-                ctx.set_cursor(CursorIcon::Default);
-            }
             MouseEvent::Down(Left) => {
                 let x = mouse.pos[0] - anchor[0];
                 let y = mouse.pos[1] - anchor[1];
@@ -365,7 +474,8 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
                             self.mouse_down = 2;
                         }
                         3..=u8::MAX => {
-                            self.editor.select_all(text_layout);
+                            self.editor
+                                .select_lines_at_byte_range(byte_index..byte_index, text_layout);
                             self.mouse_down = 3;
                         }
                     }
@@ -378,9 +488,6 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
                     }
                 }
                 self.drag_start = byte_index;
-                if let Some(event_id) = self.blink_event.take() {
-                    ctx.cancel_scheduled_event(event_id);
-                }
                 self.update_carret(this, ctx, true);
                 ctx.lock_cursor(true, mouse.id);
             }
@@ -400,7 +507,7 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
                         .move_cursor_to_byte_index(byte_index, true, text_layout);
                     self.update_carret(this, ctx, true);
                 }
-                2..=u8::MAX => {
+                2 => {
                     let x = mouse.pos[0] - anchor[0];
                     let y = mouse.pos[1] - anchor[1];
                     let byte_index = text_layout
@@ -410,6 +517,16 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
                         .select_words_at_byte_range(self.drag_start..byte_index, text_layout);
                     self.update_carret(this, ctx, true);
                 }
+                3..=u8::MAX => {
+                    let x = mouse.pos[0] - anchor[0];
+                    let y = mouse.pos[1] - anchor[1];
+                    let byte_index = text_layout
+                        .byte_index_from_position(x, y)
+                        .unwrap_or_else(|x| x);
+                    self.editor
+                        .select_lines_at_byte_range(self.drag_start..byte_index, text_layout);
+                    self.update_carret(this, ctx, true);
+                }
             },
             MouseEvent::Up(_) => {}
             MouseEvent::Down(_) => {}
@@ -419,10 +536,15 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
 
     fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
         self.on_focus = focus;
+        ctx.set_ime_allowed(focus);
+        ctx.request_virtual_keyboard(focus, self.keyboard_kind);
         if focus {
             ctx.set_graphic(this, self.style.background.focus.clone());
         } else {
             ctx.set_graphic(this, self.style.background.normal.clone());
+            self.stop_blink(ctx);
+            // discard any uncommitted composition
+            self.set_preedit("", None, this, ctx);
 
             let mut text = self.text(ctx).to_owned();
             self.callback.on_unfocus(this, ctx, &mut text);
@@ -439,9 +561,10 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
 
     fn on_keyboard_event(&mut self, event: KeyboardEvent, this: Id, ctx: &mut Context) -> bool {
         use crate::text::editor::HorizontalMotion::*;
-        if let Some(event_id) = self.blink_event.take() {
-            ctx.cancel_scheduled_event(event_id);
+        if self.disabled {
+            return false;
         }
+        self.stop_blink(ctx);
         self.update_carret(this, ctx, false);
         let mut handle_event = || {
             let fonts = ctx.get_fonts();
@@ -603,4 +726,25 @@ impl<C: TextFieldCallback> Behaviour for TextField<C> {
 
         handle_event() || self.callback.on_keyboard_event(event, this, ctx)
     }
+
+    fn on_ime_event(&mut self, event: ImeEvent, this: Id, ctx: &mut Context) -> bool {
+        if self.disabled {
+            return false;
+        }
+        match event {
+            ImeEvent::Preedit(preedit, cursor) => {
+                self.set_preedit(&preedit, cursor, this, ctx);
+            }
+            ImeEvent::Commit(commit) => {
+                self.set_preedit("", None, this, ctx);
+                let fonts = ctx.get_fonts();
+                let text_layout = self.get_layout(ctx);
+                self.editor.insert_text(&commit, fonts, text_layout);
+                self.update_text(this, ctx);
+                let text = self.text(ctx).to_owned();
+                self.callback.on_change(this, ctx, &text);
+            }
+        }
+        true
+    }
 }
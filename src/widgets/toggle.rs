@@ -1,18 +1,32 @@
 use std::{any::Any, rc::Rc};
 
 use crate::{
-    event::SetValue,
+    accessibility::{AccessValue, Role},
+    event::{SetEnabled, SetValue},
+    graphics::Graphic,
     style::{ButtonStyle, OnFocusStyle},
-    Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
+    AnimationId, Behaviour, Color, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo,
 };
 
+/// The duration of the animation of the marker of a switch, in seconds.
+const SWITCH_ANIMATION_LENGTH: f32 = 0.12;
+
+/// The state of a [`Toggle`] created with [`Toggle::new_switch`].
+struct Switch {
+    /// The color of the track when off and on.
+    track_colors: [Color; 2],
+    animation: Option<AnimationId>,
+}
+
 pub struct Toggle<F: Fn(Id, &mut Context, bool)> {
     click: bool,
     enable: bool,
+    disabled: bool,
     button: Id,
     marker: Id,
     button_style: Rc<ButtonStyle>,
     background_style: Rc<OnFocusStyle>,
+    switch: Option<Switch>,
     on_change: F,
 }
 impl<F: Fn(Id, &mut Context, bool)> Toggle<F> {
@@ -27,37 +41,301 @@ impl<F: Fn(Id, &mut Context, bool)> Toggle<F> {
         Self {
             click: false,
             enable: initial_value,
+            disabled: false,
             button,
             marker,
             button_style,
             background_style,
+            switch: None,
             on_change,
         }
     }
+
+    /// Create a sliding switch, instead of a checkbox.
+    ///
+    /// `button` is the track of the switch, and `marker` is a child of it, the knob. The knob
+    /// slides between the left edge of the track, when off, and the right edge, when on, and its
+    /// width is its min size. The color of the track changes between `track_colors[0]`, when
+    /// off, and `track_colors[1]`, when on.
+    pub fn new_switch(
+        button: Id,
+        marker: Id,
+        initial_value: bool,
+        track_colors: [Color; 2],
+        button_style: Rc<ButtonStyle>,
+        background_style: Rc<OnFocusStyle>,
+        on_change: F,
+    ) -> Self {
+        Self {
+            switch: Some(Switch {
+                track_colors,
+                animation: None,
+            }),
+            ..Self::new(
+                button,
+                marker,
+                initial_value,
+                button_style,
+                background_style,
+                on_change,
+            )
+        }
+    }
+
+    /// Set the color of the button, to show if it is hovered or pressed. A switch uses the
+    /// color of the button for the track, so it is not changed.
+    fn set_button_color(&self, color: Color, ctx: &mut Context) {
+        if self.switch.is_none() {
+            ctx.get_graphic_mut(self.button).set_color(color);
+        }
+    }
+
+    /// Show the current value in the marker. If `animate` is true, a switch slides the knob to
+    /// its new position.
+    fn update_marker(&mut self, animate: bool, ctx: &mut Context) {
+        let switch = match &mut self.switch {
+            Some(x) => x,
+            None => {
+                let alpha = if self.enable { 255 } else { 0 };
+                ctx.get_graphic_mut(self.marker).set_alpha(alpha);
+                return;
+            }
+        };
+        if let Some(animation) = switch.animation.take() {
+            ctx.remove_animation(animation);
+        }
+        let (track, knob) = (self.button, self.marker);
+        let colors = switch.track_colors;
+        let to = if self.enable { 1.0 } else { 0.0 };
+        let set_position = move |x: f32, ctx: &mut Context| {
+            let width = ctx.get_min_size(knob)[0];
+            ctx.set_anchor_left(knob, x);
+            ctx.set_anchor_right(knob, x);
+            ctx.set_margin_left(knob, -x * width);
+            ctx.set_margin_right(knob, (1.0 - x) * width);
+            ctx.get_graphic_mut(track)
+                .set_color(colors[0].lerp(colors[1], x));
+        };
+        if !animate {
+            set_position(to, ctx);
+            return;
+        }
+        let from = ctx.get_anchors(knob)[0];
+        let animation = ctx.add_animation(
+            SWITCH_ANIMATION_LENGTH,
+            move |t: f32, _: f32, _: f32, ctx: &mut Context| {
+                set_position(from + (to - from) * t, ctx)
+            },
+        );
+        switch.animation = Some(animation);
+    }
 }
 impl<F: Fn(Id, &mut Context, bool)> Behaviour for Toggle<F> {
+    fn accessibility_role(&self) -> Role {
+        if self.switch.is_some() {
+            Role::Switch
+        } else {
+            Role::CheckBox
+        }
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        Some(AccessValue::Checked(self.enable.into()))
+    }
+
     fn on_start(&mut self, this: Id, ctx: &mut Context) {
         (self.on_change)(this, ctx, self.enable);
         ctx.set_graphic(this, self.background_style.normal.clone());
         ctx.set_graphic(self.button, self.button_style.normal.clone());
-        let graphic = ctx.get_graphic_mut(self.button);
-        graphic.set_color([200, 200, 200, 255].into());
-        if self.enable {
-            ctx.get_graphic_mut(self.marker).set_alpha(255)
-        } else {
-            ctx.get_graphic_mut(self.marker).set_alpha(0)
-        }
+        self.set_button_color([200, 200, 200, 255].into(), ctx);
+        self.update_marker(false, ctx);
     }
 
     fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
-        if let Some(SetValue(x)) = event.downcast_ref() {
+        if let Some(SetEnabled(enabled)) = event.downcast_ref() {
+            self.disabled = !enabled;
+            self.click = false;
+            if self.disabled {
+                ctx.lose_focus(this);
+                ctx.set_graphic(self.button, self.button_style.disabled_graphic());
+            } else {
+                ctx.set_graphic(self.button, self.button_style.normal.clone());
+                self.set_button_color([200, 200, 200, 255].into(), ctx);
+            }
+            if self.switch.is_some() {
+                self.update_marker(false, ctx);
+            }
+        } else if let Some(SetValue(x)) = event.downcast_ref() {
             self.enable = *x;
+            ctx.dirty_accessibility();
             (self.on_change)(this, ctx, self.enable);
-            if self.enable {
-                ctx.get_graphic_mut(self.marker).set_alpha(255)
+            self.update_marker(true, ctx);
+        }
+    }
+
+    fn on_focus_change(&mut self, focus: bool, this: Id, ctx: &mut Context) {
+        if focus {
+            ctx.set_graphic(this, self.background_style.focus.clone());
+        } else {
+            ctx.set_graphic(this, self.background_style.normal.clone());
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        if self.disabled {
+            return InputFlags::empty();
+        }
+        InputFlags::MOUSE | InputFlags::FOCUS
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        use MouseButton::*;
+        match mouse.event {
+            MouseEvent::Enter => {
+                self.set_button_color([190, 190, 190, 255].into(), ctx);
+            }
+            MouseEvent::Exit => {
+                self.click = false;
+                self.set_button_color([200, 200, 200, 255].into(), ctx);
+            }
+            MouseEvent::Down(Left) => {
+                self.click = true;
+                self.set_button_color([170, 170, 170, 255].into(), ctx);
+            }
+            MouseEvent::Up(Left) => {
+                self.set_button_color([190, 190, 190, 255].into(), ctx);
+                if self.click {
+                    self.enable = !self.enable;
+                    ctx.dirty_accessibility();
+                    (self.on_change)(this, ctx, self.enable);
+                    self.update_marker(true, ctx);
+                }
+            }
+            MouseEvent::Moved => {}
+            MouseEvent::Up(_) => {}
+            MouseEvent::Down(_) => {}
+            MouseEvent::None => {}
+        }
+    }
+}
+
+/// The state of a [`TriStateToggle`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    /// Neither checked nor unchecked. Useful for a "select all" checkbox, when only some of the
+    /// items are selected.
+    Indeterminate,
+}
+impl CheckState {
+    /// The state that follows this one, when the toggle is clicked.
+    pub fn next(self) -> Self {
+        match self {
+            CheckState::Unchecked => CheckState::Checked,
+            CheckState::Checked => CheckState::Indeterminate,
+            CheckState::Indeterminate => CheckState::Unchecked,
+        }
+    }
+}
+impl From<bool> for CheckState {
+    fn from(checked: bool) -> Self {
+        if checked {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        }
+    }
+}
+
+/// A toggle with three states: unchecked, checked and indeterminate. Each click cycles to the
+/// next state.
+///
+/// The marker is hidden when unchecked, and show the graphic `markers[0]` (a check, for example)
+/// when checked, and `markers[1]` (a dash, for example) when indeterminate.
+pub struct TriStateToggle<F: Fn(Id, &mut Context, CheckState)> {
+    click: bool,
+    state: CheckState,
+    disabled: bool,
+    button: Id,
+    marker: Id,
+    markers: [Graphic; 2],
+    button_style: Rc<ButtonStyle>,
+    background_style: Rc<OnFocusStyle>,
+    on_change: F,
+}
+impl<F: Fn(Id, &mut Context, CheckState)> TriStateToggle<F> {
+    pub fn new(
+        button: Id,
+        marker: Id,
+        initial_value: CheckState,
+        markers: [Graphic; 2],
+        button_style: Rc<ButtonStyle>,
+        background_style: Rc<OnFocusStyle>,
+        on_change: F,
+    ) -> Self {
+        Self {
+            click: false,
+            state: initial_value,
+            disabled: false,
+            button,
+            marker,
+            markers,
+            button_style,
+            background_style,
+            on_change,
+        }
+    }
+
+    fn update_marker(&mut self, ctx: &mut Context) {
+        match self.state {
+            CheckState::Unchecked => ctx.get_graphic_mut(self.marker).set_alpha(0),
+            CheckState::Checked => ctx.set_graphic(self.marker, self.markers[0].clone()),
+            CheckState::Indeterminate => ctx.set_graphic(self.marker, self.markers[1].clone()),
+        }
+    }
+
+    fn set_state(&mut self, state: CheckState, this: Id, ctx: &mut Context) {
+        self.state = state;
+        ctx.dirty_accessibility();
+        (self.on_change)(this, ctx, self.state);
+        self.update_marker(ctx);
+    }
+}
+impl<F: Fn(Id, &mut Context, CheckState)> Behaviour for TriStateToggle<F> {
+    fn accessibility_role(&self) -> Role {
+        Role::CheckBox
+    }
+
+    fn accessibility_value(&self) -> Option<AccessValue> {
+        Some(AccessValue::Checked(self.state))
+    }
+
+    fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        (self.on_change)(this, ctx, self.state);
+        ctx.set_graphic(this, self.background_style.normal.clone());
+        ctx.set_graphic(self.button, self.button_style.normal.clone());
+        let graphic = ctx.get_graphic_mut(self.button);
+        graphic.set_color([200, 200, 200, 255].into());
+        self.update_marker(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(SetEnabled(enabled)) = event.downcast_ref() {
+            self.disabled = !enabled;
+            self.click = false;
+            if self.disabled {
+                ctx.lose_focus(this);
+                ctx.set_graphic(self.button, self.button_style.disabled_graphic());
             } else {
-                ctx.get_graphic_mut(self.marker).set_alpha(0)
+                ctx.set_graphic(self.button, self.button_style.normal.clone());
+                let graphic = ctx.get_graphic_mut(self.button);
+                graphic.set_color([200, 200, 200, 255].into());
             }
+        } else if let Some(SetValue(x)) = event.downcast_ref::<SetValue<CheckState>>() {
+            self.set_state(*x, this, ctx);
+        } else if let Some(SetValue(x)) = event.downcast_ref::<SetValue<bool>>() {
+            self.set_state((*x).into(), this, ctx);
         }
     }
 
@@ -70,6 +348,9 @@ impl<F: Fn(Id, &mut Context, bool)> Behaviour for Toggle<F> {
     }
 
     fn input_flags(&self) -> InputFlags {
+        if self.disabled {
+            return InputFlags::empty();
+        }
         InputFlags::MOUSE | InputFlags::FOCUS
     }
 
@@ -94,13 +375,7 @@ impl<F: Fn(Id, &mut Context, bool)> Behaviour for Toggle<F> {
                 let graphic = ctx.get_graphic_mut(self.button);
                 graphic.set_color([190, 190, 190, 255].into());
                 if self.click {
-                    self.enable = !self.enable;
-                    (self.on_change)(this, ctx, self.enable);
-                    if self.enable {
-                        ctx.get_graphic_mut(self.marker).set_alpha(255)
-                    } else {
-                        ctx.get_graphic_mut(self.marker).set_alpha(0)
-                    }
+                    self.set_state(self.state.next(), this, ctx);
                 }
             }
             MouseEvent::Moved => {}
@@ -0,0 +1,123 @@
+use std::{any::Any, time::Duration};
+
+use crate::{
+    accessibility::Role, time::Instant, Behaviour, Context, Id, InputFlags, MouseEvent, MouseInfo,
+};
+
+/// Scheduled when the mouse enter the control, to show the tooltip after the hover delay.
+struct ShowTooltip;
+
+/// Show a floating control, like a text label, when the mouse hovers over this control for some
+/// time.
+///
+/// The `tooltip` control should be a child of the root control. It is deactivated on start, and
+/// is activated after the mouse stays over this control for the hover delay. It is positioned
+/// near the cursor, but never outside of the root control.
+pub struct Tooltip {
+    tooltip: Id,
+    delay: Duration,
+    /// The id of the scheduled ShowTooltip event, if any.
+    scheduled: Option<u64>,
+    mouse_pos: [f32; 2],
+    is_shown: bool,
+}
+impl Tooltip {
+    /// The distance, in pixels, from the cursor to the top-left corner of the tooltip.
+    const OFFSET: [f32; 2] = [12.0, 16.0];
+
+    pub fn new(tooltip: Id) -> Self {
+        Self {
+            tooltip,
+            delay: Duration::from_millis(500),
+            scheduled: None,
+            mouse_pos: [0.0; 2],
+            is_shown: false,
+        }
+    }
+
+    /// Set the time the mouse must be over the control before the tooltip is shown. The default
+    /// is 500 milliseconds.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn set_position(&mut self, ctx: &mut Context) {
+        let root = ctx.get_rect(Id::ROOT_ID);
+        let [width, height] = ctx.get_min_size(self.tooltip);
+        let root_width = root[2] - root[0];
+        let root_height = root[3] - root[1];
+
+        let mut x = self.mouse_pos[0] - root[0] + Self::OFFSET[0];
+        let mut y = self.mouse_pos[1] - root[1] + Self::OFFSET[1];
+        // if there is no space below the cursor, show it above.
+        if y + height > root_height {
+            y = self.mouse_pos[1] - root[1] - height;
+        }
+        x = x.min(root_width - width).max(0.0);
+        y = y.min(root_height - height).max(0.0);
+
+        ctx.set_anchors(self.tooltip, [0.0; 4]);
+        ctx.set_margins(self.tooltip, [x, y, x + width, y + height]);
+    }
+
+    fn hide(&mut self, ctx: &mut Context) {
+        if let Some(event_id) = self.scheduled.take() {
+            ctx.cancel_scheduled_event(event_id);
+        }
+        if self.is_shown {
+            ctx.deactive(self.tooltip);
+            self.is_shown = false;
+        }
+    }
+}
+impl Behaviour for Tooltip {
+    fn accessibility_role(&self) -> Role {
+        Role::Tooltip
+    }
+
+    fn on_start(&mut self, _this: Id, ctx: &mut Context) {
+        ctx.deactive(self.tooltip);
+    }
+
+    fn on_deactive(&mut self, _this: Id, ctx: &mut Context) {
+        self.hide(ctx);
+    }
+
+    fn on_event(&mut self, event: Box<dyn Any>, _this: Id, ctx: &mut Context) {
+        if event.is::<ShowTooltip>() {
+            self.scheduled = None;
+            self.is_shown = true;
+            ctx.active(self.tooltip);
+            ctx.move_to_front(self.tooltip);
+            self.set_position(ctx);
+        }
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, this: Id, ctx: &mut Context) {
+        match mouse.event {
+            MouseEvent::Enter => {
+                self.mouse_pos = mouse.pos;
+                self.hide(ctx);
+                let event_id =
+                    ctx.send_event_to_scheduled(this, ShowTooltip, Instant::now() + self.delay);
+                self.scheduled = Some(event_id);
+            }
+            MouseEvent::Exit | MouseEvent::Down(_) => {
+                self.hide(ctx);
+            }
+            MouseEvent::Moved => {
+                self.mouse_pos = mouse.pos;
+                if self.is_shown {
+                    self.set_position(ctx);
+                }
+            }
+            MouseEvent::Up(_) => {}
+            MouseEvent::None => {}
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use winit::event::VirtualKeyCode;
+
+use super::{ListBuilder, UpdateItems};
+use crate::{
+    accessibility::Role, graphics::Graphic, layouts::HBoxLayout, Behaviour, BuilderContext,
+    Context, ControlBuilder, Id, InputFlags, KeyboardEvent, MouseInfo,
+};
+
+/// Toggle the node with the given path between expanded and collapsed. Must be sent to the List
+/// of the [`TreeView`].
+pub struct ToggleNode(pub Vec<usize>);
+
+/// Expand, if true, or collapse, if false, the node with the given path. Must be sent to the List
+/// of the [`TreeView`].
+pub struct SetExpanded(pub Vec<usize>, pub bool);
+
+/// Build the nodes of a [`TreeView`].
+///
+/// Each node is identified by its path: the index of the node in its parent, preceded by the path
+/// of its parent. The root of the tree is the empty path, and is not displayed.
+#[allow(unused_variables)]
+pub trait TreeViewBuilder {
+    /// This receive any event sent to the list control that was not handled.
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {}
+
+    /// The number of children of the node with the given path.
+    fn child_count(&mut self, path: &[usize], ctx: &mut dyn BuilderContext) -> usize;
+
+    /// Used to build the content of the row of a node. The row is already indented, and contains
+    /// the expander arrow.
+    ///
+    /// The given ControlBuilder will have the row as parent.
+    fn create_node(
+        &mut self,
+        path: &[usize],
+        list_id: Id,
+        cb: ControlBuilder,
+        ctx: &mut dyn BuilderContext,
+    ) -> ControlBuilder;
+}
+
+struct Row {
+    path: Vec<usize>,
+    has_children: bool,
+    /// The id of the control created for this row, if any.
+    id: Option<Id>,
+    /// The id of the expander arrow of the created control, if the node has children.
+    expander: Option<Id>,
+    /// If the node was expanded when its control was last created or updated. Shared with its
+    /// TreeRow behaviour.
+    expanded: Rc<Cell<bool>>,
+}
+
+/// A [`ListBuilder`] that display a hierarchical tree, where each node can be expanded and
+/// collapsed.
+///
+/// Only the nodes whose all ancestors are expanded are items of the List. Each row is indented by
+/// its depth, and nodes with children have an expander arrow, that toggles the node when clicked.
+/// When a row is focused, the keys Left and Right collapse and expand the node, respectively.
+pub struct TreeView<B: TreeViewBuilder> {
+    builder: B,
+    /// The indentation of each level of the tree, in pixels. Also the width of the expander.
+    indent: f32,
+    /// The graphic of the expander arrow, when collapsed and expanded, respectively.
+    arrows: [Graphic; 2],
+    expanded: HashSet<Vec<usize>>,
+    /// The currently visible nodes, in order.
+    rows: Vec<Row>,
+    rows_dirty: bool,
+}
+impl<B: TreeViewBuilder> TreeView<B> {
+    pub fn new(indent: f32, arrows: [Graphic; 2], builder: B) -> Self {
+        Self {
+            builder,
+            indent,
+            arrows,
+            expanded: HashSet::new(),
+            rows: Vec::new(),
+            rows_dirty: true,
+        }
+    }
+
+    /// Return true if the node with the given path is expanded.
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.expanded.contains(path)
+    }
+
+    fn set_expanded(&mut self, path: Vec<usize>, expanded: bool, list: Id, ctx: &mut Context) {
+        let changed = if expanded {
+            self.expanded.insert(path)
+        } else {
+            self.expanded.remove(&path)
+        };
+        if changed {
+            self.rows_dirty = true;
+            ctx.send_event_to(list, UpdateItems);
+        }
+    }
+
+    fn update_rows(&mut self, ctx: &mut dyn BuilderContext) {
+        if !self.rows_dirty {
+            return;
+        }
+        self.rows_dirty = false;
+        // keep the controls of the nodes that are still visible, so they are updated instead of
+        // recreated, and don't lose focus.
+        let mut old_rows: HashMap<Vec<usize>, Row> =
+            self.rows.drain(..).map(|x| (x.path.clone(), x)).collect();
+        // depth-first traversal, only entering expanded nodes
+        let mut stack = vec![Vec::new()];
+        while let Some(path) = stack.pop() {
+            let count = self.builder.child_count(&path, ctx);
+            if !path.is_empty() {
+                let expanded = self.expanded.contains(&path);
+                let row = match old_rows.remove(&path) {
+                    Some(row) => Row {
+                        has_children: count > 0,
+                        ..row
+                    },
+                    None => Row {
+                        path: path.clone(),
+                        has_children: count > 0,
+                        id: None,
+                        expander: None,
+                        expanded: Rc::new(Cell::new(expanded)),
+                    },
+                };
+                self.rows.push(row);
+                if !expanded {
+                    continue;
+                }
+            }
+            for i in (0..count).rev() {
+                let mut child = path.clone();
+                child.push(i);
+                stack.push(child);
+            }
+        }
+    }
+}
+impl<B: TreeViewBuilder> ListBuilder for TreeView<B> {
+    fn on_event(&mut self, event: Box<dyn Any>, this: Id, ctx: &mut Context) {
+        if let Some(ToggleNode(path)) = event.downcast_ref::<ToggleNode>() {
+            let expanded = !self.expanded.contains(path);
+            self.set_expanded(path.clone(), expanded, this, ctx);
+        } else if let Some(SetExpanded(path, expanded)) = event.downcast_ref::<SetExpanded>() {
+            self.set_expanded(path.clone(), *expanded, this, ctx);
+        } else {
+            self.builder.on_event(event, this, ctx);
+        }
+    }
+
+    fn item_count(&mut self, ctx: &mut dyn BuilderContext) -> usize {
+        self.update_rows(ctx);
+        self.rows.len()
+    }
+
+    fn create_item<'a>(
+        &mut self,
+        index: usize,
+        list_id: Id,
+        cb: ControlBuilder,
+        ctx: &mut dyn BuilderContext,
+    ) -> ControlBuilder {
+        self.update_rows(ctx);
+        let expanded_nodes = &self.expanded;
+        let row = &mut self.rows[index];
+        let path = row.path.clone();
+        let has_children = row.has_children;
+        let expanded = expanded_nodes.contains(&path);
+        // a new cell, so an old control of this row, that is about to be removed, is not affected.
+        row.expanded = Rc::new(Cell::new(expanded));
+        row.id = Some(cb.id());
+        row.expander = None;
+        let expanded_cell = row.expanded.clone();
+
+        let depth = path.len() - 1;
+        let cb = cb
+            .layout(HBoxLayout::new(
+                0.0,
+                [depth as f32 * self.indent, 0.0, 0.0, 0.0],
+                -1,
+            ))
+            .behaviour(TreeRow {
+                list: list_id,
+                path: path.clone(),
+                has_children,
+                expanded: expanded_cell,
+            });
+
+        let expander = ctx
+            .create_control()
+            .min_size([self.indent, 0.0])
+            .parent(cb.id());
+        if has_children {
+            let arrow = self.arrows[expanded as usize].clone();
+            let expander = expander
+                .graphic(arrow)
+                .behaviour(TreeExpander {
+                    list: list_id,
+                    path: path.clone(),
+                })
+                .build(ctx);
+            self.rows[index].expander = Some(expander);
+        } else {
+            expander.build(ctx);
+        }
+
+        let content = ctx.create_control().parent(cb.id()).expand_x(true);
+        self.builder
+            .create_node(&path, list_id, content, ctx)
+            .build(ctx);
+
+        cb
+    }
+
+    fn update_item(&mut self, index: usize, item_id: Id, ctx: &mut dyn BuilderContext) -> bool {
+        self.update_rows(ctx);
+        // if the rows changed, the item was created for another node
+        let row = match self.rows.get(index) {
+            Some(row) if row.id == Some(item_id) => row,
+            _ => return false,
+        };
+        // the node itself may have been toggled
+        let expanded = self.expanded.contains(&row.path);
+        if row.expanded.get() != expanded {
+            row.expanded.set(expanded);
+            if let Some(expander) = row.expander {
+                *ctx.get_graphic_mut(expander) = self.arrows[expanded as usize].clone();
+            }
+        }
+        true
+    }
+}
+
+/// The behaviour of the row of each node.
+struct TreeRow {
+    list: Id,
+    path: Vec<usize>,
+    has_children: bool,
+    expanded: Rc<Cell<bool>>,
+}
+impl Behaviour for TreeRow {
+    fn accessibility_role(&self) -> Role {
+        Role::TreeItem
+    }
+
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE | InputFlags::FOCUS
+    }
+
+    fn on_keyboard_event(&mut self, event: KeyboardEvent, _this: Id, ctx: &mut Context) -> bool {
+        match event {
+            KeyboardEvent::Pressed(VirtualKeyCode::Right)
+                if self.has_children && !self.expanded.get() =>
+            {
+                ctx.send_event_to(self.list, SetExpanded(self.path.clone(), true));
+                true
+            }
+            KeyboardEvent::Pressed(VirtualKeyCode::Left) if self.expanded.get() => {
+                ctx.send_event_to(self.list, SetExpanded(self.path.clone(), false));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The behaviour of the expander arrow of a node.
+struct TreeExpander {
+    list: Id,
+    path: Vec<usize>,
+}
+impl Behaviour for TreeExpander {
+    fn input_flags(&self) -> InputFlags {
+        InputFlags::MOUSE
+    }
+
+    fn on_mouse_event(&mut self, mouse: MouseInfo, _this: Id, ctx: &mut Context) {
+        if mouse.click() {
+            ctx.send_event_to(self.list, ToggleNode(self.path.clone()));
+        }
+    }
+}
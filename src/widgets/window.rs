@@ -1,6 +1,9 @@
 use winit::window::CursorIcon;
 
-use crate::{Behaviour, Context, Id, InputFlags, MouseButton, MouseEvent, MouseInfo};
+use crate::{
+    accessibility::Role, graphics::Shadow, Behaviour, Context, Id, InputFlags, MouseButton,
+    MouseEvent, MouseInfo,
+};
 
 const LEFT: u8 = 0x1;
 const RIGHT: u8 = 0x2;
@@ -11,20 +14,207 @@ const RIGHT_BOTTOM: u8 = RIGHT | BOTTOM;
 const TOP_RIGHT: u8 = TOP | RIGHT;
 const BOTTOM_LEFT: u8 = BOTTOM | LEFT;
 
-#[derive(Default)]
+/// The distance, in pixels, from a edge that the mouse must be to resize the window.
+const EDGE_SIZE: f32 = 5.0;
+/// The distance, in pixels, from a corner, along a edge, that the mouse must be to resize both
+/// axes at the same time.
+const CORNER_SIZE: f32 = 10.0;
+/// The minimum width, in pixels, of a window that must be kept inside the root control while
+/// dragging it, and the minimum height of its top that must be kept visible.
+const KEEP_VISIBLE: f32 = 30.0;
+
+/// A control that can be moved by dragging it, and resized by dragging its edges or corners.
+///
+/// The window is positioned by its margins, so its anchors must be all equal. Its size never gets
+/// smaller than its min size, or than the min size constraint, and never gets bigger than the max
+/// size constraint.
+///
+/// While being moved, the window is constrained so its top cannot leave the root control, and it
+/// can optionally snap to the edges of the root control and of its sibling controls.
 pub struct Window {
     state: u8,
     dragging: bool,
     start_dragging: [f32; 2],
     start_margins: [f32; 4],
     mouse_pos: [f32; 2],
+    min_size: [f32; 2],
+    max_size: [f32; 2],
+    snap_distance: f32,
+    shadow: Option<Shadow>,
+}
+impl Default for Window {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl Window {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            state: 0,
+            dragging: false,
+            start_dragging: [0.0; 2],
+            start_margins: [0.0; 4],
+            mouse_pos: [0.0; 2],
+            min_size: [0.0; 2],
+            max_size: [f32::INFINITY; 2],
+            snap_distance: 0.0,
+            shadow: Some(Shadow::default()),
+        }
+    }
+
+    /// Set the minimum size that the window can be resized to. The min size of the control is
+    /// always respected, even if it is bigger than this.
+    pub fn with_min_size(mut self, min_size: [f32; 2]) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the maximum size that the window can be resized to. The default is unbounded.
+    pub fn with_max_size(mut self, max_size: [f32; 2]) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Make the window snap to the edges of the root control and of its siblings, when dragged
+    /// within the given distance, in pixels, of them. The default is 0.0, which disables snapping.
+    pub fn with_snap_distance(mut self, snap_distance: f32) -> Self {
+        self.snap_distance = snap_distance;
+        self
+    }
+
+    /// Set the shadow of the window. The default is [`Shadow::default`]. None disables the
+    /// shadow.
+    pub fn with_shadow(mut self, shadow: Option<Shadow>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub fn min_size(&self) -> [f32; 2] {
+        self.min_size
+    }
+
+    pub fn max_size(&self) -> [f32; 2] {
+        self.max_size
+    }
+
+    /// Clamp the size of the given margins between the size constraints, in the given axis. If
+    /// `move_start` is true, the start edge is moved, otherwise the end edge is moved.
+    fn clamp_size(&self, margins: &mut [f32; 4], min_size: [f32; 2], i: usize, move_start: bool) {
+        let min = min_size[i].max(self.min_size[i]);
+        let max = self.max_size[i].max(min);
+        let size = (margins[i + 2] - margins[i]).max(min).min(max);
+        if move_start {
+            margins[i] = margins[i + 2] - size;
+        } else {
+            margins[i + 2] = margins[i] + size;
+        }
+    }
+
+    /// Compute the offset that must be added to the given rect, the new rect of the window while
+    /// being moved, to snap it to the nearby edges and to keep it reachable inside the root
+    /// control.
+    fn snap_offset(&self, rect: [f32; 4], this: Id, ctx: &mut Context) -> [f32; 2] {
+        let root = ctx.get_rect(Id::ROOT_ID);
+        let mut offset = [0.0; 2];
+
+        if self.snap_distance > 0.0 {
+            let mut targets = vec![root];
+            if let Some(parent) = ctx.get_parent(this) {
+                for sibling in ctx.get_active_children(parent) {
+                    if sibling != this {
+                        targets.push(ctx.get_rect(sibling));
+                    }
+                }
+            }
+            let distance = self.snap_distance;
+            let snap = |i: usize| {
+                let j = 1 - i;
+                let mut best: Option<f32> = None;
+                for target in &targets {
+                    // only snap to controls that are side by side in the other axis
+                    if target[j] > rect[j + 2] + distance || target[j + 2] < rect[j] - distance {
+                        continue;
+                    }
+                    for edge in [target[i], target[i + 2]] {
+                        for side in [rect[i], rect[i + 2]] {
+                            let d = edge - side;
+                            if d.abs() <= distance && best.map_or(true, |b| d.abs() < b.abs()) {
+                                best = Some(d);
+                            }
+                        }
+                    }
+                }
+                best.unwrap_or(0.0)
+            };
+            offset = [snap(0), snap(1)];
+        }
+
+        // keep a part of the window, and its top, inside the root control
+        let left = rect[0] + offset[0];
+        let right = rect[2] + offset[0];
+        if right < root[0] + KEEP_VISIBLE {
+            offset[0] += root[0] + KEEP_VISIBLE - right;
+        } else if left > root[2] - KEEP_VISIBLE {
+            offset[0] += root[2] - KEEP_VISIBLE - left;
+        }
+        let top = rect[1] + offset[1];
+        if top < root[1] {
+            offset[1] += root[1] - top;
+        } else if top > root[3] - KEEP_VISIBLE {
+            offset[1] += root[3] - KEEP_VISIBLE - top;
+        }
+
+        offset
+    }
+
+    /// Compute which edges are under the mouse, given the rect of the window.
+    fn edges_at(pos: [f32; 2], rect: [f32; 4]) -> u8 {
+        let mut state = 0;
+        if pos[0] - rect[0] < EDGE_SIZE {
+            state |= LEFT;
+            if pos[1] - rect[1] < CORNER_SIZE {
+                state |= TOP;
+            } else if rect[3] - pos[1] < CORNER_SIZE {
+                state |= BOTTOM;
+            }
+        } else if rect[2] - pos[0] < EDGE_SIZE {
+            state |= RIGHT;
+            if pos[1] - rect[1] < CORNER_SIZE {
+                state |= TOP;
+            } else if rect[3] - pos[1] < CORNER_SIZE {
+                state |= BOTTOM;
+            }
+        }
+
+        if pos[1] - rect[1] < EDGE_SIZE {
+            state |= TOP;
+            if pos[0] - rect[0] < CORNER_SIZE {
+                state |= LEFT;
+            } else if rect[2] - pos[0] < CORNER_SIZE {
+                state |= RIGHT;
+            }
+        } else if rect[3] - pos[1] < EDGE_SIZE {
+            state |= BOTTOM;
+            if pos[0] - rect[0] < CORNER_SIZE {
+                state |= LEFT;
+            } else if rect[2] - pos[0] < CORNER_SIZE {
+                state |= RIGHT;
+            }
+        }
+        state
     }
 }
 impl Behaviour for Window {
+    fn accessibility_role(&self) -> Role {
+        Role::Window
+    }
+
+    fn on_start(&mut self, this: Id, ctx: &mut Context) {
+        if self.shadow.is_some() {
+            ctx.set_shadow(this, self.shadow);
+        }
+    }
+
     fn input_flags(&self) -> InputFlags {
         InputFlags::MOUSE
     }
@@ -41,12 +231,8 @@ impl Behaviour for Window {
                 ctx.lock_cursor(true, mouse.id);
                 let mut margins = ctx.get_margins(this);
                 let min_size = ctx.get_min_size(this);
-                if margins[2] - margins[0] < min_size[0] {
-                    margins[2] = margins[0] + min_size[0];
-                }
-                if margins[3] - margins[1] < min_size[1] {
-                    margins[3] = margins[1] + min_size[1];
-                }
+                self.clamp_size(&mut margins, min_size, 0, false);
+                self.clamp_size(&mut margins, min_size, 1, false);
                 self.start_dragging = self.mouse_pos;
                 self.start_margins = margins;
             }
@@ -58,38 +244,7 @@ impl Behaviour for Window {
                 let [mut x, mut y] = mouse.pos;
                 if !self.dragging {
                     let rect = ctx.get_rect(this);
-                    self.state = 0;
-                    if self.mouse_pos[0] - rect[0] < 5.0 {
-                        self.state |= LEFT;
-                        if self.mouse_pos[1] - rect[1] < 10.0 {
-                            self.state |= TOP;
-                        } else if rect[3] - self.mouse_pos[1] < 10.0 {
-                            self.state |= BOTTOM;
-                        }
-                    } else if rect[2] - self.mouse_pos[0] < 5.0 {
-                        self.state |= RIGHT;
-                        if self.mouse_pos[1] - rect[1] < 10.0 {
-                            self.state |= TOP;
-                        } else if rect[3] - self.mouse_pos[1] < 10.0 {
-                            self.state |= BOTTOM;
-                        }
-                    }
-
-                    if self.mouse_pos[1] - rect[1] < 5.0 {
-                        self.state |= TOP;
-                        if self.mouse_pos[0] - rect[0] < 10.0 {
-                            self.state |= LEFT;
-                        } else if rect[2] - self.mouse_pos[0] < 10.0 {
-                            self.state |= RIGHT;
-                        }
-                    } else if rect[3] - self.mouse_pos[1] < 5.0 {
-                        self.state |= BOTTOM;
-                        if self.mouse_pos[0] - rect[0] < 10.0 {
-                            self.state |= LEFT;
-                        } else if rect[2] - self.mouse_pos[0] < 10.0 {
-                            self.state |= RIGHT;
-                        }
-                    }
+                    self.state = Self::edges_at(mouse.pos, rect);
 
                     match self.state {
                         LEFT => ctx.set_cursor(CursorIcon::WResize),
@@ -137,31 +292,32 @@ impl Behaviour for Window {
                         if (self.state & BOTTOM) != 0 {
                             margins[3] += delta[1];
                         }
-                        if margins[2] - margins[0] < min_size[0] {
-                            if (self.state & LEFT) != 0 {
-                                margins[0] = margins[2] - min_size[0];
-                            } else {
-                                margins[2] = margins[0] + min_size[0];
-                            }
-                        }
-                        if margins[3] - margins[1] < min_size[1] {
-                            if (self.state & TOP) != 0 {
-                                margins[1] = margins[3] - min_size[1];
-                            } else {
-                                margins[3] = margins[1] + min_size[1];
-                            }
-                        }
+                        self.clamp_size(&mut margins, min_size, 0, (self.state & LEFT) != 0);
+                        self.clamp_size(&mut margins, min_size, 1, (self.state & TOP) != 0);
                         ctx.set_margins(this, margins);
                     } else {
-                        ctx.set_margins(
-                            this,
-                            [
-                                margins[0] + delta[0],
-                                margins[1] + delta[1],
-                                margins[2] + delta[0],
-                                margins[3] + delta[1],
-                            ],
-                        );
+                        // the position of the anchors, to convert the margins to a rect
+                        let rect = ctx.get_rect(this);
+                        let current = ctx.get_margins(this);
+                        let anchor = [rect[0] - current[0], rect[1] - current[1]];
+                        let mut margins = [
+                            margins[0] + delta[0],
+                            margins[1] + delta[1],
+                            margins[2] + delta[0],
+                            margins[3] + delta[1],
+                        ];
+                        let new_rect = [
+                            anchor[0] + margins[0],
+                            anchor[1] + margins[1],
+                            anchor[0] + margins[2],
+                            anchor[1] + margins[3],
+                        ];
+                        let offset = self.snap_offset(new_rect, this, ctx);
+                        margins[0] += offset[0];
+                        margins[1] += offset[1];
+                        margins[2] += offset[0];
+                        margins[3] += offset[1];
+                        ctx.set_margins(this, margins);
                     }
                 }
                 self.mouse_pos = [x, y];